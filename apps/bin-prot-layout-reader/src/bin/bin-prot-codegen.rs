@@ -0,0 +1,62 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates Rust types from a bin_prot layout file. See the README for details
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use env_logger::Env;
+use log::info;
+
+use serde::Deserialize;
+use structopt::StructOpt;
+
+// Layouts are deeply nested and generating types recurses through all of them
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+#[derive(StructOpt)]
+struct Opt {
+    /// Input layout JSON file
+    #[structopt(parse(from_os_str))]
+    layout: PathBuf,
+
+    /// Output Rust file, stdout if not present
+    #[structopt(long, short, parse(from_os_str))]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+    let opt = Opt::from_args();
+
+    info!("Reading layout, please wait. This may take a minute or two...");
+
+    let layout_file = File::open(&opt.layout)
+        .with_context(|| format!("Could not open layout file to read: {:?}", opt.layout))?;
+    let mut json_deserializer = serde_json::Deserializer::from_reader(layout_file);
+    // need to use the disable_recursion_limit hack because these can be HUGE!
+    json_deserializer.disable_recursion_limit();
+    let json_deserializer = serde_stacker::Deserializer::new(&mut json_deserializer);
+    let layout = bin_prot::Layout::deserialize(json_deserializer)
+        .context("Failed to deserialize layout JSON")?;
+
+    let code = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || bin_prot::value::layout::codegen::generate_types(&layout))?
+        .join()
+        .map_err(|_| anyhow!("Type generation panicked"))?
+        .context("Failed to generate types for layout")?;
+
+    if let Some(out_path) = opt.output {
+        let mut out_file = File::create(&out_path)
+            .with_context(|| format!("Could not create output file: {:?}", &out_path))?;
+        write!(out_file, "{}", code)
+            .with_context(|| format!("Could not write to output file: {:?}", &out_path))?;
+    } else {
+        print!("{}", code);
+    }
+    Ok(())
+}
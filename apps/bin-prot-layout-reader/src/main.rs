@@ -7,55 +7,89 @@ use std::fs::File;
 use std::io::BufReader;
 use std::io::Read;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use env_logger::Env;
 use log::info;
 
 use serde::Deserialize;
+use structopt::clap::AppSettings;
 use structopt::StructOpt;
 
+// Layouts are deeply nested and diffing recurses through all of them
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
 #[derive(StructOpt)]
+#[structopt(setting = AppSettings::SubcommandsNegateReqs)]
 struct Opt {
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
     /// Input layout JSON file
-    #[structopt(parse(from_os_str))]
-    layout: PathBuf,
+    #[structopt(parse(from_os_str), required = true)]
+    layout: Option<PathBuf>,
 
-    /// Input binary file
-    #[structopt(parse(from_os_str))]
-    binary: PathBuf,
+    /// Input binary file, or JSON file when encoding
+    #[structopt(parse(from_os_str), required = true)]
+    binary: Option<PathBuf>,
 
     /// Test roundtrip
     #[structopt(long, short)]
     roundtrip: bool,
 
+    /// Write the result as JSON instead of the debug representation
+    #[structopt(long, short)]
+    json: bool,
+
+    /// Read a JSON file (as written with --json) and encode it to binary.
+    /// The binary is written to the output file, or as hex to stdout
+    #[structopt(long, short)]
+    encode: bool,
+
     /// Output file, stdout if not present
     #[structopt(long, short, parse(from_os_str))]
     output: Option<PathBuf>,
 }
 
+#[derive(StructOpt)]
+enum Command {
+    /// Print the structural differences between two layouts, e.g. before and after a protocol upgrade
+    Diff {
+        /// Layout JSON file of the old version
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+
+        /// Layout JSON file of the new version
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+    },
+}
+
 fn main() -> Result<()> {
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
     let opt = Opt::from_args();
 
-    info!("Reading layout, please wait. This may take a minute or two...");
+    if let Some(Command::Diff { old, new }) = &opt.command {
+        return diff(old, new);
+    }
+    // both are required by clap when there is no subcommand
+    let (layout_path, binary_path) = match (&opt.layout, &opt.binary) {
+        (Some(layout_path), Some(binary_path)) => (layout_path, binary_path),
+        _ => unreachable!(),
+    };
+
+    let layout = read_layout(layout_path)?;
 
-    // read the layout file, ensure it can be read as JSON
-    let layout_file = File::open(&opt.layout)
-        .with_context(|| format!("Could not open layout file to read: {:?}", opt.layout))?;
-    let mut json_deserializer = serde_json::Deserializer::from_reader(layout_file);
-    // need to use the disable_recursion_limit hack because these can be HUGE!
-    json_deserializer.disable_recursion_limit();
-    let json_deserializer = serde_stacker::Deserializer::new(&mut json_deserializer);
-    let layout = bin_prot::Layout::deserialize(json_deserializer)
-        .context("Failed to deserialize layout JSON")?;
+    if opt.encode {
+        return encode(&opt, binary_path, &layout.bin_prot_rule);
+    }
 
     // Use this layout to read the binary
     // binary file could be either actual binary encoded bin_prot
     // OR utf-8 encoded hex string representation of the binary
-    let binary_file = File::open(&opt.binary)
-        .with_context(|| format!("Could not open binary file to read: {:?}", opt.binary))?;
+    let binary_file = File::open(binary_path)
+        .with_context(|| format!("Could not open binary file to read: {:?}", binary_path))?;
     let mut reader = BufReader::new(binary_file);
     let mut buffer = Vec::new();
     reader.read_to_end(&mut buffer)?;
@@ -78,7 +112,7 @@ fn main() -> Result<()> {
     if opt.roundtrip {
         // check it can be serialized back to binary and the result is the same. Otherwise we have a problem.
         let mut reserialized_bytes = Vec::<u8>::new();
-        bin_prot::to_writer(&mut reserialized_bytes, &result)
+        bin_prot::to_writer_with_layout(&mut reserialized_bytes, &result, &layout.bin_prot_rule)
             .context("Failed to write result back to binary")?;
         assert_eq!(
             bytes, reserialized_bytes,
@@ -86,14 +120,83 @@ fn main() -> Result<()> {
         );
     }
 
+    let output = if opt.json {
+        let json = bin_prot::to_json_with_layout(&result, &layout.bin_prot_rule)
+            .context("Failed to convert result to JSON")?;
+        serde_json::to_string_pretty(&json)?
+    } else {
+        format!("{:#?}", result)
+    };
+
     // pretty print the result (or write to a file)
     if let Some(out_path) = opt.output {
         let mut out_file = File::create(&out_path)
             .with_context(|| format!("Could not create output file: {:?}", &out_path))?;
-        write!(out_file, "{:#?}", result)
+        write!(out_file, "{}", output)
             .with_context(|| format!("Could not write to output file: {:?}", &out_path))?;
     } else {
-        println!("{:#?}", result);
+        println!("{}", output);
+    }
+    Ok(())
+}
+
+fn read_layout(path: &Path) -> Result<bin_prot::Layout> {
+    info!(
+        "Reading layout {:?}, please wait. This may take a minute or two...",
+        path
+    );
+
+    // read the layout file, ensure it can be read as JSON
+    let layout_file = File::open(path)
+        .with_context(|| format!("Could not open layout file to read: {:?}", path))?;
+    let mut json_deserializer = serde_json::Deserializer::from_reader(layout_file);
+    // need to use the disable_recursion_limit hack because these can be HUGE!
+    json_deserializer.disable_recursion_limit();
+    let json_deserializer = serde_stacker::Deserializer::new(&mut json_deserializer);
+    bin_prot::Layout::deserialize(json_deserializer).context("Failed to deserialize layout JSON")
+}
+
+fn diff(old_path: &Path, new_path: &Path) -> Result<()> {
+    let old = read_layout(old_path)?;
+    let new = read_layout(new_path)?;
+
+    let changes = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || bin_prot::value::layout::diff::diff_layouts(&old, &new))?
+        .join()
+        .map_err(|_| anyhow!("Layout diff panicked"))?;
+
+    if changes.is_empty() {
+        info!("The layouts are structurally identical");
+    }
+    for change in changes {
+        println!("{}", change);
+    }
+    Ok(())
+}
+
+fn encode(opt: &Opt, json_path: &Path, rule: &bin_prot::BinProtRule) -> Result<()> {
+    let json_file = File::open(json_path)
+        .with_context(|| format!("Could not open JSON file to read: {:?}", json_path))?;
+    let mut json_deserializer = serde_json::Deserializer::from_reader(BufReader::new(json_file));
+    json_deserializer.disable_recursion_limit();
+    let json_deserializer = serde_stacker::Deserializer::new(&mut json_deserializer);
+    let json = serde_json::Value::deserialize(json_deserializer).context("Failed to read JSON")?;
+
+    let value = bin_prot::from_json_with_layout(&json, rule)
+        .context("JSON does not match the given layout")?;
+    let mut bytes = Vec::<u8>::new();
+    bin_prot::to_writer_with_layout(&mut bytes, &value, rule)
+        .context("Failed to write value to binary")?;
+
+    if let Some(out_path) = &opt.output {
+        let mut out_file = File::create(out_path)
+            .with_context(|| format!("Could not create output file: {:?}", out_path))?;
+        out_file
+            .write_all(&bytes)
+            .with_context(|| format!("Could not write to output file: {:?}", out_path))?;
+    } else {
+        println!("{}", hex::encode(&bytes));
     }
     Ok(())
 }
@@ -13,15 +13,17 @@ pub mod zkapp;
 use crate::{types::*, *};
 
 pub use self::zkapp::{ZkAppOptionHashableWrapper, ZkAppUriOptionHashableWrapper};
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{
+    AutoFrom, FromGraphQLJson, Hashable, ToChunkedROInput, ToGraphQLJson,
+};
 pub use permissions::{AuthRequired, Permissions, PermissionsLegacy};
 pub use timing::Timing;
 pub use token_permissions::TokenPermissions;
 pub use token_symbol::TokenSymbol;
 pub use zkapp::{ZkApp, ZkAppUri};
 
-use mina_crypto::hash::{ChainHash, StateHash};
-use mina_hasher::ROInput;
+use mina_crypto::base58::Base58Encodable;
+use mina_crypto::hash::{ChainHash, ReceiptChainHash, StateHash};
 use mina_serialization_types::account::*;
 use proof_systems::mina_signer::CompressedPubKey;
 
@@ -30,31 +32,45 @@ use proof_systems::mina_signer::CompressedPubKey;
 ///
 /// Accounts can also be Snapps in which case snapp data is required and proofs must
 /// be provided to perform certain actions
-#[derive(Clone, Debug, AutoFrom)]
+///
+/// The [mina_hasher::Hashable] implementation is not complete because we have switched to berkeley net,
+/// remove `skip` from the fields once they have implemented Hashable trait
+/// and add unit tests when it's complete
+#[derive(Clone, Debug, AutoFrom, Hashable)]
 #[auto_from(mina_serialization_types::account::Account)]
+#[roinput(domain = "CodaAccount")]
 pub struct AccountLegacy {
     /// Account public key
+    #[roinput(skip)]
     pub public_key: CompressedPubKey,
     /// Account token ID
     pub token_id: TokenId,
     /// Permission associated with the given token
+    #[roinput(skip)]
     pub token_permissions: TokenPermissions,
     /// Balance of token held by account
     pub balance: Amount,
     /// Nonce (incremented with each tx to prevent replay)
+    #[roinput(skip)]
     pub nonce: AccountNonce,
     /// ?
+    #[roinput(skip)]
     pub receipt_chain_hash: ChainHash,
     /// Delegate for staking purposes
+    #[roinput(skip)]
     pub delegate: Option<CompressedPubKey>,
     /// The state hash this account is voting for
+    #[roinput(skip)]
     pub voting_for: StateHash,
     /// Any timing limitations places on this accounts balance
     /// Used for vesting
+    #[roinput(skip)]
     pub timing: Timing,
     /// Level of permission required to do different account actions
+    #[roinput(skip)]
     pub permissions: PermissionsLegacy,
     /// TODO: This should contain a Snapp account data once we have something to test against
+    #[roinput(skip)]
     pub snapp: Option<()>,
 }
 
@@ -62,51 +78,47 @@ impl BinProtSerializationType<'_> for AccountLegacy {
     type T = AccountV1;
 }
 
-// This implementation is not complete because we have switched to berkeley net
-impl mina_hasher::Hashable for AccountLegacy {
-    type D = ();
-
-    // Uncomment these fields once they have implemented Hashable trait
-    // and add unit tests when it's complete
-    fn to_roinput(&self) -> ROInput {
-        ROInput::new()
-            // .append_hashable(self.public_key)
-            .append_hashable(&self.token_id)
-            // .append_hashable(self.token_permissions)
-            .append_hashable(&self.balance)
-        // .append_hashable(self.nonce)
-        // .append_hashable(self.receipt_chain_hash)
-        // .append_hashable(self.delegate)
-        // .append_hashable(self.voting_for)
-        // .append_hashable(self.timing)
-        // .append_hashable(self.permissions)
-        // .append_hashable(self.snapp)
-    }
-
-    fn domain_string(_: Self::D) -> Option<String> {
-        Some("CodaAccount".into())
-    }
-}
+/// Identifier of an account, the public key and the token ID of the account
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::account::AccountId)]
+pub struct AccountId(pub CompressedPubKey, pub TokenId);
 
 /// TODO
-#[derive(Clone, Debug, AutoFrom)]
+///
+/// Fields are declared in the same order as the OCaml record,
+/// which are hashed in reverse order
+// TODO: FromGraphQLJson has no test coverage yet because there're new hash algo changes again
+// that we are not able to follow anymore.
+// Genesis ledger test data needs to be updated and tests have to be fixed first
+#[derive(Clone, Debug, AutoFrom, Hashable, ToChunkedROInput, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
 #[auto_from(mina_serialization_types::account::AccountV0)]
+#[roinput(chunked, reverse, domain = "CodaAccount")]
 pub struct Account {
     /// Account public key
+    #[roinput(wrapper = "CompressedPubKeyHashableWrapper")]
     pub public_key: CompressedPubKey,
     /// Account token ID
+    // FIXME: wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf
+    #[graphql(name = "token")]
     pub token_id: TokenId,
-    /// Balance of token held by account
-    pub balance: Amount,
     /// Permission associated with the given token
+    #[graphql(flatten, with = "token_permissions_graphql_json")]
     pub token_permissions: TokenPermissions,
     /// Token Symbol
+    // FIXME: figure out what this is
+    #[graphql(skip)]
     pub token_symbol: TokenSymbol,
+    /// Balance of token held by account
+    #[graphql(name = "balance.total")]
+    pub balance: Amount,
     /// Nonce (incremented with each tx to prevent replay)
     pub nonce: AccountNonce,
     /// ?
+    #[graphql(with = "receipt_chain_hash_graphql_json")]
     pub receipt_chain_hash: ChainHash,
     /// Delegate for staking purposes
+    #[roinput(wrapper = "CompressedPubKeyOptionHashableWrapper")]
     pub delegate: Option<CompressedPubKey>,
     /// The state hash this account is voting for
     pub voting_for: StateHash,
@@ -116,91 +128,63 @@ pub struct Account {
     /// Level of permission required to do different account actions
     pub permissions: Permissions,
     /// TODO: This should contain a Snapp account data once we have something to test against
+    // FIXME: struct to be defined
+    #[graphql(skip)]
+    #[roinput(wrapper = "ZkAppOptionHashableWrapper")]
     pub zkapp: Option<ZkApp>,
     /// TODO: This should contain a Snapp account data once we have something to test against
+    // FIXME: struct to be defined
+    #[graphql(skip)]
+    #[roinput(wrapper = "ZkAppUriOptionHashableWrapper")]
     pub zkapp_uri: Option<ZkAppUri>,
 }
 
-impl mina_hasher::Hashable for Account {
-    type D = ();
+mod token_permissions_graphql_json {
+    use super::*;
 
-    fn to_roinput(&self) -> ROInput {
-        self.roinput()
+    pub fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<TokenPermissions> {
+        let is_disabled = json["isDisabled"].as_bool().unwrap_or_default();
+        let is_token_owner = json["isTokenOwner"].as_bool().unwrap_or_default();
+        Ok(if is_token_owner {
+            TokenPermissions::TokenOwned {
+                disable_new_accounts: is_disabled,
+            }
+        } else {
+            TokenPermissions::NotOwned {
+                account_disabled: is_disabled,
+            }
+        })
     }
 
-    fn domain_string(_: Self::D) -> Option<String> {
-        Some("CodaAccount".into())
+    pub fn to_graphql_json(token_permissions: &TokenPermissions) -> serde_json::Value {
+        let (is_token_owner, is_disabled) = match token_permissions {
+            TokenPermissions::TokenOwned {
+                disable_new_accounts,
+            } => (true, *disable_new_accounts),
+            TokenPermissions::NotOwned { account_disabled } => (false, *account_disabled),
+        };
+        serde_json::json!({
+            "isDisabled": is_disabled,
+            "isTokenOwner": is_token_owner,
+        })
     }
 }
 
-impl ToChunkedROInput for Account {
-    fn to_chunked_roinput(&self) -> ChunkedROInput {
-        ChunkedROInput::new()
-            .append_chunked(&ZkAppUriOptionHashableWrapper(&self.zkapp_uri))
-            .append_chunked(&ZkAppOptionHashableWrapper(&self.zkapp))
-            .append_chunked(&self.permissions)
-            .append_chunked(&self.timing)
-            .append_chunked(&self.voting_for)
-            .append_chunked(&CompressedPubKeyOptionHashableWrapper(&self.delegate))
-            .append_chunked(&self.receipt_chain_hash)
-            .append_chunked(&self.nonce)
-            .append_chunked(&self.balance)
-            .append_chunked(&self.token_symbol)
-            .append_chunked(&self.token_permissions)
-            .append_chunked(&self.token_id)
-            .append_chunked(&CompressedPubKeyHashableWrapper(&self.public_key))
+// The graphql API encodes the receipt chain hash as a base58 [ReceiptChainHash]
+mod receipt_chain_hash_graphql_json {
+    use super::*;
+
+    pub fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<ChainHash> {
+        Ok(<[u8; 32]>::from(ReceiptChainHash::from_base58(
+            json.as_str().unwrap_or_default(),
+        )?)
+        .into())
     }
-}
 
-// TODO: No test coverage yet because there're new hash algo changes again
-// that we are not able to follow anymore.
-// Genesis ledger test data needs to be updated and tests have to be fixed first
-impl FromGraphQLJson for Account {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self>
-    where
-        Self: Sized,
-    {
-        let is_disabled = json["isDisabled"].as_bool().unwrap_or_default();
-        let is_token_owner = json["isTokenOwner"].as_bool().unwrap_or_default();
-        Ok(Self {
-            public_key: CompressedPubKey::from_address(
-                json["publicKey"].as_str().unwrap_or_default(),
-            )?,
-            // FIXME: wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf
-            token_id: TokenId(json["token"].as_str().unwrap_or_default().parse()?),
-            balance: Amount(
-                json["balance"]["total"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .parse()?,
-            ),
-            token_permissions: if is_token_owner {
-                TokenPermissions::TokenOwned {
-                    disable_new_accounts: is_disabled,
-                }
-            } else {
-                TokenPermissions::NotOwned {
-                    account_disabled: is_disabled,
-                }
-            },
-            // FIXME: figure out what this is
-            token_symbol: Default::default(),
-            nonce: AccountNonce(json["nonce"].as_str().unwrap_or_default().parse()?),
-            receipt_chain_hash: ChainHash::from_str(
-                json["receiptChainHash"].as_str().unwrap_or_default(),
-            )?,
-            delegate: match json["delegate"].as_str() {
-                Some(s) => Some(CompressedPubKey::from_address(s)?),
-                _ => None,
-            },
-            voting_for: StateHash::from_str(json["votingFor"].as_str().unwrap_or_default())?,
-            timing: Timing::from_graphql_json(&json["timing"])?,
-            permissions: Permissions::from_graphql_json(&json["permissions"])?,
-            // FIXME: struct to be defined
-            zkapp: None,
-            // FIXME: struct to be defined
-            zkapp_uri: None,
-        })
+    pub fn to_graphql_json(hash: &ChainHash) -> serde_json::Value {
+        ReceiptChainHash::from(<[u8; 32]>::from(hash.clone()))
+            .to_base58_string()
+            .into()
     }
 }
 
@@ -3,14 +3,12 @@
 
 //! Account based permissions
 
-use crate::from_graphql_json::FromGraphQLJson;
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{AutoFrom, FromGraphQLJson, ToChunkedROInput, ToGraphQLJson};
 use proof_systems::{bitvec::prelude::BitVec, ChunkedROInput, ToChunkedROInput};
-use std::str::FromStr;
-use strum::EnumString;
+use strum::{Display, EnumString};
 
 /// The level of auth required to perform a particular action with an account
-#[derive(Clone, Debug, EnumString, AutoFrom)]
+#[derive(Clone, Debug, Display, EnumString, AutoFrom)]
 #[auto_from(mina_serialization_types::account::AuthRequired)]
 pub enum AuthRequired {
     /// None required
@@ -63,7 +61,8 @@ pub struct PermissionsLegacy {
 }
 
 /// Permissions associated with the account
-#[derive(Clone, Debug, AutoFrom)]
+#[derive(Clone, Debug, AutoFrom, ToChunkedROInput, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
 #[auto_from(mina_serialization_types::account::Permissions)]
 pub struct Permissions {
     /// Permission required to edit state
@@ -90,61 +89,10 @@ pub struct Permissions {
     pub set_voting_for: AuthRequired,
 }
 
-impl ToChunkedROInput for Permissions {
-    fn to_chunked_roinput(&self) -> ChunkedROInput {
-        ChunkedROInput::new()
-            .append_chunked(&self.edit_state)
-            .append_chunked(&self.send)
-            .append_chunked(&self.receive)
-            .append_chunked(&self.set_delegate)
-            .append_chunked(&self.set_permissions)
-            .append_chunked(&self.set_verification_key)
-            .append_chunked(&self.set_zkapp_uri)
-            .append_chunked(&self.edit_sequence_state)
-            .append_chunked(&self.set_token_symbol)
-            .append_chunked(&self.increment_nonce)
-            .append_chunked(&self.set_voting_for)
-    }
-}
-
-impl FromGraphQLJson for Permissions {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self>
-    where
-        Self: Sized,
-    {
-        Ok(Self {
-            edit_state: AuthRequired::from_str(json["editState"].as_str().unwrap_or_default())?,
-            send: AuthRequired::from_str(json["send"].as_str().unwrap_or_default())?,
-            receive: AuthRequired::from_str(json["receive"].as_str().unwrap_or_default())?,
-            set_delegate: AuthRequired::from_str(json["setDelegate"].as_str().unwrap_or_default())?,
-            set_permissions: AuthRequired::from_str(
-                json["setPermissions"].as_str().unwrap_or_default(),
-            )?,
-            set_verification_key: AuthRequired::from_str(
-                json["setVerificationKey"].as_str().unwrap_or_default(),
-            )?,
-            set_zkapp_uri: AuthRequired::from_str(
-                json["setZkappUri"].as_str().unwrap_or_default(),
-            )?,
-            edit_sequence_state: AuthRequired::from_str(
-                json["editSequenceState"].as_str().unwrap_or_default(),
-            )?,
-            set_token_symbol: AuthRequired::from_str(
-                json["setTokenSymbol"].as_str().unwrap_or_default(),
-            )?,
-            increment_nonce: AuthRequired::from_str(
-                json["editState"].as_str().unwrap_or_default(),
-            )?,
-            set_voting_for: AuthRequired::from_str(
-                json["setVotingFor"].as_str().unwrap_or_default(),
-            )?,
-        })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{from_graphql_json::FromGraphQLJson, to_graphql_json::ToGraphQLJson};
 
     #[test]
     fn permissions_from_graphql_json() -> anyhow::Result<()> {
@@ -164,7 +112,8 @@ mod tests {
           }
         "###;
         let json = serde_json::from_str(JSON_STR)?;
-        _ = Permissions::from_graphql_json(&json)?;
+        let permissions = Permissions::from_graphql_json(&json)?;
+        assert_eq!(permissions.to_graphql_json(), json);
         Ok(())
     }
 }
@@ -4,27 +4,33 @@
 //! Account Timing
 
 use ark_ff::{One, Zero};
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{AutoFrom, FromGraphQLJson, ToChunkedROInput, ToGraphQLJson};
 use proof_systems::{mina_hasher::Fp, ChunkedROInput, ToChunkedROInput};
 use smart_default::SmartDefault;
 
 use crate::{
     from_graphql_json::FromGraphQLJson,
     numbers::{Amount, BlockTime},
+    to_graphql_json::ToGraphQLJson,
 };
 
 /// Payload for the timing variant Timed
-#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[derive(
+    Clone, Debug, Eq, PartialEq, AutoFrom, ToChunkedROInput, FromGraphQLJson, ToGraphQLJson,
+)]
+#[graphql(crate = "crate")]
 #[auto_from(mina_serialization_types::account::TimedData)]
 #[auto_from(mina_serialization_types::account::TimedDataV0)]
 pub struct TimedData {
     /// Initial balance for the account
     pub initial_minimum_balance: Amount,
     /// Time when all balance is avaiable
+    #[roinput(bits = 32)]
     pub cliff_time: BlockTime,
     /// Amount extra available when fully fested
     pub cliff_amount: Amount,
     /// Period in whcih allocation is released in chunks
+    #[roinput(bits = 32)]
     pub vesting_period: BlockTime,
     /// Amount released in each vesting period
     pub vesting_increment: Amount,
@@ -42,42 +48,6 @@ impl Default for TimedData {
     }
 }
 
-impl ToChunkedROInput for TimedData {
-    fn to_chunked_roinput(&self) -> ChunkedROInput {
-        ChunkedROInput::new()
-            .append_chunked(&self.initial_minimum_balance)
-            .append_u32(self.cliff_time.0 as u32)
-            .append_chunked(&self.cliff_amount)
-            .append_u32(self.vesting_period.0 as u32)
-            .append_chunked(&self.vesting_increment)
-    }
-}
-
-impl FromGraphQLJson for TimedData {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self>
-    where
-        Self: Sized,
-    {
-        Ok(Self {
-            initial_minimum_balance: Amount(
-                json["initialMinimumBalance"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .parse()?,
-            ),
-            cliff_time: BlockTime(json["cliffTime"].as_str().unwrap_or_default().parse()?),
-            cliff_amount: Amount(json["cliffAmount"].as_str().unwrap_or_default().parse()?),
-            vesting_period: BlockTime(json["vestingPeriod"].as_str().unwrap_or_default().parse()?),
-            vesting_increment: Amount(
-                json["vestingIncrement"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .parse()?,
-            ),
-        })
-    }
-}
-
 /// Timing information for an account with regard to when its balance is accessable
 /// This is to allow vesting from an initial genesis allocation
 #[derive(Debug, Clone, SmartDefault, AutoFrom)]
@@ -103,6 +73,15 @@ impl FromGraphQLJson for Timing {
     }
 }
 
+impl ToGraphQLJson for Timing {
+    fn to_graphql_json(&self) -> serde_json::Value {
+        match self {
+            Self::Untimed => serde_json::json!({}),
+            Self::Timed(data) => data.to_graphql_json(),
+        }
+    }
+}
+
 impl ToChunkedROInput for Timing {
     fn to_chunked_roinput(&self) -> ChunkedROInput {
         match &self {
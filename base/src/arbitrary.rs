@@ -0,0 +1,56 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! [proptest] strategies for the base types, enabled with the `arbitrary` feature
+//!
+//! Values are generated as their bin-prot serialization types and converted,
+//! so that every generated value can be serialized
+//!
+
+use crate::types::*;
+use mina_serialization_types::{
+    delta_transition_chain_proof,
+    v1::{
+        AccountV1, BlockchainStateV1, ConsensusStateV1, EpochDataV1, ExternalTransitionV1,
+        GlobalSlotV1, ProofStatementV1, ProtocolConstantsV1, ProtocolStateBodyV1,
+        ProtocolStateProofV1, ProtocolStateV1, ProtocolVersionV1, SignedCommandV1,
+        StagedLedgerDiffV1, TransactionSnarkWorkV1, UserCommandWithStatusV1,
+    },
+};
+use proptest::prelude::*;
+
+macro_rules! impl_arbitrary_via_serialization_type {
+    ($($t:ty => $t_versioned:ty),* $(,)?) => {
+        $(
+            impl Arbitrary for $t {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                    any::<$t_versioned>().prop_map(Into::into).boxed()
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_via_serialization_type!(
+    ExternalTransition => ExternalTransitionV1,
+    ProtocolStateLegacy => ProtocolStateV1,
+    ProtocolStateBodyLegacy => ProtocolStateBodyV1,
+    BlockchainStateLegacy => BlockchainStateV1,
+    ConsensusState => ConsensusStateV1,
+    ProtocolConstants => ProtocolConstantsV1,
+    EpochData => EpochDataV1,
+    GlobalSlot => GlobalSlotV1,
+    ProtocolVersion => ProtocolVersionV1,
+    ProtocolStateProof => ProtocolStateProofV1,
+    ProofStatement => ProofStatementV1,
+    DeltaTransitionChainProof => delta_transition_chain_proof::DeltaTransitionChainProof,
+    StagedLedgerDiff => StagedLedgerDiffV1,
+    UserCommandWithStatus => UserCommandWithStatusV1,
+    SignedCommand => SignedCommandV1,
+    TransactionSnarkWork => TransactionSnarkWorkV1,
+    AccountLegacy => AccountV1,
+);
@@ -0,0 +1,56 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Berkeley block, which replaces the external transition
+
+use super::{proof::ProtocolStateProof, staged_ledger_diff::StagedLedgerDiffBody};
+use crate::{
+    delta_transition_chain_proof::DeltaTransitionChainProof, protocol_state::ProtocolState,
+    protocol_version::ProtocolVersion,
+};
+use mina_serialization_types::v2::{BlockV2, HashV2, HeaderV2};
+use mina_serialization_types_macros::AutoFrom;
+
+/// This structure represents a mina block received from an external block producer
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(BlockV2)]
+pub struct Block {
+    /// Header of the block
+    pub header: Header,
+    /// Body of the block
+    pub body: StagedLedgerDiffBody,
+}
+
+impl crate::BinProtSerializationType<'_> for Block {
+    type T = BlockV2;
+}
+
+/// Header of a Berkeley block, the body is committed to by
+/// [BodyReference](crate::blockchain_state::BodyReference) in the blockchain state
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(HeaderV2)]
+pub struct Header {
+    /// The blockchain state, including consensus and the ledger
+    pub protocol_state: ProtocolState,
+    /// Proof that the protocol state and entire history of the chain is valid
+    pub protocol_state_proof: ProtocolStateProof,
+    /// Proof that the block was produced within the allotted slot time
+    #[auto_from(with = "delta_block_chain_proof")]
+    pub delta_block_chain_proof: DeltaTransitionChainProof,
+    /// Current protocol version
+    pub current_protocol_version: ProtocolVersion,
+    /// Proposed protocol version
+    pub proposed_protocol_version_opt: Option<ProtocolVersion>,
+}
+
+mod delta_block_chain_proof {
+    use super::*;
+
+    pub fn into(t: DeltaTransitionChainProof) -> (HashV2, Vec<HashV2>) {
+        (t.0.into(), t.1.into_iter().map(Into::into).collect())
+    }
+
+    pub fn from(t: (HashV2, Vec<HashV2>)) -> DeltaTransitionChainProof {
+        DeltaTransitionChainProof(t.0.into(), t.1.into_iter().map(Into::into).collect())
+    }
+}
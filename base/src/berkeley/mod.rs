@@ -0,0 +1,87 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Berkeley blocks, pickles proofs and zkApp commands, converted from the
+//! [v2](mina_serialization_types::v2) serialization types
+//!
+//! Many of these types share their names with the types of the mainnet protocol,
+//! so they are not re-exported in [types](crate::types) and should be used with their path.
+//!
+
+pub mod block;
+pub use block::*;
+
+pub mod proof;
+pub use proof::*;
+
+pub mod staged_ledger_diff;
+pub use staged_ledger_diff::*;
+
+pub mod zkapp_command;
+pub use zkapp_command::*;
+
+// Conversions of pickles vectors, which are arrays followed by a unit terminator
+pub(crate) mod vector {
+    pub fn into<T: Into<U>, U, const N: usize>(t: [T; N]) -> ([U; N], ()) {
+        (t.map(Into::into), ())
+    }
+
+    pub fn from<T, U: From<T>, const N: usize>(t: ([T; N], ())) -> [U; N] {
+        t.0.map(Into::into)
+    }
+}
+
+// Conversions of pickles vectors of pickles vectors
+pub(crate) mod vector_of_vectors {
+    #[allow(clippy::type_complexity)]
+    pub fn into<T: Into<U>, U, const N: usize, const M: usize>(
+        t: [[T; N]; M],
+    ) -> ([([U; N], ()); M], ()) {
+        (t.map(super::vector::into), ())
+    }
+
+    pub fn from<T, U: From<T>, const N: usize, const M: usize>(
+        t: ([([T; N], ()); M], ()),
+    ) -> [[U; N]; M] {
+        t.0.map(super::vector::from)
+    }
+}
+
+// Conversions of lists of pickles vectors
+pub(crate) mod vec_of_vectors {
+    pub fn into<T: Into<U>, U, const N: usize>(t: Vec<[T; N]>) -> Vec<([U; N], ())> {
+        t.into_iter().map(super::vector::into).collect()
+    }
+
+    pub fn from<T, U: From<T>, const N: usize>(t: Vec<([T; N], ())>) -> Vec<[U; N]> {
+        t.into_iter().map(super::vector::from).collect()
+    }
+}
+
+// Conversions of lists of lists
+pub(crate) mod vec_of_vecs {
+    pub fn into<T: Into<U>, U>(t: Vec<Vec<T>>) -> Vec<Vec<U>> {
+        t.into_iter()
+            .map(|v| v.into_iter().map(Into::into).collect())
+            .collect()
+    }
+
+    pub fn from<T, U: From<T>>(t: Vec<Vec<T>>) -> Vec<Vec<U>> {
+        into(t)
+    }
+}
+
+// Signatures are a plain pair of a field element and a scalar since berkeley
+pub(crate) mod signature {
+    use mina_serialization_types::{signatures::SignatureV1, v2::SignatureV2};
+    use proof_systems::mina_signer::Signature;
+
+    pub fn into(t: Signature) -> SignatureV2 {
+        SignatureV1::from(t).0.t.t
+    }
+
+    pub fn from(t: SignatureV2) -> Signature {
+        SignatureV1(t.into()).into()
+    }
+}
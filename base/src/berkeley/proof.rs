@@ -0,0 +1,248 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Berkeley pickles proof, which wraps a kimchi proof
+
+// Much of this module will be replaced by arkworks and 01-proof-systems types
+// so full documentation will not be included
+#![allow(missing_docs)]
+
+use crate::{
+    numbers::Hex64,
+    protocol_state_proof::{FieldElement, FiniteECPoint, FiniteECPointPair, ShiftedValue},
+};
+use mina_serialization_types::{field_and_curve_elements as ser, v2::*};
+use mina_serialization_types_macros::AutoFrom;
+use smart_default::SmartDefault;
+
+/// Pickles proof with at most 2 previous proofs verified, used for both
+/// the protocol state proof and the ledger proofs
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProtocolStateProofV2)]
+pub struct ProtocolStateProof {
+    pub statement: ProofStatement,
+    pub prev_evals: AllEvals,
+    pub proof: Proof,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ScalarChallengeV2)]
+pub struct ScalarChallenge {
+    #[auto_from(with = "super::vector")]
+    pub inner: [Hex64; 2],
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(BulletproofChallengeV2)]
+pub struct BulletproofChallenge {
+    pub prechallenge: ScalarChallenge,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofStatementV2)]
+pub struct ProofStatement {
+    pub proof_state: ProofState,
+    pub messages_for_next_step_proof: MessagesForNextStepProof,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofStateV2)]
+pub struct ProofState {
+    pub deferred_values: ProofStateDeferredValues,
+    #[auto_from(with = "super::vector")]
+    pub sponge_digest_before_evaluations: [Hex64; 4],
+    pub messages_for_next_wrap_proof: MessagesForNextWrapProof,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofStateDeferredValuesV2)]
+pub struct ProofStateDeferredValues {
+    pub plonk: Plonk,
+    pub combined_inner_product: ShiftedValue,
+    pub b: ShiftedValue,
+    pub xi: ScalarChallenge,
+    #[auto_from(with = "super::vector")]
+    pub bulletproof_challenges: [BulletproofChallenge; 16],
+    pub branch_data: BranchData,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(PlonkV2)]
+pub struct Plonk {
+    pub alpha: ScalarChallenge,
+    #[auto_from(with = "super::vector")]
+    pub beta: [Hex64; 2],
+    #[auto_from(with = "super::vector")]
+    pub gamma: [Hex64; 2],
+    pub zeta: ScalarChallenge,
+    pub joint_combiner: Option<ScalarChallenge>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(BranchDataV2)]
+pub struct BranchData {
+    pub proofs_verified: ProofsVerified,
+    pub domain_log2: u8,
+}
+
+/// Number of previous proofs a pickles proof verifies
+#[derive(Clone, Eq, PartialEq, Debug, SmartDefault, AutoFrom)]
+#[auto_from(ProofsVerifiedV2)]
+pub enum ProofsVerified {
+    #[default]
+    N0,
+    N1,
+    N2,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(MessagesForNextWrapProofV2)]
+pub struct MessagesForNextWrapProof {
+    pub challenge_polynomial_commitment: FiniteECPoint,
+    #[auto_from(with = "super::vector_of_vectors")]
+    pub old_bulletproof_challenges: [[BulletproofChallenge; 15]; 2],
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(MessagesForNextStepProofV2)]
+pub struct MessagesForNextStepProof {
+    pub app_state: (),
+    pub challenge_polynomial_commitments: Vec<FiniteECPoint>,
+    #[auto_from(with = "super::vec_of_vectors")]
+    pub old_bulletproof_challenges: Vec<[BulletproofChallenge; 16]>,
+}
+
+/// Evaluations of a polynomial at zeta and zeta * omega
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct PointEvaluations {
+    pub zeta: Vec<FieldElement>,
+    pub zeta_omega: Vec<FieldElement>,
+}
+
+impl From<PointEvaluationsV2> for PointEvaluations {
+    fn from((zeta, zeta_omega): PointEvaluationsV2) -> Self {
+        Self {
+            zeta: zeta.into_iter().map(Into::into).collect(),
+            zeta_omega: zeta_omega.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<PointEvaluations> for PointEvaluationsV2 {
+    fn from(t: PointEvaluations) -> Self {
+        (
+            t.zeta.into_iter().map(Into::into).collect(),
+            t.zeta_omega.into_iter().map(Into::into).collect(),
+        )
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AllEvalsV2)]
+pub struct AllEvals {
+    pub evals: EvalsWithPublicInput,
+    pub ft_eval1: FieldElement,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(EvalsWithPublicInputV2)]
+pub struct EvalsWithPublicInput {
+    pub public_input: PointEvaluations,
+    pub evals: ProofEvaluations,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofEvaluationsV2)]
+pub struct ProofEvaluations {
+    #[auto_from(with = "super::vector")]
+    pub w: [PointEvaluations; 15],
+    pub z: PointEvaluations,
+    #[auto_from(with = "super::vector")]
+    pub s: [PointEvaluations; 6],
+    pub generic_selector: PointEvaluations,
+    pub poseidon_selector: PointEvaluations,
+    pub lookup: Option<LookupEvaluations>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(LookupEvaluationsV2)]
+pub struct LookupEvaluations {
+    pub sorted: Vec<PointEvaluations>,
+    pub aggreg: PointEvaluations,
+    pub table: PointEvaluations,
+    pub runtime: Option<PointEvaluations>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofV2)]
+pub struct Proof {
+    pub messages: ProofMessages,
+    pub openings: ProofOpenings,
+}
+
+/// Polynomial commitment, split into chunks
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct PolyComm(pub Vec<FiniteECPoint>);
+
+impl From<PolyCommV2> for PolyComm {
+    fn from(t: PolyCommV2) -> Self {
+        Self(t.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<PolyComm> for PolyCommV2 {
+    fn from(t: PolyComm) -> Self {
+        t.0.into_iter().map(Into::into).collect()
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofMessagesV2)]
+pub struct ProofMessages {
+    #[auto_from(with = "super::vector")]
+    pub w_comm: [PolyComm; 15],
+    pub z_comm: PolyComm,
+    pub t_comm: PolyComm,
+    pub lookup: Option<LookupCommitments>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(LookupCommitmentsV2)]
+pub struct LookupCommitments {
+    pub sorted: Vec<PolyComm>,
+    pub aggreg: PolyComm,
+    pub runtime: Option<PolyComm>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ProofOpeningsV2)]
+pub struct ProofOpenings {
+    pub proof: OpeningProof,
+    pub evals: ProofEvaluations,
+    pub ft_eval1: FieldElement,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(OpeningProofV2)]
+pub struct OpeningProof {
+    #[auto_from(with = "lr")]
+    pub lr: Vec<FiniteECPointPair>,
+    pub z_1: FieldElement,
+    pub z_2: FieldElement,
+    pub delta: FiniteECPoint,
+    pub challenge_polynomial_commitment: FiniteECPoint,
+}
+
+mod lr {
+    use super::*;
+
+    pub fn into(t: Vec<FiniteECPointPair>) -> Vec<(ser::FiniteECPoint, ser::FiniteECPoint)> {
+        t.into_iter().map(|p| (p.0.into(), p.1.into())).collect()
+    }
+
+    pub fn from(t: Vec<(ser::FiniteECPoint, ser::FiniteECPoint)>) -> Vec<FiniteECPointPair> {
+        t.into_iter()
+            .map(|(p0, p1)| FiniteECPointPair(p0.into(), p1.into()))
+            .collect()
+    }
+}
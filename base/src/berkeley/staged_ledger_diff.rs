@@ -0,0 +1,254 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Berkeley staged ledger diff, the body of a block
+
+#![allow(missing_docs)] // Don't actually know what many of the types fields are for yet
+
+use super::{proof::ProtocolStateProof, zkapp_command::ZkappCommand};
+use crate::{
+    blockchain_state_registers::{
+        failure_status_tbl_v2, signed_amount_v2, BlockchainStateRegistersLocalState,
+    },
+    numbers::{AccountNonce, Amount, GlobalSlotNumber, SignedAmount},
+    staged_ledger_diff::TransactionFailure,
+    user_commands::{signed_command::StakeDelegation, SignedCommandMemo},
+};
+use mina_crypto::hash::{Field, LedgerHash};
+use mina_serialization_types::v2::*;
+use mina_serialization_types_macros::AutoFrom;
+use proof_systems::mina_signer::{CompressedPubKey, Signature};
+
+/// Body of a Berkeley block
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(StagedLedgerDiffBodyV2)]
+pub struct StagedLedgerDiffBody {
+    pub staged_ledger_diff: StagedLedgerDiff,
+}
+
+/// Diff between the current staged ledger and the proposed next one
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(StagedLedgerDiffV2)]
+pub struct StagedLedgerDiff {
+    #[auto_from(with = "pre_diffs")]
+    pub diff: (StagedLedgerPreDiff, Option<StagedLedgerPreDiff>),
+}
+
+impl StagedLedgerDiff {
+    /// Pre diffs of the staged ledger diff, the second one is only present
+    /// when the transactions of the block do not fit in a single scan state tree
+    pub fn pre_diffs(&self) -> impl Iterator<Item = &StagedLedgerPreDiff> {
+        std::iter::once(&self.diff.0).chain(self.diff.1.as_ref())
+    }
+}
+
+mod pre_diffs {
+    use super::*;
+
+    pub fn into(
+        t: (StagedLedgerPreDiff, Option<StagedLedgerPreDiff>),
+    ) -> (StagedLedgerPreDiffV2, Option<StagedLedgerPreDiffV2>) {
+        (t.0.into(), t.1.map(Into::into))
+    }
+
+    pub fn from(
+        t: (StagedLedgerPreDiffV2, Option<StagedLedgerPreDiffV2>),
+    ) -> (StagedLedgerPreDiff, Option<StagedLedgerPreDiff>) {
+        (t.0.into(), t.1.map(Into::into))
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(StagedLedgerPreDiffV2)]
+pub struct StagedLedgerPreDiff {
+    pub completed_works: Vec<TransactionSnarkWork>,
+    pub commands: Vec<UserCommandWithStatus>,
+    pub coinbase: CoinBase,
+    pub internal_command_statuses: Vec<TransactionStatus>,
+}
+
+/// Coinbase of a pre diff, the second pre diff never has two fee transfers
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(CoinBaseV2)]
+pub enum CoinBase {
+    Zero,
+    One(Option<CoinBaseFeeTransfer>),
+    Two(
+        #[auto_from(with = "coinbase_two")]
+        Option<(CoinBaseFeeTransfer, Option<CoinBaseFeeTransfer>)>,
+    ),
+}
+
+mod coinbase_two {
+    use super::*;
+
+    pub fn into(
+        t: Option<(CoinBaseFeeTransfer, Option<CoinBaseFeeTransfer>)>,
+    ) -> Option<(CoinBaseFeeTransferV2, Option<CoinBaseFeeTransferV2>)> {
+        t.map(|(ft0, ft1)| (ft0.into(), ft1.map(Into::into)))
+    }
+
+    pub fn from(
+        t: Option<(CoinBaseFeeTransferV2, Option<CoinBaseFeeTransferV2>)>,
+    ) -> Option<(CoinBaseFeeTransfer, Option<CoinBaseFeeTransfer>)> {
+        t.map(|(ft0, ft1)| (ft0.into(), ft1.map(Into::into)))
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(CoinBaseFeeTransferV2)]
+pub struct CoinBaseFeeTransfer {
+    pub receiver_pk: CompressedPubKey,
+    pub fee: Amount,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(UserCommandWithStatusV2)]
+pub struct UserCommandWithStatus {
+    pub data: UserCommand,
+    pub status: TransactionStatus,
+}
+
+/// User command, either a signed command or a zkApp command
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(UserCommandV2)]
+pub enum UserCommand {
+    SignedCommand(SignedCommand),
+    ZkappCommand(ZkappCommand),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(TransactionStatusV2)]
+pub enum TransactionStatus {
+    Applied,
+    Failed(#[auto_from(with = "failure_status_tbl_v2")] Vec<Vec<TransactionFailure>>),
+}
+
+/// Payment or stake delegation signed by the fee payer
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(SignedCommandV2)]
+pub struct SignedCommand {
+    pub payload: SignedCommandPayload,
+    pub signer: CompressedPubKey,
+    #[auto_from(with = "super::signature")]
+    pub signature: Signature,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(SignedCommandPayloadV2)]
+pub struct SignedCommandPayload {
+    pub common: SignedCommandPayloadCommon,
+    pub body: SignedCommandPayloadBody,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(SignedCommandPayloadCommonV2)]
+pub struct SignedCommandPayloadCommon {
+    pub fee: Amount,
+    pub fee_payer_pk: CompressedPubKey,
+    pub nonce: AccountNonce,
+    pub valid_until: GlobalSlotNumber,
+    pub memo: SignedCommandMemo,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(SignedCommandPayloadBodyV2)]
+pub enum SignedCommandPayloadBody {
+    Payment(PaymentPayload),
+    StakeDelegation(StakeDelegation),
+}
+
+/// Payment of MINA, tokens other than the default one are transferred by zkApp commands
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(PaymentPayloadV2)]
+pub struct PaymentPayload {
+    pub source_pk: CompressedPubKey,
+    pub receiver_pk: CompressedPubKey,
+    pub amount: Amount,
+}
+
+/// Snark work purchased by the block producer
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(TransactionSnarkWorkV2)]
+pub struct TransactionSnarkWork {
+    pub fee: Amount,
+    pub proofs: OneOrTwo<LedgerProof>,
+    pub prover: CompressedPubKey,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum OneOrTwo<T> {
+    One(Box<T>),
+    Two(Box<T>, Box<T>),
+}
+
+impl<T, U: From<T>> From<OneOrTwoV2<T>> for OneOrTwo<U> {
+    fn from(t: OneOrTwoV2<T>) -> Self {
+        match t {
+            OneOrTwoV2::One(t) => Self::One(Box::new((*t).into())),
+            OneOrTwoV2::Two(t0, t1) => Self::Two(Box::new((*t0).into()), Box::new((*t1).into())),
+        }
+    }
+}
+
+impl<T, U: From<T>> From<OneOrTwo<T>> for OneOrTwoV2<U> {
+    fn from(t: OneOrTwo<T>) -> Self {
+        match t {
+            OneOrTwo::One(t) => Self::One(Box::new((*t).into())),
+            OneOrTwo::Two(t0, t1) => Self::Two(Box::new((*t0).into()), Box::new((*t1).into())),
+        }
+    }
+}
+
+/// Transaction snark proving a ledger transition
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(LedgerProofV2)]
+pub struct LedgerProof {
+    pub statement: Statement,
+    pub proof: ProtocolStateProof,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(StatementV2)]
+pub struct Statement {
+    pub source: Registers,
+    pub target: Registers,
+    #[auto_from(with = "signed_amount_v2")]
+    pub supply_increase: SignedAmount,
+    pub fee_excess: FeeExcess,
+    pub sok_digest: Vec<u8>,
+}
+
+/// Registers of a transaction snark statement
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(RegistersV2)]
+pub struct Registers {
+    pub ledger: LedgerHash,
+    pub pending_coinbase_stack: PendingCoinbaseStack,
+    pub local_state: BlockchainStateRegistersLocalState,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(PendingCoinbaseStackV2)]
+pub struct PendingCoinbaseStack {
+    pub data: Field,
+    pub state: StateStack,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(StateStackV2)]
+pub struct StateStack {
+    pub init: Field,
+    pub curr: Field,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(FeeExcessV2)]
+pub struct FeeExcess {
+    pub fee_token_l: Field,
+    #[auto_from(with = "signed_amount_v2")]
+    pub fee_excess_l: SignedAmount,
+    pub fee_token_r: Field,
+    #[auto_from(with = "signed_amount_v2")]
+    pub fee_excess_r: SignedAmount,
+}
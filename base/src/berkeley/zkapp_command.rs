@@ -0,0 +1,339 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! zkApp commands, the user commands introduced in Berkeley that update
+//! a forest of accounts and may be authorized by proofs
+
+#![allow(missing_docs)] // Don't actually know what many of the types fields are for yet
+
+use super::proof::{ProofsVerified, ProtocolStateProof};
+use crate::{
+    blockchain_state_registers::signed_amount_v2,
+    numbers::{AccountNonce, Amount, BlockTime, GlobalSlotNumber, Length, SignedAmount},
+    protocol_state_proof::FiniteECPoint,
+    user_commands::SignedCommandMemo,
+};
+use mina_crypto::hash::{EpochSeed, Field, LedgerHash, ReceiptChainHash, StateHash};
+use mina_serialization_types::v2::*;
+use mina_serialization_types_macros::AutoFrom;
+use proof_systems::mina_signer::{CompressedPubKey, Signature};
+
+/// A zkApp command, a fee payer along with a forest of account updates
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ZkappCommandV2)]
+pub struct ZkappCommand {
+    pub fee_payer: FeePayer,
+    pub account_updates: Vec<CallForestNode>,
+    pub memo: SignedCommandMemo,
+}
+
+/// Fee payer of a zkApp command, always authorized by a signature
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(FeePayerV2)]
+pub struct FeePayer {
+    pub body: FeePayerBody,
+    #[auto_from(with = "super::signature")]
+    pub authorization: Signature,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(FeePayerBodyV2)]
+pub struct FeePayerBody {
+    pub public_key: CompressedPubKey,
+    pub fee: Amount,
+    pub valid_until: Option<GlobalSlotNumber>,
+    pub nonce: AccountNonce,
+}
+
+/// Node of the call forest, stack hashes are not sent over the wire
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(CallForestNodeV2)]
+pub struct CallForestNode {
+    pub elt: AccountUpdateTree,
+    pub stack_hash: (),
+}
+
+/// An account update along with the account updates it calls
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AccountUpdateTreeV2)]
+pub struct AccountUpdateTree {
+    pub account_update: AccountUpdate,
+    pub account_update_digest: (),
+    pub calls: Vec<CallForestNode>,
+}
+
+/// Update of a single account
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AccountUpdateV2)]
+pub struct AccountUpdate {
+    pub body: AccountUpdateBody,
+    pub authorization: Control,
+}
+
+/// Authorization of an account update
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ControlV2)]
+pub enum Control {
+    Proof(Box<ProtocolStateProof>),
+    Signature(#[auto_from(with = "super::signature")] Signature),
+    NoneGiven,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AccountUpdateBodyV2)]
+pub struct AccountUpdateBody {
+    pub public_key: CompressedPubKey,
+    pub token_id: Field,
+    pub update: Update,
+    #[auto_from(with = "signed_amount_v2")]
+    pub balance_change: SignedAmount,
+    pub increment_nonce: bool,
+    #[auto_from(with = "super::vec_of_vecs")]
+    pub events: Vec<Vec<Field>>,
+    #[auto_from(with = "super::vec_of_vecs")]
+    pub sequence_events: Vec<Vec<Field>>,
+    pub call_data: Field,
+    pub preconditions: Preconditions,
+    pub use_full_commitment: bool,
+    pub caller: CallType,
+    pub authorization_kind: AuthorizationKind,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(CallTypeV2)]
+pub enum CallType {
+    Call,
+    DelegateCall,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AuthorizationKindV2)]
+pub enum AuthorizationKind {
+    NoneGiven,
+    Signature,
+    Proof,
+}
+
+/// Either sets a field of the account to the given value or keeps it unchanged
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SetOrKeep<T> {
+    Set(T),
+    Keep,
+}
+
+impl<T, U: From<T>> From<SetOrKeepV2<T>> for SetOrKeep<U> {
+    fn from(t: SetOrKeepV2<T>) -> Self {
+        match t {
+            SetOrKeepV2::Set(t) => Self::Set(t.into()),
+            SetOrKeepV2::Keep => Self::Keep,
+        }
+    }
+}
+
+impl<T, U: From<T>> From<SetOrKeep<T>> for SetOrKeepV2<U> {
+    fn from(t: SetOrKeep<T>) -> Self {
+        match t {
+            SetOrKeep::Set(t) => Self::Set(t.into()),
+            SetOrKeep::Keep => Self::Keep,
+        }
+    }
+}
+
+/// Either checks a precondition against the given value or ignores it
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum OrIgnore<T> {
+    Check(T),
+    Ignore,
+}
+
+impl<T, U: From<T>> From<OrIgnoreV2<T>> for OrIgnore<U> {
+    fn from(t: OrIgnoreV2<T>) -> Self {
+        match t {
+            OrIgnoreV2::Check(t) => Self::Check(t.into()),
+            OrIgnoreV2::Ignore => Self::Ignore,
+        }
+    }
+}
+
+impl<T, U: From<T>> From<OrIgnore<T>> for OrIgnoreV2<U> {
+    fn from(t: OrIgnore<T>) -> Self {
+        match t {
+            OrIgnore::Check(t) => Self::Check(t.into()),
+            OrIgnore::Ignore => Self::Ignore,
+        }
+    }
+}
+
+/// Closed interval, both bounds are inclusive
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ClosedInterval<T> {
+    pub lower: T,
+    pub upper: T,
+}
+
+impl<T, U: From<T>> From<ClosedIntervalV2<T>> for ClosedInterval<U> {
+    fn from(t: ClosedIntervalV2<T>) -> Self {
+        Self {
+            lower: t.lower.into(),
+            upper: t.upper.into(),
+        }
+    }
+}
+
+impl<T, U: From<T>> From<ClosedInterval<T>> for ClosedIntervalV2<U> {
+    fn from(t: ClosedInterval<T>) -> Self {
+        Self {
+            lower: t.lower.into(),
+            upper: t.upper.into(),
+        }
+    }
+}
+
+/// Precondition that a number lies in a closed interval
+pub type NumericPrecondition<T> = OrIgnore<ClosedInterval<T>>;
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(UpdateV2)]
+pub struct Update {
+    #[auto_from(with = "super::vector")]
+    pub app_state: [SetOrKeep<Field>; 8],
+    pub delegate: SetOrKeep<CompressedPubKey>,
+    pub verification_key: SetOrKeep<VerificationKeyWithHash>,
+    pub permissions: SetOrKeep<Permissions>,
+    pub zkapp_uri: SetOrKeep<Vec<u8>>,
+    pub token_symbol: SetOrKeep<Vec<u8>>,
+    pub timing: SetOrKeep<TimingInfo>,
+    pub voting_for: SetOrKeep<StateHash>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(VerificationKeyWithHashV2)]
+pub struct VerificationKeyWithHash {
+    pub data: VerificationKey,
+    pub hash: Field,
+}
+
+/// Side loaded verification key of a zkApp
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(VerificationKeyV2)]
+pub struct VerificationKey {
+    pub max_proofs_verified: ProofsVerified,
+    pub actual_wrap_domain_size: ProofsVerified,
+    pub wrap_index: VerificationKeyWrapIndex,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(VerificationKeyWrapIndexV2)]
+pub struct VerificationKeyWrapIndex {
+    #[auto_from(with = "super::vector")]
+    pub sigma_comm: [FiniteECPoint; 7],
+    #[auto_from(with = "super::vector")]
+    pub coefficients_comm: [FiniteECPoint; 15],
+    pub generic_comm: FiniteECPoint,
+    pub psm_comm: FiniteECPoint,
+    pub complete_add_comm: FiniteECPoint,
+    pub mul_comm: FiniteECPoint,
+    pub emul_comm: FiniteECPoint,
+    pub endomul_scalar_comm: FiniteECPoint,
+}
+
+/// Authorization required to perform an action on an account,
+/// Berkeley drops the `Both` level of [AuthRequired](crate::account::AuthRequired)
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AuthRequiredV2)]
+pub enum AuthRequired {
+    None,
+    Either,
+    Proof,
+    Signature,
+    Impossible,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(PermissionsV2)]
+pub struct Permissions {
+    pub edit_state: AuthRequired,
+    pub send: AuthRequired,
+    pub receive: AuthRequired,
+    pub set_delegate: AuthRequired,
+    pub set_permissions: AuthRequired,
+    pub set_verification_key: AuthRequired,
+    pub set_zkapp_uri: AuthRequired,
+    pub edit_sequence_state: AuthRequired,
+    pub set_token_symbol: AuthRequired,
+    pub increment_nonce: AuthRequired,
+    pub set_voting_for: AuthRequired,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(TimingInfoV2)]
+pub struct TimingInfo {
+    pub initial_minimum_balance: Amount,
+    pub cliff_time: GlobalSlotNumber,
+    pub cliff_amount: Amount,
+    pub vesting_period: GlobalSlotNumber,
+    pub vesting_increment: Amount,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(PreconditionsV2)]
+pub struct Preconditions {
+    pub network: NetworkPrecondition,
+    pub account: AccountPrecondition,
+}
+
+/// Preconditions on the protocol state of the block including the account update
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(NetworkPreconditionV2)]
+pub struct NetworkPrecondition {
+    pub snarked_ledger_hash: OrIgnore<LedgerHash>,
+    pub timestamp: NumericPrecondition<BlockTime>,
+    pub blockchain_length: NumericPrecondition<Length>,
+    pub min_window_density: NumericPrecondition<Length>,
+    pub last_vrf_output: (),
+    pub total_currency: NumericPrecondition<Amount>,
+    pub global_slot_since_hard_fork: NumericPrecondition<GlobalSlotNumber>,
+    pub global_slot_since_genesis: NumericPrecondition<GlobalSlotNumber>,
+    pub staking_epoch_data: EpochDataPrecondition,
+    pub next_epoch_data: EpochDataPrecondition,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(EpochDataPreconditionV2)]
+pub struct EpochDataPrecondition {
+    pub ledger: EpochLedgerPrecondition,
+    pub seed: OrIgnore<EpochSeed>,
+    pub start_checkpoint: OrIgnore<StateHash>,
+    pub lock_checkpoint: OrIgnore<StateHash>,
+    pub epoch_length: NumericPrecondition<Length>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(EpochLedgerPreconditionV2)]
+pub struct EpochLedgerPrecondition {
+    pub hash: OrIgnore<LedgerHash>,
+    pub total_currency: NumericPrecondition<Amount>,
+}
+
+/// Preconditions on the account being updated
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(AccountPreconditionV2)]
+pub enum AccountPrecondition {
+    Full(Box<ZkappAccountPrecondition>),
+    Nonce(AccountNonce),
+    Accept,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(ZkappAccountPreconditionV2)]
+pub struct ZkappAccountPrecondition {
+    pub balance: NumericPrecondition<Amount>,
+    pub nonce: NumericPrecondition<AccountNonce>,
+    pub receipt_chain_hash: OrIgnore<ReceiptChainHash>,
+    pub delegate: OrIgnore<CompressedPubKey>,
+    #[auto_from(with = "super::vector")]
+    pub state: [OrIgnore<Field>; 8],
+    pub sequence_state: OrIgnore<Field>,
+    pub proved_state: OrIgnore<bool>,
+    pub is_new: OrIgnore<bool>,
+}
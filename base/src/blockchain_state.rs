@@ -3,8 +3,6 @@
 
 //! Types related to the Blockchain State
 
-use std::convert::TryInto;
-
 use crate::{
     blockchain_state_registers::BlockchainStateRegisters,
     numbers::{BlockTime, TokenId},
@@ -12,7 +10,7 @@ use crate::{
 };
 use mina_crypto::hash::*;
 use mina_serialization_types::{json::*, v1::*};
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{AutoFrom, FromGraphQLJson, ToGraphQLJson};
 use proof_systems::{
     mina_hasher::{Hashable, ROInput},
     ChunkedROInput, ToChunkedROInput,
@@ -58,45 +56,74 @@ impl Hashable for BlockchainStateLegacy {
     }
 }
 
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
+#[auto_from(mina_serialization_types::v2::BlockchainStateV2)]
 /// Mina blockchain state struct
 pub struct BlockchainState {
     /// Hash of the proposed next state of the blockchain
+    #[graphql(flatten, with = "staged_ledger_hash_graphql_json")]
     pub staged_ledger_hash: StagedLedgerHash,
     /// Hash of the genesis state
+    // FIXME: missing from graphql API
     pub genesis_ledger_hash: LedgerHash,
     /// Registers
+    #[graphql(flatten, with = "registers_graphql_json")]
     pub registers: BlockchainStateRegisters,
     /// Timestamps for blocks
+    #[graphql(name = "utcDate")]
     pub timestamp: BlockTime,
     /// Body reference
     pub body_reference: BodyReference,
 }
 
-impl FromGraphQLJson for BlockchainState {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
-        Ok(Self {
-            staged_ledger_hash: StagedLedgerHash {
-                non_snark: NonSnarkStagedLedgerHash {
-                    ledger_hash: (&json["stagedLedgerHash"]).try_into()?,
-                    aux_hash: (&json["stagedLedgerAuxHash"]).try_into()?,
-                    pending_coinbase_aux: (&json["stagedLedgerPendingCoinbaseAux"]).try_into()?,
-                },
-                pending_coinbase_hash: (&json["stagedLedgerPendingCoinbaseHash"]).try_into()?,
-            },
-            // FIXME: missing from graphql API
-            genesis_ledger_hash: (&json["genesisLedgerHash"]).try_into()?,
-            registers: BlockchainStateRegisters {
-                ledger: (&json["snarkedLedgerHash"]).try_into()?,
-                pending_coinbase_stack: (),
-                local_state: Default::default(),
+mod staged_ledger_hash_graphql_json {
+    use super::*;
+    use crate::from_graphql_json::field_from_graphql_json;
+
+    pub fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<StagedLedgerHash> {
+        Ok(StagedLedgerHash {
+            non_snark: NonSnarkStagedLedgerHash {
+                ledger_hash: field_from_graphql_json(json, "stagedLedgerHash")?,
+                aux_hash: field_from_graphql_json(json, "stagedLedgerAuxHash")?,
+                pending_coinbase_aux: field_from_graphql_json(
+                    json,
+                    "stagedLedgerPendingCoinbaseAux",
+                )?,
             },
-            timestamp: BlockTime(json["utcDate"].as_str().unwrap_or_default().parse()?),
-            body_reference: BodyReference::from_hex(
-                json["bodyReference"].as_str().unwrap_or_default(),
+            pending_coinbase_hash: field_from_graphql_json(
+                json,
+                "stagedLedgerPendingCoinbaseHash",
             )?,
         })
     }
+
+    pub fn to_graphql_json(hash: &StagedLedgerHash) -> serde_json::Value {
+        serde_json::json!({
+            "stagedLedgerHash": hash.non_snark.ledger_hash.to_graphql_json(),
+            "stagedLedgerAuxHash": hash.non_snark.aux_hash.to_graphql_json(),
+            "stagedLedgerPendingCoinbaseAux": hash.non_snark.pending_coinbase_aux.to_graphql_json(),
+            "stagedLedgerPendingCoinbaseHash": hash.pending_coinbase_hash.to_graphql_json(),
+        })
+    }
+}
+
+// Only the snarked ledger hash of the registers is available from the graphql API
+mod registers_graphql_json {
+    use super::*;
+    use crate::from_graphql_json::field_from_graphql_json;
+
+    pub fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<BlockchainStateRegisters> {
+        Ok(BlockchainStateRegisters {
+            ledger: field_from_graphql_json(json, "snarkedLedgerHash")?,
+            pending_coinbase_stack: (),
+            local_state: Default::default(),
+        })
+    }
+
+    pub fn to_graphql_json(registers: &BlockchainStateRegisters) -> serde_json::Value {
+        serde_json::json!({ "snarkedLedgerHash": registers.ledger.to_graphql_json() })
+    }
 }
 
 impl ToChunkedROInput for BlockchainState {
@@ -110,7 +137,8 @@ impl ToChunkedROInput for BlockchainState {
     }
 }
 
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::v2::BodyReferenceV2)]
 /// Mina block body reference, wrapper of blake2 256-bit hash
 pub struct BodyReference(pub [u8; 32]);
 
@@ -123,6 +151,18 @@ impl BodyReference {
     }
 }
 
+impl FromGraphQLJson for BodyReference {
+    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+        Ok(Self::from_hex(json.as_str().unwrap_or_default())?)
+    }
+}
+
+impl ToGraphQLJson for BodyReference {
+    fn to_graphql_json(&self) -> serde_json::Value {
+        hex::encode(self.0).into()
+    }
+}
+
 impl ToChunkedROInput for BodyReference {
     fn to_chunked_roinput(&self) -> ChunkedROInput {
         ChunkedROInput::new().append_bytes(&self.0)
@@ -150,7 +190,11 @@ mod tests {
         }
         "###;
         let json = serde_json::from_str(JSON_STR)?;
-        _ = BlockchainState::from_graphql_json(&json)?;
+        let blockchain_state = BlockchainState::from_graphql_json(&json)?;
+        assert_eq!(
+            BlockchainState::from_graphql_json(&blockchain_state.to_graphql_json())?,
+            blockchain_state
+        );
         Ok(())
     }
 }
@@ -3,13 +3,19 @@
 
 //! Types related to the Blockchain State Registers
 
-use crate::numbers::*;
+use crate::{numbers::*, staged_ledger_diff::TransactionFailure};
 use mina_crypto::hash::*;
+use mina_serialization_types::{
+    snark_work::SgnType,
+    v2::{LocalStateV2, SignedAmountV2},
+};
+use mina_serialization_types_macros::AutoFrom;
 use proof_systems::*;
 use std::str::FromStr;
 
 /// Mina blockchain state registers
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::v2::BlockchainStateRegistersV2)]
 pub struct BlockchainStateRegisters {
     /// TODO
     pub ledger: LedgerHash,
@@ -29,7 +35,8 @@ impl ToChunkedROInput for BlockchainStateRegisters {
 
 /// Mina blockchain state register local state
 /// It will be retired after <https://github.com/MinaProtocol/mina/pull/11728> is merged
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(LocalStateV2)]
 pub struct BlockchainStateRegistersLocalState {
     /// TODO
     pub stack_frame: Field,
@@ -39,18 +46,21 @@ pub struct BlockchainStateRegistersLocalState {
     pub transaction_commitment: Field,
     /// TODO
     pub full_transaction_commitment: Field,
+    /// Token id, a field element since berkeley
+    pub token_id: Field,
     /// TODO
-    pub token_id: TokenId,
-    /// TODO
+    #[auto_from(with = "signed_amount_v2")]
     pub excess: SignedAmount,
     /// TODO
     pub ledger: LedgerHash,
     /// TODO
     pub success: bool,
     /// TODO
+    #[auto_from(rename = "account_update_index")]
     pub party_index: MinaIndex,
     /// TODO
-    pub failure_status_tbl: Vec<()>,
+    #[auto_from(with = "failure_status_tbl_v2")]
+    pub failure_status_tbl: Vec<Vec<TransactionFailure>>,
 }
 
 impl Default for BlockchainStateRegistersLocalState {
@@ -76,7 +86,11 @@ impl Default for BlockchainStateRegistersLocalState {
                 16,
             )
             .unwrap(),
-            token_id: TokenId(1),
+            token_id: Field::from_str_radix(
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                16,
+            )
+            .unwrap(),
             excess: SignedAmount(0, true),
             ledger: LedgerHash::from_str("jw6bz2wud1N6itRUHZ5ypo3267stk4UgzkiuWtAMPRZo9g4Udyd")
                 .unwrap(),
@@ -87,6 +101,38 @@ impl Default for BlockchainStateRegistersLocalState {
     }
 }
 
+pub(crate) mod signed_amount_v2 {
+    use super::*;
+
+    pub fn into(t: SignedAmount) -> SignedAmountV2 {
+        SignedAmountV2 {
+            magnitude: t.0,
+            sgn: if t.1 { SgnType::Pos } else { SgnType::Neg },
+        }
+    }
+
+    pub fn from(t: SignedAmountV2) -> SignedAmount {
+        SignedAmount(t.magnitude, t.sgn == SgnType::Pos)
+    }
+}
+
+pub(crate) mod failure_status_tbl_v2 {
+    use super::*;
+    use mina_serialization_types::v2::TransactionFailureV2;
+
+    pub fn into(t: Vec<Vec<TransactionFailure>>) -> Vec<Vec<TransactionFailureV2>> {
+        t.into_iter()
+            .map(|failures| failures.into_iter().map(Into::into).collect())
+            .collect()
+    }
+
+    pub fn from(t: Vec<Vec<TransactionFailureV2>>) -> Vec<Vec<TransactionFailure>> {
+        t.into_iter()
+            .map(|failures| failures.into_iter().map(Into::into).collect())
+            .collect()
+    }
+}
+
 impl ToChunkedROInput for BlockchainStateRegistersLocalState {
     fn to_chunked_roinput(&self) -> ChunkedROInput {
         ChunkedROInput::new()
@@ -101,3 +147,75 @@ impl ToChunkedROInput for BlockchainStateRegistersLocalState {
             .append_bool(self.success)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Local state of the berkeley genesis block, whose values are checked against
+    // the ones printed by the OCaml node in consensus/tests/berkeley.rs
+    const GENESIS_LOCAL_STATE_HEX: &str = concat!(
+        "54c0eb2a12c51652afb12dcd84b00f41a29671a6a58d88c1487faab0cf9bf902",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "0000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "01",
+        "00",
+        "00",
+    );
+
+    #[test]
+    fn genesis_local_state_v2_bytes() -> anyhow::Result<()> {
+        let bytes = hex::decode(GENESIS_LOCAL_STATE_HEX)?;
+        let v2: LocalStateV2 = bin_prot::from_reader_strict(bytes.as_slice())?;
+        let local_state = BlockchainStateRegistersLocalState::from(v2);
+        assert_eq!(
+            local_state.to_chunked_roinput(),
+            BlockchainStateRegistersLocalState::default().to_chunked_roinput()
+        );
+
+        let mut output = Vec::new();
+        bin_prot::to_writer(&mut output, &LocalStateV2::from(local_state))?;
+        assert_eq!(output, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn local_state_v2_roundtrip() -> anyhow::Result<()> {
+        // Token ids derived from an account id use all 255 bits of the field
+        let token_id = Field::from_str_radix(
+            "2CBE96E4F4C2B8D0B4B4E1AC3C4F5E2D34F4A9A1C7B4C6E9F2A1B3C4D5E6F708",
+            16,
+        )?;
+        let local_state = BlockchainStateRegistersLocalState {
+            token_id,
+            excess: SignedAmount(42, false),
+            party_index: MinaIndex(3),
+            failure_status_tbl: vec![
+                vec![],
+                vec![
+                    TransactionFailure::Cancelled,
+                    TransactionFailure::AccountAppStatePreconditionUnsatisfied(2),
+                ],
+            ],
+            ..Default::default()
+        };
+        let v2: LocalStateV2 = local_state.clone().into();
+        assert_eq!(v2.excess.sgn, SgnType::Neg);
+        assert_eq!(v2.account_update_index, 3);
+        assert_eq!(v2.failure_status_tbl[1].len(), 2);
+
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, &v2)?;
+        let decoded: LocalStateV2 = bin_prot::from_reader_strict(bytes.as_slice())?;
+        assert_eq!(decoded, v2);
+        assert_eq!(
+            BlockchainStateRegistersLocalState::from(decoded),
+            local_state
+        );
+        Ok(())
+    }
+}
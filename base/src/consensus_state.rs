@@ -14,8 +14,9 @@ use blake2::{
     digest::{Update, VariableOutput},
     Blake2bVar,
 };
+use mina_crypto::base58::{Base58Encodable, Base58Error, Base58Kind};
 use mina_serialization_types::{json::*, v1::*, *};
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{AutoFrom, FromGraphQLJson, ToGraphQLJson};
 use proof_systems::{
     mina_hasher::{Hashable, ROInput},
     ToChunkedROInput,
@@ -58,22 +59,40 @@ impl VrfOutputTruncated {
     /// From base58 str
     /// TODO: Switch to [From] and [std::fmt::Display] traits
     pub fn from_base58_str(s: &str) -> anyhow::Result<Self> {
-        let json_string = serde_json::to_string(s)?;
-        let json: VrfOutputTruncatedBase58Json = serde_json::from_str(&json_string)?;
-        Ok(json.into())
+        Ok(<Self as Base58Encodable>::from_base58(s)?)
     }
 
     /// To base58 string
     /// TODO: Switch to [From] and [std::fmt::Display] traits
     pub fn to_base58_string(&self) -> anyhow::Result<String> {
-        let h: VrfOutputTruncatedBase58Json = self.clone().into();
-        let json_string = serde_json::to_string(&h)?;
-        Ok(serde_json::from_str(&json_string)?)
+        Ok(Base58Encodable::to_base58_string(self))
     }
 }
 
 impl_strconv_via_json!(VrfOutputTruncated, VrfOutputTruncatedBase64Json);
 
+impl Base58Encodable for VrfOutputTruncated {
+    const KIND: Base58Kind = Base58Kind::VrfTruncatedOutput;
+
+    fn to_base58_payload(&self) -> Vec<u8> {
+        // the output is prefixed with its length
+        let mut payload = Vec::with_capacity(self.0.len() + 1);
+        payload.push(self.0.len() as u8);
+        payload.extend_from_slice(&self.0);
+        payload
+    }
+
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error> {
+        match payload.split_first() {
+            Some((&len, output)) if len as usize == output.len() => Ok(Self(output.to_vec())),
+            _ => Err(Base58Error::invalid_payload(
+                Self::KIND,
+                "length prefix does not match the output",
+            )),
+        }
+    }
+}
+
 impl Hashable for VrfOutputTruncated {
     type D = ();
 
@@ -129,24 +148,31 @@ impl ToChunkedROInput for VrfOutputTruncated {
 /// approach where the future stake distribution snapshot is prepared by the current consensus epoch.
 ///
 /// Samasika prepares the past for the future! This future state is stored in the next_epoch_data field.
-#[derive(Clone, Debug, Eq, PartialEq, SmartDefault, AutoFrom)]
+#[derive(Clone, Debug, Eq, PartialEq, SmartDefault, AutoFrom, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
 #[auto_from(mina_serialization_types::consensus_state::ConsensusState)]
+#[auto_from(mina_serialization_types::v2::ConsensusStateV2)]
 pub struct ConsensusState {
     /// Height of block
+    #[graphql(name = "blockHeight")]
     pub blockchain_length: Length,
     /// Epoch number
     pub epoch_count: Length,
     /// Minimum window density oberved on the chain
     pub min_window_density: Length,
     /// Current sliding window of densities
+    // FIXME: Hard coded?
+    #[graphql(default = "default_sub_window_densities")]
     pub sub_window_densities: Vec<Length>,
     /// Additional VRS output from leader (for seeding Random Oracle)
     pub last_vrf_output: VrfOutputTruncated,
     /// Total supply of currency
     pub total_currency: Amount,
     /// Current global slot number relative to the current hard fork
+    #[graphql(name = "slot", with = "curr_global_slot_graphql_json")]
     pub curr_global_slot: GlobalSlot,
     /// Absolute global slot number since genesis
+    #[graphql(name = "slotSinceGenesis")]
     pub global_slot_since_genesis: GlobalSlotNumber,
     /// Epoch data for previous epoch
     pub staking_epoch_data: EpochData,
@@ -162,8 +188,10 @@ pub struct ConsensusState {
     pub block_creator: CompressedPubKey,
     /// Compresed public key of account receiving the block reward
     #[default(CompressedPubKey::from_address("B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg").unwrap())]
+    #[graphql(name = "coinbaseReceiever")]
     pub coinbase_receiver: CompressedPubKey,
     /// true if block_stake_winner has no locked tokens, false otherwise
+    #[graphql(name = "superchargedCoinbase")]
     pub supercharge_coinbase: bool,
 }
 
@@ -176,77 +204,37 @@ impl ConsensusState {
     }
 }
 
-impl FromGraphQLJson for ConsensusState {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
-        Ok(Self {
-            blockchain_length: json["blockHeight"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u32>()?
-                .into(),
-            epoch_count: json["epochCount"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u32>()?
-                .into(),
-            min_window_density: json["minWindowDensity"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u32>()?
-                .into(),
+fn default_sub_window_densities() -> Vec<Length> {
+    vec![
+        1.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+        7.into(),
+    ]
+}
+
+// The graphql API only has the slot number of the current global slot
+mod curr_global_slot_graphql_json {
+    use super::*;
+
+    pub fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<GlobalSlot> {
+        Ok(GlobalSlot {
+            slot_number: GlobalSlotNumber::from_graphql_json(json)?,
             // FIXME: Hard coded?
-            sub_window_densities: vec![
-                1.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-                7.into(),
-            ],
-            last_vrf_output: VrfOutputTruncated::from_base58_str(
-                json["lastVrfOutput"].as_str().unwrap_or_default(),
-            )?,
-            total_currency: json["totalCurrency"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u64>()?
-                .into(),
-            curr_global_slot: GlobalSlot {
-                slot_number: json["slot"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .parse::<u32>()?
-                    .into(),
-                // FIXME: Hard coded?
-                slots_per_epoch: 7140.into(),
-            },
-            global_slot_since_genesis: json["slotSinceGenesis"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u32>()?
-                .into(),
-            staking_epoch_data: EpochData::from_graphql_json(&json["stakingEpochData"])?,
-            next_epoch_data: EpochData::from_graphql_json(&json["nextEpochData"])?,
-            has_ancestor_in_same_checkpoint_window: json["hasAncestorInSameCheckpointWindow"]
-                .as_bool()
-                .unwrap_or_default(),
-            block_stake_winner: CompressedPubKey::from_address(
-                json["blockStakeWinner"].as_str().unwrap_or_default(),
-            )?,
-            block_creator: CompressedPubKey::from_address(
-                json["blockCreator"].as_str().unwrap_or_default(),
-            )?,
-            coinbase_receiver: CompressedPubKey::from_address(
-                json["coinbaseReceiever"].as_str().unwrap_or_default(),
-            )?,
-            supercharge_coinbase: json["superchargedCoinbase"].as_bool().unwrap_or_default(),
+            slots_per_epoch: 7140.into(),
         })
     }
+
+    pub fn to_graphql_json(slot: &GlobalSlot) -> serde_json::Value {
+        slot.slot_number.to_graphql_json()
+    }
 }
 
 impl Hashable for ConsensusState {
@@ -349,7 +337,11 @@ mod tests {
           }
         "###;
         let json = serde_json::from_str(JSON_STR)?;
-        _ = ConsensusState::from_graphql_json(&json)?;
+        let consensus_state = ConsensusState::from_graphql_json(&json)?;
+        assert_eq!(
+            ConsensusState::from_graphql_json(&consensus_state.to_graphql_json())?,
+            consensus_state
+        );
         Ok(())
     }
 
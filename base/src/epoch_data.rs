@@ -3,19 +3,18 @@
 
 //! Types and functions related to the EpochData structure
 
-use crate::{
-    numbers::{Amount, Length},
-    *,
-};
+use crate::numbers::{Amount, Length};
 use mina_crypto::hash::*;
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{AutoFrom, FromGraphQLJson, ToGraphQLJson};
 use proof_systems::{
     mina_hasher::{Hashable, ROInput},
     ChunkedROInput, ToChunkedROInput,
 };
 
-#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
 #[auto_from(mina_serialization_types::epoch_data::EpochLedger)]
+#[auto_from(mina_serialization_types::v2::EpochLedgerV2)]
 /// Epoch Ledger
 pub struct EpochLedger {
     /// A unique identifier of the EpochLedger
@@ -24,19 +23,6 @@ pub struct EpochLedger {
     pub total_currency: Amount,
 }
 
-impl FromGraphQLJson for EpochLedger {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
-        Ok(Self {
-            hash: LedgerHash::from_str(json["hash"].as_str().unwrap_or_default())?,
-            total_currency: json["totalCurrency"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u64>()?
-                .into(),
-        })
-    }
-}
-
 impl Hashable for EpochLedger {
     type D = ();
 
@@ -59,8 +45,10 @@ impl ToChunkedROInput for EpochLedger {
     }
 }
 
-#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
+#[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
 #[auto_from(mina_serialization_types::epoch_data::EpochData)]
+#[auto_from(mina_serialization_types::v2::EpochDataV2)]
 /// Epoch data
 pub struct EpochData {
     /// Epoch Ledger, contains ledger related data for the epoch
@@ -75,26 +63,6 @@ pub struct EpochData {
     pub epoch_length: Length,
 }
 
-impl FromGraphQLJson for EpochData {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
-        Ok(Self {
-            ledger: EpochLedger::from_graphql_json(&json["ledger"])?,
-            seed: EpochSeed::from_str(json["seed"].as_str().unwrap_or_default())?,
-            start_checkpoint: StateHash::from_str(
-                json["startCheckpoint"].as_str().unwrap_or_default(),
-            )?,
-            lock_checkpoint: StateHash::from_str(
-                json["lockCheckpoint"].as_str().unwrap_or_default(),
-            )?,
-            epoch_length: json["epochLength"]
-                .as_str()
-                .unwrap_or_default()
-                .parse::<u32>()?
-                .into(),
-        })
-    }
-}
-
 impl Hashable for EpochData {
     type D = ();
 
@@ -126,6 +94,7 @@ impl ToChunkedROInput for EpochData {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{from_graphql_json::FromGraphQLJson, to_graphql_json::ToGraphQLJson};
 
     #[test]
     fn epoch_data_from_graphql_json() -> anyhow::Result<()> {
@@ -142,7 +111,8 @@ mod tests {
           }
         "###;
         let json = serde_json::from_str(JSON_STR)?;
-        _ = EpochData::from_graphql_json(&json)?;
+        let epoch_data = EpochData::from_graphql_json(&json)?;
+        assert_eq!(epoch_data.to_graphql_json(), json);
         Ok(())
     }
 }
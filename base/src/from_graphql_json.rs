@@ -3,10 +3,246 @@
 
 //! GraphQL API json conversion traits and utilities
 
+use std::str::FromStr;
+
+use mina_crypto::{base58::Base58Encodable, hash::*};
+use proof_systems::mina_signer::CompressedPubKey;
+use thiserror::Error;
+
+use crate::{account::AuthRequired, consensus_state::VrfOutputTruncated, numbers::*};
+
 /// Trait that deserializes a struct from graphql json
+///
+/// Values are parsed strictly, a missing key is `null` which only parses as `None`
 pub trait FromGraphQLJson {
     /// Deserialize from graphql json
     fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self>
     where
         Self: Sized;
 }
+
+/// Error that records the json path of the field that fails to deserialize
+#[derive(Debug, Error)]
+#[error("Failed to deserialize graphql json at `{path}`: {error}")]
+pub struct FromGraphQLJsonError {
+    /// Json path of the field, with keys separated by `.`
+    pub path: String,
+    /// The underlying error
+    pub error: anyhow::Error,
+}
+
+/// Prepends `key` to the json path of the error, if any
+pub fn with_json_path<T>(result: anyhow::Result<T>, key: &str) -> anyhow::Result<T> {
+    result.map_err(|e| match e.downcast::<FromGraphQLJsonError>() {
+        Ok(mut e) => {
+            e.path = format!("{key}.{}", e.path);
+            e.into()
+        }
+        Err(error) => FromGraphQLJsonError {
+            path: key.into(),
+            error,
+        }
+        .into(),
+    })
+}
+
+/// Deserialize the field at `key` of a json object, recording `key` in the json path of the error
+pub fn field_from_graphql_json<T: FromGraphQLJson>(
+    json: &serde_json::Value,
+    key: &str,
+) -> anyhow::Result<T> {
+    with_json_path(T::from_graphql_json(&json[key]), key)
+}
+
+fn as_str(json: &serde_json::Value) -> anyhow::Result<&str> {
+    json.as_str()
+        .ok_or_else(|| anyhow::anyhow!("expected a string, got {json}"))
+}
+
+impl FromGraphQLJson for bool {
+    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+        json.as_bool()
+            .ok_or_else(|| anyhow::anyhow!("expected a boolean, got {json}"))
+    }
+}
+
+impl<T: FromGraphQLJson> FromGraphQLJson for Option<T> {
+    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+        if json.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(T::from_graphql_json(json)?))
+        }
+    }
+}
+
+impl<T: FromGraphQLJson> FromGraphQLJson for Vec<T> {
+    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+        json.as_array()
+            .ok_or_else(|| anyhow::anyhow!("expected an array, got {json}"))?
+            .iter()
+            .enumerate()
+            .map(|(i, v)| with_json_path(T::from_graphql_json(v), &i.to_string()))
+            .collect()
+    }
+}
+
+impl FromGraphQLJson for CompressedPubKey {
+    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+        Ok(CompressedPubKey::from_address(as_str(json)?)?)
+    }
+}
+
+impl FromGraphQLJson for VrfOutputTruncated {
+    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+        Ok(Self::from_base58(as_str(json)?)?)
+    }
+}
+
+// Integers are encoded as json strings by the graphql API
+macro_rules! impl_from_graphql_json_for_integer {
+    ($($t:ty),*) => {
+        $(
+            impl FromGraphQLJson for $t {
+                fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+                    match json {
+                        serde_json::Value::Number(n) => Ok(n.to_string().parse()?),
+                        _ => Ok(as_str(json)?.parse()?),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_graphql_json_for_integer!(u32, u64, i32, i64);
+
+macro_rules! impl_from_graphql_json_via_inner {
+    ($($t:ty => $inner:ty),*) => {
+        $(
+            impl FromGraphQLJson for $t {
+                fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+                    Ok(<$inner>::from_graphql_json(json)?.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_graphql_json_via_inner!(
+    TokenId => u64,
+    Length => u32,
+    Amount => u64,
+    AccountNonce => u32,
+    GlobalSlotNumber => u32,
+    BlockTime => u64
+);
+
+macro_rules! impl_from_graphql_json_via_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl FromGraphQLJson for $t {
+                fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
+                    Ok(<$t>::from_str(as_str(json)?)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_graphql_json_via_from_str!(
+    StateHash,
+    LedgerHash,
+    EpochSeed,
+    AuxHash,
+    PendingCoinbaseAuxHash,
+    CoinBaseHash,
+    AuthRequired
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{account::Account, epoch_data::EpochData, to_graphql_json::ToGraphQLJson};
+
+    #[test]
+    fn account_from_graphql_json() -> anyhow::Result<()> {
+        const JSON_STR: &str = r###"
+        {
+            "publicKey": "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g",
+            "token": "1",
+            "balance": { "total": "720000000000" },
+            "isDisabled": false,
+            "isTokenOwner": false,
+            "nonce": "3",
+            "receiptChainHash": "2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe",
+            "delegate": null,
+            "votingFor": "3NLUmnTBMCeExeWErijZ2GeLnjLtBgsDjN3qM8M8gcJDtk8k89xf",
+            "timing": {},
+            "permissions": {
+                "editSequenceState": "Signature",
+                "editState": "Signature",
+                "incrementNonce": "Either",
+                "receive": "None",
+                "send": "Signature",
+                "setDelegate": "Signature",
+                "setPermissions": "Signature",
+                "setTokenSymbol": "Signature",
+                "setVerificationKey": "Signature",
+                "setVotingFor": "Signature",
+                "setZkappUri": "Signature"
+            }
+        }
+        "###;
+        let json = serde_json::from_str(JSON_STR)?;
+        let account = Account::from_graphql_json(&json)?;
+        assert_eq!(account.token_id, TokenId(1));
+        assert_eq!(account.balance, Amount(720000000000));
+        assert_eq!(account.nonce, AccountNonce(3));
+        assert!(account.delegate.is_none());
+        assert!(matches!(
+            account.permissions.increment_nonce,
+            AuthRequired::Either
+        ));
+        assert_eq!(account.to_graphql_json(), json);
+        Ok(())
+    }
+
+    #[test]
+    fn from_graphql_json_error_path() -> anyhow::Result<()> {
+        const JSON_STR: &str = r###"
+        {
+            "epochLength": "2",
+            "lockCheckpoint": "3NLUmnTBMCeExeWErijZ2GeLnjLtBgsDjN3qM8M8gcJDtk8k89xf",
+            "seed": "2vc1zQHJx2xN72vaR4YDH31KwFSr5WHSEH2dzcfcq8jxBPcGiJJA",
+            "startCheckpoint": "3NK2tkzqqK5spR2sZ7tujjqPksL45M3UUrcA4WhCkeiPtnugyE2x",
+            "ledger": {
+              "hash": "jwNYQU34Jb9FD6ZbKnWRALZqVDKbMrjZBKWFYZwAw8ZPMgv9Ld4",
+              "totalCurrency": "not a number"
+            }
+          }
+        "###;
+        let json = serde_json::from_str(JSON_STR)?;
+        let err = EpochData::from_graphql_json(&json).unwrap_err();
+        let err = err.downcast::<FromGraphQLJsonError>()?;
+        assert_eq!(err.path, "ledger.totalCurrency");
+
+        let json = serde_json::json!(["1", "2", null]);
+        let err = Vec::<Length>::from_graphql_json(&json).unwrap_err();
+        assert_eq!(err.downcast::<FromGraphQLJsonError>()?.path, "2");
+        Ok(())
+    }
+
+    #[test]
+    fn integer_from_graphql_json() -> anyhow::Result<()> {
+        assert_eq!(u64::from_graphql_json(&serde_json::json!("42"))?, 42);
+        assert_eq!(u64::from_graphql_json(&serde_json::json!(42))?, 42);
+        assert!(u32::from_graphql_json(&serde_json::json!("-1")).is_err());
+        assert!(u32::from_graphql_json(&serde_json::Value::Null).is_err());
+        assert_eq!(
+            Option::<u32>::from_graphql_json(&serde_json::Value::Null)?,
+            None
+        );
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@ use proof_systems::{
 
 #[derive(Clone, Default, Eq, PartialEq, Debug, AutoFrom)]
 #[auto_from(mina_serialization_types::global_slot::GlobalSlot)]
+#[auto_from(mina_serialization_types::v2::GlobalSlotV2)]
 /// A global slot
 pub struct GlobalSlot {
     /// The global slot number of a chain or block
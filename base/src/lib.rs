@@ -11,6 +11,9 @@
 #![deny(missing_docs)]
 
 pub mod account;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+pub mod berkeley;
 pub mod blockchain_state;
 pub mod blockchain_state_registers;
 pub mod common;
@@ -22,12 +25,16 @@ pub mod external_transition;
 pub mod from_graphql_json;
 pub mod global_slot;
 pub mod numbers;
+pub mod pending_coinbase;
 pub mod protocol_state;
 pub mod protocol_state_proof;
 pub mod protocol_version;
+pub mod scan_state;
 mod serialization_type_conversions;
 pub mod snark_work;
 pub mod staged_ledger_diff;
+pub mod staged_ledger_hash;
+pub mod to_graphql_json;
 pub mod user_commands;
 pub mod verifiable;
 pub mod verification_key;
@@ -35,6 +42,7 @@ pub mod verification_key;
 /// Import common traits;
 use from_graphql_json::FromGraphQLJson;
 use std::str::FromStr;
+use to_graphql_json::ToGraphQLJson;
 
 /// Import all crates from proof_systems
 use proof_systems::*;
@@ -54,11 +62,14 @@ pub mod types {
     pub use super::external_transition::*;
     pub use super::global_slot::*;
     pub use super::numbers::*;
+    pub use super::pending_coinbase::*;
     pub use super::protocol_state::*;
     pub use super::protocol_state_proof::*;
     pub use super::protocol_version::*;
+    pub use super::scan_state::*;
     pub use super::snark_work::*;
     pub use super::staged_ledger_diff::*;
+    pub use super::staged_ledger_hash::*;
     pub use super::user_commands::*;
     pub use super::verification_key::*;
 }
@@ -0,0 +1,364 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pending coinbase collection of the staged ledger.
+//!
+//! Coinbases and protocol state body hashes of the blocks whose transactions are
+//! not yet snarked are pushed onto stacks, which are stored in the leaves of a fixed
+//! height merkle tree. The root of the tree is the pending coinbase hash of [StagedLedgerHash].
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/mina_base/pending_coinbase.ml>
+
+use crate::{numbers::Amount, snark_work::*};
+use mina_crypto::hash::*;
+use mina_merkle::*;
+use proof_systems::{
+    mina_hasher::{create_legacy, Fp, Hashable, Hasher, ROInput},
+    mina_signer::CompressedPubKey,
+};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// Depth of the pending coinbase merkle tree on mainnet, derived from
+/// `transaction_capacity_log_2` = 7 and `work_delay` = 2
+pub const PENDING_COINBASE_DEPTH: u32 = 5;
+
+/// Merkle tree that stores pending coinbase stacks in its leaves
+pub type PendingCoinbaseMerkleTree = MinaMerkleTree<
+    PendingCoinbase,
+    Fp,
+    MinaPoseidonMerkleHasherLegacy<PendingCoinbase>,
+    MinaPoseidonCoinbaseMerkleMergerLegacy,
+    FixedHeightMode,
+>;
+
+/// Error type for pending coinbase operations
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum PendingCoinbaseError {
+    /// All stacks of the tree are in use
+    #[error("No empty stack is available in the pending coinbase tree of depth {0}")]
+    NoEmptyStack(u32),
+    /// There is no stack to update or remove
+    #[error("No pending coinbase stack has been created")]
+    NoStack,
+}
+
+/// Coinbase data that is pushed onto the coinbase stack
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoinbaseData {
+    /// Receiver of the coinbase
+    pub receiver: CompressedPubKey,
+    /// Coinbase amount
+    pub amount: Amount,
+}
+
+impl Hashable for CoinbaseData {
+    type D = ();
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new()
+            .append_field(self.receiver.x)
+            .append_bool(self.receiver.is_odd)
+            .append_hashable(&self.amount)
+    }
+
+    fn domain_string(_: Self::D) -> Option<String> {
+        None
+    }
+}
+
+// Input of the hash of a coinbase stack after a coinbase is pushed
+#[derive(Clone)]
+struct CoinbaseStackPush<'a>(&'a CoinbaseData, Fp);
+
+impl<'a> Hashable for CoinbaseStackPush<'a> {
+    type D = ();
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new().append_hashable(self.0).append_field(self.1)
+    }
+
+    fn domain_string(_: Self::D) -> Option<String> {
+        Some("CoinbaseStackData".into())
+    }
+}
+
+// Input of the hash of a state stack after a state body hash is pushed
+#[derive(Clone)]
+struct StateStackPush(Fp, Fp);
+
+impl Hashable for StateStackPush {
+    type D = ();
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new().append_field(self.0).append_field(self.1)
+    }
+
+    fn domain_string(_: Self::D) -> Option<String> {
+        Some("CoinbaseStackStaHash".into())
+    }
+}
+
+// Salt of the empty coinbase stack
+#[derive(Clone)]
+struct EmptyCoinbaseStack;
+
+impl Hashable for EmptyCoinbaseStack {
+    type D = ();
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new()
+    }
+
+    fn domain_string(_: Self::D) -> Option<String> {
+        Some("CoinbaseStack".into())
+    }
+}
+
+fn to_fp(hash: &StateHash) -> Fp {
+    hash.try_into().expect("Failed to convert Hash into Fp")
+}
+
+impl StateStack {
+    /// Creates an empty state stack
+    pub fn empty() -> Self {
+        Self {
+            init: Default::default(),
+            curr: Default::default(),
+        }
+    }
+
+    /// Creates a state stack that starts from the given hash
+    pub fn create(init: StateHash) -> Self {
+        Self {
+            init: init.clone(),
+            curr: init,
+        }
+    }
+
+    /// Pushes a protocol state body hash onto the stack
+    pub fn push(&mut self, state_body_hash: &StateBodyHash) {
+        let body_hash: Fp = state_body_hash
+            .try_into()
+            .expect("Failed to convert Hash into Fp");
+        let mut hasher = create_legacy(());
+        let curr = hasher.hash(&StateStackPush(to_fp(&self.curr), body_hash));
+        self.curr = (&curr).into();
+    }
+}
+
+impl PendingCoinbase {
+    /// Creates an empty pending coinbase stack
+    pub fn empty() -> Self {
+        let mut hasher = create_legacy(());
+        let data_stack = hasher.hash(&EmptyCoinbaseStack);
+        Self {
+            data_stack: (&data_stack).into(),
+            state_stack: StateStack::empty(),
+        }
+    }
+
+    /// Creates an empty stack whose state stack continues from
+    /// the current state of this stack
+    pub fn create_with(&self) -> Self {
+        Self {
+            state_stack: StateStack::create(self.state_stack.curr.clone()),
+            ..Self::empty()
+        }
+    }
+
+    /// Pushes a coinbase onto the coinbase stack
+    pub fn push_coinbase(&mut self, coinbase: &CoinbaseData) {
+        let mut hasher = create_legacy(());
+        let data_stack = hasher.hash(&CoinbaseStackPush(coinbase, to_fp(&self.data_stack)));
+        self.data_stack = (&data_stack).into();
+    }
+
+    /// Pushes a protocol state body hash onto the state stack
+    pub fn push_state(&mut self, state_body_hash: &StateBodyHash) {
+        self.state_stack.push(state_body_hash)
+    }
+
+    /// Checks whether the `second` stack can follow the `first` one, i.e. the
+    /// coinbase stacks are the same or `second` is a new stack, and the state stack of
+    /// `second` is the same, newly created, or continues from `first` or `prev`
+    pub fn connected(first: &Self, second: &Self, prev: Option<&Self>) -> bool {
+        let coinbase_stack_connected =
+            first.data_stack == second.data_stack || second.data_stack == Self::empty().data_stack;
+        let state_stack_connected = first.state_stack == second.state_stack
+            || second.state_stack.init == second.state_stack.curr
+            || first.state_stack.curr == second.state_stack.curr
+            || prev
+                .map(|prev| prev.state_stack.curr == second.state_stack.curr)
+                .unwrap_or(true);
+        coinbase_stack_connected && state_stack_connected
+    }
+
+    /// Calculates the hash of the stack, which is the leaf hash
+    /// in the pending coinbase merkle tree
+    pub fn hash_fp(&self) -> Fp {
+        let mut hasher = create_legacy(());
+        hasher.hash(self)
+    }
+}
+
+impl Hashable for PendingCoinbase {
+    type D = ();
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new()
+            .append_field(to_fp(&self.data_stack))
+            .append_field(to_fp(&self.state_stack.init))
+            .append_field(to_fp(&self.state_stack.curr))
+    }
+
+    fn domain_string(_: Self::D) -> Option<String> {
+        Some("CoinbaseStack".into())
+    }
+}
+
+/// Collection of pending coinbase stacks, stored in a fixed height merkle tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingCoinbaseCollection {
+    depth: u32,
+    stacks: Vec<PendingCoinbase>,
+    // Indices of the stacks in use, from the newest to the oldest
+    pos_list: Vec<usize>,
+    // Index of the next new stack
+    new_pos: usize,
+}
+
+impl Default for PendingCoinbaseCollection {
+    fn default() -> Self {
+        Self::new(PENDING_COINBASE_DEPTH)
+    }
+}
+
+impl PendingCoinbaseCollection {
+    /// Creates a collection of empty stacks with the given tree depth
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth,
+            stacks: vec![PendingCoinbase::empty(); 1 << depth],
+            pos_list: Vec::new(),
+            new_pos: 0,
+        }
+    }
+
+    /// Depth of the merkle tree, leaf nodes not counted
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Indices of the stacks in use, from the newest to the oldest
+    pub fn pos_list(&self) -> &[usize] {
+        &self.pos_list
+    }
+
+    /// Index of the stack that is used by the next new stack
+    pub fn new_pos(&self) -> usize {
+        self.new_pos
+    }
+
+    /// All stacks stored in the leaves of the merkle tree
+    pub fn stacks(&self) -> &[PendingCoinbase] {
+        &self.stacks
+    }
+
+    /// Gets the newest stack
+    pub fn latest_stack(&self) -> Option<&PendingCoinbase> {
+        self.pos_list.first().map(|&i| &self.stacks[i])
+    }
+
+    /// Gets the oldest stack, which is the next to be removed
+    /// once its transactions are snarked
+    pub fn oldest_stack(&self) -> Option<&PendingCoinbase> {
+        self.pos_list.last().map(|&i| &self.stacks[i])
+    }
+
+    /// Replaces the newest stack with the given one, or
+    /// stores it as a new stack when `is_new` is true
+    pub fn update_coinbase_stack(
+        &mut self,
+        stack: PendingCoinbase,
+        is_new: bool,
+    ) -> Result<(), PendingCoinbaseError> {
+        if is_new {
+            if self.pos_list.len() == self.stacks.len() {
+                return Err(PendingCoinbaseError::NoEmptyStack(self.depth));
+            }
+            self.stacks[self.new_pos] = stack;
+            self.pos_list.insert(0, self.new_pos);
+            self.new_pos = (self.new_pos + 1) % self.stacks.len();
+        } else {
+            let &pos = self.pos_list.first().ok_or(PendingCoinbaseError::NoStack)?;
+            self.stacks[pos] = stack;
+        }
+        Ok(())
+    }
+
+    /// Removes the oldest stack and resets its leaf to an empty stack
+    pub fn remove_coinbase_stack(&mut self) -> Result<PendingCoinbase, PendingCoinbaseError> {
+        let pos = self.pos_list.pop().ok_or(PendingCoinbaseError::NoStack)?;
+        Ok(std::mem::replace(
+            &mut self.stacks[pos],
+            PendingCoinbase::empty(),
+        ))
+    }
+
+    /// Pushes a coinbase onto the newest stack, or onto a new stack when `is_new` is true
+    pub fn add_coinbase(
+        &mut self,
+        coinbase: &CoinbaseData,
+        is_new: bool,
+    ) -> Result<(), PendingCoinbaseError> {
+        let mut stack = self.stack_to_update(is_new)?;
+        stack.push_coinbase(coinbase);
+        self.update_coinbase_stack(stack, is_new)
+    }
+
+    /// Pushes a protocol state body hash onto the newest stack,
+    /// or onto a new stack when `is_new` is true
+    pub fn add_state(
+        &mut self,
+        state_body_hash: &StateBodyHash,
+        is_new: bool,
+    ) -> Result<(), PendingCoinbaseError> {
+        let mut stack = self.stack_to_update(is_new)?;
+        stack.push_state(state_body_hash);
+        self.update_coinbase_stack(stack, is_new)
+    }
+
+    /// Calculates the merkle root of the pending coinbase tree
+    pub fn merkle_root_fp(&self) -> Fp {
+        let mut tree = PendingCoinbaseMerkleTree::new(self.depth);
+        tree.add_batch(self.stacks.iter().cloned());
+        tree.root()
+            .expect("Pending coinbase merkle tree should be full")
+    }
+
+    /// Calculates the pending coinbase hash that is part of [StagedLedgerHash]
+    pub fn merkle_root(&self) -> CoinBaseHash {
+        (&self.merkle_root_fp()).into()
+    }
+
+    /// Calculates the pending coinbase aux hash that is part of [NonSnarkStagedLedgerHash],
+    /// which is the sha256 digest of the decimal stack indices in use followed by
+    /// the index of the next new stack
+    pub fn hash_extra(&self) -> PendingCoinbaseAuxHash {
+        let mut hasher = Sha256::new();
+        for pos in &self.pos_list {
+            hasher.update(pos.to_string());
+        }
+        hasher.update(self.new_pos.to_string());
+        PendingCoinbaseAuxHash(hasher.finalize().to_vec())
+    }
+
+    fn stack_to_update(&self, is_new: bool) -> Result<PendingCoinbase, PendingCoinbaseError> {
+        match (is_new, self.latest_stack()) {
+            (true, Some(latest)) => Ok(latest.create_with()),
+            (true, None) => Ok(PendingCoinbase::empty()),
+            (false, Some(latest)) => Ok(latest.clone()),
+            (false, None) => Err(PendingCoinbaseError::NoStack),
+        }
+    }
+}
@@ -6,14 +6,15 @@
 use crate::{
     blockchain_state::*,
     consensus_state::ConsensusState,
-    from_graphql_json::FromGraphQLJson,
     global_slot::GlobalSlot,
     numbers::{BlockTime, Length},
     *,
 };
 use mina_crypto::hash::StateHash;
 use mina_serialization_types::{json::*, v1::*};
-use mina_serialization_types_macros::AutoFrom;
+use mina_serialization_types_macros::{
+    AutoFrom, FromGraphQLJson, Hashable, ToChunkedROInput, ToGraphQLJson,
+};
 use proof_systems::{
     mina_hasher::{create_kimchi, create_legacy, Fp, Hashable, Hasher, ROInput},
     *,
@@ -21,17 +22,20 @@ use proof_systems::{
 use versioned::*;
 
 /// Constants that define the consensus parameters
-#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+///
+/// Fields are declared in hashing order
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom, Hashable, ToChunkedROInput)]
 #[auto_from(mina_serialization_types::protocol_constants::ProtocolConstants)]
+#[auto_from(mina_serialization_types::v2::ProtocolConstantsV2)]
 pub struct ProtocolConstants {
     /// Point of finality (number of confirmations)
     pub k: Length,
+    /// Maximum permissable delay of packets (in slots after the current)
+    pub delta: Length,
     /// Number of slots per epoch
     pub slots_per_epoch: Length,
     /// No of slots in a sub-window = 7
     pub slots_per_sub_window: Length,
-    /// Maximum permissable delay of packets (in slots after the current)
-    pub delta: Length,
     /// Timestamp of genesis block in unixtime
     pub genesis_state_timestamp: BlockTime,
 }
@@ -54,34 +58,6 @@ impl Default for ProtocolConstants {
     }
 }
 
-impl Hashable for ProtocolConstants {
-    type D = ();
-
-    fn to_roinput(&self) -> ROInput {
-        ROInput::new()
-            .append_hashable(&self.k)
-            .append_hashable(&self.delta)
-            .append_hashable(&self.slots_per_epoch)
-            .append_hashable(&self.slots_per_sub_window)
-            .append_hashable(&self.genesis_state_timestamp)
-    }
-
-    fn domain_string(_: Self::D) -> Option<String> {
-        None
-    }
-}
-
-impl ToChunkedROInput for ProtocolConstants {
-    fn to_chunked_roinput(&self) -> ChunkedROInput {
-        ChunkedROInput::new()
-            .append_chunked(&self.k)
-            .append_chunked(&self.delta)
-            .append_chunked(&self.slots_per_epoch)
-            .append_chunked(&self.slots_per_sub_window)
-            .append_chunked(&self.genesis_state_timestamp)
-    }
-}
-
 #[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::protocol_state::ProtocolState)]
 /// This structure can be thought of like the block header. It contains the most essential information of a block. (legacy)
@@ -129,18 +105,21 @@ impl ProtocolStateLegacy {
     }
 }
 
-#[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom)]
+#[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom, Hashable)]
 #[auto_from(mina_serialization_types::protocol_state_body::ProtocolStateBody)]
+#[roinput(domain = "CodaProtoStateBody")]
 /// Body of the protocol state (legacy)
+///
+/// Fields are declared in hashing order
 pub struct ProtocolStateBodyLegacy {
+    /// Consensus constants
+    pub constants: ProtocolConstants,
     /// Genesis protocol state hash (used for hardforks)
     pub genesis_state_hash: StateHash,
     /// Ledger related state
     pub blockchain_state: BlockchainStateLegacy,
     /// Consensus related state
     pub consensus_state: ConsensusState,
-    /// Consensus constants
-    pub constants: ProtocolConstants,
 }
 
 impl_from_with_proxy!(
@@ -149,22 +128,6 @@ impl_from_with_proxy!(
     ProtocolStateBodyJson
 );
 
-impl Hashable for ProtocolStateBodyLegacy {
-    type D = ();
-
-    fn to_roinput(&self) -> ROInput {
-        ROInput::new()
-            .append_hashable(&self.constants)
-            .append_hashable(&self.genesis_state_hash)
-            .append_hashable(&self.blockchain_state)
-            .append_hashable(&self.consensus_state)
-    }
-
-    fn domain_string(_: Self::D) -> Option<String> {
-        Some("CodaProtoStateBody".into())
-    }
-}
-
 /// Implementing types have some notion of height and can return it
 pub trait ProtocolStateHeader {
     /// Get the height for the implementing type
@@ -207,62 +170,53 @@ impl ProtocolStateHeader for ProtocolStateLegacy {
     }
 }
 
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[derive(
+    Clone,
+    Default,
+    Debug,
+    Eq,
+    PartialEq,
+    AutoFrom,
+    Hashable,
+    ToChunkedROInput,
+    FromGraphQLJson,
+    ToGraphQLJson,
+)]
+#[graphql(crate = "crate")]
+#[auto_from(mina_serialization_types::v2::ProtocolStateBodyV2)]
+#[roinput(chunked, domain = "CodaProtoStateBody")]
 /// Body of the protocol state
+///
+/// Fields are declared in hashing order
 pub struct ProtocolStateBody {
+    /// Consensus constants
+    // FIXME: Hard coded?
+    #[graphql(skip)]
+    pub constants: ProtocolConstants,
     /// Genesis protocol state hash (used for hardforks)
+    // FIXME: Hard coded?
+    #[graphql(default = "default_genesis_state_hash")]
     pub genesis_state_hash: StateHash,
     /// Ledger related state
     pub blockchain_state: BlockchainState,
     /// Consensus related state
     pub consensus_state: ConsensusState,
-    /// Consensus constants
-    pub constants: ProtocolConstants,
-}
-
-impl FromGraphQLJson for ProtocolStateBody {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
-        Ok(Self {
-            // FIXME: Hard coded?
-            genesis_state_hash: StateHash::from_str(
-                "3NLUmnTBMCeExeWErijZ2GeLnjLtBgsDjN3qM8M8gcJDtk8k89xf",
-            )?,
-            blockchain_state: BlockchainState::from_graphql_json(&json["blockchainState"])?,
-            consensus_state: ConsensusState::from_graphql_json(&json["consensusState"])?,
-            // FIXME: Hard coded?
-            constants: Default::default(),
-        })
-    }
-}
-
-impl Hashable for ProtocolStateBody {
-    type D = ();
-
-    fn to_roinput(&self) -> ROInput {
-        self.roinput()
-    }
-
-    fn domain_string(_: Self::D) -> Option<String> {
-        Some("CodaProtoStateBody".into())
-    }
 }
 
-impl ToChunkedROInput for ProtocolStateBody {
-    fn to_chunked_roinput(&self) -> ChunkedROInput {
-        ChunkedROInput::new()
-            .append_chunked(&self.constants)
-            .append_chunked(&self.genesis_state_hash)
-            .append_chunked(&self.blockchain_state)
-            .append_chunked(&self.consensus_state)
-    }
+fn default_genesis_state_hash() -> StateHash {
+    StateHash::from_str("3NLUmnTBMCeExeWErijZ2GeLnjLtBgsDjN3qM8M8gcJDtk8k89xf")
+        .expect("Failed to parse genesis state hash")
 }
 
-#[derive(Clone, Default, Debug, Eq, PartialEq)]
+#[derive(Clone, Default, Debug, Eq, PartialEq, AutoFrom, FromGraphQLJson, ToGraphQLJson)]
+#[graphql(crate = "crate")]
+#[auto_from(mina_serialization_types::v2::ProtocolStateV2)]
 /// This structure can be thought of like the block header. It contains the most essential information of a block.
 pub struct ProtocolState {
     /// Commitment to previous block (hash of previous protocol state hash and body hash)
     pub previous_state_hash: StateHash,
     /// The body of the protocol state
+    #[graphql(flatten)]
     pub body: ProtocolStateBody,
 }
 
@@ -292,17 +246,6 @@ impl ProtocolStateHeader for ProtocolState {
     }
 }
 
-impl FromGraphQLJson for ProtocolState {
-    fn from_graphql_json(json: &serde_json::Value) -> anyhow::Result<Self> {
-        Ok(Self {
-            previous_state_hash: StateHash::from_str(
-                json["previousStateHash"].as_str().unwrap_or_default(),
-            )?,
-            body: ProtocolStateBody::from_graphql_json(json)?,
-        })
-    }
-}
-
 impl Hashable for ProtocolState {
     type D = ();
 
@@ -325,6 +268,10 @@ impl ToChunkedROInput for ProtocolState {
     }
 }
 
+impl BinProtSerializationType<'_> for ProtocolState {
+    type T = mina_serialization_types::v2::ProtocolStateV2;
+}
+
 impl ProtocolState {
     /// Calculates the state hash field of current protocol state
     pub fn state_hash_fp(&self) -> Fp {
@@ -342,6 +289,7 @@ impl ProtocolState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::from_graphql_json::FromGraphQLJson;
 
     #[test]
     fn protocol_state_from_graphql_json() -> anyhow::Result<()> {
@@ -406,6 +354,8 @@ mod tests {
             ps.state_hash().to_string().as_str(),
             "3NKrvXDzp7gskxqWUmwDJTFeSGA6ohYMjd38uKwDgkg8RH89QcgH"
         );
+        // Ensure the graphql json we serialize round-trips
+        assert_eq!(ProtocolState::from_graphql_json(&ps.to_graphql_json())?, ps);
         Ok(())
     }
 }
@@ -92,6 +92,7 @@ pub struct Plonk {
 
 #[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
 #[auto_from(mina_serialization_types::protocol_state_proof::ShiftedValue)]
+#[auto_from(mina_serialization_types::v2::ShiftedValueV2)]
 pub enum ShiftedValue {
     ShiftedValue(BigInt256),
 }
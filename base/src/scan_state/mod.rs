@@ -0,0 +1,14 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transaction snark scan state, the parallel trees of pending transactions
+//! and completed snark work that the staged ledger maintains
+
+mod parallel_scan;
+pub use parallel_scan::*;
+mod sparse_ledger;
+pub use sparse_ledger::*;
+mod transaction;
+pub use transaction::*;
+mod transaction_logic;
+pub use transaction_logic::*;
@@ -0,0 +1,1040 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parallel scan state, a list of complete binary trees whose leaves are base jobs
+//! and whose non-leaf nodes are merge jobs.
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/parallel_scan/parallel_scan.ml>
+
+use crate::snark_work::SnarkWorkError;
+use mina_crypto::hash::AuxHash;
+use mina_serialization_types::scan_state as serialization;
+use mina_serialization_types_macros::AutoFrom;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use thiserror::Error;
+
+/// Error type for parallel scan state operations
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum ScanStateError {
+    /// More completed works are provided than there are jobs to do
+    #[error("Too many completed works, {available} jobs available, {actual} provided")]
+    TooManyCompletedWorks {
+        /// Number of jobs that can be done
+        available: usize,
+        /// Number of completed works
+        actual: usize,
+    },
+    /// More data is enqueued than a tree can hold
+    #[error("Data count {actual} exceeded maximum {available}")]
+    TooMuchData {
+        /// Number of data that can be enqueued
+        available: usize,
+        /// Number of data provided
+        actual: usize,
+    },
+    /// Not enough completed works are provided to make room for the data
+    #[error("Insufficient completed works, {required} required, {actual} provided")]
+    InsufficientWork {
+        /// Number of completed works required
+        required: usize,
+        /// Number of completed works provided
+        actual: usize,
+    },
+    /// Completed works or data do not fit the jobs of a tree
+    #[error("Completed work does not match the job at level {0}")]
+    UnexpectedJob(usize),
+    /// More trees are added than the scan state can hold
+    #[error("Scan state is full, the maximum number of trees is {0}")]
+    TooManyTrees(usize),
+    /// More than one tree is completed in a single update
+    #[error("Multiple values emitted in a single update")]
+    MultipleValuesEmitted,
+    /// Completed works do not prove the statements of the jobs they have to complete
+    #[error("Invalid completed works: {0}")]
+    InvalidCompletedWorks(#[from] SnarkWorkError),
+    /// The maximum number of base jobs of a snapshot is not a power of two
+    #[error("Max base jobs {0} is not a power of two")]
+    InvalidMaxBaseJobs(u64),
+    /// A tree of a snapshot does not have max base jobs leaves
+    #[error("Tree {0} does not have max base jobs leaves")]
+    InvalidTreeSize(usize),
+}
+
+/// Number of base jobs that can be added and of merge jobs that are waiting
+/// for completed work in a subtree
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::scan_state::Weight)]
+pub struct Weight {
+    /// Number of empty base jobs
+    pub base: u64,
+    /// Number of jobs waiting for completed work
+    pub merge: u64,
+}
+
+impl Weight {
+    fn sum(&self, other: &Self) -> Self {
+        Self {
+            base: self.base + other.base,
+            merge: self.merge + other.merge,
+        }
+    }
+
+    fn to_hashable_string(self) -> String {
+        format!("{}{}", self.base, self.merge)
+    }
+}
+
+// Which part of the weights an update or a reset applies to
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum WeightKind {
+    Base,
+    Merge,
+    Both,
+}
+
+impl WeightKind {
+    fn get(self, w: &Weight) -> usize {
+        match self {
+            Self::Base => w.base as usize,
+            Self::Merge | Self::Both => w.merge as usize,
+        }
+    }
+
+    fn set(self, w: &mut Weight, value: usize) {
+        match self {
+            Self::Base => w.base = value as u64,
+            Self::Merge | Self::Both => w.merge = value as u64,
+        }
+    }
+}
+
+/// Status of a job
+#[derive(Clone, Copy, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::scan_state::JobStatus)]
+pub enum JobStatus {
+    /// The job is waiting for completed work
+    Todo,
+    /// The work of the job has been completed
+    Done,
+}
+
+impl JobStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Todo => "Todo",
+            Self::Done => "Done",
+        }
+    }
+}
+
+/// Job at a leaf of a scan tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BaseJob<B> {
+    /// No data yet
+    Empty,
+    /// Data enqueued at the given sequence number
+    Full {
+        /// Data of the job
+        job: B,
+        /// Sequence number of the update that created the job
+        seq_no: u64,
+        /// Status of the job
+        status: JobStatus,
+    },
+}
+
+/// Job at a non-leaf node of a scan tree
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MergeJob<M> {
+    /// Neither child is completed
+    Empty,
+    /// Only the left child is completed
+    Part(M),
+    /// Both children are completed
+    Full {
+        /// Completed work of the left child
+        left: M,
+        /// Completed work of the right child
+        right: M,
+        /// Sequence number of the update that created the job
+        seq_no: u64,
+        /// Status of the job
+        status: JobStatus,
+    },
+}
+
+/// Job that is waiting for completed work
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AvailableJob<B, M> {
+    /// Work on enqueued data
+    Base(B),
+    /// Work that merges the completed works of two children
+    Merge(M, M),
+}
+
+// New data or a completed work that updates a tree
+#[derive(Clone, Debug)]
+enum Job<B, M> {
+    Base(B),
+    Merge(M),
+}
+
+// Splits the jobs between the left and the right subtrees by their weights
+fn split_jobs<T: Clone>(
+    jobs: &[T],
+    kind: WeightKind,
+    weights: &(Weight, Weight),
+) -> (Vec<T>, Vec<T>) {
+    let left = kind.get(&weights.0).min(jobs.len());
+    let right = kind.get(&weights.1).min(jobs.len() - left);
+    (jobs[..left].to_vec(), jobs[left..left + right].to_vec())
+}
+
+/// Complete binary tree of jobs. Merge jobs are stored in breadth first order,
+/// the root being at index 0 and the children of node `i` at `2i + 1` and `2i + 2`,
+/// each with the weights of its left and right subtrees
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScanTree<B, M> {
+    merge_weights: Vec<(Weight, Weight)>,
+    merge_jobs: Vec<MergeJob<M>>,
+    base_weights: Vec<Weight>,
+    base_jobs: Vec<BaseJob<B>>,
+}
+
+impl<B, M> ScanTree<B, M>
+where
+    B: Clone,
+    M: Clone,
+{
+    /// Creates an empty tree with the given number of leaves
+    pub fn new(max_base_jobs: usize) -> Self {
+        let depth = max_base_jobs.trailing_zeros() as usize;
+        let merge_weights = (0..max_base_jobs - 1)
+            .map(|i| {
+                let w = Weight {
+                    base: 1 << (depth - Self::level_of(i) - 1),
+                    merge: 0,
+                };
+                (w, w)
+            })
+            .collect();
+        Self {
+            merge_weights,
+            merge_jobs: vec![MergeJob::Empty; max_base_jobs - 1],
+            base_weights: vec![Weight { base: 1, merge: 0 }; max_base_jobs],
+            base_jobs: vec![BaseJob::Empty; max_base_jobs],
+        }
+    }
+
+    /// Merge jobs in breadth first order
+    pub fn merge_jobs(&self) -> &[MergeJob<M>] {
+        &self.merge_jobs
+    }
+
+    /// Weights of the left and right subtrees of merge jobs, in breadth first order
+    pub fn merge_weights(&self) -> &[(Weight, Weight)] {
+        &self.merge_weights
+    }
+
+    /// Base jobs from left to right
+    pub fn base_jobs(&self) -> &[BaseJob<B>] {
+        &self.base_jobs
+    }
+
+    /// Weights of base jobs from left to right
+    pub fn base_weights(&self) -> &[Weight] {
+        &self.base_weights
+    }
+
+    /// Number of levels of merge jobs, the level of the base jobs
+    pub fn depth(&self) -> usize {
+        self.base_jobs.len().trailing_zeros() as usize
+    }
+
+    /// Number of base jobs that can still be added
+    pub fn available_space(&self) -> usize {
+        self.root_weight().base as usize
+    }
+
+    /// Number of completed works the tree is waiting for
+    pub fn required_job_count(&self) -> usize {
+        self.root_weight().merge as usize
+    }
+
+    /// Data of all base jobs from left to right
+    pub fn base_data(&self) -> Vec<B> {
+        self.base_jobs
+            .iter()
+            .filter_map(|j| match j {
+                BaseJob::Full { job, .. } => Some(job.clone()),
+                BaseJob::Empty => None,
+            })
+            .collect()
+    }
+
+    /// Jobs on the given level that are waiting for completed work, from left to right,
+    /// the base jobs are on the level of the depth of the tree
+    pub fn jobs_on_level(&self, level: usize) -> Vec<AvailableJob<B, M>> {
+        if level == self.depth() {
+            return self
+                .base_jobs
+                .iter()
+                .filter_map(|j| match j {
+                    BaseJob::Full {
+                        job,
+                        status: JobStatus::Todo,
+                        ..
+                    } => Some(AvailableJob::Base(job.clone())),
+                    _ => None,
+                })
+                .collect();
+        }
+        let range = (1 << level) - 1..(1 << (level + 1)) - 1;
+        self.merge_jobs
+            .get(range)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|j| match j {
+                MergeJob::Full {
+                    left,
+                    right,
+                    status: JobStatus::Todo,
+                    ..
+                } => Some(AvailableJob::Merge(left.clone(), right.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn level_of(index: usize) -> usize {
+        (usize::BITS - 1 - (index + 1).leading_zeros()) as usize
+    }
+
+    fn root_weight(&self) -> Weight {
+        match self.merge_weights.first() {
+            Some((l, r)) => l.sum(r),
+            None => self.base_weights[0],
+        }
+    }
+
+    // Sum of the weights of the subtree at the given node, nodes are numbered
+    // in breadth first order with the base jobs following the merge jobs
+    fn subtree_weight(&self, node: usize) -> Weight {
+        match self.merge_weights.get(node) {
+            Some((l, r)) => l.sum(r),
+            None => self.base_weights[node - self.merge_weights.len()],
+        }
+    }
+
+    // Adds the jobs to the nodes on the update level, jobs are distributed
+    // from the root to the subtrees by the weights of the given kind.
+    // Returns the completed work of the root when it is updated
+    fn update(
+        &mut self,
+        jobs: Vec<Job<B, M>>,
+        update_level: usize,
+        seq_no: u64,
+        kind: WeightKind,
+    ) -> Result<Option<M>, ScanStateError> {
+        self.update_node(0, 0, jobs, update_level, seq_no, kind)
+    }
+
+    fn update_node(
+        &mut self,
+        node: usize,
+        level: usize,
+        jobs: Vec<Job<B, M>>,
+        update_level: usize,
+        seq_no: u64,
+        kind: WeightKind,
+    ) -> Result<Option<M>, ScanStateError> {
+        if node >= self.merge_jobs.len() {
+            self.add_base(node - self.merge_jobs.len(), level, jobs, seq_no)?;
+            return Ok(None);
+        }
+        let weights = self.merge_weights[node];
+        let (left_jobs, right_jobs) = split_jobs(&jobs, kind, &weights);
+        let scan_result = self.add_merge(node, level, jobs, update_level, seq_no, kind)?;
+        if level < update_level {
+            self.update_node(
+                2 * node + 1,
+                level + 1,
+                left_jobs,
+                update_level,
+                seq_no,
+                kind,
+            )?;
+            self.update_node(
+                2 * node + 2,
+                level + 1,
+                right_jobs,
+                update_level,
+                seq_no,
+                kind,
+            )?;
+        }
+        Ok(scan_result)
+    }
+
+    fn add_merge(
+        &mut self,
+        node: usize,
+        level: usize,
+        jobs: Vec<Job<B, M>>,
+        update_level: usize,
+        seq_no: u64,
+        kind: WeightKind,
+    ) -> Result<Option<M>, ScanStateError> {
+        if jobs.is_empty() {
+            return Ok(None);
+        }
+        let (left, right) = &mut self.merge_weights[node];
+        let job = &mut self.merge_jobs[node];
+        if level + 1 == update_level {
+            // Creates new jobs from the completed works of the children
+            let mut jobs = jobs.into_iter();
+            *job = match (
+                jobs.next(),
+                jobs.next(),
+                jobs.next(),
+                std::mem::replace(job, MergeJob::Empty),
+            ) {
+                (Some(Job::Merge(a)), Some(Job::Merge(b)), None, MergeJob::Empty)
+                | (Some(Job::Merge(b)), None, None, MergeJob::Part(a)) => MergeJob::Full {
+                    left: a,
+                    right: b,
+                    seq_no,
+                    status: JobStatus::Todo,
+                },
+                (Some(Job::Merge(a)), None, None, MergeJob::Empty) => MergeJob::Part(a),
+                (Some(Job::Base(_)), None, None, MergeJob::Empty) => {
+                    // Depending on whether this is the first or the second of the two base jobs
+                    if left.base == 0 {
+                        right.base = right.base.saturating_sub(1);
+                    } else {
+                        left.base -= 1;
+                    }
+                    MergeJob::Empty
+                }
+                (Some(Job::Base(_)), Some(Job::Base(_)), None, MergeJob::Empty) => {
+                    left.base = left.base.saturating_sub(1);
+                    right.base = right.base.saturating_sub(1);
+                    MergeJob::Empty
+                }
+                _ => return Err(ScanStateError::UnexpectedJob(level)),
+            };
+            Ok(None)
+        } else if level == update_level {
+            // Marks the job as done
+            let mut jobs = jobs.into_iter();
+            match (jobs.next(), jobs.next(), job) {
+                (
+                    Some(Job::Merge(a)),
+                    None,
+                    MergeJob::Full {
+                        status: status @ JobStatus::Todo,
+                        ..
+                    },
+                ) => {
+                    *status = JobStatus::Done;
+                    if level == 0 {
+                        *left = Weight::default();
+                        *right = Weight::default();
+                        Ok(Some(a))
+                    } else {
+                        Ok(None)
+                    }
+                }
+                _ => Err(ScanStateError::UnexpectedJob(level)),
+            }
+        } else {
+            // Updates the job count of the levels above
+            let sent_left = kind.get(left).min(jobs.len());
+            let sent_right = kind.get(right).min(jobs.len() - sent_left);
+            let (l, r) = (kind.get(left), kind.get(right));
+            kind.set(left, l - sent_left);
+            kind.set(right, r - sent_right);
+            Ok(None)
+        }
+    }
+
+    fn add_base(
+        &mut self,
+        index: usize,
+        level: usize,
+        jobs: Vec<Job<B, M>>,
+        seq_no: u64,
+    ) -> Result<(), ScanStateError> {
+        let weight = &mut self.base_weights[index];
+        let job = &mut self.base_jobs[index];
+        let mut jobs = jobs.into_iter();
+        match (jobs.next(), jobs.next(), job) {
+            (None, _, _) => {}
+            (Some(Job::Base(d)), None, job @ BaseJob::Empty) => {
+                weight.base = weight.base.saturating_sub(1);
+                *job = BaseJob::Full {
+                    job: d,
+                    seq_no,
+                    status: JobStatus::Todo,
+                };
+            }
+            (Some(Job::Merge(_)), None, BaseJob::Full { status, .. }) => {
+                weight.merge = weight.merge.saturating_sub(1);
+                *status = JobStatus::Done;
+            }
+            _ => return Err(ScanStateError::UnexpectedJob(level)),
+        }
+        Ok(())
+    }
+
+    // Recomputes the weights from the jobs, from the leaves to the root
+    fn reset_weights(&mut self, kind: WeightKind) {
+        for (weight, job) in self.base_weights.iter_mut().zip(&self.base_jobs) {
+            if kind != WeightKind::Base {
+                weight.merge = matches!(
+                    job,
+                    BaseJob::Full {
+                        status: JobStatus::Todo,
+                        ..
+                    }
+                ) as u64;
+            }
+            if kind != WeightKind::Merge {
+                weight.base = matches!(job, BaseJob::Empty) as u64;
+            }
+        }
+        for node in (0..self.merge_jobs.len()).rev() {
+            let l = self.subtree_weight(2 * node + 1);
+            let r = self.subtree_weight(2 * node + 2);
+            let (left, right) = &mut self.merge_weights[node];
+            if kind != WeightKind::Base {
+                // Only the jobs waiting for completed work are counted
+                if let MergeJob::Full {
+                    status: JobStatus::Todo,
+                    ..
+                } = self.merge_jobs[node]
+                {
+                    left.merge = 1;
+                    right.merge = 0;
+                } else {
+                    left.merge = l.merge;
+                    right.merge = r.merge;
+                }
+            }
+            if kind != WeightKind::Merge {
+                left.base = l.base;
+                right.base = r.base;
+            }
+        }
+    }
+
+    fn hash(
+        &self,
+        hasher: &mut Sha256,
+        f_merge: &impl Fn(&M) -> Vec<u8>,
+        f_base: &impl Fn(&B) -> Vec<u8>,
+    ) {
+        for ((l, r), job) in self.merge_weights.iter().zip(&self.merge_jobs) {
+            let w = l.to_hashable_string() + &r.to_hashable_string();
+            match job {
+                // Completed merge jobs are hashed as empty ones to keep the hashed data small
+                MergeJob::Empty
+                | MergeJob::Full {
+                    status: JobStatus::Done,
+                    ..
+                } => hasher.update(w + "Empty"),
+                MergeJob::Full {
+                    left,
+                    right,
+                    seq_no,
+                    status,
+                } => {
+                    hasher.update(format!("{w}Full{seq_no}{}", status.as_str()));
+                    hasher.update(f_merge(left));
+                    hasher.update(f_merge(right));
+                }
+                MergeJob::Part(m) => {
+                    hasher.update(w + "Part");
+                    hasher.update(f_merge(m));
+                }
+            }
+        }
+        for (weight, job) in self.base_weights.iter().zip(&self.base_jobs) {
+            let w = weight.to_hashable_string();
+            match job {
+                BaseJob::Empty => hasher.update(w + "Empty"),
+                BaseJob::Full {
+                    job,
+                    seq_no,
+                    status,
+                } => {
+                    hasher.update(format!("{w}Full{seq_no}{}", status.as_str()));
+                    hasher.update(f_base(job));
+                }
+            }
+        }
+    }
+}
+
+/// Parallel scan state, trees are ordered from the newest to the oldest,
+/// new data are added to the newest tree and values are emitted from the oldest
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParallelScan<B, M> {
+    trees: VecDeque<ScanTree<B, M>>,
+    acc: Option<(M, Vec<B>)>,
+    curr_job_seq_no: u64,
+    max_base_jobs: usize,
+    delay: u32,
+}
+
+impl<B, M> ParallelScan<B, M>
+where
+    B: Clone,
+    M: Clone,
+{
+    /// Creates an empty scan state with trees of `2^transaction_capacity_log_2` leaves
+    pub fn new(transaction_capacity_log_2: u32, work_delay: u32) -> Self {
+        let max_base_jobs = 1 << transaction_capacity_log_2;
+        Self {
+            trees: VecDeque::from([ScanTree::new(max_base_jobs)]),
+            acc: None,
+            curr_job_seq_no: 0,
+            max_base_jobs,
+            delay: work_delay,
+        }
+    }
+
+    /// Trees from the newest to the oldest
+    pub fn trees(&self) -> impl Iterator<Item = &ScanTree<B, M>> {
+        self.trees.iter()
+    }
+
+    /// The last value emitted by a completed tree along with the data of its base jobs
+    pub fn last_emitted_value(&self) -> Option<&(M, Vec<B>)> {
+        self.acc.as_ref()
+    }
+
+    /// Sequence number of the latest update
+    pub fn curr_job_seq_no(&self) -> u64 {
+        self.curr_job_seq_no
+    }
+
+    /// Number of leaves of each tree
+    pub fn max_base_jobs(&self) -> usize {
+        self.max_base_jobs
+    }
+
+    /// Number of blocks a job waits before its work is required
+    pub fn delay(&self) -> u32 {
+        self.delay
+    }
+
+    /// Maximum number of trees the scan state holds
+    pub fn max_trees(&self) -> usize {
+        (self.depth() + 1) * (self.delay as usize + 1) + 1
+    }
+
+    /// Number of data that can be added to the newest tree
+    pub fn free_space(&self) -> usize {
+        self.trees[0].available_space()
+    }
+
+    /// All jobs that are waiting for completed work, from the oldest tree to the newest
+    /// and from the root of each tree to its leaves
+    pub fn jobs(&self) -> Vec<AvailableJob<B, M>> {
+        self.trees
+            .iter()
+            .rev()
+            .flat_map(|tree| (0..=tree.depth()).flat_map(|level| tree.jobs_on_level(level)))
+            .collect()
+    }
+
+    /// Jobs whose completed work is required to add `data_count` new data, in the order
+    /// the completed works are expected. The first list holds the jobs that the newest tree
+    /// depends on, the second one the jobs that a new tree depends on when the data
+    /// do not fit in the newest tree
+    pub fn work_for_next_update(&self, data_count: usize) -> Vec<Vec<AvailableJob<B, M>>> {
+        let current_tree_space = self.free_space();
+        let set1 = self.work(self.trees.iter().skip(1));
+        let count = data_count.min(self.max_base_jobs);
+        if current_tree_space < count {
+            let mut set2 = self.work(self.trees.iter());
+            set2.truncate((count - current_tree_space) * 2);
+            [set1, set2].into_iter().filter(|s| !s.is_empty()).collect()
+        } else {
+            let mut set1 = set1;
+            set1.truncate(2 * count);
+            if set1.is_empty() {
+                vec![]
+            } else {
+                vec![set1]
+            }
+        }
+    }
+
+    /// Adds the completed works and the new data of a block, both or neither are applied.
+    /// Completed works are applied to the trees that the current tree depends on before
+    /// the data are added, then to the trees of the next tree if the data do not fit in
+    /// the current tree. Returns the value emitted by a completed tree if any
+    pub fn update(
+        &mut self,
+        data: Vec<B>,
+        works: Vec<M>,
+    ) -> Result<Option<(M, Vec<B>)>, ScanStateError> {
+        let data_count = data.len();
+        if data_count > self.max_base_jobs {
+            return Err(ScanStateError::TooMuchData {
+                available: self.max_base_jobs,
+                actual: data_count,
+            });
+        }
+        let required_jobs: usize = self
+            .work_for_next_update(data_count)
+            .iter()
+            .map(Vec::len)
+            .sum();
+        // Each pair of completed works frees up a slot for new data
+        let required = (required_jobs + 1) / 2;
+        let got = (works.len() + 1) / 2;
+        if got < required && data_count + required > self.max_base_jobs + got {
+            return Err(ScanStateError::InsufficientWork {
+                required: required_jobs,
+                actual: works.len(),
+            });
+        }
+
+        let mut state = self.clone();
+        state.curr_job_seq_no += 1;
+        let mut data = data;
+        let data2 = data.split_off(state.free_space().min(data.len()));
+        let mut works = works;
+        let works2 = works.split_off(state.work_for_tree(true).len().min(works.len()));
+        let res1 = state.add_merge_jobs(works)?;
+        state.add_data(data)?;
+        let res2 = state.add_merge_jobs(works2)?;
+        state.add_data(data2)?;
+        let result = match (res1, res2) {
+            (Some(_), Some(_)) => return Err(ScanStateError::MultipleValuesEmitted),
+            (res1, res2) => res1.or(res2),
+        };
+        if state.trees.len() > state.max_trees() {
+            return Err(ScanStateError::TooManyTrees(state.max_trees()));
+        }
+        if result.is_some() {
+            state.acc = result.clone();
+        }
+        *self = state;
+        Ok(result)
+    }
+
+    /// Calculates the sha256 digest of the scan state, with the given functions
+    /// that serialize merge and base values
+    pub fn hash(&self, f_merge: impl Fn(&M) -> Vec<u8>, f_base: impl Fn(&B) -> Vec<u8>) -> AuxHash {
+        let mut hasher = Sha256::new();
+        for tree in &self.trees {
+            tree.hash(&mut hasher, &f_merge, &f_base);
+        }
+        match &self.acc {
+            Some((m, data)) => {
+                hasher.update(f_merge(m));
+                for d in data {
+                    hasher.update(f_base(d));
+                }
+            }
+            None => hasher.update("None"),
+        }
+        hasher.update(self.curr_job_seq_no.to_string());
+        hasher.update(self.max_base_jobs.to_string());
+        hasher.update(self.delay.to_string());
+        AuxHash(hasher.finalize().to_vec())
+    }
+
+    fn depth(&self) -> usize {
+        self.max_base_jobs.trailing_zeros() as usize
+    }
+
+    // Jobs of every `delay + 1`-th tree, the first of them at the leaves
+    // and each following one a level closer to the root
+    fn work<'a>(&self, trees: impl Iterator<Item = &'a ScanTree<B, M>>) -> Vec<AvailableJob<B, M>>
+    where
+        B: 'a,
+        M: 'a,
+    {
+        let delay = self.delay as usize + 1;
+        let depth = self.depth();
+        trees
+            .enumerate()
+            .filter(|(i, _)| i % delay == delay - 1)
+            .take(depth + 1)
+            .enumerate()
+            .flat_map(|(k, (_, tree))| tree.jobs_on_level(depth - k))
+            .collect()
+    }
+
+    // Jobs the current tree depends on, or the next tree if a new tree is added
+    fn work_for_tree(&self, current: bool) -> Vec<AvailableJob<B, M>> {
+        if current {
+            self.work(self.trees.iter().skip(1))
+        } else {
+            self.work(self.trees.iter())
+        }
+    }
+
+    fn add_merge_jobs(&mut self, works: Vec<M>) -> Result<Option<(M, Vec<B>)>, ScanStateError> {
+        if works.is_empty() {
+            return Ok(None);
+        }
+        let delay = self.delay as usize + 1;
+        let depth = self.depth();
+        let jobs_required = self.work_for_tree(true).len();
+        if works.len() > jobs_required {
+            return Err(ScanStateError::TooManyCompletedWorks {
+                available: jobs_required,
+                actual: works.len(),
+            });
+        }
+        let completed_count = works.len();
+        let mut jobs: Vec<_> = works.into_iter().map(Job::Merge).collect();
+        let mut scan_result = None;
+        for (i, tree) in self.trees.iter_mut().skip(1).enumerate() {
+            if i % delay != delay - 1 {
+                continue;
+            }
+            let update_level = match depth.checked_sub(i / delay) {
+                Some(level) => level,
+                None => break,
+            };
+            // Each tree takes the completed works of the jobs `work` lists for it
+            let rest = jobs.split_off(tree.jobs_on_level(update_level).len().min(jobs.len()));
+            scan_result =
+                tree.update(jobs, update_level, self.curr_job_seq_no, WeightKind::Merge)?;
+            jobs = rest;
+        }
+        let result = match scan_result {
+            Some(res) => self.trees.pop_back().map(|tree| (res, tree.base_data())),
+            None => None,
+        };
+        if result.is_some()
+            || (self.trees.len() < self.max_trees() && completed_count == jobs_required)
+        {
+            for tree in self.trees.iter_mut().skip(1) {
+                tree.reset_weights(WeightKind::Merge);
+            }
+        }
+        Ok(result)
+    }
+
+    fn add_data(&mut self, data: Vec<B>) -> Result<(), ScanStateError> {
+        let depth = self.depth();
+        let available_space = self.free_space();
+        if data.len() > available_space {
+            return Err(ScanStateError::TooMuchData {
+                available: available_space,
+                actual: data.len(),
+            });
+        }
+        let data_count = data.len();
+        let jobs = data.into_iter().map(Job::Base).collect();
+        let tree = &mut self.trees[0];
+        tree.update(jobs, depth, self.curr_job_seq_no, WeightKind::Base)?;
+        if data_count == available_space {
+            tree.reset_weights(WeightKind::Both);
+            self.trees.push_front(ScanTree::new(self.max_base_jobs));
+        } else {
+            tree.reset_weights(WeightKind::Merge);
+        }
+        Ok(())
+    }
+}
+
+fn base_job_from<B, T: Into<B>>(job: serialization::BaseJobV1<T>) -> BaseJob<B> {
+    match job.inner() {
+        serialization::BaseJob::Empty => BaseJob::Empty,
+        serialization::BaseJob::Full(record) => {
+            let record = record.inner();
+            BaseJob::Full {
+                job: record.job.into(),
+                seq_no: record.seq_no.inner(),
+                status: record.status.into(),
+            }
+        }
+    }
+}
+
+fn base_job_into<B, T: From<B>>(job: BaseJob<B>) -> serialization::BaseJobV1<T> {
+    match job {
+        BaseJob::Empty => serialization::BaseJob::Empty,
+        BaseJob::Full {
+            job,
+            seq_no,
+            status,
+        } => serialization::BaseJob::Full(
+            serialization::BaseJobRecord {
+                job: job.into(),
+                seq_no: seq_no.into(),
+                status: status.into(),
+            }
+            .into(),
+        ),
+    }
+    .into()
+}
+
+fn merge_job_from<M, T: Into<M>>(job: serialization::MergeJobV1<T>) -> MergeJob<M> {
+    match job.inner() {
+        serialization::MergeJob::Empty => MergeJob::Empty,
+        serialization::MergeJob::Part(m) => MergeJob::Part(m.into()),
+        serialization::MergeJob::Full(record) => {
+            let record = record.inner();
+            MergeJob::Full {
+                left: record.left.into(),
+                right: record.right.into(),
+                seq_no: record.seq_no.inner(),
+                status: record.status.into(),
+            }
+        }
+    }
+}
+
+fn merge_job_into<M, T: From<M>>(job: MergeJob<M>) -> serialization::MergeJobV1<T> {
+    match job {
+        MergeJob::Empty => serialization::MergeJob::Empty,
+        MergeJob::Part(m) => serialization::MergeJob::Part(m.into()),
+        MergeJob::Full {
+            left,
+            right,
+            seq_no,
+            status,
+        } => serialization::MergeJob::Full(
+            serialization::MergeJobRecord {
+                left: left.into(),
+                right: right.into(),
+                seq_no: seq_no.into(),
+                status: status.into(),
+            }
+            .into(),
+        ),
+    }
+    .into()
+}
+
+impl<B, M, TB, TM> From<serialization::ScanTree<TM, TB>> for ScanTree<B, M>
+where
+    TB: Into<B>,
+    TM: Into<M>,
+{
+    fn from(t: serialization::ScanTree<TM, TB>) -> Self {
+        let (merge_weights, merge_jobs) = t
+            .merge_nodes
+            .into_iter()
+            .map(|node| {
+                let serialization::MergeNode((l, r), job) = node.inner();
+                (
+                    (Weight::from(l.inner()), Weight::from(r.inner())),
+                    merge_job_from(job),
+                )
+            })
+            .unzip();
+        let (base_weights, base_jobs) = t
+            .base_nodes
+            .into_iter()
+            .map(|node| {
+                let serialization::BaseNode(w, job) = node.inner();
+                (Weight::from(w.inner()), base_job_from(job))
+            })
+            .unzip();
+        Self {
+            merge_weights,
+            merge_jobs,
+            base_weights,
+            base_jobs,
+        }
+    }
+}
+
+impl<B, M, TB, TM> From<ScanTree<B, M>> for serialization::ScanTree<TM, TB>
+where
+    TB: From<B>,
+    TM: From<M>,
+{
+    fn from(t: ScanTree<B, M>) -> Self {
+        Self {
+            merge_nodes: t
+                .merge_weights
+                .into_iter()
+                .zip(t.merge_jobs)
+                .map(|((l, r), job)| {
+                    serialization::MergeNode((l.into(), r.into()), merge_job_into(job)).into()
+                })
+                .collect(),
+            base_nodes: t
+                .base_weights
+                .into_iter()
+                .zip(t.base_jobs)
+                .map(|(w, job)| serialization::BaseNode(w.into(), base_job_into(job)).into())
+                .collect(),
+        }
+    }
+}
+
+/// Snapshots are untrusted, the trees have to fit the maximum number of base jobs
+impl<B, M, TB, TM> TryFrom<serialization::ParallelScanState<TM, TB>> for ParallelScan<B, M>
+where
+    TB: Into<B>,
+    TM: Into<M>,
+{
+    type Error = ScanStateError;
+
+    fn try_from(t: serialization::ParallelScanState<TM, TB>) -> Result<Self, Self::Error> {
+        if !t.max_base_jobs.is_power_of_two() {
+            return Err(ScanStateError::InvalidMaxBaseJobs(t.max_base_jobs));
+        }
+        let max_base_jobs = t.max_base_jobs as usize;
+        let serialization::NonEmptyList(head, tail) = t.trees.inner();
+        let trees = std::iter::once(head)
+            .chain(tail)
+            .enumerate()
+            .map(|(i, tree)| {
+                let tree = tree.inner();
+                if tree.base_nodes.len() != max_base_jobs
+                    || tree.merge_nodes.len() != max_base_jobs - 1
+                {
+                    return Err(ScanStateError::InvalidTreeSize(i));
+                }
+                Ok(tree.into())
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            trees,
+            acc: t
+                .acc
+                .map(|(m, data)| (m.into(), data.into_iter().map(Into::into).collect())),
+            curr_job_seq_no: t.curr_job_seq_no.inner(),
+            max_base_jobs,
+            delay: t.delay as u32,
+        })
+    }
+}
+
+impl<B, M, TB, TM> From<ParallelScan<B, M>> for serialization::ParallelScanState<TM, TB>
+where
+    TB: From<B>,
+    TM: From<M>,
+{
+    fn from(t: ParallelScan<B, M>) -> Self {
+        let mut trees = t
+            .trees
+            .into_iter()
+            .map(|tree| serialization::ScanTree::from(tree).into());
+        let head = trees.next().expect("Scan state has at least one tree");
+        Self {
+            trees: serialization::NonEmptyList(head, trees.collect()).into(),
+            acc: t
+                .acc
+                .map(|(m, data)| (m.into(), data.into_iter().map(Into::into).collect())),
+            curr_job_seq_no: t.curr_job_seq_no.into(),
+            max_base_jobs: t.max_base_jobs as u64,
+            delay: t.delay as u64,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sparse merkle ledger that holds the accounts a transaction touches,
+//! the ledger witness of the base jobs of the transaction snark scan state
+#![allow(missing_docs)]
+
+use crate::{
+    account::{AccountId, AccountLegacy},
+    numbers::TokenId,
+};
+use mina_crypto::hash::LedgerHash;
+use mina_serialization_types_macros::AutoFrom;
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::sparse_ledger::SparseLedgerTree)]
+pub enum SparseLedgerTree {
+    Account(Box<AccountLegacy>),
+    Hash(LedgerHash),
+    Node(LedgerHash, Box<SparseLedgerTree>, Box<SparseLedgerTree>),
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::sparse_ledger::SparseLedger)]
+pub struct SparseLedger {
+    /// Account ids and their indexes in the ledger
+    #[auto_from(with = "indexes")]
+    pub indexes: Vec<(AccountId, u64)>,
+    pub depth: u64,
+    pub tree: SparseLedgerTree,
+    pub next_available_token: TokenId,
+}
+
+mod indexes {
+    use super::*;
+    use mina_serialization_types::v1::AccountIdV1;
+
+    pub fn into(t: Vec<(AccountId, u64)>) -> Vec<(AccountIdV1, u64)> {
+        t.into_iter().map(|(id, i)| (id.into(), i)).collect()
+    }
+
+    pub fn from(t: Vec<(AccountIdV1, u64)>) -> Vec<(AccountId, u64)> {
+        t.into_iter().map(|(id, i)| (id.into(), i)).collect()
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scan state of the staged ledger, whose base jobs are transactions
+//! and whose merge jobs are ledger proofs
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/transaction_snark_scan_state/transaction_snark_scan_state.ml>
+
+use super::*;
+use crate::{numbers::Amount, snark_work::*, staged_ledger_diff::StagedLedgerPreDiff};
+use mina_crypto::hash::{AuxHash, StateBodyHash, StateHash};
+use mina_serialization_types::v1::{
+    LedgerProofWithSokMessageV1, TransactionScanStateV1, TransactionWithWitnessV1,
+};
+use mina_serialization_types_macros::AutoFrom;
+use proof_systems::mina_signer::CompressedPubKey;
+
+/// `transaction_capacity_log_2` on mainnet, each tree holds 128 transactions
+pub const TRANSACTION_CAPACITY_LOG_2: u32 = 7;
+
+/// `work_delay` on mainnet
+pub const WORK_DELAY: u32 = 2;
+
+/// Pending coinbase stack that a transaction starts from
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::scan_state::InitStack)]
+pub enum InitStack {
+    /// Stack of a base transaction
+    Base(PendingCoinbase),
+    /// Merged transactions have no initial stack
+    Merge,
+}
+
+/// Transaction applied to the staged ledger, along with the statement and
+/// the ledger witness of the transaction snark that proves it
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::scan_state::TransactionWithWitness)]
+pub struct TransactionWithWitness {
+    /// The applied transaction with the data to undo it
+    pub transaction_with_info: TransactionUndo,
+    /// State hash and state body hash of the block that includes the transaction
+    #[auto_from(with = "state_hash")]
+    pub state_hash: (StateHash, StateBodyHash),
+    /// Statement of the transaction snark that proves the ledger transition
+    pub statement: Statement,
+    /// Pending coinbase stack the transaction starts from
+    pub init_stack: InitStack,
+    /// Accounts the transaction touches
+    pub ledger_witness: SparseLedger,
+}
+
+mod state_hash {
+    use super::*;
+    use mina_serialization_types::v1::HashV1;
+
+    pub fn into(t: (StateHash, StateBodyHash)) -> (HashV1, HashV1) {
+        (t.0.into(), t.1.into())
+    }
+
+    pub fn from(t: (HashV1, HashV1)) -> (StateHash, StateBodyHash) {
+        (t.0.into(), t.1.into())
+    }
+}
+
+/// Fee and prover of a snark work, the sok digest of the statement is the hash of it
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::scan_state::SokMessage)]
+pub struct SokMessage {
+    /// Fee of the snark work
+    pub fee: Amount,
+    /// Prover of the snark work
+    pub prover: CompressedPubKey,
+}
+
+/// Ledger proof along with the fee and the prover of the snark work
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::scan_state::LedgerProofWithSokMessage)]
+pub struct LedgerProofWithSokMessage(pub TransactionSnark, pub SokMessage);
+
+impl LedgerProofWithSokMessage {
+    /// Splits a completed snark work into one or two ledger proofs
+    pub fn from_completed_work(work: &TransactionSnarkWork) -> Vec<Self> {
+        let proofs = match &work.proofs {
+            OneORTwo::One(p) => vec![p.as_ref().clone()],
+            OneORTwo::Two(p1, p2) => vec![p1.as_ref().clone(), p2.as_ref().clone()],
+        };
+        let sok_message = SokMessage {
+            fee: work.fee,
+            prover: work.prover.clone(),
+        };
+        proofs
+            .into_iter()
+            .map(|proof| Self(proof, sok_message.clone()))
+            .collect()
+    }
+
+    /// Statement of the ledger proof
+    pub fn statement(&self) -> &Statement {
+        &self.0.statement
+    }
+}
+
+/// Scan state of the staged ledger
+pub type TransactionScanState = ParallelScan<TransactionWithWitness, LedgerProofWithSokMessage>;
+
+impl Default for TransactionScanState {
+    fn default() -> Self {
+        Self::new(TRANSACTION_CAPACITY_LOG_2, WORK_DELAY)
+    }
+}
+
+/// Scan state snapshots are decoded as [TransactionScanStateV1] and checked when converted
+impl TryFrom<TransactionScanStateV1> for TransactionScanState {
+    type Error = ScanStateError;
+
+    fn try_from(t: TransactionScanStateV1) -> Result<Self, Self::Error> {
+        t.inner().inner().try_into()
+    }
+}
+
+impl From<TransactionScanState> for TransactionScanStateV1 {
+    fn from(t: TransactionScanState) -> Self {
+        let t: mina_serialization_types::scan_state::ParallelScanState<
+            LedgerProofWithSokMessageV1,
+            TransactionWithWitnessV1,
+        > = t.into();
+        t.into()
+    }
+}
+
+impl TransactionScanState {
+    /// Applies the transactions and the completed works of a staged ledger pre diff,
+    /// returns the ledger proof emitted by a completed tree if any.
+    /// Fails if the completed works do not prove the statements of the required jobs,
+    /// see [TransactionScanState::validate_completed_works]
+    pub fn apply_pre_diff(
+        &mut self,
+        pre_diff: &StagedLedgerPreDiff,
+        transactions: Vec<TransactionWithWitness>,
+    ) -> Result<Option<(LedgerProofWithSokMessage, Vec<TransactionWithWitness>)>, ScanStateError>
+    {
+        let works = self.completed_work_proofs(&pre_diff.completed_works, transactions.len())?;
+        self.update(transactions, works)
+    }
+
+    /// Statements of the jobs whose completed work is required to add `data_count`
+    /// transactions, in the order of [ParallelScan::work_for_next_update].
+    /// Each group holds the one or two statements that a single snark work proves
+    pub fn required_statements(
+        &self,
+        data_count: usize,
+    ) -> Result<Vec<Vec<Statement>>, SnarkWorkError> {
+        self.work_for_next_update(data_count)
+            .iter()
+            .flat_map(|jobs| jobs.chunks(2))
+            .map(|jobs| {
+                jobs.iter()
+                    .map(|job| match job {
+                        AvailableJob::Base(b) => Ok(b.statement.clone()),
+                        AvailableJob::Merge(l, r) => l.statement().merge(r.statement()),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Validates that the completed works of a block prove the statements of the first
+    /// jobs the scan state requires to add `data_count` transactions.
+    /// Like `check_completed_works` of Mina, the n-th work has to prove the n-th group of
+    /// [TransactionScanState::required_statements]
+    pub fn validate_completed_works(
+        &self,
+        works: &[TransactionSnarkWork],
+        data_count: usize,
+    ) -> Result<(), SnarkWorkError> {
+        self.completed_work_proofs(works, data_count).map(|_| ())
+    }
+
+    // Ledger proofs of the validated completed works
+    fn completed_work_proofs(
+        &self,
+        works: &[TransactionSnarkWork],
+        data_count: usize,
+    ) -> Result<Vec<LedgerProofWithSokMessage>, SnarkWorkError> {
+        let required = self.required_statements(data_count)?;
+        if works.len() > required.len() {
+            return Err(SnarkWorkError::TooManyCompletedWorks {
+                required: required.len(),
+                actual: works.len(),
+            });
+        }
+        let proofs = works
+            .iter()
+            .zip(&required)
+            .enumerate()
+            .map(|(i, (work, statements))| {
+                let proofs = LedgerProofWithSokMessage::from_completed_work(work);
+                if statements.len() == proofs.len()
+                    && statements
+                        .iter()
+                        .zip(&proofs)
+                        .all(|(s, p)| p.statement().eq_without_sok_digest(s))
+                {
+                    Ok(proofs)
+                } else {
+                    Err(SnarkWorkError::StatementMismatch(i))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(proofs.concat())
+    }
+
+    /// Calculates the aux hash of the staged ledger hash, ledger proofs and transactions
+    /// are hashed by the bin-prot encodings of their versioned types
+    pub fn aux_hash(&self) -> AuxHash {
+        self.hash(
+            |m| {
+                let mut output = Vec::new();
+                bin_prot::to_writer(&mut output, &LedgerProofWithSokMessageV1::from(m.clone()))
+                    .expect("Failed to serialize ledger proof");
+                output
+            },
+            |b| {
+                let mut output = Vec::new();
+                bin_prot::to_writer(&mut output, &TransactionWithWitnessV1::from(b.clone()))
+                    .expect("Failed to serialize transaction with witness");
+                output
+            },
+        )
+    }
+}
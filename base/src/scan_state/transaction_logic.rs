@@ -0,0 +1,123 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transactions applied to the staged ledger along with the data to undo them,
+//! these are the base jobs of the transaction snark scan state
+#![allow(missing_docs)]
+
+use crate::{
+    account::{AccountId, Timing},
+    numbers::{Amount, TokenId},
+    staged_ledger_diff::{
+        CoinBaseBalanceData, CoinBaseFeeTransfer, FeeTransferBalanceData, TransactionStatus,
+    },
+    user_commands::SignedCommand,
+};
+use mina_crypto::hash::{LedgerHash, ReceiptChainHash};
+use mina_serialization_types_macros::AutoFrom;
+use proof_systems::mina_signer::CompressedPubKey;
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::FeeTransferSingle)]
+pub struct FeeTransferSingle {
+    pub receiver_pk: CompressedPubKey,
+    pub fee: Amount,
+    pub fee_token: TokenId,
+}
+
+/// Fee transfers to one or two snark workers
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::FeeTransfer)]
+pub enum FeeTransfer {
+    One(FeeTransferSingle),
+    Two(FeeTransferSingle, FeeTransferSingle),
+}
+
+/// Coinbase with an optional fee transfer to a snark worker
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::Coinbase)]
+pub struct Coinbase {
+    pub receiver: CompressedPubKey,
+    pub amount: Amount,
+    pub fee_transfer: Option<CoinBaseFeeTransfer>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::SignedCommandWithStatus)]
+pub struct SignedCommandWithStatus {
+    pub data: SignedCommand,
+    pub status: TransactionStatus,
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::SignedCommandUndoCommon)]
+pub struct SignedCommandUndoCommon {
+    pub user_command: SignedCommandWithStatus,
+    pub previous_receipt_chain_hash: ReceiptChainHash,
+    pub fee_payer_timing: Timing,
+    pub source_timing: Option<Timing>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::SignedCommandUndoBody)]
+pub enum SignedCommandUndoBody {
+    Payment {
+        previous_empty_accounts: Vec<AccountId>,
+    },
+    StakeDelegation {
+        previous_delegate: Option<CompressedPubKey>,
+    },
+    CreateNewToken {
+        created_token: TokenId,
+    },
+    CreateTokenAccount,
+    MintTokens,
+    Failed,
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::SignedCommandUndo)]
+pub struct SignedCommandUndo {
+    pub common: SignedCommandUndoCommon,
+    pub body: SignedCommandUndoBody,
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::CommandUndo)]
+pub enum CommandUndo {
+    SignedCommand(SignedCommandUndo),
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::FeeTransferUndo)]
+pub struct FeeTransferUndo {
+    pub fee_transfer: FeeTransfer,
+    pub previous_empty_accounts: Vec<AccountId>,
+    pub receiver_timing: Timing,
+    pub balances: FeeTransferBalanceData,
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::CoinbaseUndo)]
+pub struct CoinbaseUndo {
+    pub coinbase: Coinbase,
+    pub previous_empty_accounts: Vec<AccountId>,
+    pub receiver_timing: Timing,
+    pub balances: CoinBaseBalanceData,
+}
+
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::TransactionUndoVarying)]
+pub enum TransactionUndoVarying {
+    Command(Box<CommandUndo>),
+    FeeTransfer(FeeTransferUndo),
+    Coinbase(CoinbaseUndo),
+}
+
+/// Transaction applied to the staged ledger, with the ledger hash before applying it
+#[derive(Clone, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::transaction_logic::TransactionUndo)]
+pub struct TransactionUndo {
+    pub previous_hash: LedgerHash,
+    pub varying: TransactionUndoVarying,
+}
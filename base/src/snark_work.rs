@@ -9,8 +9,43 @@ use mina_crypto::hash::*;
 use mina_serialization_types::json::*;
 use mina_serialization_types_macros::AutoFrom;
 use proof_systems::mina_signer::CompressedPubKey;
+use thiserror::Error;
 use versioned::*;
 
+/// Error type for fee excess arithmetic and statement merging
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum SnarkWorkError {
+    /// Adding fee excesses overflows
+    #[error("Fee excess overflow")]
+    FeeExcessOverflow,
+    /// Fee excesses of more than two tokens cannot be combined
+    #[error("Did not eliminate fee excess of token {0}")]
+    FeeExcessNotEliminated(u64),
+    /// Adding supply increases overflows
+    #[error("Supply increase overflow")]
+    SupplyIncreaseOverflow,
+    /// Target ledger hash of the first statement is not the source of the second
+    #[error("Statements have incompatible ledger hashes")]
+    LedgerHashMismatch,
+    /// Pending coinbase stacks of the statements do not connect
+    #[error("Statements have disconnected pending coinbase stacks")]
+    PendingCoinbaseStackDisconnected,
+    /// Next available tokens of the statements do not line up
+    #[error("Statements have incompatible next available tokens")]
+    NextAvailableTokenMismatch,
+    /// More completed works are included than the scan state requires
+    #[error("Too many completed works, {required} required, {actual} provided")]
+    TooManyCompletedWorks {
+        /// Number of jobs that require completed work
+        required: usize,
+        /// Number of completed works
+        actual: usize,
+    },
+    /// Statements of a completed work do not match the job at its position
+    #[error("Statements of completed work {0} do not match the required job")]
+    StatementMismatch(usize),
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::snark_work::TransactionSnarkWork)]
 pub struct TransactionSnarkWork {
@@ -59,6 +94,57 @@ impl_from_with_proxy!(
     StatementJson
 );
 
+impl Statement {
+    /// Merges two statements of consecutive ledger transitions into the statement
+    /// of the merged transition, sok digest of the result is empty
+    pub fn merge(&self, other: &Self) -> Result<Self, SnarkWorkError> {
+        if self.target != other.source {
+            return Err(SnarkWorkError::LedgerHashMismatch);
+        }
+        if !PendingCoinbase::connected(
+            &self.pending_coinbase_stack_state.target,
+            &other.pending_coinbase_stack_state.source,
+            None,
+        ) {
+            return Err(SnarkWorkError::PendingCoinbaseStackDisconnected);
+        }
+        if self.next_available_token_after != other.next_available_token_before {
+            return Err(SnarkWorkError::NextAvailableTokenMismatch);
+        }
+        let fee_excess = self.fee_excess.combine(&other.fee_excess)?;
+        let supply_increase = self
+            .supply_increase
+            .0
+            .checked_add(other.supply_increase.0)
+            .ok_or(SnarkWorkError::SupplyIncreaseOverflow)?;
+        Ok(Self {
+            source: self.source.clone(),
+            target: other.target.clone(),
+            supply_increase: Amount(supply_increase),
+            pending_coinbase_stack_state: PendingCoinbaseStackState {
+                source: self.pending_coinbase_stack_state.source.clone(),
+                target: other.pending_coinbase_stack_state.target.clone(),
+            },
+            fee_excess,
+            next_available_token_before: self.next_available_token_before.clone(),
+            next_available_token_after: other.next_available_token_after.clone(),
+            sok_digest: ByteVec(Vec::new()),
+        })
+    }
+
+    /// Compares two statements without their sok digests,
+    /// which bind the statement to the fee and the prover of the snark work
+    pub fn eq_without_sok_digest(&self, other: &Self) -> bool {
+        self.source == other.source
+            && self.target == other.target
+            && self.supply_increase == other.supply_increase
+            && self.pending_coinbase_stack_state == other.pending_coinbase_stack_state
+            && self.fee_excess == other.fee_excess
+            && self.next_available_token_before == other.next_available_token_before
+            && self.next_available_token_after == other.next_available_token_after
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::snark_work::PendingCoinbaseStackState)]
 pub struct PendingCoinbaseStackState {
@@ -80,10 +166,79 @@ pub struct StateStack {
     pub curr: StateHash,
 }
 
+/// Fee excesses of the left and the right fee tokens
 #[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::snark_work::FeeExcessPair)]
 pub struct FeeExcessPair(pub FeeExcess, pub FeeExcess);
 
+impl Default for FeeExcessPair {
+    fn default() -> Self {
+        Self(FeeExcess::zero(), FeeExcess::zero())
+    }
+}
+
+impl FeeExcessPair {
+    /// Moves the fee excess to the left when the left is zero, merges the excesses
+    /// of the same token and resets the token of zero excesses to the default token
+    pub fn rebalance(&self) -> Result<Self, SnarkWorkError> {
+        let (mut left, mut right) = (self.0.clone(), self.1.clone());
+        if left.amount.is_zero() {
+            left.token = right.token.clone();
+        }
+        if left.token == right.token {
+            left.amount = left.amount.add(&right.amount)?;
+            right.amount = Signed::zero();
+        }
+        if right.amount.is_zero() {
+            right.token = TokenId(DEFAULT_TOKEN_ID);
+        }
+        if left.amount.is_zero() {
+            left.token = TokenId(DEFAULT_TOKEN_ID);
+        }
+        Ok(Self(left, right))
+    }
+
+    /// Combines the fee excesses of two consecutive transitions,
+    /// fails when more than two tokens have non-zero excess
+    pub fn combine(&self, other: &Self) -> Result<Self, SnarkWorkError> {
+        // Eliminate the right excess of the first pair
+        let (left1, left2) = eliminate_fee_excess(&self.0, &other.0, &self.1)?;
+        // Eliminate the left excess of the second pair
+        let (left, right) = eliminate_fee_excess(&left1, &other.1, &left2)?;
+        Self(left, right).rebalance()
+    }
+}
+
+// Adds the middle excess to whichever of the left or the right excess
+// has the same token or is zero
+fn eliminate_fee_excess(
+    left: &FeeExcess,
+    right: &FeeExcess,
+    middle: &FeeExcess,
+) -> Result<(FeeExcess, FeeExcess), SnarkWorkError> {
+    if left.token == middle.token || left.amount.is_zero() {
+        let left = FeeExcess {
+            token: middle.token.clone(),
+            amount: left.amount.add(&middle.amount)?,
+        };
+        Ok((left, right.clone()))
+    } else if right.token == middle.token || right.amount.is_zero() {
+        let right = FeeExcess {
+            token: middle.token.clone(),
+            amount: right.amount.add(&middle.amount)?,
+        };
+        Ok((left.clone(), right))
+    } else if middle.amount.is_zero() {
+        Ok((left.clone(), right.clone()))
+    } else {
+        Err(SnarkWorkError::FeeExcessNotEliminated(middle.token.0))
+    }
+}
+
+/// Default token id, the token of mina
+pub const DEFAULT_TOKEN_ID: u64 = 1;
+
+/// Fee excess of a token
 #[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::snark_work::FeeExcess)]
 pub struct FeeExcess {
@@ -91,6 +246,17 @@ pub struct FeeExcess {
     pub amount: Signed,
 }
 
+impl FeeExcess {
+    /// Zero fee excess of the default token
+    pub fn zero() -> Self {
+        Self {
+            token: TokenId(DEFAULT_TOKEN_ID),
+            amount: Signed::zero(),
+        }
+    }
+}
+
+/// Signed amount
 #[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::snark_work::Signed)]
 pub struct Signed {
@@ -98,6 +264,62 @@ pub struct Signed {
     pub sgn: SgnType,
 }
 
+impl Default for Signed {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl Signed {
+    /// Positive zero
+    pub fn zero() -> Self {
+        Self {
+            magnitude: Amount(0),
+            sgn: SgnType::Pos,
+        }
+    }
+
+    /// Creates a signed amount from its magnitude and sign
+    pub fn new(magnitude: Amount, sgn: SgnType) -> Self {
+        Self { magnitude, sgn }
+    }
+
+    /// Whether the magnitude is zero, regardless of the sign
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.0 == 0
+    }
+
+    /// Negates the amount, zero stays positive
+    pub fn negate(&self) -> Self {
+        if self.is_zero() {
+            return Self::zero();
+        }
+        let sgn = match self.sgn {
+            SgnType::Pos => SgnType::Neg,
+            SgnType::Neg => SgnType::Pos,
+        };
+        Self::new(self.magnitude, sgn)
+    }
+
+    /// Adds two signed amounts, fails on overflow
+    pub fn add(&self, other: &Self) -> Result<Self, SnarkWorkError> {
+        let (a, b) = (self.magnitude.0, other.magnitude.0);
+        let result = if self.sgn == other.sgn {
+            let magnitude = a.checked_add(b).ok_or(SnarkWorkError::FeeExcessOverflow)?;
+            Self::new(Amount(magnitude), self.sgn.clone())
+        } else if a >= b {
+            Self::new(Amount(a - b), self.sgn.clone())
+        } else {
+            Self::new(Amount(b - a), other.sgn.clone())
+        };
+        Ok(if result.is_zero() {
+            Self::zero()
+        } else {
+            result
+        })
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, AutoFrom)]
 #[auto_from(mina_serialization_types::snark_work::SgnType)]
 pub enum SgnType {
@@ -152,6 +152,54 @@ impl_from_with_proxy!(
     TransactionStatusFailedTypeJson
 );
 
+/// Reason of a transaction failure since berkeley
+#[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
+#[auto_from(mina_serialization_types::v2::TransactionFailureV2)]
+pub enum TransactionFailure {
+    Predicate,
+    SourceNotPresent,
+    ReceiverNotPresent,
+    AmountInsufficientToCreateAccount,
+    CannotPayCreationFeeInToken,
+    SourceInsufficientBalance,
+    SourceMinimumBalanceViolation,
+    ReceiverAlreadyExists,
+    TokenOwnerNotCaller,
+    Overflow,
+    GlobalExcessOverflow,
+    LocalExcessOverflow,
+    LocalSupplyIncreaseOverflow,
+    GlobalSupplyIncreaseOverflow,
+    SignedCommandOnZkappAccount,
+    ZkappAccountNotPresent,
+    UpdateNotPermittedBalance,
+    UpdateNotPermittedTimingExistingAccount,
+    UpdateNotPermittedDelegate,
+    UpdateNotPermittedAppState,
+    UpdateNotPermittedVerificationKey,
+    UpdateNotPermittedSequenceState,
+    UpdateNotPermittedZkappUri,
+    UpdateNotPermittedTokenSymbol,
+    UpdateNotPermittedPermissions,
+    UpdateNotPermittedNonce,
+    UpdateNotPermittedVotingFor,
+    ZkappCommandReplayCheckFailed,
+    FeePayerNonceMustIncrease,
+    FeePayerMustBeSigned,
+    AccountBalancePreconditionUnsatisfied,
+    AccountNoncePreconditionUnsatisfied,
+    AccountReceiptChainHashPreconditionUnsatisfied,
+    AccountDelegatePreconditionUnsatisfied,
+    AccountSequenceStatePreconditionUnsatisfied,
+    AccountAppStatePreconditionUnsatisfied(i64),
+    AccountProvedStatePreconditionUnsatisfied,
+    AccountIsNewPreconditionUnsatisfied,
+    ProtocolStatePreconditionUnsatisfied,
+    IncorrectNonce,
+    InvalidFeeExcess,
+    Cancelled,
+}
+
 #[derive(Clone, Eq, PartialEq, Debug, SmartDefault, AutoFrom)]
 #[auto_from(mina_serialization_types::staged_ledger_diff::CoinBase)]
 pub enum CoinBase {
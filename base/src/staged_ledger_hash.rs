@@ -0,0 +1,147 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Computation and verification of the staged ledger hash of a block.
+//!
+//! The staged ledger hash combines the merkle root of the staged ledger,
+//! the aux hash of the scan state, the pending coinbase aux hash and the
+//! merkle root of the pending coinbase collection
+
+use crate::{
+    blockchain_state::*, pending_coinbase::PendingCoinbaseCollection,
+    scan_state::TransactionScanState,
+};
+use mina_crypto::hash::*;
+use thiserror::Error;
+
+/// Error type for staged ledger hash verification,
+/// that names the first component that does not match
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum StagedLedgerHashError {
+    /// Ledger hash mismatch
+    #[error("Ledger hash mismatch, expected: {expected}, actual: {actual}")]
+    LedgerHash {
+        /// The ledger hash in the block
+        expected: String,
+        /// The computed ledger hash
+        actual: String,
+    },
+    /// Scan state aux hash mismatch
+    #[error("Aux hash mismatch, expected: {expected}, actual: {actual}")]
+    AuxHash {
+        /// The aux hash in the block
+        expected: String,
+        /// The computed aux hash
+        actual: String,
+    },
+    /// Pending coinbase aux hash mismatch
+    #[error("Pending coinbase aux hash mismatch, expected: {expected}, actual: {actual}")]
+    PendingCoinbaseAux {
+        /// The pending coinbase aux hash in the block
+        expected: String,
+        /// The computed pending coinbase aux hash
+        actual: String,
+    },
+    /// Pending coinbase hash mismatch
+    #[error("Pending coinbase hash mismatch, expected: {expected}, actual: {actual}")]
+    PendingCoinbaseHash {
+        /// The pending coinbase hash in the block
+        expected: String,
+        /// The computed pending coinbase hash
+        actual: String,
+    },
+}
+
+/// Computes the staged ledger hash from the ledger hash, the scan state
+/// and the pending coinbase collection
+pub fn compute_staged_ledger_hash(
+    ledger_hash: LedgerHash,
+    scan_state: &TransactionScanState,
+    pending_coinbase: &PendingCoinbaseCollection,
+) -> StagedLedgerHash {
+    StagedLedgerHash::from_components(
+        ledger_hash,
+        scan_state.aux_hash(),
+        pending_coinbase.hash_extra(),
+        pending_coinbase.merkle_root(),
+    )
+}
+
+/// Computes the staged ledger hash of the genesis block, which has the dummy
+/// aux hashes instead of the hashes of its empty scan state and pending coinbase
+pub fn genesis_staged_ledger_hash(
+    ledger_hash: LedgerHash,
+    pending_coinbase: &PendingCoinbaseCollection,
+) -> StagedLedgerHash {
+    StagedLedgerHash::genesis(ledger_hash, pending_coinbase.merkle_root())
+}
+
+/// Compares a staged ledger hash against the computed one component by component
+pub fn verify_staged_ledger_hash(
+    expected: &StagedLedgerHash,
+    actual: &StagedLedgerHash,
+) -> Result<(), StagedLedgerHashError> {
+    macro_rules! ensure_eq {
+        ($variant:ident, $expected:expr, $actual:expr) => {
+            if $expected != $actual {
+                return Err(StagedLedgerHashError::$variant {
+                    expected: $expected.to_string(),
+                    actual: $actual.to_string(),
+                });
+            }
+        };
+    }
+
+    ensure_eq!(
+        LedgerHash,
+        expected.non_snark.ledger_hash,
+        actual.non_snark.ledger_hash
+    );
+    ensure_eq!(
+        AuxHash,
+        expected.non_snark.aux_hash,
+        actual.non_snark.aux_hash
+    );
+    ensure_eq!(
+        PendingCoinbaseAux,
+        expected.non_snark.pending_coinbase_aux,
+        actual.non_snark.pending_coinbase_aux
+    );
+    ensure_eq!(
+        PendingCoinbaseHash,
+        expected.pending_coinbase_hash,
+        actual.pending_coinbase_hash
+    );
+    Ok(())
+}
+
+/// Implementing types carry a staged ledger hash that can be verified
+/// against the staged ledger components
+pub trait StagedLedgerHashVerifiable {
+    /// The staged ledger hash reported by the implementing type
+    fn staged_ledger_hash(&self) -> &StagedLedgerHash;
+
+    /// Verifies the reported staged ledger hash against the one
+    /// computed from the given components
+    fn verify_staged_ledger_hash(
+        &self,
+        ledger_hash: LedgerHash,
+        scan_state: &TransactionScanState,
+        pending_coinbase: &PendingCoinbaseCollection,
+    ) -> Result<(), StagedLedgerHashError> {
+        let computed = compute_staged_ledger_hash(ledger_hash, scan_state, pending_coinbase);
+        verify_staged_ledger_hash(self.staged_ledger_hash(), &computed)
+    }
+}
+
+impl StagedLedgerHashVerifiable for BlockchainStateLegacy {
+    fn staged_ledger_hash(&self) -> &StagedLedgerHash {
+        &self.staged_ledger_hash
+    }
+}
+
+impl StagedLedgerHashVerifiable for BlockchainState {
+    fn staged_ledger_hash(&self) -> &StagedLedgerHash {
+        &self.staged_ledger_hash
+    }
+}
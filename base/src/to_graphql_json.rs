@@ -0,0 +1,159 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serialization of types into the json returned by the GraphQL API,
+//! the reverse of [crate::from_graphql_json]
+
+use mina_crypto::{base58::Base58Encodable, hash::*};
+use proof_systems::mina_signer::CompressedPubKey;
+use serde_json::{Map, Value};
+
+use crate::{account::AuthRequired, consensus_state::VrfOutputTruncated, numbers::*};
+
+/// Trait that serializes a struct into graphql json
+pub trait ToGraphQLJson {
+    /// Serialize into graphql json
+    fn to_graphql_json(&self) -> Value;
+}
+
+/// Inserts `value` into a json object at `path`, with keys separated by `.`,
+/// creating the intermediate objects as needed
+pub fn insert_json_path(json: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((key, rest)) => {
+            let entry = json.entry(key).or_insert_with(|| Value::Object(Map::new()));
+            if !entry.is_object() {
+                *entry = Value::Object(Map::new());
+            }
+            if let Value::Object(inner) = entry {
+                insert_json_path(inner, rest, value);
+            }
+        }
+        None => {
+            json.insert(path.into(), value);
+        }
+    }
+}
+
+/// Merges the entries of `value` into a json object, `value` that is
+/// not a json object is ignored
+pub fn merge_json_object(json: &mut Map<String, Value>, value: Value) {
+    if let Value::Object(value) = value {
+        json.extend(value);
+    }
+}
+
+impl ToGraphQLJson for bool {
+    fn to_graphql_json(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl<T: ToGraphQLJson> ToGraphQLJson for Option<T> {
+    fn to_graphql_json(&self) -> Value {
+        match self {
+            Some(v) => v.to_graphql_json(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl<T: ToGraphQLJson> ToGraphQLJson for Vec<T> {
+    fn to_graphql_json(&self) -> Value {
+        Value::Array(self.iter().map(ToGraphQLJson::to_graphql_json).collect())
+    }
+}
+
+impl ToGraphQLJson for CompressedPubKey {
+    fn to_graphql_json(&self) -> Value {
+        Value::String(self.into_address())
+    }
+}
+
+impl ToGraphQLJson for VrfOutputTruncated {
+    fn to_graphql_json(&self) -> Value {
+        Value::String(Base58Encodable::to_base58_string(self))
+    }
+}
+
+// Integers, hashes and enums are encoded as json strings by the graphql API
+macro_rules! impl_to_graphql_json_via_to_string {
+    ($($t:ty),*) => {
+        $(
+            impl ToGraphQLJson for $t {
+                fn to_graphql_json(&self) -> Value {
+                    Value::String(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+impl_to_graphql_json_via_to_string!(
+    u32,
+    u64,
+    i32,
+    i64,
+    StateHash,
+    LedgerHash,
+    EpochSeed,
+    AuxHash,
+    PendingCoinbaseAuxHash,
+    CoinBaseHash,
+    AuthRequired
+);
+
+macro_rules! impl_to_graphql_json_via_inner {
+    ($($t:ty),*) => {
+        $(
+            impl ToGraphQLJson for $t {
+                fn to_graphql_json(&self) -> Value {
+                    self.0.to_graphql_json()
+                }
+            }
+        )*
+    };
+}
+
+impl_to_graphql_json_via_inner!(
+    TokenId,
+    Length,
+    Amount,
+    AccountNonce,
+    GlobalSlotNumber,
+    BlockTime
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_merge_json_path() {
+        let mut json = Map::new();
+        insert_json_path(&mut json, "nonce", Value::from("3"));
+        insert_json_path(&mut json, "balance.total", Value::from("42"));
+        insert_json_path(&mut json, "balance.locked", Value::Null);
+        merge_json_object(&mut json, serde_json::json!({ "isDisabled": false }));
+        merge_json_object(&mut json, Value::from("ignored"));
+        assert_eq!(
+            Value::Object(json),
+            serde_json::json!({
+                "nonce": "3",
+                "balance": { "total": "42", "locked": null },
+                "isDisabled": false
+            })
+        );
+    }
+
+    #[test]
+    fn integer_to_graphql_json() {
+        assert_eq!(42_u64.to_graphql_json(), Value::from("42"));
+        assert_eq!(Amount(7).to_graphql_json(), Value::from("7"));
+        assert_eq!(Option::<u32>::None.to_graphql_json(), Value::Null);
+        assert_eq!(
+            vec![Length(1), Length(2)].to_graphql_json(),
+            serde_json::json!(["1", "2"])
+        );
+    }
+}
@@ -4,6 +4,7 @@
 //! Commands can include memo fields which contain arbitrary byte data
 //!
 
+use mina_crypto::base58::{Base58Encodable, Base58Error, Base58Kind};
 use mina_serialization_types::{json::*, *};
 use mina_serialization_types_macros::AutoFrom;
 use thiserror::Error;
@@ -12,6 +13,7 @@ use thiserror::Error;
 #[derive(Clone, Default, Eq, PartialEq, Debug, derive_more::From, derive_more::Into, AutoFrom)]
 #[auto_from(mina_serialization_types::staged_ledger_diff::SignedCommandMemo)]
 #[auto_from(mina_serialization_types::staged_ledger_diff::SignedCommandMemoJson)]
+#[auto_from(mina_serialization_types::v2::MemoV2)]
 pub struct SignedCommandMemo(pub Vec<u8>);
 
 impl_strconv_via_json!(SignedCommandMemo, SignedCommandMemoJson);
@@ -45,6 +47,18 @@ impl SignedCommandMemo {
     }
 }
 
+impl Base58Encodable for SignedCommandMemo {
+    const KIND: Base58Kind = Base58Kind::UserCommandMemo;
+
+    fn to_base58_payload(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error> {
+        Ok(Self(payload.to_vec()))
+    }
+}
+
 /// Error type for converting memo types
 #[derive(Debug, Error)]
 pub enum SignedCommandMemoError {
@@ -68,6 +82,8 @@ mod tests {
         let memo = memo_from_bs58;
         assert_eq!(&memo.to_string(), bs58_encoded);
         assert_eq!(&memo.to_text(), text_expected);
+        assert_eq!(SignedCommandMemo::from_base58(bs58_encoded)?, memo);
+        assert_eq!(memo.to_base58_string(), bs58_encoded);
         Ok(())
     }
 }
@@ -10,6 +10,16 @@ use crate::user_commands::memo::SignedCommandMemo;
 use crate::user_commands::payment::PaymentPayload;
 use crate::verifiable::Verifiable;
 
+use blake2::{
+    digest::{Update, VariableOutput},
+    Blake2bVar,
+};
+use mina_crypto::{
+    base58::{Base58Encodable, Base58Kind},
+    hash::TransactionHash,
+    impl_base58_encodable_via_binprot,
+};
+use mina_serialization_types::staged_ledger_diff::SignedCommandV1;
 use mina_serialization_types_macros::AutoFrom;
 use proof_systems::mina_hasher::{Hashable, ROInput};
 use proof_systems::mina_signer::{CompressedPubKey, Keypair, NetworkId, PubKey, Signature, Signer};
@@ -47,8 +57,20 @@ impl SignedCommand {
             signature,
         }
     }
+
+    /// Hash of the command, the `Ckp...` identifier used for it by block explorers and the daemon
+    ///
+    /// This is the blake2b digest of the base58check encoding of the bin_prot serialized
+    /// versioned command
+    pub fn hash(&self) -> TransactionHash {
+        let mut hasher = Blake2bVar::new(32).expect("Invalid Blake2bVar output size");
+        hasher.update(self.to_base58_string().as_bytes());
+        TransactionHash(hasher.finalize_boxed().to_vec())
+    }
 }
 
+impl_base58_encodable_via_binprot!(SignedCommand, SignedCommandV1, Base58Kind::SignedCommandV1);
+
 impl<CTX> Verifiable<CTX> for SignedCommand
 where
     CTX: Signer<SignedCommandPayload>,
@@ -178,6 +200,7 @@ pub enum SignedCommandPayloadBody {
 /// Enum of variable fields for stake delegation
 #[derive(Clone, Eq, PartialEq, Debug, AutoFrom)]
 #[auto_from(mina_serialization_types::staged_ledger_diff::StakeDelegation)]
+#[auto_from(mina_serialization_types::v2::StakeDelegationV2)]
 pub enum StakeDelegation {
     /// Set Delegate
     SetDelegate {
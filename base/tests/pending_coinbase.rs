@@ -0,0 +1,168 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use mina_crypto::hash::*;
+    use mina_rs_base::types::*;
+    use proof_systems::{mina_hasher::Fp, mina_signer::CompressedPubKey};
+    use test_fixtures::*;
+
+    fn coinbase() -> CoinbaseData {
+        CoinbaseData {
+            receiver: CompressedPubKey::from_address(
+                "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g",
+            )
+            .unwrap(),
+            amount: Amount(720000000000),
+        }
+    }
+
+    #[test]
+    fn empty_collection() {
+        let collection = PendingCoinbaseCollection::default();
+        assert_eq!(collection.depth(), PENDING_COINBASE_DEPTH);
+        assert_eq!(collection.stacks().len(), 32);
+        assert!(collection.latest_stack().is_none());
+        assert_eq!(
+            collection.merkle_root(),
+            PendingCoinbaseCollection::new(PENDING_COINBASE_DEPTH).merkle_root()
+        );
+    }
+
+    #[test]
+    fn push_coinbase_and_state() -> anyhow::Result<()> {
+        let mut collection = PendingCoinbaseCollection::default();
+        let empty_root = collection.merkle_root();
+
+        collection.add_coinbase(&coinbase(), true)?;
+        assert_eq!(collection.pos_list(), &[0]);
+        assert_eq!(collection.new_pos(), 1);
+        let root_with_coinbase = collection.merkle_root();
+        assert_ne!(root_with_coinbase, empty_root);
+
+        let state_body_hash: StateBodyHash = (&Fp::from(1_u64)).into();
+        collection.add_state(&state_body_hash, false)?;
+        assert_eq!(collection.pos_list(), &[0]);
+        let latest = collection.latest_stack().unwrap();
+        assert_ne!(latest.state_stack.curr, latest.state_stack.init);
+        assert_ne!(collection.merkle_root(), root_with_coinbase);
+
+        // A new stack continues from the state of the previous one
+        collection.add_coinbase(&coinbase(), true)?;
+        assert_eq!(collection.pos_list(), &[1, 0]);
+        let latest = collection.latest_stack().unwrap().clone();
+        let oldest = collection.oldest_stack().unwrap().clone();
+        assert_eq!(latest.state_stack.init, oldest.state_stack.curr);
+
+        let removed = collection.remove_coinbase_stack()?;
+        assert_eq!(removed, oldest);
+        assert_eq!(collection.pos_list(), &[1]);
+        assert_eq!(collection.stacks()[0], PendingCoinbase::empty());
+        Ok(())
+    }
+
+    #[test]
+    fn pushes_are_deterministic() {
+        let mut s1 = PendingCoinbase::empty();
+        let mut s2 = PendingCoinbase::empty();
+        s1.push_coinbase(&coinbase());
+        s2.push_coinbase(&coinbase());
+        assert_eq!(s1, s2);
+        assert_eq!(s1.hash_fp(), s2.hash_fp());
+        assert_ne!(s1.hash_fp(), PendingCoinbase::empty().hash_fp());
+    }
+
+    #[test]
+    fn update_without_stack_fails() {
+        let mut collection = PendingCoinbaseCollection::default();
+        assert_eq!(
+            collection.add_coinbase(&coinbase(), false),
+            Err(PendingCoinbaseError::NoStack)
+        );
+        assert_eq!(
+            collection.remove_coinbase_stack(),
+            Err(PendingCoinbaseError::NoStack)
+        );
+    }
+
+    #[test]
+    fn stacks_exhausted() -> anyhow::Result<()> {
+        let mut collection = PendingCoinbaseCollection::new(1);
+        collection.add_coinbase(&coinbase(), true)?;
+        collection.add_coinbase(&coinbase(), true)?;
+        assert_eq!(
+            collection.add_coinbase(&coinbase(), true),
+            Err(PendingCoinbaseError::NoEmptyStack(1))
+        );
+        collection.remove_coinbase_stack()?;
+        collection.add_coinbase(&coinbase(), true)?;
+        assert_eq!(collection.pos_list(), &[0, 1]);
+        Ok(())
+    }
+
+    fn staged_ledger_hash(block: &BlockFixture) -> anyhow::Result<StagedLedgerHash> {
+        let block = ExternalTransition::from(block.external_transitionv1()?);
+        Ok(block
+            .protocol_state
+            .body
+            .blockchain_state
+            .staged_ledger_hash)
+    }
+
+    #[test]
+    fn genesis_pending_coinbase_hash() -> anyhow::Result<()> {
+        // All stacks of the genesis pending coinbase are empty
+        let expected = staged_ledger_hash(&GENESIS_BLOCK_MAINNET)?;
+        assert_eq!(
+            PendingCoinbaseCollection::default().merkle_root(),
+            expected.pending_coinbase_hash
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rebuild_pending_coinbase_stack_positions() -> anyhow::Result<()> {
+        // Block 77748 has 24 stacks in use, 13 to 0 then 31 to 22, and 14 is the next new stack.
+        // The stacks themselves are pushed by earlier blocks, so its merkle root cannot be
+        // rebuilt from the fixtures, but the positions that make up the aux hash can
+        let block_77748 = TEST_BLOCKS
+            .get("3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK.hex")
+            .expect("Failed to load block");
+        let block_77749 = TEST_BLOCKS
+            .get("3NK3P5bJHhqR7xkZBquGGfq3sERUeXNYNma5YXRMjgCNsTJRZpgL.hex")
+            .expect("Failed to load block");
+
+        let mut collection = PendingCoinbaseCollection::default();
+        let state_body_hash: StateBodyHash = (&Fp::from(1_u64)).into();
+        for _ in 0..22 {
+            collection.add_state(&state_body_hash, true)?;
+        }
+        for _ in 0..22 {
+            collection.remove_coinbase_stack()?;
+        }
+        for _ in 0..24 {
+            collection.add_state(&state_body_hash, true)?;
+        }
+        let expected: Vec<usize> = (0..14).rev().chain((22..32).rev()).collect();
+        assert_eq!(collection.pos_list(), expected.as_slice());
+        assert_eq!(collection.new_pos(), 14);
+        assert_eq!(
+            collection.hash_extra(),
+            staged_ledger_hash(block_77748)?
+                .non_snark
+                .pending_coinbase_aux
+        );
+
+        // Block 77749 pushes its coinbase and state onto the newest stack
+        collection.add_coinbase(&coinbase(), false)?;
+        collection.add_state(&state_body_hash, false)?;
+        assert_eq!(
+            collection.hash_extra(),
+            staged_ledger_hash(block_77749)?
+                .non_snark
+                .pending_coinbase_aux
+        );
+        Ok(())
+    }
+}
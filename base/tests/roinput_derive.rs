@@ -0,0 +1,288 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Regression tests that compare the derived random oracle inputs
+//! with the hand-written implementations they replace
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use mina_crypto::hash::*;
+    use mina_rs_base::{account::timing::TimedData, types::*};
+    use proof_systems::{
+        mina_hasher::{create_kimchi, create_legacy, Hashable, Hasher, ROInput},
+        mina_signer::CompressedPubKey,
+        ChunkedROInput, ToChunkedROInput,
+    };
+
+    struct Reference<'a, T>(&'a T);
+
+    fn constants() -> ProtocolConstants {
+        ProtocolConstants {
+            k: 290.into(),
+            delta: 3.into(),
+            slots_per_epoch: 7140.into(),
+            slots_per_sub_window: 7.into(),
+            genesis_state_timestamp: 1655755201000.into(),
+        }
+    }
+
+    fn timed_data() -> TimedData {
+        TimedData {
+            initial_minimum_balance: Amount(1000),
+            cliff_time: BlockTime(u32::MAX as u64 + 7),
+            cliff_amount: Amount(300),
+            vesting_period: BlockTime(86400000),
+            vesting_increment: Amount(5),
+        }
+    }
+
+    fn permissions() -> Permissions {
+        Permissions {
+            edit_state: AuthRequired::Signature,
+            send: AuthRequired::Either,
+            receive: AuthRequired::None,
+            set_delegate: AuthRequired::Proof,
+            set_permissions: AuthRequired::Both,
+            set_verification_key: AuthRequired::Impossible,
+            set_zkapp_uri: AuthRequired::Signature,
+            edit_sequence_state: AuthRequired::Proof,
+            set_token_symbol: AuthRequired::Signature,
+            increment_nonce: AuthRequired::Either,
+            set_voting_for: AuthRequired::None,
+        }
+    }
+
+    fn account() -> anyhow::Result<Account> {
+        let mut token_symbol = [0; 32];
+        token_symbol[..4].copy_from_slice(b"MINA");
+        Ok(Account {
+            public_key: CompressedPubKey::from_address(
+                "B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g",
+            )
+            .unwrap(),
+            token_id: TokenId(1),
+            token_permissions: TokenPermissions::NotOwned {
+                account_disabled: false,
+            },
+            token_symbol: token_symbol.into(),
+            balance: Amount(720000000000),
+            nonce: AccountNonce(42),
+            receipt_chain_hash: ChainHash::default(),
+            delegate: Some(
+                CompressedPubKey::from_address(
+                    "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+                )
+                .unwrap(),
+            ),
+            voting_for: StateHash::from_str(
+                "3NK9fHpzfPWhuxFhQ9Dau1X1JWtstB6kGC4xrurSPU1kctMCsU9U",
+            )?,
+            timing: Timing::Timed(timed_data()),
+            permissions: permissions(),
+            zkapp: None,
+            zkapp_uri: None,
+        })
+    }
+
+    impl ToChunkedROInput for Reference<'_, ProtocolConstants> {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new()
+                .append_chunked(&self.0.k)
+                .append_chunked(&self.0.delta)
+                .append_chunked(&self.0.slots_per_epoch)
+                .append_chunked(&self.0.slots_per_sub_window)
+                .append_chunked(&self.0.genesis_state_timestamp)
+        }
+    }
+
+    impl Hashable for Reference<'_, ProtocolConstants> {
+        type D = ();
+
+        fn to_roinput(&self) -> ROInput {
+            ROInput::new()
+                .append_hashable(&self.0.k)
+                .append_hashable(&self.0.delta)
+                .append_hashable(&self.0.slots_per_epoch)
+                .append_hashable(&self.0.slots_per_sub_window)
+                .append_hashable(&self.0.genesis_state_timestamp)
+        }
+
+        fn domain_string(_: Self::D) -> Option<String> {
+            None
+        }
+    }
+
+    impl ToChunkedROInput for Reference<'_, ProtocolStateBody> {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new()
+                .append_chunked(&self.0.constants)
+                .append_chunked(&self.0.genesis_state_hash)
+                .append_chunked(&self.0.blockchain_state)
+                .append_chunked(&self.0.consensus_state)
+        }
+    }
+
+    impl Hashable for Reference<'_, ProtocolStateBody> {
+        type D = ();
+
+        fn to_roinput(&self) -> ROInput {
+            self.roinput()
+        }
+
+        fn domain_string(_: Self::D) -> Option<String> {
+            Some("CodaProtoStateBody".into())
+        }
+    }
+
+    impl Hashable for Reference<'_, ProtocolStateBodyLegacy> {
+        type D = ();
+
+        fn to_roinput(&self) -> ROInput {
+            ROInput::new()
+                .append_hashable(&self.0.constants)
+                .append_hashable(&self.0.genesis_state_hash)
+                .append_hashable(&self.0.blockchain_state)
+                .append_hashable(&self.0.consensus_state)
+        }
+
+        fn domain_string(_: Self::D) -> Option<String> {
+            Some("CodaProtoStateBody".into())
+        }
+    }
+
+    impl ToChunkedROInput for Reference<'_, TimedData> {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new()
+                .append_chunked(&self.0.initial_minimum_balance)
+                .append_u32(self.0.cliff_time.0 as u32)
+                .append_chunked(&self.0.cliff_amount)
+                .append_u32(self.0.vesting_period.0 as u32)
+                .append_chunked(&self.0.vesting_increment)
+        }
+    }
+
+    impl ToChunkedROInput for Reference<'_, Permissions> {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new()
+                .append_chunked(&self.0.edit_state)
+                .append_chunked(&self.0.send)
+                .append_chunked(&self.0.receive)
+                .append_chunked(&self.0.set_delegate)
+                .append_chunked(&self.0.set_permissions)
+                .append_chunked(&self.0.set_verification_key)
+                .append_chunked(&self.0.set_zkapp_uri)
+                .append_chunked(&self.0.edit_sequence_state)
+                .append_chunked(&self.0.set_token_symbol)
+                .append_chunked(&self.0.increment_nonce)
+                .append_chunked(&self.0.set_voting_for)
+        }
+    }
+
+    impl ToChunkedROInput for Reference<'_, Account> {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new()
+                .append_chunked(&ZkAppUriOptionHashableWrapper(&self.0.zkapp_uri))
+                .append_chunked(&ZkAppOptionHashableWrapper(&self.0.zkapp))
+                .append_chunked(&self.0.permissions)
+                .append_chunked(&self.0.timing)
+                .append_chunked(&self.0.voting_for)
+                .append_chunked(&CompressedPubKeyOptionHashableWrapper(&self.0.delegate))
+                .append_chunked(&self.0.receipt_chain_hash)
+                .append_chunked(&self.0.nonce)
+                .append_chunked(&self.0.balance)
+                .append_chunked(&self.0.token_symbol)
+                .append_chunked(&self.0.token_permissions)
+                .append_chunked(&self.0.token_id)
+                .append_chunked(&CompressedPubKeyHashableWrapper(&self.0.public_key))
+        }
+    }
+
+    impl Hashable for Reference<'_, Account> {
+        type D = ();
+
+        fn to_roinput(&self) -> ROInput {
+            self.roinput()
+        }
+
+        fn domain_string(_: Self::D) -> Option<String> {
+            Some("CodaAccount".into())
+        }
+    }
+
+    #[test]
+    fn test_protocol_constants() {
+        for constants in [ProtocolConstants::default(), constants()] {
+            assert_eq!(
+                constants.to_chunked_roinput(),
+                Reference(&constants).to_chunked_roinput()
+            );
+            assert_eq!(
+                create_legacy(()).hash(&constants),
+                create_legacy(()).hash(&Reference(&constants))
+            );
+        }
+    }
+
+    #[test]
+    fn test_protocol_state_body() {
+        let body = ProtocolStateBody {
+            constants: constants(),
+            ..Default::default()
+        };
+        assert_eq!(
+            body.to_chunked_roinput(),
+            Reference(&body).to_chunked_roinput()
+        );
+        assert_eq!(
+            create_kimchi(()).hash(&body),
+            create_kimchi(()).hash(&Reference(&body))
+        );
+    }
+
+    #[test]
+    fn test_protocol_state_body_legacy() {
+        let body = ProtocolStateBodyLegacy {
+            constants: constants(),
+            ..Default::default()
+        };
+        assert_eq!(
+            create_legacy(()).hash(&body),
+            create_legacy(()).hash(&Reference(&body))
+        );
+    }
+
+    #[test]
+    fn test_timed_data() {
+        for timed in [TimedData::default(), timed_data()] {
+            assert_eq!(
+                timed.to_chunked_roinput(),
+                Reference(&timed).to_chunked_roinput()
+            );
+        }
+    }
+
+    #[test]
+    fn test_permissions() {
+        let permissions = permissions();
+        assert_eq!(
+            permissions.to_chunked_roinput(),
+            Reference(&permissions).to_chunked_roinput()
+        );
+    }
+
+    #[test]
+    fn test_account() -> anyhow::Result<()> {
+        let account = account()?;
+        assert_eq!(
+            account.to_chunked_roinput(),
+            Reference(&account).to_chunked_roinput()
+        );
+        assert_eq!(
+            create_kimchi(()).hash(&account),
+            create_kimchi(()).hash(&Reference(&account))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use mina_crypto::hash::*;
+    use mina_rs_base::types::*;
+    use mina_serialization_types::{scan_state::ParallelScanState, v1::TransactionScanStateV1};
+    use proof_systems::{mina_hasher::Fp, mina_signer::CompressedPubKey};
+
+    // Base jobs are numbers and completed works are sums of the numbers they cover
+    type SumScanState = ParallelScan<u64, u64>;
+
+    fn complete(jobs: &[Vec<AvailableJob<u64, u64>>]) -> Vec<u64> {
+        jobs.iter()
+            .flatten()
+            .map(|job| match job {
+                AvailableJob::Base(b) => *b,
+                AvailableJob::Merge(l, r) => l + r,
+            })
+            .collect()
+    }
+
+    // Adds the data along with the completed works the scan state requires for them
+    fn update(state: &mut SumScanState, data: Vec<u64>) -> anyhow::Result<Option<(u64, Vec<u64>)>> {
+        let works = complete(&state.work_for_next_update(data.len()));
+        Ok(state.update(data, works)?)
+    }
+
+    #[test]
+    fn scan_state_emits_sum() -> anyhow::Result<()> {
+        let mut state = SumScanState::new(2, 1);
+        assert_eq!(state.max_base_jobs(), 4);
+        assert_eq!(state.max_trees(), 7);
+        assert_eq!(state.free_space(), 4);
+
+        assert_eq!(update(&mut state, vec![1, 2, 3, 4])?, None);
+        assert_eq!(state.free_space(), 4);
+        assert_eq!(state.trees().count(), 2);
+        // No work is required until the delay has passed
+        assert!(state.work_for_next_update(4).is_empty());
+
+        assert_eq!(update(&mut state, vec![5, 6])?, None);
+        assert_eq!(update(&mut state, vec![7, 8])?, None);
+        assert_eq!(
+            state.work_for_next_update(2),
+            vec![vec![
+                AvailableJob::Base(1),
+                AvailableJob::Base(2),
+                AvailableJob::Base(3),
+                AvailableJob::Base(4),
+            ]],
+        );
+
+        let mut emitted = None;
+        for i in 9..100 {
+            if let Some(value) = update(&mut state, vec![i])? {
+                emitted = Some(value);
+                break;
+            }
+        }
+        assert_eq!(emitted, Some((10, vec![1, 2, 3, 4])));
+        assert_eq!(state.last_emitted_value(), Some(&(10, vec![1, 2, 3, 4])));
+        assert!(state.trees().count() <= state.max_trees());
+        Ok(())
+    }
+
+    #[test]
+    fn scan_state_emits_in_order() -> anyhow::Result<()> {
+        let mut state = SumScanState::new(3, 2);
+        let mut next = 0;
+        let mut emitted = Vec::new();
+        for _ in 0..200 {
+            let data = (next..next + 8).collect();
+            next += 8;
+            if let Some(value) = update(&mut state, data)? {
+                emitted.push(value);
+            }
+            assert!(state.trees().count() <= state.max_trees());
+        }
+        assert!(emitted.len() > 2);
+        for (i, (sum, data)) in emitted.into_iter().enumerate() {
+            let expected: Vec<_> = (8 * i as u64..8 * (i as u64 + 1)).collect();
+            assert_eq!(sum, expected.iter().sum::<u64>());
+            assert_eq!(data, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn too_much_data() {
+        let mut state = SumScanState::new(2, 1);
+        assert_eq!(
+            state.update(vec![1, 2, 3, 4, 5], vec![]),
+            Err(ScanStateError::TooMuchData {
+                available: 4,
+                actual: 5
+            })
+        );
+    }
+
+    #[test]
+    fn failed_update_leaves_state_unchanged() -> anyhow::Result<()> {
+        let mut state = SumScanState::new(2, 1);
+        for data in [vec![1, 2, 3, 4], vec![5, 6, 7, 8]] {
+            update(&mut state, data)?;
+        }
+        let before = state.clone();
+        assert_eq!(
+            state.update(vec![9, 10, 11, 12], vec![]),
+            Err(ScanStateError::InsufficientWork {
+                required: 4,
+                actual: 0
+            })
+        );
+        assert_eq!(state, before);
+        Ok(())
+    }
+
+    #[test]
+    fn too_many_completed_works() -> anyhow::Result<()> {
+        let mut state = SumScanState::new(2, 1);
+        assert_eq!(
+            state.update(vec![], vec![1]),
+            Err(ScanStateError::TooManyCompletedWorks {
+                available: 0,
+                actual: 1
+            })
+        );
+        assert_eq!(state, SumScanState::new(2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn scan_state_hash_changes_with_updates() -> anyhow::Result<()> {
+        let f = |v: &u64| v.to_le_bytes().to_vec();
+        let mut state = SumScanState::new(2, 1);
+        let empty_hash = state.hash(f, f);
+        assert_eq!(empty_hash, SumScanState::new(2, 1).hash(f, f));
+        assert_ne!(empty_hash, SumScanState::new(2, 0).hash(f, f));
+        update(&mut state, vec![1])?;
+        let hash = state.hash(f, f);
+        assert_ne!(hash, empty_hash);
+        // Weights are hashed along with the jobs
+        update(&mut state, vec![])?;
+        assert_ne!(state.hash(f, f), hash);
+        Ok(())
+    }
+
+    #[test]
+    fn scan_state_snapshot_roundtrip() -> anyhow::Result<()> {
+        let mut state = SumScanState::new(2, 1);
+        for i in 0..10 {
+            update(&mut state, vec![3 * i, 3 * i + 1, 3 * i + 2])?;
+        }
+        assert!(state.last_emitted_value().is_some());
+
+        let snapshot: ParallelScanState<u64, u64> = state.clone().into();
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, &snapshot)?;
+        let decoded: ParallelScanState<u64, u64> = bin_prot::from_reader_strict(bytes.as_slice())?;
+        assert_eq!(decoded, snapshot);
+
+        let mut loaded = SumScanState::try_from(decoded)?;
+        assert_eq!(loaded, state);
+        update(&mut loaded, vec![100])?;
+        update(&mut state, vec![100])?;
+        assert_eq!(loaded, state);
+        Ok(())
+    }
+
+    // FIXME: Also load a scan state snapshot of a mainnet daemon and check its aux hash
+    // against the staged_ledger_aux_hash of the matching block, no snapshot fixture is available yet
+    #[test]
+    fn transaction_scan_state_snapshot_roundtrip() -> anyhow::Result<()> {
+        let state = TransactionScanState::default();
+        assert_eq!(state.max_base_jobs(), 128);
+        assert_eq!(state.max_trees(), 25);
+        assert!(state.jobs().is_empty());
+
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, &TransactionScanStateV1::from(state.clone()))?;
+        let decoded: TransactionScanStateV1 = bin_prot::from_reader_strict(bytes.as_slice())?;
+        let loaded = TransactionScanState::try_from(decoded)?;
+        assert_eq!(loaded.aux_hash(), state.aux_hash());
+
+        let mut reencoded = Vec::new();
+        bin_prot::to_writer(&mut reencoded, &TransactionScanStateV1::from(loaded))?;
+        assert_eq!(reencoded, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn scan_state_snapshot_invalid_size() {
+        let snapshot: ParallelScanState<u64, u64> = SumScanState::new(2, 1).into();
+        for max_base_jobs in [0, 3, 8] {
+            let invalid = ParallelScanState {
+                max_base_jobs,
+                ..snapshot.clone()
+            };
+            let expected = if max_base_jobs == 8 {
+                ScanStateError::InvalidTreeSize(0)
+            } else {
+                ScanStateError::InvalidMaxBaseJobs(max_base_jobs)
+            };
+            assert_eq!(SumScanState::try_from(invalid), Err(expected));
+        }
+    }
+
+    fn prover() -> CompressedPubKey {
+        CompressedPubKey::from_address("B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g")
+            .unwrap()
+    }
+
+    // Statement of the transition from ledger `i` to ledger `i + 1`
+    fn statement(i: u64) -> Statement {
+        Statement {
+            source: (&Fp::from(i)).into(),
+            target: (&Fp::from(i + 1)).into(),
+            supply_increase: Amount(0),
+            pending_coinbase_stack_state: PendingCoinbaseStackState {
+                source: PendingCoinbase::empty(),
+                target: PendingCoinbase::empty(),
+            },
+            fee_excess: FeeExcessPair(FeeExcess::zero(), FeeExcess::zero()),
+            next_available_token_before: TokenId(2),
+            next_available_token_after: TokenId(2),
+            sok_digest: ByteVec(vec![]),
+        }
+    }
+
+    fn transaction(i: u64) -> TransactionWithWitness {
+        let coinbase = CoinbaseUndo {
+            coinbase: Coinbase {
+                receiver: prover(),
+                amount: Amount(720000000000),
+                fee_transfer: None,
+            },
+            previous_empty_accounts: vec![],
+            receiver_timing: Timing::default(),
+            balances: CoinBaseBalanceData {
+                coinbase_receiver_balance: Amount(720000000000),
+                fee_transfer_receiver_balance: None,
+            },
+        };
+        TransactionWithWitness {
+            transaction_with_info: TransactionUndo {
+                previous_hash: LedgerHash::default(),
+                varying: TransactionUndoVarying::Coinbase(coinbase),
+            },
+            state_hash: (StateHash::default(), StateBodyHash::default()),
+            statement: statement(i),
+            init_stack: InitStack::Merge,
+            ledger_witness: SparseLedger {
+                indexes: vec![],
+                depth: 20,
+                tree: SparseLedgerTree::Hash(LedgerHash::default()),
+                next_available_token: TokenId(2),
+            },
+        }
+    }
+
+    fn work(statements: &[u64]) -> TransactionSnarkWork {
+        let mut proofs = statements.iter().map(|i| {
+            Box::new(TransactionSnark {
+                statement: Statement {
+                    sok_digest: ByteVec(vec![1, 2, 3]),
+                    ..statement(*i)
+                },
+                transaction_snark_proof: ProtocolStateProof::default(),
+            })
+        });
+        let proofs = match (proofs.next(), proofs.next()) {
+            (Some(p1), Some(p2)) => OneORTwo::Two(p1, p2),
+            (Some(p), None) => OneORTwo::One(p),
+            _ => unreachable!(),
+        };
+        TransactionSnarkWork {
+            fee: Amount(10),
+            proofs,
+            prover: prover(),
+        }
+    }
+
+    #[test]
+    fn completed_works_match_required_statements() -> anyhow::Result<()> {
+        let mut state = TransactionScanState::new(2, 0);
+        state.update((0..4).map(transaction).collect(), vec![])?;
+        assert_eq!(
+            state.required_statements(4)?,
+            vec![
+                vec![statement(0), statement(1)],
+                vec![statement(2), statement(3)]
+            ]
+        );
+        assert_eq!(state.required_statements(1)?.len(), 1);
+
+        state.validate_completed_works(&[work(&[0, 1]), work(&[2, 3])], 4)?;
+        state.validate_completed_works(&[work(&[0, 1])], 4)?;
+
+        // Works have to complete the first jobs in the order of the jobs
+        assert_eq!(
+            state.validate_completed_works(&[work(&[2, 3]), work(&[0, 1])], 4),
+            Err(SnarkWorkError::StatementMismatch(0))
+        );
+        assert_eq!(
+            state.validate_completed_works(&[work(&[2, 3])], 4),
+            Err(SnarkWorkError::StatementMismatch(0))
+        );
+        assert_eq!(
+            state.validate_completed_works(&[work(&[0, 1]), work(&[1, 0])], 4),
+            Err(SnarkWorkError::StatementMismatch(1))
+        );
+        assert_eq!(
+            state.validate_completed_works(&[work(&[0, 1]), work(&[0, 1])], 4),
+            Err(SnarkWorkError::StatementMismatch(1))
+        );
+        assert_eq!(
+            state.validate_completed_works(&[work(&[0, 1]), work(&[2, 3])], 1),
+            Err(SnarkWorkError::TooManyCompletedWorks {
+                required: 1,
+                actual: 2
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_pre_diff_rejects_mismatched_works() -> anyhow::Result<()> {
+        let mut state = TransactionScanState::new(2, 0);
+        state.update((0..4).map(transaction).collect(), vec![])?;
+
+        let pre_diff = StagedLedgerPreDiff {
+            completed_works: vec![work(&[2, 3]), work(&[0, 1])],
+            ..Default::default()
+        };
+        assert_eq!(
+            state
+                .apply_pre_diff(&pre_diff, (4..8).map(transaction).collect())
+                .unwrap_err(),
+            ScanStateError::InvalidCompletedWorks(SnarkWorkError::StatementMismatch(0))
+        );
+
+        // the works are validated before the state is updated
+
+        let pre_diff = StagedLedgerPreDiff {
+            completed_works: vec![work(&[0, 1]), work(&[2, 3])],
+            ..Default::default()
+        };
+        state.apply_pre_diff(&pre_diff, (4..8).map(transaction).collect())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use mina_rs_base::types::*;
+    use proof_systems::{mina_hasher::Fp, mina_signer::CompressedPubKey};
+
+    fn pos(v: u64) -> Signed {
+        Signed::new(Amount(v), SgnType::Pos)
+    }
+
+    fn neg(v: u64) -> Signed {
+        Signed::new(Amount(v), SgnType::Neg)
+    }
+
+    fn excess(token: u64, amount: Signed) -> FeeExcess {
+        FeeExcess {
+            token: TokenId(token),
+            amount,
+        }
+    }
+
+    fn statement(source: u64, target: u64, fee_excess: FeeExcessPair) -> Statement {
+        Statement {
+            source: (&Fp::from(source)).into(),
+            target: (&Fp::from(target)).into(),
+            supply_increase: Amount(10),
+            pending_coinbase_stack_state: PendingCoinbaseStackState {
+                source: PendingCoinbase::empty(),
+                target: PendingCoinbase::empty(),
+            },
+            fee_excess,
+            next_available_token_before: TokenId(2),
+            next_available_token_after: TokenId(2),
+            sok_digest: ByteVec(vec![1, 2, 3]),
+        }
+    }
+
+    #[test]
+    fn signed_arithmetic() {
+        assert_eq!(pos(3).add(&neg(5)), Ok(neg(2)));
+        assert_eq!(neg(3).add(&neg(5)), Ok(neg(8)));
+        assert_eq!(pos(5).add(&neg(5)), Ok(Signed::zero()));
+        assert_eq!(neg(5).negate(), pos(5));
+        assert_eq!(neg(0).negate(), Signed::zero());
+        assert!(neg(0).is_zero());
+        assert_eq!(
+            pos(u64::MAX).add(&pos(1)),
+            Err(SnarkWorkError::FeeExcessOverflow)
+        );
+    }
+
+    #[test]
+    fn fee_excess_rebalance() {
+        let pair = FeeExcessPair(excess(1, Signed::zero()), excess(5, pos(3)));
+        assert_eq!(
+            pair.rebalance(),
+            Ok(FeeExcessPair(excess(5, pos(3)), FeeExcess::zero()))
+        );
+
+        let pair = FeeExcessPair(excess(5, pos(3)), excess(5, neg(1)));
+        assert_eq!(
+            pair.rebalance(),
+            Ok(FeeExcessPair(excess(5, pos(2)), FeeExcess::zero()))
+        );
+    }
+
+    #[test]
+    fn fee_excess_combine() {
+        let first = FeeExcessPair(excess(1, pos(10)), FeeExcess::zero());
+        let second = FeeExcessPair(excess(1, neg(4)), FeeExcess::zero());
+        assert_eq!(
+            first.combine(&second),
+            Ok(FeeExcessPair(excess(1, pos(6)), FeeExcess::zero()))
+        );
+
+        let first = FeeExcessPair(excess(1, pos(10)), excess(2, pos(1)));
+        let second = FeeExcessPair(excess(2, neg(1)), excess(3, pos(1)));
+        assert_eq!(
+            first.combine(&second),
+            Ok(FeeExcessPair(excess(1, pos(10)), excess(3, pos(1))))
+        );
+
+        let first = FeeExcessPair(excess(1, pos(10)), excess(2, pos(1)));
+        let second = FeeExcessPair(excess(3, pos(1)), excess(4, pos(1)));
+        assert_eq!(
+            first.combine(&second),
+            Err(SnarkWorkError::FeeExcessNotEliminated(2))
+        );
+    }
+
+    #[test]
+    fn statement_merge() {
+        let s1 = statement(1, 2, FeeExcessPair(excess(1, pos(10)), FeeExcess::zero()));
+        let s2 = statement(2, 3, FeeExcessPair(excess(1, neg(4)), FeeExcess::zero()));
+        let merged = s1.merge(&s2).unwrap();
+        assert_eq!(merged.source, s1.source);
+        assert_eq!(merged.target, s2.target);
+        assert_eq!(merged.supply_increase, Amount(20));
+        assert_eq!(
+            merged.fee_excess,
+            FeeExcessPair(excess(1, pos(6)), FeeExcess::zero())
+        );
+        assert!(merged.sok_digest.0.is_empty());
+        assert!(!merged.eq_without_sok_digest(&s1));
+
+        assert_eq!(s2.merge(&s1), Err(SnarkWorkError::LedgerHashMismatch));
+
+        let mut s3 = statement(2, 3, FeeExcessPair::default());
+        s3.next_available_token_before = TokenId(3);
+        assert_eq!(
+            s1.merge(&s3),
+            Err(SnarkWorkError::NextAvailableTokenMismatch)
+        );
+
+        let mut s4 = statement(2, 3, FeeExcessPair::default());
+        s4.pending_coinbase_stack_state
+            .source
+            .push_coinbase(&CoinbaseData {
+                receiver: CompressedPubKey::empty(),
+                amount: Amount(1),
+            });
+        assert_eq!(
+            s1.merge(&s4),
+            Err(SnarkWorkError::PendingCoinbaseStackDisconnected)
+        );
+    }
+}
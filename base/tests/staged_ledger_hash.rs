@@ -0,0 +1,105 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use mina_crypto::hash::*;
+    use mina_rs_base::types::*;
+    use std::str::FromStr;
+    use test_fixtures::*;
+
+    #[test]
+    fn genesis_dummy_aux_hashes() {
+        assert_eq!(
+            AuxHash::dummy().to_string(),
+            "UDRUFHSvxUAtV8sh7gzMVPqpbd46roG1wzWR6dYvB6RunPihom"
+        );
+        assert_eq!(
+            PendingCoinbaseAuxHash::dummy().to_string(),
+            "WAAeUjUnP9Q2JiabhJzJozcjiEmkZe8ob4cfFKSuq6pQSNmHh7"
+        );
+    }
+
+    #[test]
+    fn pending_coinbase_aux_hash() -> anyhow::Result<()> {
+        // Pending coinbase aux hash of mainnet block 3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK
+        // whose pending coinbase stacks are 13 to 0 and 31 to 22, next new stack is 14
+        let mut collection = PendingCoinbaseCollection::default();
+        let state_body_hash = StateBodyHash::default();
+        for _ in 0..22 {
+            collection.add_state(&state_body_hash, true)?;
+        }
+        for _ in 0..22 {
+            collection.remove_coinbase_stack()?;
+        }
+        for _ in 0..24 {
+            collection.add_state(&state_body_hash, true)?;
+        }
+        assert_eq!(collection.new_pos(), 14);
+        assert_eq!(
+            collection.hash_extra().to_string(),
+            "XbwfEKZjgcZiyDhHRZjHUx72TuxpnuzLPwVYpVWkMAAXkSy7go"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn verify_staged_ledger_hash_components() -> anyhow::Result<()> {
+        let ledger_hash =
+            LedgerHash::from_str("jwD5Kx1GtLKJGSWufhkvCn8m7EFLm2LmAM7neyzLtTiN8wyn2po")?;
+        let scan_state = TransactionScanState::default();
+        let pending_coinbase = PendingCoinbaseCollection::default();
+        let staged_ledger_hash =
+            compute_staged_ledger_hash(ledger_hash.clone(), &scan_state, &pending_coinbase);
+        assert_eq!(staged_ledger_hash.non_snark.aux_hash, scan_state.aux_hash());
+        assert_eq!(
+            staged_ledger_hash.non_snark.pending_coinbase_aux,
+            pending_coinbase.hash_extra()
+        );
+        assert_eq!(
+            staged_ledger_hash.pending_coinbase_hash,
+            pending_coinbase.merkle_root()
+        );
+
+        let blockchain_state = BlockchainStateLegacy {
+            staged_ledger_hash,
+            ..Default::default()
+        };
+        blockchain_state.verify_staged_ledger_hash(
+            ledger_hash.clone(),
+            &scan_state,
+            &pending_coinbase,
+        )?;
+        let err = blockchain_state
+            .verify_staged_ledger_hash(
+                ledger_hash,
+                &TransactionScanState::new(TRANSACTION_CAPACITY_LOG_2, 0),
+                &pending_coinbase,
+            )
+            .unwrap_err();
+        assert!(matches!(err, StagedLedgerHashError::AuxHash { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn genesis_staged_ledger_hash_fixture() -> anyhow::Result<()> {
+        // The ledger is not rebuilt, so the ledger hash is taken from the block.
+        // Later blocks need a scan state snapshot to rebuild their aux hashes
+        let block = ExternalTransition::from(GENESIS_BLOCK_MAINNET.external_transitionv1()?);
+        let expected = &block
+            .protocol_state
+            .body
+            .blockchain_state
+            .staged_ledger_hash;
+        let computed = genesis_staged_ledger_hash(
+            expected.non_snark.ledger_hash.clone(),
+            &PendingCoinbaseCollection::default(),
+        );
+        verify_staged_ledger_hash(expected, &computed)?;
+        assert_ne!(
+            expected.non_snark.aux_hash,
+            TransactionScanState::default().aux_hash()
+        );
+        Ok(())
+    }
+}
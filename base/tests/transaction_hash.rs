@@ -0,0 +1,75 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use mina_crypto::{base58::Base58Encodable, hash::TransactionHash};
+    use mina_rs_base::types::ExternalTransition;
+    use mina_rs_base::user_commands::{SignedCommand, UserCommand};
+    use test_fixtures::*;
+
+    fn signed_commands(block: &ExternalTransition) -> Vec<SignedCommand> {
+        let diff = &block.staged_ledger_diff.diff;
+        std::iter::once(diff.diff_two())
+            .chain(diff.diff_one())
+            .flat_map(|pre_diff| pre_diff.commands.iter())
+            .map(|cmd| match &cmd.data {
+                UserCommand::SignedCommand(sc) => sc.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_signed_command_hash_all_binprot_fixtures() -> anyhow::Result<()> {
+        let (mut commands, mut hashes) = (HashSet::new(), HashSet::new());
+        for (_, v) in TEST_BLOCKS.iter() {
+            let block = ExternalTransition::from(v.external_transitionv1()?);
+            for command in signed_commands(&block) {
+                let hash = command.hash();
+                assert_eq!(hash, command.hash());
+                assert_eq!(
+                    SignedCommand::from_base58(command.to_base58_string())?,
+                    command
+                );
+
+                let s = hash.to_string();
+                assert!(s.starts_with("Ckp"), "unexpected transaction hash {s}");
+                assert_eq!(s.len(), 53);
+                assert_eq!(TransactionHash::from_str(&s)?, hash);
+
+                commands.insert(format!("{command:?}"));
+                hashes.insert(s);
+            }
+        }
+        assert!(!hashes.is_empty());
+        assert_eq!(hashes.len(), commands.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_signed_command_hash_regression() -> anyhow::Result<()> {
+        let block = TEST_BLOCKS
+            .get("3NK9fHpzfPWhuxFhQ9Dau1X1JWtstB6kGC4xrurSPU1kctMCsU9U.hex")
+            .expect("Failed to load block");
+        let block = ExternalTransition::from(block.external_transitionv1()?);
+        let hashes: Vec<_> = signed_commands(&block)
+            .iter()
+            .map(|c| c.hash().to_string())
+            .collect();
+        // These are the ids computed by this implementation, they guard against regressions
+        // FIXME: Check them against the user command hashes that a block explorer or the daemon
+        // reports for block 3NK9fHpzfPWhuxFhQ9Dau1X1JWtstB6kGC4xrurSPU1kctMCsU9U
+        assert_eq!(
+            hashes,
+            vec![
+                "Ckpa5n9at846LmQyoVYv4JTSKyxuJ6yVfa8uYjxPXny5RqR8uUkn5",
+                "CkpYjp4E1XuTZ8BnT8qiLkCiPub4TxDFuxhYB9joYLjTR2Sdx68h5",
+                "CkpYV9trsipUY2GiRxaZT8DCfn1iFdFdYJSJkw6ALrSCXiAnn1whL",
+            ]
+        );
+        Ok(())
+    }
+}
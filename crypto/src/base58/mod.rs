@@ -0,0 +1,396 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Registry of every kind of value Mina encodes with Base58Check and a trait for types that use it
+//!
+//! The first byte of the decoded string is a version byte that identifies the kind of value
+//! (see [mina_serialization_types::version_bytes]). Decoding checks it against the kind that was
+//! expected and, when it does not match, reports what the string looks like instead.
+//!
+//! zkApp accounts have no encoding of their own, they are identified by a public key and a token id.
+//! Token ids are not in the registry either, `TokenId` of mina-rs-base is a v1 token id which is
+//! encoded as a decimal integer. The kinds of the v2 types (token id keys, zkApp commands and
+//! verification keys) are out of scope for now.
+//!
+
+use std::fmt;
+
+use mina_serialization_types::{
+    field_and_curve_elements::{FieldElement, InnerCurveScalar},
+    signatures::{SignatureJson, SignatureV1},
+    v1::PublicKeyV1,
+    version_bytes,
+};
+use proof_systems::mina_signer::{CompressedPubKey, ScalarField, SecKey, Signature};
+use proof_systems::o1_utils::FieldHelpers;
+use versioned::Versioned;
+
+/// Every kind of value Mina encodes with Base58Check
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Base58Kind {
+    LedgerHash,
+    CoinbaseHash,
+    ReceiptChainHash,
+    EpochSeed,
+    StagedLedgerHashAuxHash,
+    StagedLedgerHashPendingCoinbaseAux,
+    StateHash,
+    StateBodyHash,
+    TransactionHash,
+    SignedCommandV1,
+    UserCommandMemo,
+    VrfTruncatedOutput,
+    CoinbaseStackData,
+    CoinbaseStackHash,
+    PrivateKey,
+    Signature,
+    PublicKey,
+}
+
+impl Base58Kind {
+    /// All kinds in the registry, ordered by version byte
+    pub const ALL: &'static [Base58Kind] = &[
+        Base58Kind::LedgerHash,
+        Base58Kind::CoinbaseHash,
+        Base58Kind::ReceiptChainHash,
+        Base58Kind::EpochSeed,
+        Base58Kind::StagedLedgerHashAuxHash,
+        Base58Kind::StagedLedgerHashPendingCoinbaseAux,
+        Base58Kind::StateHash,
+        Base58Kind::StateBodyHash,
+        Base58Kind::TransactionHash,
+        Base58Kind::SignedCommandV1,
+        Base58Kind::UserCommandMemo,
+        Base58Kind::VrfTruncatedOutput,
+        Base58Kind::CoinbaseStackData,
+        Base58Kind::CoinbaseStackHash,
+        Base58Kind::PrivateKey,
+        Base58Kind::Signature,
+        Base58Kind::PublicKey,
+    ];
+
+    /// The version byte that prefixes values of this kind
+    pub const fn version_byte(self) -> u8 {
+        match self {
+            Self::LedgerHash => version_bytes::LEDGER_HASH,
+            Self::CoinbaseHash => version_bytes::COINBASE_HASH,
+            Self::ReceiptChainHash => version_bytes::RECEIPT_CHAIN_HASH,
+            Self::EpochSeed => version_bytes::EPOCH_SEED,
+            Self::StagedLedgerHashAuxHash => version_bytes::STAGED_LEDGER_HASH_AUX_HASH,
+            Self::StagedLedgerHashPendingCoinbaseAux => {
+                version_bytes::STAGED_LEDGER_HASH_PENDING_COINBASE_AUX
+            }
+            Self::StateHash => version_bytes::STATE_HASH,
+            Self::StateBodyHash => version_bytes::STATE_BODY_HASH,
+            Self::TransactionHash => version_bytes::TRANSACTION_HASH,
+            Self::SignedCommandV1 => version_bytes::SIGNED_COMMAND_V1,
+            Self::UserCommandMemo => version_bytes::USER_COMMAND_MEMO,
+            Self::VrfTruncatedOutput => version_bytes::VRF_TRUNCATED_OUTPUT,
+            Self::CoinbaseStackData => version_bytes::COINBASE_STACK_DATA,
+            Self::CoinbaseStackHash => version_bytes::COINBASE_STACK_HASH,
+            Self::PrivateKey => version_bytes::PRIVATE_KEY,
+            Self::Signature => version_bytes::SIGNATURE,
+            Self::PublicKey => version_bytes::PUBLIC_KEY,
+        }
+    }
+
+    /// Human readable name of the kind
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::LedgerHash => "ledger hash",
+            Self::CoinbaseHash => "coinbase hash",
+            Self::ReceiptChainHash => "receipt chain hash",
+            Self::EpochSeed => "epoch seed",
+            Self::StagedLedgerHashAuxHash => "staged ledger aux hash",
+            Self::StagedLedgerHashPendingCoinbaseAux => "pending coinbase aux hash",
+            Self::StateHash => "state hash",
+            Self::StateBodyHash => "state body hash",
+            Self::TransactionHash => "transaction hash",
+            Self::SignedCommandV1 => "signed command",
+            Self::UserCommandMemo => "user command memo",
+            Self::VrfTruncatedOutput => "vrf output",
+            Self::CoinbaseStackData => "coinbase stack data",
+            Self::CoinbaseStackHash => "coinbase stack hash",
+            Self::PrivateKey => "private key",
+            Self::Signature => "signature",
+            Self::PublicKey => "public key",
+        }
+    }
+
+    /// The kinds that are prefixed with the given version byte, some kinds share a byte
+    pub fn from_version_byte(version: u8) -> impl Iterator<Item = Base58Kind> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(move |kind| kind.version_byte() == version)
+    }
+}
+
+impl fmt::Display for Base58Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error decoding a Base58Check string
+#[derive(Debug, thiserror::Error)]
+pub enum Base58Error {
+    /// The string is not base58 or its checksum does not match
+    #[error("Invalid base58check string: {0}")]
+    Decode(#[from] bs58::decode::Error),
+
+    /// The version byte is for a different kind of value than the one expected
+    #[error("This looks like {}, expected {}", describe_version_byte(*found), with_article(expected.name()))]
+    WrongKind {
+        /// The kind that was expected
+        expected: Base58Kind,
+        /// The version byte that was found
+        found: u8,
+    },
+
+    /// The bytes after the version byte are not a valid value of the expected kind
+    #[error("Invalid {kind}: {reason}")]
+    InvalidPayload {
+        /// The kind that was expected
+        kind: Base58Kind,
+        /// Why the bytes are invalid
+        reason: String,
+    },
+}
+
+impl Base58Error {
+    /// Error for bytes that are not a valid value of the given kind
+    pub fn invalid_payload(kind: Base58Kind, reason: impl fmt::Display) -> Self {
+        Self::InvalidPayload {
+            kind,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+fn with_article(name: &str) -> String {
+    match name.chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => format!("an {name}"),
+        _ => format!("a {name}"),
+    }
+}
+
+fn describe_version_byte(version: u8) -> String {
+    let names: Vec<_> = Base58Kind::from_version_byte(version)
+        .map(Base58Kind::name)
+        .collect();
+    if names.is_empty() {
+        format!("an unknown kind of value (version byte {version:#04x})")
+    } else {
+        with_article(&names.join(" or "))
+    }
+}
+
+/// Encode the payload of a value of the given kind with its version byte and a checksum
+pub fn encode(kind: Base58Kind, payload: impl AsRef<[u8]>) -> String {
+    bs58::encode(payload)
+        .with_check_version(kind.version_byte())
+        .into_string()
+}
+
+/// Decode a string that is expected to be a value of the given kind into its payload,
+/// which is what follows the version byte
+pub fn decode(input: impl AsRef<[u8]>, kind: Base58Kind) -> Result<Vec<u8>, Base58Error> {
+    let mut bytes = bs58::decode(input).with_check(None).into_vec()?;
+    match bytes.first() {
+        Some(&version) if version == kind.version_byte() => {
+            bytes.remove(0);
+            Ok(bytes)
+        }
+        Some(&found) => Err(Base58Error::WrongKind {
+            expected: kind,
+            found,
+        }),
+        None => Err(Base58Error::invalid_payload(kind, "missing version byte")),
+    }
+}
+
+/// A type whose human readable form is the Base58Check encoding of one kind in the registry
+pub trait Base58Encodable: Sized {
+    /// The kind of value, which determines the version byte
+    const KIND: Base58Kind;
+
+    /// The bytes that follow the version byte
+    fn to_base58_payload(&self) -> Vec<u8>;
+
+    /// Build a value from the bytes that follow the version byte
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error>;
+
+    /// Encode into a Base58Check string
+    fn to_base58_string(&self) -> String {
+        encode(Self::KIND, self.to_base58_payload())
+    }
+
+    /// Decode from a Base58Check string, failing if it is a different kind of value
+    fn from_base58(input: impl AsRef<[u8]>) -> Result<Self, Base58Error> {
+        Self::from_base58_payload(&decode(input, Self::KIND)?)
+    }
+}
+
+/// Macro that implements [Base58Encodable] for a type whose payload
+/// is the bin_prot encoding of a convertible versioned type
+#[macro_export]
+macro_rules! impl_base58_encodable_via_binprot {
+    ($t:ty, $tv:ty, $kind:expr) => {
+        impl $crate::base58::Base58Encodable for $t {
+            const KIND: $crate::base58::Base58Kind = $kind;
+
+            fn to_base58_payload(&self) -> Vec<u8> {
+                let v: $tv = self.clone().into();
+                let mut buf = Vec::new();
+                ::bin_prot::to_writer(&mut buf, &v)
+                    .expect("Failed to serialize base58 payload with bin_prot");
+                buf
+            }
+
+            fn from_base58_payload(payload: &[u8]) -> Result<Self, $crate::base58::Base58Error> {
+                let v: $tv = ::bin_prot::from_slice_strict(payload)
+                    .map_err(|e| $crate::base58::Base58Error::invalid_payload($kind, e))?;
+                Ok(v.into())
+            }
+        }
+    };
+}
+
+impl_base58_encodable_via_binprot!(CompressedPubKey, PublicKeyV1, Base58Kind::PublicKey);
+
+impl Base58Encodable for Signature {
+    const KIND: Base58Kind = Base58Kind::Signature;
+
+    fn to_base58_payload(&self) -> Vec<u8> {
+        let v1: SignatureV1 = self.clone().into();
+        let json: SignatureJson = v1.into();
+        let mut buf = Vec::new();
+        bin_prot::to_writer(&mut buf, &json.0).expect("Failed to serialize signature");
+        buf
+    }
+
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error> {
+        let t: Versioned<(FieldElement, InnerCurveScalar), 1> =
+            bin_prot::from_slice_strict(payload)
+                .map_err(|e| Base58Error::invalid_payload(Self::KIND, e))?;
+        let v1: SignatureV1 = SignatureJson(t).into();
+        Ok(v1.into())
+    }
+}
+
+impl Base58Encodable for SecKey {
+    const KIND: Base58Kind = Base58Kind::PrivateKey;
+
+    fn to_base58_payload(&self) -> Vec<u8> {
+        // a versioned scalar, the scalar is written as its 32 little endian bytes
+        let mut buf = vec![1];
+        buf.extend(self.scalar().to_bytes());
+        buf
+    }
+
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error> {
+        match payload.split_first() {
+            Some((&1, bytes)) => ScalarField::from_bytes(bytes)
+                .map(SecKey::new)
+                .map_err(|e| Base58Error::invalid_payload(Self::KIND, e)),
+            _ => Err(Base58Error::invalid_payload(
+                Self::KIND,
+                "expected version 1",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::*;
+    use proof_systems::mina_signer::Keypair;
+
+    #[test]
+    fn registry_version_bytes() {
+        for kind in Base58Kind::ALL {
+            assert!(Base58Kind::from_version_byte(kind.version_byte()).any(|k| k == *kind));
+        }
+        let shared: Vec<_> = Base58Kind::from_version_byte(0x0c).collect();
+        assert_eq!(
+            shared,
+            vec![Base58Kind::CoinbaseHash, Base58Kind::ReceiptChainHash]
+        );
+        assert_eq!(Base58Kind::from_version_byte(0x42).count(), 0);
+    }
+
+    fn roundtrip<T: Base58Encodable>(s: &str) {
+        let v = T::from_base58(s).unwrap();
+        assert_eq!(v.to_base58_string(), s);
+    }
+
+    #[test]
+    fn base58_roundtrip() {
+        roundtrip::<LedgerHash>("jxV4SS44wHUVrGEucCsfxLisZyUC5QddsiokGH3kz5xm2hJWZ25");
+        roundtrip::<CoinBaseHash>("2n1tLdP2gkifmyVmrmzYXTS4ohPbZPJn6Qq4x55ywrbRWB4543cC");
+        roundtrip::<ChainHash>("2mzbV7WevxLuchs2dAMY4vQBS6XttnCUF8Hvks4XNBQ5qiSGGBQe");
+        roundtrip::<EpochSeed>("2va9BGv9JrLTtrzZttiEMDYw1Zj6a6EHzXjmP9evHDTG3oEquURA");
+        roundtrip::<StateHash>("3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ");
+        roundtrip::<TransactionHash>("Ckpa5n9at846LmQyoVYv4JTSKyxuJ6yVfa8uYjxPXny5RqR8uUkn5");
+        roundtrip::<CompressedPubKey>("B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg");
+        roundtrip::<Signature>(
+            "7mXKt9nuSoA9wyF3kMLYBcxZLWTQNM9PCZcC2uC217YF1KjpNS28QtgTgEgh5e3FXc2mvPGb7bok4bA82xFXAR9BFQkChk8n",
+        );
+    }
+
+    #[test]
+    fn public_key_matches_address() {
+        let s = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+        let pk = CompressedPubKey::from_base58(s).unwrap();
+        assert_eq!(pk, CompressedPubKey::from_address(s).unwrap());
+    }
+
+    #[test]
+    fn private_key_roundtrip() {
+        let keypair =
+            Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+                .unwrap();
+        let s = keypair.secret.to_base58_string();
+        assert!(s.starts_with("EK"));
+        assert_eq!(
+            SecKey::from_base58(&s).unwrap().scalar(),
+            keypair.secret.scalar()
+        );
+    }
+
+    #[test]
+    fn wrong_kind() {
+        let ledger_hash = "jxV4SS44wHUVrGEucCsfxLisZyUC5QddsiokGH3kz5xm2hJWZ25";
+        let err = StateHash::from_base58(ledger_hash).unwrap_err();
+        assert!(matches!(
+            err,
+            Base58Error::WrongKind {
+                expected: Base58Kind::StateHash,
+                found: 0x05
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "This looks like a ledger hash, expected a state hash"
+        );
+
+        let coinbase_hash = "2n1tLdP2gkifmyVmrmzYXTS4ohPbZPJn6Qq4x55ywrbRWB4543cC";
+        assert_eq!(
+            EpochSeed::from_base58(coinbase_hash)
+                .unwrap_err()
+                .to_string(),
+            "This looks like a coinbase hash or receipt chain hash, expected an epoch seed"
+        );
+
+        let unknown = bs58::encode([0u8; 33])
+            .with_check_version(0x42)
+            .into_string();
+        assert_eq!(
+            StateHash::from_base58(unknown).unwrap_err().to_string(),
+            "This looks like an unknown kind of value (version byte 0x42), expected a state hash"
+        );
+    }
+}
@@ -10,8 +10,10 @@
 //! Depending on the type of hash a different byte prefix is used in the human readable form
 //!
 
+use crate::base58::{Base58Encodable, Base58Error, Base58Kind};
+use crate::impl_base58_encodable_via_binprot;
 use ark_ff::BigInteger256;
-use mina_serialization_types::{impl_strconv_via_json, json::*, v1::*};
+use mina_serialization_types::{common::ByteVec, impl_strconv_via_json, json::*, v1::*};
 use num::{BigUint, Num};
 use proof_systems::{
     mina_hasher::{Fp, Hashable, ROInput},
@@ -156,6 +158,7 @@ pub struct StateBodyHash(BaseHash);
 impl_from_for_hash!(StateBodyHash, HashV1);
 impl_from_for_generic_with_proxy!(StateBodyHash, HashV1, StateBodyHashV1Json);
 impl_strconv_via_json!(StateBodyHash, StateBodyHashV1Json);
+impl_base58_encodable_via_binprot!(StateBodyHash, HashV1, Base58Kind::StateBodyHash);
 
 impl From<&Fp> for StateBodyHash {
     fn from(i: &Fp) -> Self {
@@ -164,6 +167,14 @@ impl From<&Fp> for StateBodyHash {
     }
 }
 
+impl TryFrom<&StateBodyHash> for Fp {
+    type Error = FieldHelpersError;
+
+    fn try_from(i: &StateBodyHash) -> Result<Self, Self::Error> {
+        (&i.0).try_into()
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////
 #[derive(Clone, Debug, Default, Eq, PartialEq, derive_more::From, derive_more::Into)]
 pub struct StateHash(BaseHash);
@@ -171,6 +182,7 @@ pub struct StateHash(BaseHash);
 impl_from_for_hash!(StateHash, HashV1);
 impl_from_for_generic_with_proxy!(StateHash, HashV1, StateHashV1Json);
 impl_strconv_via_json!(StateHash, StateHashV1Json);
+impl_base58_encodable_via_binprot!(StateHash, HashV1, Base58Kind::StateHash);
 
 impl From<[u8; 32]> for StateHash {
     fn from(v: [u8; 32]) -> Self {
@@ -223,8 +235,10 @@ impl ToChunkedROInput for StateHash {
 pub struct LedgerHash(BaseHash);
 
 impl_from_for_hash!(LedgerHash, HashV1);
+impl_from_for_hash!(LedgerHash, [u8; 32]);
 impl_from_for_generic_with_proxy!(LedgerHash, HashV1, LedgerHashV1Json);
 impl_strconv_via_json!(LedgerHash, LedgerHashV1Json);
+impl_base58_encodable_via_binprot!(LedgerHash, HashV1, Base58Kind::LedgerHash);
 impl_from_json_value_for_hash!(LedgerHash);
 
 impl From<&Fp> for LedgerHash {
@@ -268,6 +282,7 @@ pub struct ChainHash(BaseHash);
 impl_from_for_hash!(ChainHash, HashV1);
 impl_from_for_generic_with_proxy!(ChainHash, HashV1, ChainHashV1Json);
 impl_strconv_via_json!(ChainHash, ChainHashV1Json);
+impl_base58_encodable_via_binprot!(ChainHash, HashV1, Base58Kind::ReceiptChainHash);
 
 impl Hashable for ChainHash {
     type D = ();
@@ -313,11 +328,28 @@ impl From<ChainHash> for [u8; 32] {
 pub struct CoinBaseHash(BaseHash);
 
 impl_from_for_hash!(CoinBaseHash, HashV1);
+impl_from_for_hash!(CoinBaseHash, [u8; 32]);
 impl_from_for_hash!(CoinBaseHash, Hash2V1);
 impl_from_for_generic_with_proxy!(CoinBaseHash, HashV1, CoinBaseHashV1Json);
 impl_strconv_via_json!(CoinBaseHash, CoinBaseHashV1Json);
+impl_base58_encodable_via_binprot!(CoinBaseHash, HashV1, Base58Kind::CoinbaseHash);
 impl_from_json_value_for_hash!(CoinBaseHash);
 
+impl From<&Fp> for CoinBaseHash {
+    fn from(i: &Fp) -> Self {
+        let base: BaseHash = i.into();
+        base.into()
+    }
+}
+
+impl TryFrom<&CoinBaseHash> for Fp {
+    type Error = FieldHelpersError;
+
+    fn try_from(i: &CoinBaseHash) -> Result<Self, Self::Error> {
+        (&i.0).try_into()
+    }
+}
+
 impl ToChunkedROInput for CoinBaseHash {
     fn to_chunked_roinput(&self) -> ChunkedROInput {
         self.0.to_chunked_roinput()
@@ -338,12 +370,26 @@ impl Hashable for CoinBaseHash {
 
 //////////////////////////////////////////////////////////////////////////
 
+#[derive(Clone, Default, Debug, Eq, PartialEq, derive_more::From, derive_more::Into)]
+pub struct ReceiptChainHash(BaseHash);
+
+impl_from_for_hash!(ReceiptChainHash, HashV1);
+impl_from_for_hash!(ReceiptChainHash, [u8; 32]);
+impl_from_for_generic_with_proxy!(ReceiptChainHash, HashV1, ReceiptChainHashV1Json);
+impl_strconv_via_json!(ReceiptChainHash, ReceiptChainHashV1Json);
+impl_base58_encodable_via_binprot!(ReceiptChainHash, HashV1, Base58Kind::ReceiptChainHash);
+impl_from_json_value_for_hash!(ReceiptChainHash);
+
+//////////////////////////////////////////////////////////////////////////
+
 #[derive(Clone, Default, Debug, Eq, PartialEq, derive_more::From, derive_more::Into)]
 pub struct EpochSeed(BaseHash);
 
 impl_from_for_hash!(EpochSeed, HashV1);
+impl_from_for_hash!(EpochSeed, [u8; 32]);
 impl_from_for_generic_with_proxy!(EpochSeed, HashV1, EpochSeedHashV1Json);
 impl_strconv_via_json!(EpochSeed, EpochSeedHashV1Json);
+impl_base58_encodable_via_binprot!(EpochSeed, HashV1, Base58Kind::EpochSeed);
 
 impl ToChunkedROInput for EpochSeed {
     fn to_chunked_roinput(&self) -> ChunkedROInput {
@@ -371,6 +417,36 @@ pub struct StagedLedgerHash {
     pub pending_coinbase_hash: CoinBaseHash,
 }
 
+impl StagedLedgerHash {
+    /// Combines the hashes of the ledger, the scan state and the pending coinbase collection
+    /// into a staged ledger hash
+    pub fn from_components(
+        ledger_hash: LedgerHash,
+        aux_hash: AuxHash,
+        pending_coinbase_aux: PendingCoinbaseAuxHash,
+        pending_coinbase_hash: CoinBaseHash,
+    ) -> Self {
+        Self {
+            non_snark: NonSnarkStagedLedgerHash {
+                ledger_hash,
+                aux_hash,
+                pending_coinbase_aux,
+            },
+            pending_coinbase_hash,
+        }
+    }
+
+    /// Staged ledger hash of the genesis block, whose aux hashes are dummy values
+    pub fn genesis(ledger_hash: LedgerHash, pending_coinbase_hash: CoinBaseHash) -> Self {
+        Self::from_components(
+            ledger_hash,
+            AuxHash::dummy(),
+            PendingCoinbaseAuxHash::dummy(),
+            pending_coinbase_hash,
+        )
+    }
+}
+
 impl ToChunkedROInput for StagedLedgerHash {
     fn to_chunked_roinput(&self) -> ChunkedROInput {
         ChunkedROInput::new()
@@ -432,19 +508,57 @@ impl Hashable for NonSnarkStagedLedgerHash {
 #[derive(Clone, Default, Debug, Eq, PartialEq, derive_more::From, derive_more::Into)]
 pub struct AuxHash(pub Vec<u8>);
 
+impl AuxHash {
+    /// Dummy aux hash of 32 zero bytes, used by the genesis block
+    pub fn dummy() -> Self {
+        Self(vec![0; 32])
+    }
+}
+
 impl_from_for_newtype!(AuxHash, AuxHashJson);
 impl_strconv_via_json!(AuxHash, AuxHashJson);
 impl_from_json_value_for_hash!(AuxHash);
 
+impl Base58Encodable for AuxHash {
+    const KIND: Base58Kind = Base58Kind::StagedLedgerHashAuxHash;
+
+    fn to_base58_payload(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error> {
+        Ok(Self(payload.to_vec()))
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////
 
 #[derive(Clone, Default, Debug, Eq, PartialEq, derive_more::From)]
 pub struct PendingCoinbaseAuxHash(pub Vec<u8>);
 
+impl PendingCoinbaseAuxHash {
+    /// Dummy pending coinbase aux hash of 32 zero bytes, used by the genesis block
+    pub fn dummy() -> Self {
+        Self(vec![0; 32])
+    }
+}
+
 impl_from_for_newtype!(PendingCoinbaseAuxHash, PendingCoinbaseAuxHashJson);
 impl_strconv_via_json!(PendingCoinbaseAuxHash, PendingCoinbaseAuxHashJson);
 impl_from_json_value_for_hash!(PendingCoinbaseAuxHash);
 
+impl Base58Encodable for PendingCoinbaseAuxHash {
+    const KIND: Base58Kind = Base58Kind::StagedLedgerHashPendingCoinbaseAux;
+
+    fn to_base58_payload(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_base58_payload(payload: &[u8]) -> Result<Self, Base58Error> {
+        Ok(Self(payload.to_vec()))
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////
 
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
@@ -453,6 +567,31 @@ pub struct VrfOutputHash(BaseHash);
 impl_from_for_hash!(VrfOutputHash, HashV1);
 impl_from_for_generic_with_proxy!(VrfOutputHash, HashV1, VrfOutputHashV1Json);
 impl_strconv_via_json!(VrfOutputHash, VrfOutputHashV1Json);
+impl_base58_encodable_via_binprot!(VrfOutputHash, HashV1, Base58Kind::VrfTruncatedOutput);
+
+//////////////////////////////////////////////////////////////////////////
+
+/// Hash identifying a transaction, e.g. in block explorers and the daemon GraphQL API
+#[derive(Clone, Debug, Eq, PartialEq, Hash, derive_more::From, derive_more::Into)]
+pub struct TransactionHash(pub Vec<u8>);
+
+impl From<TransactionHash> for ByteVecV1 {
+    fn from(h: TransactionHash) -> Self {
+        ByteVec(h.0).into()
+    }
+}
+
+impl From<ByteVecV1> for TransactionHash {
+    fn from(v: ByteVecV1) -> Self {
+        let bv: ByteVec = v.into();
+        Self(bv.0)
+    }
+}
+
+impl_from_for_generic_with_proxy!(TransactionHash, ByteVecV1, TransactionHashV1Json);
+impl_strconv_via_json!(TransactionHash, TransactionHashV1Json);
+impl_from_json_value_for_hash!(TransactionHash);
+impl_base58_encodable_via_binprot!(TransactionHash, ByteVecV1, Base58Kind::TransactionHash);
 
 //////////////////////////////////////////////////////////////////////////
 
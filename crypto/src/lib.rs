@@ -8,10 +8,12 @@
 
 #![deny(warnings)]
 
+pub mod base58;
 pub mod hash;
 pub mod hex;
 mod serialization_type_conversions;
 
 pub mod prelude {
+    pub use crate::base58::Base58Encodable;
     pub use crate::hex::HexEncodable;
 }
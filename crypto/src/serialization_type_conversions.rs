@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::hash::{AuxHash, NonSnarkStagedLedgerHash, PendingCoinbaseAuxHash, StagedLedgerHash};
-use mina_serialization_types::v1::{NonSnarkStagedLedgerHashV1, StagedLedgerHashV1};
+use mina_serialization_types::{
+    v1::{NonSnarkStagedLedgerHashV1, StagedLedgerHashV1},
+    v2::{NonSnarkStagedLedgerHashV2, StagedLedgerHashV2},
+};
 use versioned::*;
 
 impl From<NonSnarkStagedLedgerHash> for NonSnarkStagedLedgerHashV1 {
@@ -42,3 +45,39 @@ impl From<StagedLedgerHashV1> for StagedLedgerHash {
         }
     }
 }
+
+impl From<NonSnarkStagedLedgerHash> for NonSnarkStagedLedgerHashV2 {
+    fn from(t: NonSnarkStagedLedgerHash) -> Self {
+        Self {
+            ledger_hash: t.ledger_hash.into(),
+            aux_hash: t.aux_hash.0,
+            pending_coinbase_aux: t.pending_coinbase_aux.0,
+        }
+    }
+}
+impl From<NonSnarkStagedLedgerHashV2> for NonSnarkStagedLedgerHash {
+    fn from(t: NonSnarkStagedLedgerHashV2) -> Self {
+        Self {
+            ledger_hash: t.ledger_hash.into(),
+            aux_hash: AuxHash(t.aux_hash),
+            pending_coinbase_aux: PendingCoinbaseAuxHash(t.pending_coinbase_aux),
+        }
+    }
+}
+
+impl From<StagedLedgerHash> for StagedLedgerHashV2 {
+    fn from(t: StagedLedgerHash) -> Self {
+        Self {
+            non_snark: t.non_snark.into(),
+            pending_coinbase_hash: t.pending_coinbase_hash.into(),
+        }
+    }
+}
+impl From<StagedLedgerHashV2> for StagedLedgerHash {
+    fn from(t: StagedLedgerHashV2) -> Self {
+        Self {
+            non_snark: t.non_snark.into(),
+            pending_coinbase_hash: t.pending_coinbase_hash.into(),
+        }
+    }
+}
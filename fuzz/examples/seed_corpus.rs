@@ -0,0 +1,47 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Writes the seed corpus of every fuzz target from the test fixtures into `fuzz/corpus/<target>`,
+//! where `cargo fuzz run` picks it up.
+//!
+//! Run with `cargo run --manifest-path fuzz/Cargo.toml --example seed_corpus`
+//!
+
+use std::{fs, path::Path};
+use test_fixtures::{GENESIS_BLOCK_MAINNET, TEST_BLOCKS};
+
+const WALLET: &str = include_str!("../../secrets/tests/data/test-wallet");
+
+fn write_seed(target: &str, name: &str, bytes: &[u8]) -> std::io::Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("corpus")
+        .join(target);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(name), bytes)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let genesis = ("genesis", &*GENESIS_BLOCK_MAINNET);
+    let blocks = TEST_BLOCKS
+        .iter()
+        .map(|(file_name, block)| (file_name.trim_end_matches(".hex"), block))
+        .chain(std::iter::once(genesis));
+
+    for (name, block) in blocks {
+        write_seed("external_transition", name, &block.bytes)?;
+        write_seed("block_layout", name, &block.bytes)?;
+
+        // the parts of the block are re-encoded on their own
+        let et = block.external_transitionv1()?.t;
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, &et.protocol_state)?;
+        write_seed("protocol_state", name, &bytes)?;
+        bytes.clear();
+        bin_prot::to_writer(&mut bytes, &et.staged_ledger_diff)?;
+        write_seed("staged_ledger_diff", name, &bytes)?;
+    }
+
+    write_seed("secret_box", "test-wallet", WALLET.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,16 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decodes arbitrary bytes into a loosely typed value following the block layout
+
+#![no_main]
+
+use bin_prot::{Deserializer, Value};
+use libfuzzer_sys::fuzz_target;
+use serde::Deserialize;
+use test_fixtures::BLOCK_RULE;
+
+fuzz_target!(|data: &[u8]| {
+    let mut de = Deserializer::from_reader(data).with_layout(&BLOCK_RULE);
+    let _: bin_prot::error::Result<Value> = Deserialize::deserialize(&mut de);
+});
@@ -0,0 +1,13 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decodes arbitrary bytes as a block as gossiped by peers
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mina_serialization_types::v1::ExternalTransitionV1;
+
+fuzz_target!(|data: &[u8]| {
+    let _: bin_prot::error::Result<ExternalTransitionV1> = bin_prot::from_reader_strict(data);
+});
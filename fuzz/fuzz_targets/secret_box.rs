@@ -0,0 +1,21 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parses arbitrary strings as wallet files
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mina_secrets::secret_box::SecretBox;
+
+fuzz_target!(|json: &str| {
+    if let Ok(secret_box) = SecretBox::try_from(json) {
+        // a parsed wallet that can be written back must be read back the same
+        let written: Result<String, _> = secret_box.clone().try_into();
+        if let Ok(written) = written {
+            let read_back =
+                SecretBox::try_from(written.as_str()).expect("Failed to read back wallet");
+            assert_eq!(format!("{:?}", read_back), format!("{:?}", secret_box));
+        }
+    }
+});
@@ -0,0 +1,13 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decodes arbitrary bytes as a staged ledger diff, the largest and most nested part of a block
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mina_serialization_types::v1::StagedLedgerDiffV1;
+
+fuzz_target!(|data: &[u8]| {
+    let _: bin_prot::error::Result<StagedLedgerDiffV1> = bin_prot::from_reader_strict(data);
+});
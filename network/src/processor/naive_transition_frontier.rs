@@ -158,6 +158,14 @@ impl TryFrom<serde_json::Value> for ProtocolStateWithSparseMerkleLedger {
     }
 }
 
+/// Blocks decoded from the wire do not carry merkle proofs of the ledger,
+/// the sparse merkle ledger is left empty
+impl From<mina_rs_base::berkeley::Block> for ProtocolStateWithSparseMerkleLedger {
+    fn from(block: mina_rs_base::berkeley::Block) -> Self {
+        Self(block.header.protocol_state, vec![])
+    }
+}
+
 #[async_trait(?Send)]
 impl TransitionFrontier for NaiveTransitionFrontier<ProtocolState, MerkleProofBerkeleyNet> {
     type Block = ProtocolStateWithSparseMerkleLedger;
@@ -44,6 +44,24 @@ impl ChunkedROInput {
         self.append(other.to_chunked_roinput())
     }
 
+    /// Append [ToChunkedROInput] with each of its packed elements
+    /// truncated to the lowest `max_bits` bits, e.g. to hash a
+    /// 64-bit integer type as a 32-bit one
+    pub fn append_chunked_packed(self, other: &impl ToChunkedROInput, max_bits: u32) -> Self {
+        let mut other = other.to_chunked_roinput();
+        for (f, n_bits) in other.packed.iter_mut() {
+            let big: BigInteger256 = (*f).into();
+            let bits: BitVec<u8> = big
+                .to_bits_le()
+                .into_iter()
+                .take(max_bits as usize)
+                .collect();
+            *f = Self::bits_to_fp_unsafe(bits);
+            *n_bits = max_bits;
+        }
+        self.append(other)
+    }
+
     /// Append a base field element
     pub fn append_field(mut self, f: Fp) -> Self {
         self.fields.push(f);
@@ -170,3 +188,33 @@ impl Display for ChunkedROInput {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct U64(u64);
+
+    impl ToChunkedROInput for U64 {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new().append_u64(self.0)
+        }
+    }
+
+    #[test]
+    fn append_chunked_packed_truncates() {
+        for x in [
+            0,
+            1,
+            86400000,
+            u32::MAX as u64,
+            u32::MAX as u64 + 2,
+            u64::MAX,
+        ] {
+            assert_eq!(
+                ChunkedROInput::new().append_chunked_packed(&U64(x), u32::BITS),
+                ChunkedROInput::new().append_u32(x as u32),
+            );
+        }
+    }
+}
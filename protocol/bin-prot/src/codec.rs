@@ -0,0 +1,206 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Codecs for reading and writing length prefixed bin_prot messages with tokio
+//!
+//! Mina RPC messages are framed with the 8 byte little endian length prefix of Async RPC followed by
+//! the bin_prot encoding of the message. The codecs here implement the tokio-util `Decoder` and `Encoder`
+//! traits for such frames so they can be used with `FramedRead`, `FramedWrite` and `Framed` on any
+//! async reader or writer.
+//!
+//! `BinProtCodec<T>` reads and writes a strongly typed `T`. With the `loose_deserialization` feature
+//! `LayoutCodec` reads and writes a `Value` with the shape given by a layout.
+//!
+//! A frame must be decoded completely, any bytes left over after the message are an error. Frames
+//! longer than the maximum frame size are rejected before any of their body is buffered.
+//!
+
+use std::marker::PhantomData;
+
+use bytes::{Buf, BufMut, BytesMut};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::error::{Error, Result};
+use crate::ser::to_writer;
+
+#[cfg(feature = "loose_deserialization")]
+use crate::{de::Deserializer, to_writer_with_layout, BinProtRule, Value};
+
+/// Number of bytes in the length prefix of a frame
+pub const LENGTH_PREFIX_SIZE: usize = 8;
+
+/// Maximum frame size used when none is given, the same as the total allocation limit of decoding
+/// so that a frame the codec accepts is not rejected by the default [DecodeLimits](crate::DecodeLimits)
+pub const DEFAULT_MAX_FRAME_SIZE: usize = crate::DEFAULT_MAX_TOTAL_ALLOC;
+
+/// Codec for length prefixed bin_prot messages of type `T`
+#[derive(Debug)]
+pub struct BinProtCodec<T> {
+    max_frame_size: usize,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<T> BinProtCodec<T> {
+    /// Create a codec that accepts frames of up to `DEFAULT_MAX_FRAME_SIZE` bytes
+    pub fn new() -> Self {
+        Self::with_max_frame_size(DEFAULT_MAX_FRAME_SIZE)
+    }
+
+    /// Create a codec that accepts frames of up to `max_frame_size` bytes, excluding the length prefix
+    pub fn with_max_frame_size(max_frame_size: usize) -> Self {
+        Self {
+            max_frame_size,
+            _message: PhantomData,
+        }
+    }
+
+    /// The largest frame this codec reads or writes
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+}
+
+impl<T> Default for BinProtCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for BinProtCodec<T> {
+    fn clone(&self) -> Self {
+        Self::with_max_frame_size(self.max_frame_size)
+    }
+}
+
+impl<T: DeserializeOwned> Decoder for BinProtCodec<T> {
+    type Item = T;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<T>> {
+        match next_frame(src, self.max_frame_size)? {
+            Some(frame) => crate::from_slice_strict(&frame).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: Serialize> Encoder<T> for BinProtCodec<T> {
+    type Error = Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> Result<()> {
+        write_frame(dst, self.max_frame_size, |writer| to_writer(writer, &item))
+    }
+}
+
+/// Codec for length prefixed bin_prot messages read into a `Value` using a layout
+#[cfg(feature = "loose_deserialization")]
+#[derive(Debug, Clone)]
+pub struct LayoutCodec {
+    rule: BinProtRule,
+    max_frame_size: usize,
+}
+
+#[cfg(feature = "loose_deserialization")]
+impl LayoutCodec {
+    /// Create a codec for messages with the given layout that accepts frames of up to
+    /// `DEFAULT_MAX_FRAME_SIZE` bytes
+    pub fn new(rule: BinProtRule) -> Self {
+        Self::with_max_frame_size(rule, DEFAULT_MAX_FRAME_SIZE)
+    }
+
+    /// Create a codec for messages with the given layout that accepts frames of up to
+    /// `max_frame_size` bytes, excluding the length prefix
+    pub fn with_max_frame_size(rule: BinProtRule, max_frame_size: usize) -> Self {
+        Self {
+            rule,
+            max_frame_size,
+        }
+    }
+
+    /// The largest frame this codec reads or writes
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+}
+
+#[cfg(feature = "loose_deserialization")]
+impl Decoder for LayoutCodec {
+    type Item = Value;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Value>> {
+        let frame = match next_frame(src, self.max_frame_size)? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        let mut de = Deserializer::from_slice(&frame).with_layout(&self.rule);
+        let value = serde::Deserialize::deserialize(&mut de)?;
+        match de.rdr.remaining().len() {
+            0 => Ok(Some(value)),
+            unconsumed => Err(Error::StreamNotFullyConsumed(unconsumed)),
+        }
+    }
+}
+
+#[cfg(feature = "loose_deserialization")]
+impl Encoder<Value> for LayoutCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Value, dst: &mut BytesMut) -> Result<()> {
+        let rule = &self.rule;
+        write_frame(dst, self.max_frame_size, |writer| {
+            to_writer_with_layout(writer, &item, rule)
+        })
+    }
+}
+
+// Removes the next complete frame from the buffer and returns its body
+fn next_frame(src: &mut BytesMut, max_frame_size: usize) -> Result<Option<BytesMut>> {
+    if src.len() < LENGTH_PREFIX_SIZE {
+        return Ok(None);
+    }
+    let mut prefix = [0u8; LENGTH_PREFIX_SIZE];
+    prefix.copy_from_slice(&src[..LENGTH_PREFIX_SIZE]);
+    let size = u64::from_le_bytes(prefix);
+    if size > max_frame_size as u64 {
+        return Err(Error::FrameTooLarge {
+            size,
+            max: max_frame_size,
+        });
+    }
+    let frame_len = LENGTH_PREFIX_SIZE + size as usize;
+    if src.len() < frame_len {
+        // wait for the rest of the frame
+        src.reserve(frame_len - src.len());
+        return Ok(None);
+    }
+    src.advance(LENGTH_PREFIX_SIZE);
+    Ok(Some(src.split_to(size as usize)))
+}
+
+// Appends a frame to the buffer with the body written by `write`
+fn write_frame<F>(dst: &mut BytesMut, max_frame_size: usize, write: F) -> Result<()>
+where
+    F: FnOnce(&mut bytes::buf::Writer<&mut BytesMut>) -> Result<()>,
+{
+    let start = dst.len();
+    // the length is only known once the body has been written so it is filled in afterwards
+    dst.put_u64_le(0);
+    if let Err(e) = write(&mut (&mut *dst).writer()) {
+        dst.truncate(start);
+        return Err(e);
+    }
+    let size = dst.len() - start - LENGTH_PREFIX_SIZE;
+    if size > max_frame_size {
+        dst.truncate(start);
+        return Err(Error::FrameTooLarge {
+            size: size as u64,
+            max: max_frame_size,
+        });
+    }
+    dst[start..start + LENGTH_PREFIX_SIZE].copy_from_slice(&(size as u64).to_le_bytes());
+    Ok(())
+}
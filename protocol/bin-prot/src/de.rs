@@ -3,7 +3,7 @@
 
 //! Deserialization for BinProt following the standard serde module layout
 
-use crate::error::{Error, Result};
+use crate::error::{Error, PathSegment, Result};
 #[cfg(feature = "loose_deserialization")]
 use crate::value::layout::*;
 use crate::ReadBinProtExt;
@@ -11,7 +11,7 @@ use crate::{caml_hash_variant, VariantHash};
 use byteorder::{LittleEndian, ReadBytesExt};
 use serde::de::{self, value::U8Deserializer, EnumAccess, IntoDeserializer, Visitor};
 use serde::Deserialize;
-use std::io::{BufReader, Read};
+use std::io::{self, BufReader, Read};
 
 /// the modes of operation for the deserializer
 pub struct StronglyTyped;
@@ -21,23 +21,316 @@ pub struct LooselyTyped {
     pub(crate) layout_iter: BinProtRuleIterator,
 }
 
-/// A BinProt deserializer that reads from a BufReader
-/// Can operate in strong or loose deserialization mode
+/// Source of bytes for the deserializer.
+/// Decides whether strings and byte strings can be handed to visitors borrowed from the input
+/// or must be copied into a freshly allocated buffer.
+pub trait BinProtRead<'de>: Read {
+    /// Read a byte string of `len` bytes, whose length prefix has been read already,
+    /// and pass it to the visitor
+    fn bin_visit_bytes<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value>;
+
+    /// Read a utf-8 string of `len` bytes, whose length prefix has been read already,
+    /// and pass it to the visitor
+    fn bin_visit_str<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value>;
+
+    /// Number of bytes consumed from the start of the input
+    fn offset(&self) -> usize;
+}
+
+/// Buffered reader over an `io::Read` source that keeps track of the bytes consumed from it
+pub struct IoReader<R> {
+    inner: BufReader<CountingReader<R>>,
+}
+
+// Counting happens when the buffer is refilled so reads served from the buffer stay cheap
+struct CountingReader<R> {
+    inner: R,
+    count: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.count += n;
+        Ok(n)
+    }
+}
+
+impl<R: Read> IoReader<R> {
+    /// Wrap a reader
+    pub fn new(rdr: R) -> Self {
+        Self {
+            inner: BufReader::new(CountingReader {
+                inner: rdr,
+                count: 0,
+            }),
+        }
+    }
+
+    /// Bytes that have been read from the source but not consumed yet
+    pub fn buffer(&self) -> &[u8] {
+        self.inner.buffer()
+    }
+
+    /// Gets a reference to the wrapped reader.
+    /// Bytes already in the [IoReader::buffer] have been read from it
+    pub fn get_ref(&self) -> &R {
+        &self.inner.get_ref().inner
+    }
+}
+
+impl<R: Read> Read for IoReader<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(buf)
+    }
+}
+
+impl<'de, R: Read> BinProtRead<'de> for IoReader<R> {
+    fn bin_visit_bytes<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_byte_buf(self.bin_read_bytes_of_len(len as u64)?)
+    }
+
+    fn bin_visit_str<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value> {
+        let s = String::from_utf8(self.bin_read_bytes_of_len(len as u64)?).map_err(|e| {
+            Error::InvalidUtf8 {
+                bytes: e.into_bytes(),
+            }
+        })?;
+        visitor.visit_string(s)
+    }
+
+    fn offset(&self) -> usize {
+        self.inner.get_ref().count - self.inner.buffer().len()
+    }
+}
+
+/// Reader over an in-memory byte slice.
+/// Reading from a slice lets the output reference the input directly
+pub struct SliceReader<'de> {
+    remaining: &'de [u8],
+    len: usize,
+}
+
+impl<'de> SliceReader<'de> {
+    /// Wrap a byte slice
+    pub fn new(bytes: &'de [u8]) -> Self {
+        Self {
+            remaining: bytes,
+            len: bytes.len(),
+        }
+    }
+
+    /// Bytes that have not been consumed yet
+    pub fn remaining(&self) -> &'de [u8] {
+        self.remaining
+    }
+
+    fn read_borrowed_bytes(&mut self, len: usize) -> Result<&'de [u8]> {
+        if len > self.remaining.len() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let (bytes, rest) = self.remaining.split_at(len);
+        self.remaining = rest;
+        Ok(bytes)
+    }
+}
+
+impl Read for SliceReader<'_> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.remaining.read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.remaining.read_exact(buf)
+    }
+}
+
+impl<'de> BinProtRead<'de> for SliceReader<'de> {
+    fn bin_visit_bytes<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value> {
+        visitor.visit_borrowed_bytes(self.read_borrowed_bytes(len)?)
+    }
+
+    fn bin_visit_str<V: Visitor<'de>>(&mut self, len: usize, visitor: V) -> Result<V::Value> {
+        let bytes = self.read_borrowed_bytes(len)?;
+        let s = std::str::from_utf8(bytes).map_err(|_| Error::InvalidUtf8 {
+            bytes: bytes.to_vec(),
+        })?;
+        visitor.visit_borrowed_str(s)
+    }
+
+    fn offset(&self) -> usize {
+        self.len - self.remaining.len()
+    }
+}
+
+/// Default maximum number of elements of a list or map, see [DecodeLimits]
+pub const DEFAULT_MAX_SEQ_LEN: usize = 1 << 16;
+
+/// Default maximum number of bytes of a string or byte string, see [DecodeLimits]
+pub const DEFAULT_MAX_STRING_LEN: usize = 1 << 20;
+
+/// Default maximum total of the lengths read from one input, see [DecodeLimits]
+pub const DEFAULT_MAX_TOTAL_ALLOC: usize = 1 << 24;
+
+/// Default maximum nesting depth of values, see [DecodeLimits]
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Limits on the lengths read from the input and on the nesting of values,
+/// so that decoding untrusted input cannot allocate or recurse without bound.
+///
+/// The defaults are safe for gossip messages. They are well above what mainnet blocks need,
+/// about 50 levels of nesting and lengths adding up to tens of thousands for the largest blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeLimits {
+    /// Maximum number of elements of a list or map
+    pub max_seq_len: usize,
+    /// Maximum number of bytes of a string or byte string
+    pub max_string_len: usize,
+    /// Maximum total of the lengths of all lists and maps (in elements) and
+    /// strings (in bytes) of one input
+    pub max_total_alloc: usize,
+    /// Maximum nesting depth of sequences, tuples, structs, maps and enums
+    pub max_depth: usize,
+}
+
+impl DecodeLimits {
+    /// No limits, only for trusted input
+    pub const UNLIMITED: Self = Self {
+        max_seq_len: usize::MAX,
+        max_string_len: usize::MAX,
+        max_total_alloc: usize::MAX,
+        max_depth: usize::MAX,
+    };
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_seq_len: DEFAULT_MAX_SEQ_LEN,
+            max_string_len: DEFAULT_MAX_STRING_LEN,
+            max_total_alloc: DEFAULT_MAX_TOTAL_ALLOC,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+}
+
+/// A BinProt deserializer that reads from a buffered reader or directly from a byte slice
+/// Can operate in strong or loose deserialization mode.
+/// The input is checked against [DecodeLimits], the defaults unless set with `with_limits`
 pub struct Deserializer<R: Read, Mode> {
-    /// BufReader to read the bytes from
-    pub rdr: BufReader<R>,
+    /// Reader to read the bytes from, an [IoReader] or a [SliceReader].
+    /// Before 0.2.0 this was the `std::io::Read` passed to `from_reader`,
+    /// it can still be reached with [IoReader::get_ref]
+    pub rdr: R,
     /// Deserialization mode, StronglyTyped or LooselyTyped
     pub mode: Mode,
+    limits: DecodeLimits,
+    // total of the lengths read and current nesting depth, checked against the limits
+    allocated: usize,
+    depth: usize,
 }
 
-impl<R: Read> Deserializer<R, StronglyTyped> {
+impl<R: Read> Deserializer<IoReader<R>, StronglyTyped> {
     /// Create a BinProt deserializer from a reader
     pub fn from_reader(rdr: R) -> Self {
+        Self::new(IoReader::new(rdr), StronglyTyped)
+    }
+}
+
+impl<'de> Deserializer<SliceReader<'de>, StronglyTyped> {
+    /// Create a BinProt deserializer that borrows from a byte slice.
+    /// Strings and byte strings can then be deserialized as `&'de str` and `&'de [u8]`
+    /// without copying
+    pub fn from_slice(bytes: &'de [u8]) -> Self {
+        Self::new(SliceReader::new(bytes), StronglyTyped)
+    }
+}
+
+impl<R: Read, Mode> Deserializer<R, Mode> {
+    fn new(rdr: R, mode: Mode) -> Self {
         Self {
-            rdr: BufReader::new(rdr),
-            mode: StronglyTyped,
+            rdr,
+            mode,
+            limits: DecodeLimits::default(),
+            allocated: 0,
+            depth: 0,
+        }
+    }
+
+    /// Check the input against the given limits instead of the defaults
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The limits the input is checked against
+    pub fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+
+    // Read the length of a list or map and count it against the limits
+    pub(crate) fn read_seq_len(&mut self) -> Result<usize> {
+        let len = self.rdr.bin_read_nat0::<u64>()?;
+        if len > self.limits.max_seq_len as u64 {
+            return Err(Error::SeqTooLong {
+                len,
+                max: self.limits.max_seq_len,
+            });
+        }
+        self.allocate(len as usize)
+    }
+
+    // Read the length of a string or byte string and count it against the limits
+    pub(crate) fn read_string_len(&mut self) -> Result<usize> {
+        let len = self.rdr.bin_read_nat0::<u64>()?;
+        if len > self.limits.max_string_len as u64 {
+            return Err(Error::StringTooLong {
+                len,
+                max: self.limits.max_string_len,
+            });
+        }
+        self.allocate(len as usize)
+    }
+
+    fn allocate(&mut self, len: usize) -> Result<usize> {
+        match self.allocated.checked_add(len) {
+            Some(total) if total <= self.limits.max_total_alloc => {
+                self.allocated = total;
+                Ok(len)
+            }
+            _ => Err(Error::AllocationLimitExceeded {
+                max: self.limits.max_total_alloc,
+            }),
         }
     }
+
+    // Deserialize the children of a value one level deeper, within the depth limit
+    pub(crate) fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth >= self.limits.max_depth {
+            return Err(Error::DepthLimitExceeded {
+                max: self.limits.max_depth,
+            });
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+}
+
+impl<'de, R: BinProtRead<'de>, Mode> Deserializer<R, Mode> {
+    /// Number of bytes consumed from the start of the input
+    pub fn offset(&self) -> usize {
+        self.rdr.offset()
+    }
 }
 
 #[cfg(feature = "loose_deserialization")]
@@ -50,6 +343,9 @@ impl<R: Read> Deserializer<R, StronglyTyped> {
             mode: LooselyTyped {
                 layout_iter: layout.clone().into_iter(),
             },
+            limits: self.limits,
+            allocated: self.allocated,
+            depth: self.depth,
         }
     }
 }
@@ -58,8 +354,7 @@ impl<R: Read> Deserializer<R, StronglyTyped> {
 /// read from it
 pub fn from_reader<'de, R: Read, T: Deserialize<'de>>(rdr: R) -> Result<T> {
     let mut de = Deserializer::from_reader(rdr);
-    let value = Deserialize::deserialize(&mut de)?;
-    Ok(value)
+    deserialize_root(&mut de)
 }
 
 /// Convenience method, create a BinProt deserializer from the given reader and then
@@ -67,23 +362,57 @@ pub fn from_reader<'de, R: Read, T: Deserialize<'de>>(rdr: R) -> Result<T> {
 /// This method also ensures the input byte stream is fully consumed.
 pub fn from_reader_strict<'de, R: Read, T: Deserialize<'de>>(rdr: R) -> Result<T> {
     let mut de = Deserializer::from_reader(rdr);
-    let value = Deserialize::deserialize(&mut de)?;
+    let value = deserialize_root(&mut de)?;
     match de.rdr.buffer().len() {
         0 => Ok(value),
-        unconsumed => Err(Error::StreamNotFullyConsumed(unconsumed)),
+        unconsumed => {
+            Err(Error::StreamNotFullyConsumed(unconsumed).with_context(de.offset(), None))
+        }
+    }
+}
+
+/// Convenience method, create a BinProt deserializer borrowing from the given slice and then
+/// read from it
+pub fn from_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T> {
+    let mut de = Deserializer::from_slice(bytes);
+    deserialize_root(&mut de)
+}
+
+/// Convenience method, create a BinProt deserializer borrowing from the given slice and then
+/// read from it.
+/// This method also ensures the input slice is fully consumed.
+pub fn from_slice_strict<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T> {
+    let mut de = Deserializer::from_slice(bytes);
+    let value = deserialize_root(&mut de)?;
+    match de.rdr.remaining().len() {
+        0 => Ok(value),
+        unconsumed => {
+            Err(Error::StreamNotFullyConsumed(unconsumed).with_context(de.offset(), None))
+        }
     }
 }
 
+// Errors raised by nested values already carry their position, this makes sure
+// errors raised by the root value do too
+fn deserialize_root<'de, R: BinProtRead<'de>, T: Deserialize<'de>>(
+    de: &mut Deserializer<R, StronglyTyped>,
+) -> Result<T> {
+    let offset = de.offset();
+    T::deserialize(de).map_err(|e| e.with_context(offset, None))
+}
+
 // In the loosely typed case we want to use deserialize_any for every field
 // This includes the hybrid strong/loose case
 #[cfg(feature = "loose_deserialization")]
-impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, LooselyTyped> {
+impl<'de, 'a, R: BinProtRead<'de>> de::Deserializer<'de> for &'a mut Deserializer<R, LooselyTyped> {
     type Error = Error;
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        let offset = self.offset();
         self.deserialize_loose(visitor)
+            .map_err(|e| e.with_context(offset, None))
     }
 
     serde::forward_to_deserialize_any! {
@@ -95,7 +424,9 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Loosely
 
 // Otherwise if no layout is provided and we are targeting a strong type destination
 // we can go for efficiency
-impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, StronglyTyped> {
+impl<'de, 'a, R: BinProtRead<'de>> de::Deserializer<'de>
+    for &'a mut Deserializer<R, StronglyTyped>
+{
     type Error = Error;
     fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
     where
@@ -167,7 +498,9 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     where
         V: Visitor<'de>,
     {
-        visitor.visit_u64(self.rdr.bin_read_integer()?)
+        // u64 is serialized as an i64 (as Mina does with Int64 for its UInt64 values),
+        // so values above i64::MAX are read back from negative integers
+        visitor.visit_u64(self.rdr.bin_read_integer::<i64>()? as u64)
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
@@ -191,32 +524,38 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
         visitor.visit_char(self.rdr.bin_read_char()?)
     }
 
-    fn deserialize_str<V>(self, _visitor: V) -> Result<V::Value>
+    // Strings are borrowed from the input when reading from a slice
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        todo!()
+        let len = self.read_string_len()?;
+        self.rdr.bin_visit_str(len, visitor)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_string(self.rdr.bin_read_string()?)
+        let len = self.read_string_len()?;
+        self.rdr.bin_visit_str(len, visitor)
     }
 
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value>
+    // Byte strings are encoded like strings, a Nat0 length followed by the raw bytes
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::WontImplement)
+        let len = self.read_string_len()?;
+        self.rdr.bin_visit_bytes(len, visitor)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        Err(Error::WontImplement)
+        let len = self.read_string_len()?;
+        self.rdr.bin_visit_bytes(len, visitor)
     }
 
     // An absent optional is represented as 0x00
@@ -264,8 +603,8 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     where
         V: Visitor<'de>,
     {
-        let len = self.rdr.bin_read_nat0()?;
-        visitor.visit_seq(SeqAccess::new(self, len))
+        let len = self.read_seq_len()?;
+        self.nested(|de| visitor.visit_seq(SeqAccess::new(de, len)))
     }
 
     // Tuples look just like sequences
@@ -273,7 +612,7 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(SeqAccess::new(self, len))
+        self.nested(|de| visitor.visit_seq(SeqAccess::new(de, len)))
     }
 
     // Tuple structs look just like sequences
@@ -286,7 +625,7 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(SeqAccess::new(self, len))
+        self.nested(|de| visitor.visit_seq(SeqAccess::new(de, len)))
     }
 
     // Much like `deserialize_seq` but calls the visitors `visit_map` method
@@ -298,10 +637,9 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
         V: Visitor<'de>,
     {
         // we can't know the field names (and don't need to) if we are deserializing in
-        // stronly typed mode. To make everything work just add some dummy field names
-        let len: usize = self.rdr.bin_read_nat0()?;
-        let dummy_fields = std::iter::repeat("".to_string()).take(len).collect();
-        visitor.visit_map(MapAccess::new(self, dummy_fields))
+        // stronly typed mode. To make everything work the entries get empty dummy names
+        let len = self.read_seq_len()?;
+        self.nested(|de| visitor.visit_map(MapAccess::unnamed(de, len)))
     }
 
     // Structs look just like sequences
@@ -314,7 +652,9 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(SeqAccess::new(self, fields.len()))
+        self.nested(|de| {
+            visitor.visit_seq(SeqAccess::new(de, fields.len()).with_field_names(fields))
+        })
     }
 
     fn deserialize_enum<V>(
@@ -328,14 +668,17 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     {
         // Deserialize polyvar enum using serde container attribute #[serde(rename = "Polyvar")]
         // Refer tests/polyvar.rs for more info
+        let offset = self.offset();
         match name {
             "Polyvar" => {
                 let hash = self.rdr.bin_read_polyvar_tag()?;
-                visitor.visit_enum(PolyvarEnum::new(self, hash, variants))
+                self.nested(|de| visitor.visit_enum(PolyvarEnum::new(de, hash, variants)))
+                    .map_err(|e| polyvar_error(e, offset, hash, variants))
             }
             _ => {
                 let index = self.rdr.bin_read_variant_index()?;
-                visitor.visit_enum(Enum::new(self, index))
+                self.nested(|de| visitor.visit_enum(Enum::new(de, index)))
+                    .map_err(|e| variant_error(e, offset, variants.get(index as usize)))
             }
         }
     }
@@ -367,6 +710,45 @@ impl<'de, 'a, R: Read> de::Deserializer<'de> for &'a mut Deserializer<R, Strongl
     }
 }
 
+// The error helpers are kept out of line so the happy path of the hot
+// deserialization loops stays small enough to be inlined
+
+#[cold]
+#[inline(never)]
+fn variant_error(e: Error, offset: usize, variant: Option<&&str>) -> Error {
+    e.with_context(offset, variant.map(|v| PathSegment::Variant(v.to_string())))
+}
+
+#[cold]
+#[inline(never)]
+fn polyvar_error(e: Error, offset: usize, hash: VariantHash, variants: &[&str]) -> Error {
+    let variant = variants
+        .iter()
+        .find(|v| v.is_ascii() && caml_hash_variant(v) == hash);
+    variant_error(e, offset, variant)
+}
+
+#[cold]
+#[inline(never)]
+fn element_error(e: Error, offset: usize, index: usize, field_names: &[&str]) -> Error {
+    let segment = match field_names.get(index) {
+        Some(name) => PathSegment::Field(name.to_string()),
+        None => PathSegment::Index(index),
+    };
+    e.with_context(offset, Some(segment))
+}
+
+#[cold]
+#[inline(never)]
+fn entry_error(e: Error, offset: usize, index: usize, name: Option<String>) -> Error {
+    // dummy names are used in strongly typed mode, fall back to the position
+    let segment = match name {
+        Some(name) if !name.is_empty() => PathSegment::Field(name),
+        _ => PathSegment::Index(index),
+    };
+    e.with_context(offset, Some(segment))
+}
+
 pub struct PolyvarEnum<'a, R: Read, Mode> {
     de: &'a mut Deserializer<R, Mode>,
     hash: VariantHash,
@@ -385,7 +767,7 @@ impl<'a, R: Read, Mode> PolyvarEnum<'a, R, Mode> {
 
 macro_rules! impl_polyvar_enum_access {
     ($ty: ty) => {
-        impl<'de, 'a, R: Read> EnumAccess<'de> for PolyvarEnum<'a, R, $ty> {
+        impl<'de, 'a, R: BinProtRead<'de>> EnumAccess<'de> for PolyvarEnum<'a, R, $ty> {
             type Error = Error;
             type Variant = Enum<'a, R, StronglyTyped>;
 
@@ -432,7 +814,7 @@ impl<'a, R: Read, Mode> Enum<'a, R, Mode> {
 // which variant of the enum is supposed to be deserialized.
 macro_rules! impl_enum_access {
     ($ty: ty) => {
-        impl<'de, 'a, R: Read> EnumAccess<'de> for Enum<'a, R, $ty> {
+        impl<'de, 'a, R: BinProtRead<'de>> EnumAccess<'de> for Enum<'a, R, $ty> {
             type Error = Error;
             type Variant = Self;
 
@@ -457,7 +839,7 @@ impl_enum_access!(LooselyTyped);
 // the content of the single variant that it decided to deserialize.
 macro_rules! impl_variant_access {
     ($ty: ty, $ident: ident) => {
-        impl<'de, 'a, R: Read> de::VariantAccess<'de> for $ident<'a, R, $ty> {
+        impl<'de, 'a, R: BinProtRead<'de>> de::VariantAccess<'de> for $ident<'a, R, $ty> {
             type Error = Error;
 
             fn unit_variant(self) -> Result<()> {
@@ -503,26 +885,48 @@ impl_variant_access!(LooselyTyped, PolyvarEnum);
 pub(crate) struct MapAccess<'a, R: Read + 'a, Mode> {
     de: &'a mut Deserializer<R, Mode>,
     field_names: Vec<String>, // field names should be stored as a stack (first element last)
+    unnamed: usize,           // entries left after the named ones, counted rather than allocated
+    current: Option<String>,
+    index: usize,
 }
 
 impl<'a, R: Read + 'a, Mode> MapAccess<'a, R, Mode> {
     pub fn new(de: &'a mut Deserializer<R, Mode>, field_names: Vec<String>) -> Self {
-        Self { de, field_names }
+        Self {
+            de,
+            field_names,
+            unnamed: 0,
+            current: None,
+            index: 0,
+        }
+    }
+
+    /// Entries without names, the length is read from the input so no names are allocated
+    pub fn unnamed(de: &'a mut Deserializer<R, Mode>, len: usize) -> Self {
+        Self {
+            unnamed: len,
+            ..Self::new(de, Vec::new())
+        }
     }
 }
 
 macro_rules! impl_map_access {
     ($ty: ty) => {
-        impl<'de: 'a, 'a, R: Read> de::MapAccess<'de> for MapAccess<'a, R, $ty> {
+        impl<'de: 'a, 'a, R: BinProtRead<'de>> de::MapAccess<'de> for MapAccess<'a, R, $ty> {
             type Error = Error;
 
             fn next_key_seed<T: de::DeserializeSeed<'de>>(
                 &mut self,
                 seed: T,
             ) -> Result<Option<T::Value>> {
-                if let Some(name) = self.field_names.pop() {
+                let name = self.field_names.pop().or_else(|| {
+                    self.unnamed = self.unnamed.checked_sub(1)?;
+                    Some(String::new())
+                });
+                if let Some(name) = name {
                     // create a new deserializer to read the name from memory
                     // as it isn't present in the serialized output
+                    self.current = Some(name.clone());
                     seed.deserialize(name.into_deserializer()).map(Some)
                 } else {
                     Ok(None)
@@ -533,11 +937,15 @@ macro_rules! impl_map_access {
                 &mut self,
                 seed: T,
             ) -> Result<T::Value> {
+                let index = self.index;
+                self.index += 1;
+                let offset = self.de.offset();
                 seed.deserialize(&mut *self.de)
+                    .map_err(|e| entry_error(e, offset, index, self.current.take()))
             }
 
             fn size_hint(&self) -> Option<usize> {
-                Some(self.field_names.len())
+                Some(self.field_names.len() + self.unnamed)
             }
         }
     };
@@ -553,6 +961,7 @@ pub(crate) struct SeqAccess<'a, R: Read + 'a, Mode> {
     total_len: usize,
     len: usize,
     is_list: bool,
+    field_names: &'static [&'static str],
 }
 
 impl<'a, R: Read + 'a, Mode> SeqAccess<'a, R, Mode> {
@@ -562,6 +971,7 @@ impl<'a, R: Read + 'a, Mode> SeqAccess<'a, R, Mode> {
             len,
             total_len: len,
             is_list: false,
+            field_names: &[],
         }
     }
 
@@ -572,13 +982,20 @@ impl<'a, R: Read + 'a, Mode> SeqAccess<'a, R, Mode> {
             len,
             total_len: len,
             is_list: true,
+            field_names: &[],
         }
     }
+
+    /// Name the elements after struct fields when reporting errors
+    pub fn with_field_names(mut self, field_names: &'static [&'static str]) -> Self {
+        self.field_names = field_names;
+        self
+    }
 }
 
 macro_rules! impl_seq_access {
     ($ty: ty) => {
-        impl<'de: 'a, 'a, R: Read> de::SeqAccess<'de> for SeqAccess<'a, R, $ty> {
+        impl<'de: 'a, 'a, R: BinProtRead<'de>> de::SeqAccess<'de> for SeqAccess<'a, R, $ty> {
             type Error = Error;
 
             fn next_element_seed<T: de::DeserializeSeed<'de>>(
@@ -587,7 +1004,11 @@ macro_rules! impl_seq_access {
             ) -> Result<Option<T::Value>> {
                 if self.len > 0 {
                     self.len -= 1;
-                    seed.deserialize(&mut *self.de).map(Some)
+                    let index = self.total_len - self.len - 1;
+                    let offset = self.de.offset();
+                    seed.deserialize(&mut *self.de)
+                        .map(Some)
+                        .map_err(|e| element_error(e, offset, index, self.field_names))
                 } else {
                     Ok(None)
                 }
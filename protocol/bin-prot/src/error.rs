@@ -112,10 +112,82 @@ pub enum Error {
     #[error("Unrecognised Polyvar tag {0}")]
     UnknownPolyvarTag(u32),
 
+    /// When serializing with a layout the value does not have the shape the layout requires
+    #[error("Value does not match layout. Expected: {expected}, found: {found}")]
+    ValueLayoutMismatch {
+        /// What the layout requires
+        expected: String,
+        /// What the value provided
+        found: String,
+    },
+
+    /// A Rust type does not have the structure a layout describes
+    #[error("Type does not match layout at {path}. Expected: {expected}, found: {found}")]
+    TypeLayoutMismatch {
+        /// Path from the root of the type to the divergence
+        path: ErrorPath,
+        /// What the layout requires
+        expected: String,
+        /// What the type provided
+        found: String,
+    },
+
     /// When deserializing a polyvar the tag does not match any known tags for the type
     #[error("Input stream has {0} unconsumed bytes")]
     StreamNotFullyConsumed(usize),
 
+    /// A length prefixed frame is larger than the codec allows
+    #[error("Frame of {size} bytes exceeds the maximum frame size of {max} bytes")]
+    FrameTooLarge {
+        /// Length of the frame
+        size: u64,
+        /// Maximum length allowed by the codec
+        max: usize,
+    },
+
+    /// A list or map is longer than the deserializer allows, see `DecodeLimits`
+    #[error("Sequence of {len} elements exceeds the maximum length of {max}")]
+    SeqTooLong {
+        /// Length read from the input
+        len: u64,
+        /// Maximum length allowed by the deserializer
+        max: usize,
+    },
+
+    /// A string or byte string is longer than the deserializer allows, see `DecodeLimits`
+    #[error("String of {len} bytes exceeds the maximum length of {max} bytes")]
+    StringTooLong {
+        /// Length read from the input
+        len: u64,
+        /// Maximum length allowed by the deserializer
+        max: usize,
+    },
+
+    /// The lengths read from the input add up to more than the deserializer allows, see `DecodeLimits`
+    #[error("Lengths read from the input exceed the total allocation limit of {max}")]
+    AllocationLimitExceeded {
+        /// Maximum total allowed by the deserializer
+        max: usize,
+    },
+
+    /// Values are nested deeper than the deserializer allows, see `DecodeLimits`
+    #[error("Value is nested deeper than the maximum depth of {max}")]
+    DepthLimitExceeded {
+        /// Maximum depth allowed by the deserializer
+        max: usize,
+    },
+
+    /// An error annotated with the position of the value that failed to deserialize
+    #[error("{source} (at byte offset {offset}, path {path})")]
+    WithContext {
+        /// Byte offset in the input where the failing value starts
+        offset: usize,
+        /// Path from the root value to the failing value
+        path: ErrorPath,
+        /// The error that occurred
+        source: Box<Error>,
+    },
+
     //////////////////////////////////
     /// Some user-defined error occurred.
     #[error("{message}")]
@@ -125,6 +197,90 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Byte offset in the input where the failing value starts, if known
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Error::WithContext { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Path from the root value to the failing value, if known
+    pub fn path(&self) -> Option<&ErrorPath> {
+        match self {
+            Error::WithContext { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The underlying error with the position context removed
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::WithContext { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
+
+    // Errors are annotated as they propagate up through the nested values.
+    // The innermost value sets the offset and each enclosing value prepends its segment
+    pub(crate) fn with_context(self, offset: usize, segment: Option<PathSegment>) -> Self {
+        match self {
+            Error::WithContext {
+                offset,
+                mut path,
+                source,
+            } => {
+                if let Some(segment) = segment {
+                    path.0.insert(0, segment);
+                }
+                Error::WithContext {
+                    offset,
+                    path,
+                    source,
+                }
+            }
+            e => Error::WithContext {
+                offset,
+                path: ErrorPath(segment.into_iter().collect()),
+                source: Box::new(e),
+            },
+        }
+    }
+}
+
+/// A single step from a value into one of its children
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A named struct or record field
+    Field(String),
+    /// An element of a sequence or tuple, or an unnamed field
+    Index(usize),
+    /// The variant of an enum or polyvar
+    Variant(String),
+}
+
+/// Path from the root value to a nested value, e.g. `header.commands[2]::Payment.amount`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorPath(pub Vec<PathSegment>);
+
+impl fmt::Display for ErrorPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "<root>");
+        }
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => write!(f, "{name}")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::Variant(name) => write!(f, "::{name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
 impl de::Error for Error {
     fn custom<T: fmt::Display>(msg: T) -> Error {
         Error::Custom {
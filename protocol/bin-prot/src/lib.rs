@@ -6,12 +6,16 @@
 #![doc = include_str!("../README.md")]
 
 // mod array;
+#[cfg(feature = "codec")]
+pub mod codec;
 mod consts;
 mod de;
 pub mod error;
 pub mod integers;
 #[cfg(feature = "loose_deserialization")]
 mod loose_deserializer;
+#[cfg(feature = "loose_deserialization")]
+mod loose_serializer;
 mod polyvar;
 mod read_ext;
 mod ser;
@@ -19,10 +23,18 @@ pub mod value;
 mod write_ext;
 
 // pub use array::OcamlArray;
-pub use de::{from_reader, from_reader_strict, Deserializer};
+pub use de::{
+    from_reader, from_reader_strict, from_slice, from_slice_strict, BinProtRead, DecodeLimits,
+    Deserializer, IoReader, SliceReader, DEFAULT_MAX_DEPTH, DEFAULT_MAX_SEQ_LEN,
+    DEFAULT_MAX_STRING_LEN, DEFAULT_MAX_TOTAL_ALLOC,
+};
+#[cfg(feature = "loose_deserialization")]
+pub use loose_serializer::to_writer_with_layout;
 pub use polyvar::{caml_hash_variant, VariantHash};
 pub use read_ext::ReadBinProtExt;
-pub use ser::{to_writer, Serializer};
+pub use ser::{serialized_size, to_writer, Serializer};
+#[cfg(feature = "loose_deserialization")]
+pub use value::json::{from_json_with_layout, to_json_with_layout};
 #[cfg(feature = "loose_deserialization")]
 pub use value::layout::{BinProtRule, Layout};
 pub use value::Value;
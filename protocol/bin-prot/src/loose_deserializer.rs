@@ -5,10 +5,10 @@ use byteorder::LittleEndian;
 use std::convert::TryInto;
 use std::io::Read;
 
-use crate::de::{Enum, LooselyTyped, MapAccess, SeqAccess};
-use crate::error::{Error, Result};
+use crate::de::{BinProtRead, Enum, LooselyTyped, MapAccess, SeqAccess};
+use crate::error::{Error, PathSegment, Result};
 use crate::value::layout::Summand;
-use crate::value::layout::{BinProtRule, Polyvar, TaggedPolyvar};
+use crate::value::layout::{BinProtRule, CustomType, Polyvar, TaggedPolyvar};
 use crate::Deserializer as DS;
 use crate::ReadBinProtExt;
 use serde::de::Visitor;
@@ -16,7 +16,7 @@ use serde::{Deserialize, Serialize};
 
 use byteorder::ReadBytesExt;
 
-impl<'de, R: Read> DS<R, LooselyTyped> {
+impl<'de, R: BinProtRead<'de>> DS<R, LooselyTyped> {
     /// The loose deserializer version of deserialize
     /// Only implemented on the LooselyTyped variant of the Deserializer
     pub fn deserialize_loose<V>(&mut self, visitor: V) -> Result<V::Value>
@@ -32,30 +32,38 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
                     }
                     BinProtRule::Record(fields) => {
                         // Grab the field names from the rule to pass to the map access
-                        visitor.visit_map(MapAccess::new(
-                            self,
-                            fields.into_iter().map(|f| f.field_name).rev().collect(),
-                        ))
+                        let field_names = fields.into_iter().map(|f| f.field_name).rev().collect();
+                        self.nested(|de| visitor.visit_map(MapAccess::new(de, field_names)))
                     }
                     BinProtRule::Tuple(items) => {
-                        visitor.visit_seq(SeqAccess::new(self, items.len()))
+                        self.nested(|de| visitor.visit_seq(SeqAccess::new(de, items.len())))
                     }
                     BinProtRule::Bool => visitor.visit_bool(self.rdr.bin_read_bool()?),
                     BinProtRule::Sum(summands) => {
                         // read the enum variant index.
                         // We need this to select which variant layout to use
                         // when deserializing the variants data
+                        let offset = self.offset();
                         let index = self.rdr.bin_read_variant_index()?;
-                        let variant_rules = summands[index as usize].ctor_args.clone();
+                        let summand = summands
+                            .get(index as usize)
+                            .ok_or_else(|| Error::InvalidByte {
+                                byte: index,
+                                dtype: "variant index".to_string(),
+                                allowed: (0..summands.len() as u8).collect(),
+                            })?
+                            .clone();
+                        let name = summand.ctor_name.clone();
                         self.mode
                             .layout_iter
-                            .push(vec![BinProtRule::Tuple(variant_rules)]);
-                        visitor.visit_enum(ValueEnum::new(
-                            self,
-                            VariantType::Sum(summands[index as usize].clone()),
-                        ))
+                            .push(vec![BinProtRule::Tuple(summand.ctor_args.clone())]);
+                        self.nested(|de| {
+                            visitor.visit_enum(ValueEnum::new(de, VariantType::Sum(summand)))
+                        })
+                        .map_err(|e| e.with_context(offset, Some(PathSegment::Variant(name))))
                     }
                     BinProtRule::Polyvar(summands) => {
+                        let offset = self.offset();
                         let tag = self.rdr.bin_read_polyvar_tag()?;
                         let (index, variant) = summands
                             .into_iter()
@@ -74,13 +82,17 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
                                 }
                             })
                             .ok_or(Error::UnknownPolyvarTag(tag))?;
+                        let name = variant.polyvar_name.clone();
                         self.mode
                             .layout_iter
                             .push(vec![BinProtRule::Tuple(variant.clone().polyvar_args)]);
-                        visitor.visit_enum(ValueEnum::new(
-                            self,
-                            VariantType::Polyvar(index as u8, variant),
-                        ))
+                        self.nested(|de| {
+                            visitor.visit_enum(ValueEnum::new(
+                                de,
+                                VariantType::Polyvar(index as u8, variant),
+                            ))
+                        })
+                        .map_err(|e| e.with_context(offset, Some(PathSegment::Variant(name))))
                     }
                     BinProtRule::Option(some_rule) => {
                         let index = self.rdr.bin_read_variant_index()?; // 0 or 1
@@ -93,7 +105,10 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
                             _ => Err(Error::InvalidOptionByte { got: index }),
                         }
                     }
-                    BinProtRule::String => visitor.visit_bytes(&self.rdr.bin_read_bytes()?),
+                    BinProtRule::String => {
+                        let len = self.read_string_len()?;
+                        self.rdr.bin_visit_bytes(len, visitor)
+                    }
                     BinProtRule::Float => visitor.visit_f64(self.rdr.read_f64::<LittleEndian>()?),
                     BinProtRule::Char => {
                         let c = self.rdr.read_u8()?;
@@ -101,11 +116,11 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
                     }
                     BinProtRule::List(element_rule) => {
                         // read the length
-                        let len = self.rdr.bin_read_nat0()?;
+                        let len = self.read_seq_len()?;
                         // request the iterator repeats the list elements the current number of times
                         self.mode.layout_iter.push_n(*element_rule, len);
                         // read the elements
-                        visitor.visit_seq(SeqAccess::new_list(self, len))
+                        self.nested(|de| visitor.visit_seq(SeqAccess::new_list(de, len)))
                     }
                     BinProtRule::Int
                     | BinProtRule::Int32
@@ -126,43 +141,20 @@ impl<'de, R: Read> DS<R, LooselyTyped> {
                     }
                     BinProtRule::CustomForPath(path, rules) => {
                         // here is where custom deser methods can be looked up by path
-                        match path.as_str() {
+                        match CustomType::for_path(&path) {
                             // These vector types will be handled like any other sequence
-                            "Pickles_type.Vector.Vector2" // the missing 's' on 'types' here is intention due to a bug in layout producing code
-                            | "Pickles_types.Vector.Vector2" // in case it gets fixed :P
-                            | "Pickles_types.Vector.Vector4"
-                            | "Pickles_types.Vector.Vector8"
-                            | "Pickles_types.Vector.Vector17"
-                            | "Pickles_types.Vector.Vector18" => {
+                            Some(CustomType::Vector(len)) => {
                                 let element_rule = rules.first().unwrap();
-                                let len = match path.as_str() {
-                                    "Pickles_type.Vector.Vector2"
-                                    | "Pickles_types.Vector.Vector2" => 2,
-                                    "Pickles_types.Vector.Vector4" => 4,
-                                    "Pickles_types.Vector.Vector8" => 8,
-                                    "Pickles_types.Vector.Vector17" => 17,
-                                    "Pickles_types.Vector.Vector18" => 18,
-                                    _ => unreachable!()
-                                };
                                 self.mode.layout_iter.push(vec![BinProtRule::Unit]); // zero byte terminator, will be read last
                                 self.mode.layout_iter.push_n(element_rule.clone(), len);
-                                visitor.visit_seq(SeqAccess::new(self, len+1))
+                                self.nested(|de| visitor.visit_seq(SeqAccess::new(de, len + 1)))
                             }
-                            "Ledger_hash0" // these are all BigInt (32 bytes)
-                            | "State_hash"
-                            | "Pending_coinbase.Stack_hash"
-                            | "State_body_hash"
-                            | "Pending_coinbase.Hash_builder"
-                            | "Snark_params.Make_inner_curve_scalar"
-                            | "Snark_params.Tick"
-                            | "Epoch_seed"
-                            | "Zexe_backend.Zexe_backend_common.Stable.Field"
-                            | "Pending_coinbase.Coinbase_stack" => {
+                            Some(CustomType::BigInt) => {
                                 // force it to read a 32 element long tuple of u8/chars
                                 self.mode.layout_iter.push_n(BinProtRule::Char, 32);
-                                visitor.visit_seq(SeqAccess::new(self, 32))
+                                self.nested(|de| visitor.visit_seq(SeqAccess::new(de, 32)))
                             }
-                            _ => Err(Error::UnknownCustomType{ typ: path })
+                            None => Err(Error::UnknownCustomType { typ: path }),
                         }
                     }
                 }
@@ -205,7 +197,7 @@ impl<'a, R: Read, Mode> ValueEnum<'a, R, Mode> {
     }
 }
 
-impl<'de, 'a, R: Read> serde::de::EnumAccess<'de> for ValueEnum<'a, R, LooselyTyped> {
+impl<'de, 'a, R: BinProtRead<'de>> serde::de::EnumAccess<'de> for ValueEnum<'a, R, LooselyTyped> {
     type Error = Error;
     type Variant = Enum<'a, R, LooselyTyped>;
 
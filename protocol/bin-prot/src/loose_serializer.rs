@@ -0,0 +1,310 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serialization of a loosely typed Value guided by a BinProt layout.
+//!
+//! The Serialize implementation of Value has to guess the encoding from the value alone which
+//! breaks for polyvars and for the custom encodings the loose deserializer handles by path.
+//! Walking the layout alongside the value removes the guesswork and checks the value actually
+//! matches the layout, so that anything read with a layout can be written back byte for byte.
+
+use std::io::{self, Write};
+
+use crate::error::{Error, PathSegment, Result};
+use crate::value::layout::{BinProtRule, CustomType, Polyvar, RuleRef};
+use crate::{Value, WriteBinProtExt};
+
+/// Write a Value using the encoding described by the given layout rule.
+/// Errors if the value does not have the shape the layout requires.
+pub fn to_writer_with_layout<W>(writer: &mut W, value: &Value, rule: &BinProtRule) -> Result<()>
+where
+    W: Write,
+{
+    let mut ser = LayoutSerializer {
+        writer: CountingWriter {
+            inner: writer,
+            count: 0,
+        },
+    };
+    ser.serialize(value, rule, None)
+        .map_err(|e| e.with_context(0, None))
+}
+
+struct CountingWriter<'a, W> {
+    inner: &'a mut W,
+    count: usize,
+}
+
+impl<W: Write> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+struct LayoutSerializer<'a, W> {
+    writer: CountingWriter<'a, W>,
+}
+
+impl<W: Write> LayoutSerializer<'_, W> {
+    // The module path of the most recently resolved reference is needed to pick
+    // the encoding of custom rules, the same way the rule iterator does when reading
+    fn serialize(
+        &mut self,
+        value: &Value,
+        rule: &BinProtRule,
+        module_path: Option<&str>,
+    ) -> Result<()> {
+        match (rule, value) {
+            (BinProtRule::Reference(RuleRef::Resolved(payload)), _) => self.serialize(
+                value,
+                &payload.ref_rule,
+                Some(payload.source_module_path.as_str()),
+            ),
+            (BinProtRule::Reference(RuleRef::Unresolved(_)), _) => Err(Error::UnimplementedRule),
+            (BinProtRule::Unit, Value::Unit) => Ok(self.writer.bin_write_unit()?),
+            (BinProtRule::Bool, Value::Bool(b)) => Ok(self.writer.bin_write_bool(*b)?),
+            (BinProtRule::Char, Value::Char(c)) => {
+                self.writer.bin_write_char(*c as char)?;
+                Ok(())
+            }
+            (BinProtRule::Nat0, Value::Nat0(n)) => {
+                self.writer.bin_write_nat0(*n)?;
+                Ok(())
+            }
+            (BinProtRule::Int32, Value::Int(n)) if i32::try_from(*n).is_err() => {
+                Err(Error::DestinationIntegerOverflow)
+            }
+            (
+                BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt,
+                Value::Int(n),
+            ) => {
+                self.writer.bin_write_integer(*n)?;
+                Ok(())
+            }
+            (BinProtRule::Float, Value::Float(f)) => {
+                self.writer.bin_write_float64(f)?;
+                Ok(())
+            }
+            // the loose deserializer reads strings as a list of chars
+            (BinProtRule::String, Value::String(bytes)) => self.write_string(bytes),
+            (BinProtRule::String, Value::List(chars)) => {
+                let bytes = chars
+                    .iter()
+                    .map(|c| match c {
+                        Value::Char(c) => Ok(*c),
+                        other => Err(mismatch("Char", other)),
+                    })
+                    .collect::<Result<Vec<u8>>>()?;
+                self.write_string(&bytes)
+            }
+            (BinProtRule::Option(_), Value::Option(None)) => {
+                Ok(self.writer.bin_write_bool(false)?)
+            }
+            (BinProtRule::Option(some_rule), Value::Option(Some(inner))) => {
+                self.writer.bin_write_bool(true)?;
+                self.serialize(inner, some_rule, module_path)
+            }
+            (BinProtRule::Record(fields), Value::Record(entries)) => {
+                if fields.len() != entries.len() {
+                    return Err(length_mismatch(fields.len(), entries.len()));
+                }
+                for (field, (name, entry)) in fields.iter().zip(entries) {
+                    if &field.field_name != name {
+                        return Err(Error::ValueLayoutMismatch {
+                            expected: format!("field {}", field.field_name),
+                            found: format!("field {}", name),
+                        });
+                    }
+                    self.serialize_child(
+                        entry,
+                        &field.field_rule,
+                        module_path,
+                        PathSegment::Field(name.clone()),
+                    )?;
+                }
+                Ok(())
+            }
+            (BinProtRule::Tuple(rules), Value::Tuple(values)) => {
+                self.serialize_elements(values, rules.iter(), rules.len(), module_path)
+            }
+            (BinProtRule::List(element_rule), Value::List(values)) => {
+                self.writer.bin_write_nat0(values.len() as u64)?;
+                let rules = std::iter::repeat(element_rule.as_ref());
+                self.serialize_elements(values, rules, values.len(), module_path)
+            }
+            (
+                BinProtRule::Sum(summands),
+                Value::Sum {
+                    name,
+                    index,
+                    value: args,
+                },
+            ) => {
+                let summand = summands
+                    .iter()
+                    .find(|s| s.index == *index as i32 && &s.ctor_name == name)
+                    .ok_or_else(|| Error::ValueLayoutMismatch {
+                        expected: "a summand of the layout".to_string(),
+                        found: format!("summand {} with index {}", name, index),
+                    })?;
+                self.writer.bin_write_variant_index(*index)?;
+                self.serialize_variant_args(args, &summand.ctor_args, name, module_path)
+            }
+            (
+                BinProtRule::Polyvar(polyvars),
+                Value::Polyvar {
+                    name,
+                    tag,
+                    value: args,
+                },
+            ) => {
+                let polyvar = polyvars
+                    .iter()
+                    .find_map(|p| match p {
+                        Polyvar::Tagged(t) if t.hash == *tag && &t.polyvar_name == name => Some(t),
+                        _ => None,
+                    })
+                    .ok_or_else(|| Error::ValueLayoutMismatch {
+                        expected: "a tagged polyvar of the layout".to_string(),
+                        found: format!("polyvar {} with tag {}", name, tag),
+                    })?;
+                self.writer.bin_write_polyvar_tag(*tag)?;
+                self.serialize_variant_args(args, &polyvar.polyvar_args, name, module_path)
+            }
+            (BinProtRule::Custom(rules), _) => match module_path {
+                Some(path) => self.serialize_custom(value, path, rules),
+                None => Err(Error::LayoutIteratorError),
+            },
+            (
+                BinProtRule::Vec(_, _)
+                | BinProtRule::Hashtable(_)
+                | BinProtRule::TypeVar(_)
+                | BinProtRule::Bigstring
+                | BinProtRule::SelfReference(_)
+                | BinProtRule::TypeClosure(_, _)
+                | BinProtRule::TypeAbstraction(_, _)
+                | BinProtRule::CustomForPath(_, _),
+                _,
+            ) => Err(Error::UnimplementedRule),
+            (rule, value) => Err(mismatch(rule_name(rule), value)),
+        }
+    }
+
+    fn serialize_child(
+        &mut self,
+        value: &Value,
+        rule: &BinProtRule,
+        module_path: Option<&str>,
+        segment: PathSegment,
+    ) -> Result<()> {
+        let offset = self.writer.count;
+        self.serialize(value, rule, module_path)
+            .map_err(|e| e.with_context(offset, Some(segment)))
+    }
+
+    fn serialize_elements<'r>(
+        &mut self,
+        values: &[Value],
+        rules: impl Iterator<Item = &'r BinProtRule>,
+        expected_len: usize,
+        module_path: Option<&str>,
+    ) -> Result<()> {
+        if values.len() != expected_len {
+            return Err(length_mismatch(expected_len, values.len()));
+        }
+        for (i, (value, rule)) in values.iter().zip(rules).enumerate() {
+            self.serialize_child(value, rule, module_path, PathSegment::Index(i))?;
+        }
+        Ok(())
+    }
+
+    // Variant arguments are read into a tuple by the loose deserializer
+    fn serialize_variant_args(
+        &mut self,
+        args: &Value,
+        rules: &[BinProtRule],
+        name: &str,
+        module_path: Option<&str>,
+    ) -> Result<()> {
+        let offset = self.writer.count;
+        match args {
+            Value::Tuple(values) => {
+                self.serialize_elements(values, rules.iter(), rules.len(), module_path)
+            }
+            other => Err(mismatch("Tuple", other)),
+        }
+        .map_err(|e| e.with_context(offset, Some(PathSegment::Variant(name.to_string()))))
+    }
+
+    // Mirrors the custom types handled by the loose deserializer
+    fn serialize_custom(&mut self, value: &Value, path: &str, rules: &[BinProtRule]) -> Result<()> {
+        let values = match value {
+            Value::Tuple(values) => values,
+            other => return Err(mismatch("Tuple", other)),
+        };
+        match CustomType::for_path(path) {
+            Some(CustomType::Vector(len)) => {
+                let element_rule = rules.first().ok_or(Error::LayoutIteratorError)?;
+                // elements are followed by a zero byte terminator
+                let rules = (0..len)
+                    .map(|_| element_rule)
+                    .chain(std::iter::once(&BinProtRule::Unit));
+                self.serialize_elements(values, rules, len + 1, Some(path))
+            }
+            Some(CustomType::BigInt) => {
+                let rules = std::iter::repeat(&BinProtRule::Char);
+                self.serialize_elements(values, rules, 32, Some(path))
+            }
+            None => Err(Error::UnknownCustomType {
+                typ: path.to_string(),
+            }),
+        }
+    }
+
+    fn write_string(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.bin_write_nat0(bytes.len() as u64)?;
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+}
+
+fn mismatch(expected: &str, found: &Value) -> Error {
+    Error::ValueLayoutMismatch {
+        expected: expected.to_string(),
+        found: found.type_name().to_string(),
+    }
+}
+
+fn length_mismatch(expected: usize, found: usize) -> Error {
+    Error::ValueLayoutMismatch {
+        expected: format!("{} elements", expected),
+        found: format!("{} elements", found),
+    }
+}
+
+fn rule_name(rule: &BinProtRule) -> &'static str {
+    match rule {
+        BinProtRule::Nat0 => "Nat0",
+        BinProtRule::Unit => "Unit",
+        BinProtRule::Bool => "Bool",
+        BinProtRule::String => "String",
+        BinProtRule::Char => "Char",
+        BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt => {
+            "Int"
+        }
+        BinProtRule::Float => "Float",
+        BinProtRule::Option(_) => "Option",
+        BinProtRule::Record(_) => "Record",
+        BinProtRule::Tuple(_) => "Tuple",
+        BinProtRule::Sum(_) => "Sum",
+        BinProtRule::Polyvar(_) => "Polyvar",
+        BinProtRule::List(_) => "List",
+        _ => "a supported rule",
+    }
+}
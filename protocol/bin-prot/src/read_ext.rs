@@ -5,7 +5,7 @@ use crate::consts::*;
 use crate::error::{Error, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
 use num::{FromPrimitive, Unsigned};
-use std::io;
+use std::io::{self, Read};
 
 /// Extension trait for readers implementing io::Read to allow them to read a bin_prot encoded values
 pub trait ReadBinProtExt: io::Read {
@@ -118,19 +118,27 @@ pub trait ReadBinProtExt: io::Read {
 
     /// Read a string
     fn bin_read_string(&mut self) -> Result<String> {
-        let len = self.bin_read_nat0::<u64>()? as usize;
-        let mut buf = vec![0u8; len as usize];
-        self.read_exact(&mut buf)?;
-        let s = std::str::from_utf8(&buf)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
-        Ok(s.to_string())
+        let buf = self.bin_read_bytes()?;
+        String::from_utf8(buf).map_err(|e| Error::InvalidUtf8 {
+            bytes: e.into_bytes(),
+        })
     }
 
     /// read some bytes
     fn bin_read_bytes(&mut self) -> Result<Vec<u8>> {
-        let len = self.bin_read_nat0::<u64>()? as usize;
-        let mut buf = vec![0u8; len as usize];
-        self.read_exact(&mut buf)?;
+        let len = self.bin_read_nat0::<u64>()?;
+        self.bin_read_bytes_of_len(len)
+    }
+
+    /// Read the `len` bytes of a string or byte string whose length prefix has been read already
+    fn bin_read_bytes_of_len(&mut self, len: u64) -> Result<Vec<u8>> {
+        // the buffer grows with the bytes actually read rather than being allocated
+        // from the length prefix, which is untrusted and can be arbitrarily large
+        let mut buf = Vec::new();
+        Read::take(&mut *self, len).read_to_end(&mut buf)?;
+        if (buf.len() as u64) < len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
         Ok(buf)
     }
 }
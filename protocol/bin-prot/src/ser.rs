@@ -64,6 +64,32 @@ where
     value.serialize(&mut Serializer::new(writer))
 }
 
+/// Returns the number of bytes `to_writer` would write for the value without writing them.
+/// The value is passed through the same serializer as `to_writer` so variable length integers,
+/// polyvar tags and version bytes are all counted exactly as they are encoded.
+pub fn serialized_size<T>(value: &T) -> Result<usize>
+where
+    T: ?Sized + Serialize,
+{
+    let mut counter = ByteCounter(0);
+    value.serialize(&mut Serializer::new(&mut counter))?;
+    Ok(counter.0)
+}
+
+// A writer that discards the bytes written to it and only keeps count of them
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 impl<'a, W> ser::Serializer for &'a mut Serializer<W>
 where
     W: std::io::Write,
@@ -0,0 +1,469 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversion between a loosely typed Value and JSON using a layout
+//!
+//! The layout provides the names of record fields and variants and resolves the ambiguities
+//! in the JSON so the conversion is lossless in both directions. The representation is
+//!
+//! | Layout rule              | JSON                                                          |
+//! |--------------------------|---------------------------------------------------------------|
+//! | Unit                     | `null`                                                        |
+//! | Bool                     | `true` / `false`                                              |
+//! | Char                     | number in 0..=255                                             |
+//! | Nat0, Int, Int32, ...    | number                                                        |
+//! | Float                    | number (must be finite)                                       |
+//! | String                   | string if valid UTF-8, otherwise an array of byte numbers     |
+//! | Option                   | `null` for None, the value for Some. If the inner value can itself be `null` (unit or option) Some is written as a single element array |
+//! | Record                   | object keyed by field name                                    |
+//! | Tuple, List              | array                                                         |
+//! | Sum, Polyvar             | `"Name"` without arguments, `{"Name": arg}` with one argument, `{"Name": [args...]}` with several |
+//! | Pickles vectors          | array of the elements (the terminator is omitted)             |
+//! | 32 byte big integers     | hex string                                                    |
+//!
+//! Values produced from JSON have the same shape as values read with the loose deserializer so
+//! they can be compared against decoded values and written with `to_writer_with_layout`.
+
+use serde_json::{Map, Number, Value as Json};
+
+use crate::error::{Error, Result};
+use crate::value::layout::{BinProtRule, CustomType, Polyvar, RuleRef};
+use crate::Value;
+
+/// Convert a Value to JSON using the names and encodings described by the layout rule
+pub fn to_json_with_layout(value: &Value, rule: &BinProtRule) -> Result<Json> {
+    value_to_json(value, rule, None)
+}
+
+/// Convert JSON in the representation produced by `to_json_with_layout` back to a Value
+pub fn from_json_with_layout(json: &Json, rule: &BinProtRule) -> Result<Value> {
+    json_to_value(json, rule, None)
+}
+
+fn value_to_json(value: &Value, rule: &BinProtRule, module_path: Option<&str>) -> Result<Json> {
+    match (rule, value) {
+        (BinProtRule::Reference(RuleRef::Resolved(payload)), _) => value_to_json(
+            value,
+            &payload.ref_rule,
+            Some(payload.source_module_path.as_str()),
+        ),
+        (BinProtRule::Unit, Value::Unit) => Ok(Json::Null),
+        (BinProtRule::Bool, Value::Bool(b)) => Ok(Json::Bool(*b)),
+        (BinProtRule::Char, Value::Char(c)) => Ok(Json::from(*c)),
+        (BinProtRule::Nat0, Value::Nat0(n)) => Ok(Json::from(*n)),
+        (
+            BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt,
+            Value::Int(n),
+        ) => Ok(Json::from(*n)),
+        (BinProtRule::Float, Value::Float(f)) => Number::from_f64(*f)
+            .map(Json::Number)
+            .ok_or_else(|| mismatch("a finite float", f.to_string())),
+        (BinProtRule::String, Value::String(bytes)) => Ok(bytes_to_json(bytes)),
+        (BinProtRule::String, Value::List(chars)) => Ok(bytes_to_json(&chars_to_bytes(chars)?)),
+        (BinProtRule::Option(_), Value::Option(None)) => Ok(Json::Null),
+        (BinProtRule::Option(some_rule), Value::Option(Some(inner))) => {
+            let json = value_to_json(inner, some_rule, module_path)?;
+            if is_nullable(some_rule) {
+                Ok(Json::Array(vec![json]))
+            } else {
+                Ok(json)
+            }
+        }
+        (BinProtRule::Record(fields), Value::Record(entries)) => {
+            if fields.len() != entries.len() {
+                return Err(length_mismatch(fields.len(), entries.len()));
+            }
+            let mut map = Map::new();
+            for (field, (name, entry)) in fields.iter().zip(entries) {
+                if &field.field_name != name {
+                    return Err(mismatch(
+                        format!("field {}", field.field_name),
+                        format!("field {}", name),
+                    ));
+                }
+                map.insert(
+                    name.clone(),
+                    value_to_json(entry, &field.field_rule, module_path)?,
+                );
+            }
+            Ok(Json::Object(map))
+        }
+        (BinProtRule::Tuple(rules), Value::Tuple(values)) => {
+            if rules.len() != values.len() {
+                return Err(length_mismatch(rules.len(), values.len()));
+            }
+            values
+                .iter()
+                .zip(rules)
+                .map(|(v, r)| value_to_json(v, r, module_path))
+                .collect()
+        }
+        (BinProtRule::List(element_rule), Value::List(values)) => values
+            .iter()
+            .map(|v| value_to_json(v, element_rule, module_path))
+            .collect(),
+        (BinProtRule::Sum(summands), Value::Sum { name, index, value }) => {
+            let summand = summands
+                .iter()
+                .find(|s| s.index == *index as i32 && &s.ctor_name == name)
+                .ok_or_else(|| mismatch("a summand of the layout", name.clone()))?;
+            variant_to_json(name, value, &summand.ctor_args, module_path)
+        }
+        (BinProtRule::Polyvar(polyvars), Value::Polyvar { name, tag, value }) => {
+            let polyvar = polyvars
+                .iter()
+                .find_map(|p| match p {
+                    Polyvar::Tagged(t) if t.hash == *tag && &t.polyvar_name == name => Some(t),
+                    _ => None,
+                })
+                .ok_or_else(|| mismatch("a tagged polyvar of the layout", name.clone()))?;
+            variant_to_json(name, value, &polyvar.polyvar_args, module_path)
+        }
+        (BinProtRule::Custom(rules), Value::Tuple(values)) => {
+            let path = module_path.ok_or(Error::LayoutIteratorError)?;
+            match CustomType::for_path(path) {
+                Some(CustomType::Vector(len)) => {
+                    let element_rule = rules.first().ok_or(Error::LayoutIteratorError)?;
+                    // the elements are followed by a unit terminator
+                    match values.split_last() {
+                        Some((Value::Unit, elements)) if elements.len() == len => elements
+                            .iter()
+                            .map(|v| value_to_json(v, element_rule, Some(path)))
+                            .collect(),
+                        _ => Err(length_mismatch(len + 1, values.len())),
+                    }
+                }
+                Some(CustomType::BigInt) => {
+                    if values.len() != 32 {
+                        return Err(length_mismatch(32, values.len()));
+                    }
+                    Ok(Json::String(to_hex(&chars_to_bytes(values)?)))
+                }
+                None => Err(Error::UnknownCustomType {
+                    typ: path.to_string(),
+                }),
+            }
+        }
+        (rule, _) if !is_supported(rule) => Err(Error::UnimplementedRule),
+        (_, value) => Err(mismatch("a value matching the layout", value.type_name())),
+    }
+}
+
+fn variant_to_json(
+    name: &str,
+    args: &Value,
+    rules: &[BinProtRule],
+    module_path: Option<&str>,
+) -> Result<Json> {
+    let values = match args {
+        Value::Tuple(values) if values.len() == rules.len() => values,
+        Value::Tuple(values) => return Err(length_mismatch(rules.len(), values.len())),
+        other => return Err(mismatch("Tuple", other.type_name())),
+    };
+    let mut args = values
+        .iter()
+        .zip(rules)
+        .map(|(v, r)| value_to_json(v, r, module_path))
+        .collect::<Result<Vec<_>>>()?;
+    let args = match args.len() {
+        0 => return Ok(Json::String(name.to_string())),
+        1 => args.remove(0),
+        _ => Json::Array(args),
+    };
+    let mut map = Map::new();
+    map.insert(name.to_string(), args);
+    Ok(Json::Object(map))
+}
+
+fn json_to_value(json: &Json, rule: &BinProtRule, module_path: Option<&str>) -> Result<Value> {
+    match (rule, json) {
+        (BinProtRule::Reference(RuleRef::Resolved(payload)), _) => json_to_value(
+            json,
+            &payload.ref_rule,
+            Some(payload.source_module_path.as_str()),
+        ),
+        (BinProtRule::Unit, Json::Null) => Ok(Value::Unit),
+        (BinProtRule::Bool, Json::Bool(b)) => Ok(Value::Bool(*b)),
+        (BinProtRule::Char, Json::Number(n)) => n
+            .as_u64()
+            .and_then(|c| u8::try_from(c).ok())
+            .map(Value::Char)
+            .ok_or_else(|| mismatch("a char in 0..=255", n.to_string())),
+        (BinProtRule::Nat0, Json::Number(n)) => {
+            let n = n
+                .as_u64()
+                .ok_or_else(|| mismatch("a natural number", n.to_string()))?;
+            Ok(Value::Nat0(
+                u32::try_from(n).map_err(|_| Error::DestinationIntegerOverflow)?,
+            ))
+        }
+        (
+            BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt,
+            Json::Number(n),
+        ) => {
+            let n = n
+                .as_i64()
+                .ok_or_else(|| mismatch("an integer", n.to_string()))?;
+            if matches!(rule, BinProtRule::Int32) && i32::try_from(n).is_err() {
+                return Err(Error::DestinationIntegerOverflow);
+            }
+            Ok(Value::Int(n))
+        }
+        (BinProtRule::Float, Json::Number(n)) => n
+            .as_f64()
+            .map(Value::Float)
+            .ok_or_else(|| mismatch("a float", n.to_string())),
+        // the loose deserializer reads strings as a list of chars
+        (BinProtRule::String, Json::String(s)) => Ok(bytes_to_chars(s.as_bytes())),
+        (BinProtRule::String, Json::Array(values)) => values
+            .iter()
+            .map(|v| json_to_value(v, &BinProtRule::Char, None))
+            .collect::<Result<_>>()
+            .map(Value::List),
+        (BinProtRule::Option(_), Json::Null) => Ok(Value::Option(None)),
+        (BinProtRule::Option(some_rule), _) => {
+            let inner = if is_nullable(some_rule) {
+                match json {
+                    Json::Array(values) if values.len() == 1 => &values[0],
+                    _ => return Err(mismatch("a single element array", json_kind(json))),
+                }
+            } else {
+                json
+            };
+            Ok(Value::Option(Some(Box::new(json_to_value(
+                inner,
+                some_rule,
+                module_path,
+            )?))))
+        }
+        (BinProtRule::Record(fields), Json::Object(map)) => {
+            if let Some(unknown) = map
+                .keys()
+                .find(|k| !fields.iter().any(|f| &f.field_name == *k))
+            {
+                return Err(mismatch(
+                    "a field of the layout",
+                    format!("field {}", unknown),
+                ));
+            }
+            fields
+                .iter()
+                .map(|field| {
+                    let json = map.get(&field.field_name).ok_or_else(|| {
+                        mismatch(format!("field {}", field.field_name), "nothing")
+                    })?;
+                    Ok((
+                        field.field_name.clone(),
+                        json_to_value(json, &field.field_rule, module_path)?,
+                    ))
+                })
+                .collect::<Result<_>>()
+                .map(Value::Record)
+        }
+        (BinProtRule::Tuple(rules), Json::Array(values)) => {
+            json_to_values(values, rules, module_path).map(Value::Tuple)
+        }
+        (BinProtRule::List(element_rule), Json::Array(values)) => values
+            .iter()
+            .map(|v| json_to_value(v, element_rule, module_path))
+            .collect::<Result<_>>()
+            .map(Value::List),
+        (BinProtRule::Sum(summands), Json::String(_) | Json::Object(_)) => {
+            let (name, args) = json_variant(json)?;
+            let summand = summands
+                .iter()
+                .find(|s| s.ctor_name == name)
+                .ok_or_else(|| mismatch("a summand of the layout", name.to_string()))?;
+            Ok(Value::Sum {
+                name: summand.ctor_name.clone(),
+                index: summand.index as u8,
+                value: Box::new(json_to_variant_args(args, &summand.ctor_args, module_path)?),
+            })
+        }
+        (BinProtRule::Polyvar(polyvars), Json::String(_) | Json::Object(_)) => {
+            let (name, args) = json_variant(json)?;
+            let polyvar = polyvars
+                .iter()
+                .find_map(|p| match p {
+                    Polyvar::Tagged(t) if t.polyvar_name == name => Some(t),
+                    _ => None,
+                })
+                .ok_or_else(|| mismatch("a tagged polyvar of the layout", name.to_string()))?;
+            Ok(Value::Polyvar {
+                name: polyvar.polyvar_name.clone(),
+                tag: polyvar.hash,
+                value: Box::new(json_to_variant_args(
+                    args,
+                    &polyvar.polyvar_args,
+                    module_path,
+                )?),
+            })
+        }
+        (BinProtRule::Custom(rules), _) => {
+            let path = module_path.ok_or(Error::LayoutIteratorError)?;
+            match (CustomType::for_path(path), json) {
+                (Some(CustomType::Vector(len)), Json::Array(values)) => {
+                    let element_rule = rules.first().ok_or(Error::LayoutIteratorError)?;
+                    if values.len() != len {
+                        return Err(length_mismatch(len, values.len()));
+                    }
+                    let mut elements = values
+                        .iter()
+                        .map(|v| json_to_value(v, element_rule, Some(path)))
+                        .collect::<Result<Vec<_>>>()?;
+                    elements.push(Value::Unit);
+                    Ok(Value::Tuple(elements))
+                }
+                (Some(CustomType::BigInt), Json::String(s)) => {
+                    let bytes = from_hex(s)?;
+                    if bytes.len() != 32 {
+                        return Err(length_mismatch(32, bytes.len()));
+                    }
+                    Ok(Value::Tuple(bytes.into_iter().map(Value::Char).collect()))
+                }
+                (Some(CustomType::Vector(_)), _) => Err(mismatch("array", json_kind(json))),
+                (Some(CustomType::BigInt), _) => Err(mismatch("hex string", json_kind(json))),
+                (None, _) => Err(Error::UnknownCustomType {
+                    typ: path.to_string(),
+                }),
+            }
+        }
+        (rule, _) if !is_supported(rule) => Err(Error::UnimplementedRule),
+        (_, json) => Err(mismatch("a value matching the layout", json_kind(json))),
+    }
+}
+
+fn json_to_values(
+    values: &[Json],
+    rules: &[BinProtRule],
+    module_path: Option<&str>,
+) -> Result<Vec<Value>> {
+    if values.len() != rules.len() {
+        return Err(length_mismatch(rules.len(), values.len()));
+    }
+    values
+        .iter()
+        .zip(rules)
+        .map(|(v, r)| json_to_value(v, r, module_path))
+        .collect()
+}
+
+// Returns the variant name and its arguments, if any
+fn json_variant(json: &Json) -> Result<(&str, Option<&Json>)> {
+    match json {
+        Json::String(name) => Ok((name, None)),
+        Json::Object(map) if map.len() == 1 => {
+            let (name, args) = map.iter().next().unwrap();
+            Ok((name, Some(args)))
+        }
+        _ => Err(mismatch(
+            "a variant name or an object with a single variant",
+            json_kind(json),
+        )),
+    }
+}
+
+// Variant arguments are read into a tuple by the loose deserializer
+fn json_to_variant_args(
+    args: Option<&Json>,
+    rules: &[BinProtRule],
+    module_path: Option<&str>,
+) -> Result<Value> {
+    let values = match (args, rules.len()) {
+        (None, 0) => vec![],
+        (Some(arg), 1) => vec![json_to_value(arg, &rules[0], module_path)?],
+        (Some(Json::Array(args)), _) if rules.len() > 1 => {
+            json_to_values(args, rules, module_path)?
+        }
+        (None, n) => return Err(length_mismatch(n, 0)),
+        (Some(json), _) => return Err(mismatch("variant arguments", json_kind(json))),
+    };
+    Ok(Value::Tuple(values))
+}
+
+fn is_nullable(rule: &BinProtRule) -> bool {
+    match rule {
+        BinProtRule::Unit | BinProtRule::Option(_) => true,
+        BinProtRule::Reference(RuleRef::Resolved(payload)) => is_nullable(&payload.ref_rule),
+        _ => false,
+    }
+}
+
+// Rules that cannot be read by the loose deserializer either
+fn is_supported(rule: &BinProtRule) -> bool {
+    !matches!(
+        rule,
+        BinProtRule::Vec(_, _)
+            | BinProtRule::Hashtable(_)
+            | BinProtRule::TypeVar(_)
+            | BinProtRule::Bigstring
+            | BinProtRule::SelfReference(_)
+            | BinProtRule::TypeClosure(_, _)
+            | BinProtRule::TypeAbstraction(_, _)
+            | BinProtRule::CustomForPath(_, _)
+            | BinProtRule::Reference(RuleRef::Unresolved(_))
+    )
+}
+
+fn bytes_to_json(bytes: &[u8]) -> Json {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => Json::String(s.to_string()),
+        Err(_) => bytes.iter().map(|b| Json::from(*b)).collect(),
+    }
+}
+
+fn chars_to_bytes(chars: &[Value]) -> Result<Vec<u8>> {
+    chars
+        .iter()
+        .map(|c| match c {
+            Value::Char(c) => Ok(*c),
+            other => Err(mismatch("Char", other.type_name())),
+        })
+        .collect()
+}
+
+fn bytes_to_chars(bytes: &[u8]) -> Value {
+    Value::List(bytes.iter().map(|b| Value::Char(*b)).collect())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>> {
+    s.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [hi, lo] => match ((*hi as char).to_digit(16), (*lo as char).to_digit(16)) {
+                (Some(hi), Some(lo)) => Ok((hi << 4 | lo) as u8),
+                _ => Err(mismatch("hex string", s.to_string())),
+            },
+            _ => Err(mismatch("hex string", s.to_string())),
+        })
+        .collect()
+}
+
+fn json_kind(json: &Json) -> &'static str {
+    match json {
+        Json::Null => "null",
+        Json::Bool(_) => "bool",
+        Json::Number(_) => "number",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
+
+fn mismatch(expected: impl Into<String>, found: impl Into<String>) -> Error {
+    Error::ValueLayoutMismatch {
+        expected: expected.into(),
+        found: found.into(),
+    }
+}
+
+fn length_mismatch(expected: usize, found: usize) -> Error {
+    mismatch(
+        format!("{} elements", expected),
+        format!("{} elements", found),
+    )
+}
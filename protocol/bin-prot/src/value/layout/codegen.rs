@@ -0,0 +1,579 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Generates Rust type definitions from a layout
+//!
+//! The generated types are meant to serialize to the binary the layout describes when used with this crate.
+//! They follow the conventions of the mina-serialization-types crate:
+//!
+//! - Records with exactly the fields `version: int` and `t` become `Versioned<T, N>`. The version number
+//!   is taken from the `Stable.V<N>` module the record wraps. A type alias with a `V<N>` suffix is emitted for
+//!   the outermost versioned type of each reference.
+//! - Records become structs and sums become enums, named after the module path of the reference that contains them.
+//!   Anonymous records and sums are named after their parent type and the field or constructor that holds them.
+//! - Polymorphic variants become enums with `#[serde(rename = "Polyvar")]` and each variant renamed to its OCaml name.
+//! - Nat0 becomes a `u64` annotated with `#[serde(with = "bin_prot::integers::nat0")]` (or wrapped in a `Nat0` newtype
+//!   where an annotation is not possible). Integers are `i64`/`i32` as in OCaml, also for unsigned types such as
+//!   `Unsigned_extended.UInt64` which OCaml stores in its signed integers.
+//! - Custom Pickles vectors become tuple structs with a trailing `()` for the terminator and 32 byte big integers
+//!   become `[u8; 32]`.
+//!
+//! The output is a starting point. Generated names are mechanical and should be reviewed before the types are
+//! added to mina-serialization-types. The test-serialization crate compiles the types generated for the external
+//! transition layout and checks that they round trip the block fixtures, other layouts are not checked.
+//!
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::error::{Error, Result};
+use crate::value::layout::{
+    find_version, versioned_inner, BinProtRule, CustomType, Layout, Polyvar, RuleRef,
+};
+
+/// Generate the source of a Rust module with types for everything in the layout
+pub fn generate_types(layout: &Layout) -> Result<String> {
+    let mut gen = Generator::default();
+    let root_name = type_name_for_path(&layout.module_path);
+    let root_name = if root_name.is_empty() {
+        "Root".to_string()
+    } else {
+        root_name
+    };
+    match versioned_inner(&layout.bin_prot_rule) {
+        Some(t_rule) => {
+            let version = layout
+                .version_opt
+                .map(|v| v as u16)
+                .unwrap_or_else(|| find_version(t_rule));
+            let inner = gen.nested_type(t_rule, &root_name, None, true)?;
+            let root = RustType {
+                expr: format!("Versioned<{}, {}>", inner.expr, version),
+                eq: inner.eq,
+                nat0: false,
+            };
+            gen.push_alias(
+                &format!("{}V{}", root_name, version),
+                &format!(
+                    "Versioned type for the layout of `{}`",
+                    layout.module_path.trim()
+                ),
+                &root,
+            )?;
+        }
+        None => {
+            gen.nested_type(&layout.bin_prot_rule, &root_name, None, false)?;
+        }
+    }
+    Ok(gen.render(&layout.module_path))
+}
+
+struct RustType {
+    expr: String,
+    eq: bool,
+    // a Nat0 which needs to be annotated or wrapped to serialize correctly
+    nat0: bool,
+}
+
+impl RustType {
+    fn new(expr: impl Into<String>) -> Self {
+        Self {
+            expr: expr.into(),
+            eq: true,
+            nat0: false,
+        }
+    }
+}
+
+struct Item {
+    doc: String,
+    attrs: String,
+    // everything after the name of the item
+    body: String,
+    keyword: &'static str,
+}
+
+#[derive(Default)]
+struct Generator {
+    items: Vec<(String, Item)>,
+    // name -> index into items, used to avoid defining the same type twice
+    defined: HashMap<String, usize>,
+    eq: HashMap<String, bool>,
+    uses_versioned: bool,
+}
+
+const NAT0_WITH: &str = "#[serde(with = \"bin_prot::integers::nat0\")]";
+
+impl Generator {
+    // The type of a rule used where a serde field annotation is possible
+    fn field_type(
+        &mut self,
+        rule: &BinProtRule,
+        context: &str,
+        module_path: Option<&str>,
+    ) -> Result<RustType> {
+        match rule {
+            BinProtRule::Reference(RuleRef::Resolved(payload)) => {
+                let path = payload.source_module_path.as_str();
+                match versioned_inner(&payload.ref_rule) {
+                    Some(t_rule) => {
+                        let version = find_version(t_rule);
+                        let inner = self.nested_type(t_rule, context, Some(path), true)?;
+                        let versioned = RustType {
+                            expr: format!("Versioned<{}, {}>", inner.expr, version),
+                            eq: inner.eq,
+                            nat0: false,
+                        };
+                        let name = format!("{}V{}", type_name_for_path(path), version);
+                        self.push_alias(
+                            &name,
+                            &format!("Versioned type for `{}`", path),
+                            &versioned,
+                        )
+                    }
+                    None => {
+                        let context = context_for_reference(context, path, &payload.ref_rule);
+                        self.field_type(&payload.ref_rule, &context, Some(path))
+                    }
+                }
+            }
+            BinProtRule::Reference(RuleRef::Unresolved(_)) => Err(Error::UnimplementedRule),
+            BinProtRule::Unit => Ok(RustType::new("()")),
+            BinProtRule::Bool => Ok(RustType::new("bool")),
+            BinProtRule::Char => Ok(RustType::new("u8")),
+            BinProtRule::String => Ok(RustType::new("Vec<u8>")),
+            BinProtRule::Float => Ok(RustType {
+                expr: "f64".into(),
+                eq: false,
+                nat0: false,
+            }),
+            BinProtRule::Nat0 => Ok(RustType {
+                expr: "u64".into(),
+                eq: true,
+                nat0: true,
+            }),
+            BinProtRule::Int | BinProtRule::Int64 | BinProtRule::NativeInt => {
+                Ok(RustType::new("i64"))
+            }
+            BinProtRule::Int32 => Ok(RustType::new("i32")),
+            BinProtRule::Option(inner) => {
+                let inner = self.nested_type(inner, context, module_path, false)?;
+                Ok(RustType {
+                    expr: format!("Option<{}>", inner.expr),
+                    eq: inner.eq,
+                    nat0: false,
+                })
+            }
+            BinProtRule::List(inner) => {
+                let inner = self.nested_type(inner, context, module_path, false)?;
+                Ok(RustType {
+                    expr: format!("Vec<{}>", inner.expr),
+                    eq: inner.eq,
+                    nat0: false,
+                })
+            }
+            // std only implements traits for tuples of up to 12 elements
+            BinProtRule::Tuple(rules) if rules.len() <= 12 => {
+                let elements = rules
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| {
+                        self.nested_type(r, &format!("{}{}", context, i), module_path, false)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let mut expr = elements
+                    .iter()
+                    .map(|e| e.expr.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if elements.len() == 1 {
+                    expr.push(',');
+                }
+                Ok(RustType {
+                    expr: format!("({})", expr),
+                    eq: elements.iter().all(|e| e.eq),
+                    nat0: false,
+                })
+            }
+            BinProtRule::Tuple(rules) => {
+                let fields = rules
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| self.field_type(r, &format!("{}{}", context, i), module_path))
+                    .collect::<Result<Vec<_>>>()?;
+                self.push_tuple_struct(context, "Tuple", fields)
+            }
+            BinProtRule::Record(fields) => {
+                let name = context.to_string();
+                let mut body = " {\n".to_string();
+                let mut eq = true;
+                for field in fields {
+                    let ty = self.field_type(
+                        &field.field_rule,
+                        &format!("{}{}", name, camel_case(&field.field_name)),
+                        module_path,
+                    )?;
+                    eq &= ty.eq;
+                    if ty.nat0 {
+                        writeln!(body, "    {}", NAT0_WITH).unwrap();
+                    }
+                    writeln!(
+                        body,
+                        "    pub {}: {},",
+                        escape_keyword(&field.field_name),
+                        ty.expr
+                    )
+                    .unwrap();
+                }
+                body.push('}');
+                self.push_item(
+                    &name,
+                    Item {
+                        doc: doc_for(module_path),
+                        attrs: derives(eq),
+                        body,
+                        keyword: "struct",
+                    },
+                    eq,
+                )
+            }
+            BinProtRule::Sum(summands) => {
+                let name = context.to_string();
+                let mut summands: Vec<_> = summands.iter().collect();
+                summands.sort_by_key(|s| s.index);
+                // variants are serialized with their position so the indices must be contiguous
+                if let Some((i, s)) = summands
+                    .iter()
+                    .enumerate()
+                    .find(|(i, s)| s.index != *i as i32)
+                {
+                    return Err(Error::Custom {
+                        message: format!(
+                            "Summand {} of {} has index {} but is in position {}",
+                            s.ctor_name, name, s.index, i
+                        ),
+                    });
+                }
+                let mut body = " {\n".to_string();
+                let mut eq = true;
+                for summand in summands {
+                    let variant = camel_case(&summand.ctor_name);
+                    let args =
+                        self.variant_args(&summand.ctor_args, &name, &variant, module_path)?;
+                    eq &= args.1;
+                    writeln!(body, "    {}{},", variant, args.0).unwrap();
+                }
+                body.push('}');
+                self.push_item(
+                    &name,
+                    Item {
+                        doc: doc_for(module_path),
+                        attrs: derives(eq),
+                        body,
+                        keyword: "enum",
+                    },
+                    eq,
+                )
+            }
+            BinProtRule::Polyvar(polyvars) => {
+                let name = context.to_string();
+                let mut body = " {\n".to_string();
+                let mut eq = true;
+                for polyvar in polyvars {
+                    let tagged = match polyvar {
+                        Polyvar::Tagged(tagged) => tagged,
+                        Polyvar::Inherited(_) => return Err(Error::UnimplementedRule),
+                    };
+                    let variant = camel_case(&tagged.polyvar_name);
+                    let args =
+                        self.variant_args(&tagged.polyvar_args, &name, &variant, module_path)?;
+                    eq &= args.1;
+                    writeln!(
+                        body,
+                        "    #[serde(rename = \"{}\")]\n    {}{},",
+                        tagged.polyvar_name, variant, args.0
+                    )
+                    .unwrap();
+                }
+                body.push('}');
+                let attrs = format!("{}\n#[serde(rename = \"Polyvar\")]", derives(eq));
+                self.push_item(
+                    &name,
+                    Item {
+                        doc: doc_for(module_path),
+                        attrs,
+                        body,
+                        keyword: "enum",
+                    },
+                    eq,
+                )
+            }
+            BinProtRule::Custom(rules) => {
+                let path = module_path.ok_or(Error::LayoutIteratorError)?;
+                match CustomType::for_path(path) {
+                    Some(CustomType::BigInt) => Ok(RustType::new("[u8; 32]")),
+                    Some(CustomType::Vector(len)) => {
+                        let element_rule = rules.first().ok_or(Error::LayoutIteratorError)?;
+                        let mut fields = (0..len)
+                            .map(|_| self.field_type(element_rule, context, Some(path)))
+                            .collect::<Result<Vec<_>>>()?;
+                        // zero byte terminator
+                        fields.push(RustType::new("()"));
+                        self.push_tuple_struct(context, &format!("Vector{}", len), fields)
+                    }
+                    None => Err(Error::UnknownCustomType {
+                        typ: path.to_string(),
+                    }),
+                }
+            }
+            BinProtRule::Vec(_, _)
+            | BinProtRule::Hashtable(_)
+            | BinProtRule::TypeVar(_)
+            | BinProtRule::Bigstring
+            | BinProtRule::SelfReference(_)
+            | BinProtRule::TypeClosure(_, _)
+            | BinProtRule::TypeAbstraction(_, _)
+            | BinProtRule::CustomForPath(_, _) => Err(Error::UnimplementedRule),
+        }
+    }
+
+    // The type of a rule used as a type parameter, where a serde field annotation is not possible.
+    // If inside a versioned record the references do not get their own alias
+    fn nested_type(
+        &mut self,
+        rule: &BinProtRule,
+        context: &str,
+        module_path: Option<&str>,
+        in_versioned: bool,
+    ) -> Result<RustType> {
+        let ty = match (rule, in_versioned) {
+            (BinProtRule::Reference(RuleRef::Resolved(payload)), true) => {
+                let path = payload.source_module_path.as_str();
+                match versioned_inner(&payload.ref_rule) {
+                    Some(t_rule) => {
+                        let version = find_version(t_rule);
+                        let inner = self.nested_type(t_rule, context, Some(path), true)?;
+                        self.uses_versioned = true;
+                        RustType {
+                            expr: format!("Versioned<{}, {}>", inner.expr, version),
+                            eq: inner.eq,
+                            nat0: false,
+                        }
+                    }
+                    None => {
+                        let context = context_for_reference(context, path, &payload.ref_rule);
+                        self.nested_type(&payload.ref_rule, &context, Some(path), false)?
+                    }
+                }
+            }
+            _ => self.field_type(rule, context, module_path)?,
+        };
+        if ty.nat0 {
+            let nat0 = RustType {
+                expr: "u64".into(),
+                eq: true,
+                nat0: true,
+            };
+            return self.push_tuple_struct("", "Nat0", vec![nat0]);
+        }
+        Ok(ty)
+    }
+
+    // Returns the arguments in tuple variant syntax and whether they are Eq
+    fn variant_args(
+        &mut self,
+        rules: &[BinProtRule],
+        enum_name: &str,
+        variant: &str,
+        module_path: Option<&str>,
+    ) -> Result<(String, bool)> {
+        if rules.is_empty() {
+            return Ok((String::new(), true));
+        }
+        let context = format!("{}{}", enum_name, variant);
+        let args = rules
+            .iter()
+            .map(|r| self.field_type(r, &context, module_path))
+            .collect::<Result<Vec<_>>>()?;
+        let eq = args.iter().all(|a| a.eq);
+        let args = args
+            .iter()
+            .map(|a| {
+                if a.nat0 {
+                    format!("{} {}", NAT0_WITH, a.expr)
+                } else {
+                    a.expr.clone()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok((format!("({})", args), eq))
+    }
+
+    fn push_tuple_struct(
+        &mut self,
+        context: &str,
+        suffix: &str,
+        fields: Vec<RustType>,
+    ) -> Result<RustType> {
+        let eq = fields.iter().all(|f| f.eq);
+        let fields = fields
+            .iter()
+            .map(|f| {
+                if f.nat0 {
+                    format!("{} pub {}", NAT0_WITH, f.expr)
+                } else {
+                    format!("pub {}", f.expr)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.push_item(
+            &format!("{}{}", context, suffix),
+            Item {
+                doc: format!("/// {} of {}\n", suffix, context),
+                attrs: derives(eq),
+                body: format!("({});", fields),
+                keyword: "struct",
+            },
+            eq,
+        )
+    }
+
+    fn push_alias(&mut self, name: &str, doc: &str, ty: &RustType) -> Result<RustType> {
+        self.uses_versioned = true;
+        self.push_item(
+            name,
+            Item {
+                doc: format!("/// {}\n", doc),
+                attrs: String::new(),
+                body: format!(" = {};", ty.expr),
+                keyword: "type",
+            },
+            ty.eq,
+        )
+    }
+
+    // Adds the item unless an identical one already exists. Returns the name of the type which
+    // gets a numeric suffix if a different item already uses the name
+    fn push_item(&mut self, name: &str, item: Item, eq: bool) -> Result<RustType> {
+        let mut candidate = name.to_string();
+        let mut suffix = 1;
+        while let Some(&index) = self.defined.get(&candidate) {
+            let existing = &self.items[index].1;
+            if existing.body == item.body
+                && existing.attrs == item.attrs
+                && existing.keyword == item.keyword
+            {
+                return Ok(RustType {
+                    expr: candidate.clone(),
+                    eq: self.eq[&candidate],
+                    nat0: false,
+                });
+            }
+            suffix += 1;
+            candidate = format!("{}{}", name, suffix);
+        }
+        self.defined.insert(candidate.clone(), self.items.len());
+        self.eq.insert(candidate.clone(), eq);
+        self.items.push((candidate.clone(), item));
+        Ok(RustType {
+            expr: candidate,
+            eq,
+            nat0: false,
+        })
+    }
+
+    fn render(&self, module_path: &str) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "//! Types generated from the layout of `{}`\n\n#![allow(missing_docs)]\n",
+            module_path.trim()
+        )
+        .unwrap();
+        writeln!(out, "use serde::{{Deserialize, Serialize}};").unwrap();
+        if self.uses_versioned {
+            writeln!(out, "use versioned::Versioned;").unwrap();
+        }
+        for (name, item) in &self.items {
+            out.push('\n');
+            out.push_str(&item.doc);
+            if !item.attrs.is_empty() {
+                writeln!(out, "{}", item.attrs).unwrap();
+            }
+            writeln!(out, "pub {} {}{}", item.keyword, name, item.body).unwrap();
+        }
+        out
+    }
+}
+
+// Named types take their name from the module path of the reference which defines them,
+// other types are named after the field or variant which holds them
+fn context_for_reference(context: &str, path: &str, rule: &BinProtRule) -> String {
+    match rule {
+        BinProtRule::Record(_) | BinProtRule::Sum(_) | BinProtRule::Polyvar(_) => {
+            let name = type_name_for_path(path);
+            if name.is_empty() {
+                context.to_string()
+            } else {
+                name
+            }
+        }
+        _ => context.to_string(),
+    }
+}
+
+// Protocol_state.Poly.Stable.V1 -> ProtocolStatePoly
+fn type_name_for_path(path: &str) -> String {
+    path.split('.')
+        .filter(|segment| {
+            *segment != "Stable"
+                && !(segment.starts_with('V') && segment[1..].parse::<u32>().is_ok())
+        })
+        .map(camel_case)
+        .collect()
+}
+
+fn camel_case(s: &str) -> String {
+    s.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn escape_keyword(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
+        "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+        "move", "mut", "pub", "ref", "return", "static", "struct", "super", "trait", "true", "try",
+        "type", "unsafe", "use", "where", "while", "yield",
+    ];
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn derives(eq: bool) -> String {
+    if eq {
+        "#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]".to_string()
+    } else {
+        "#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]".to_string()
+    }
+}
+
+fn doc_for(module_path: Option<&str>) -> String {
+    match module_path {
+        Some(path) => format!("/// Generated from `{}`\n", path),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,749 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Checks that a Rust type has the structure described by a layout
+//!
+//! Decoding sample data only exercises the parts of a type that the sample happens to contain.
+//! Instead the `Deserialize` implementation of the type is driven by a deserializer that reads the
+//! layout rather than any input. Every value the type asks for is compared with the next value of
+//! the layout and the first divergence is reported with its path, for example a missing field,
+//! an integer read with the wrong encoding or a version byte the type does not expect.
+//!
+//! Records and tuples are encoded as their elements one after another so a type is free to group or
+//! name them differently to the layout. Options are checked as `Some` and lists as holding a single
+//! element. The walk is repeated, choosing enum variants that have not been visited yet, until every
+//! variant has been checked.
+//!
+
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+
+use crate::caml_hash_variant;
+use crate::error::{Error, ErrorPath, PathSegment, Result};
+use crate::value::layout::{
+    collect_tagged, describe, find_version, versioned_inner, BinProtRule, CustomType, RuleRef,
+};
+
+static UNIT: BinProtRule = BinProtRule::Unit;
+static CHAR: BinProtRule = BinProtRule::Char;
+
+/// Check that values of type `T` are encoded the way the layout rule describes.
+/// Returns a `TypeLayoutMismatch` error for the first place where the type and the layout diverge.
+pub fn check_type<T: DeserializeOwned>(rule: &BinProtRule) -> Result<()> {
+    let mut state = State::default();
+    loop {
+        state.path.clear();
+        state.progress = false;
+        state.scopes = vec![vec![Pending::new(rule, None, Vec::new())]];
+        T::deserialize(RuleDeserializer { state: &mut state })
+            .and_then(|_| state.end_scope())
+            .map_err(|e| state.divergence(e))?;
+        if !state.progress || state.enums.iter().all(EnumSite::is_complete) {
+            return Ok(());
+        }
+    }
+}
+
+/// A value of the layout that has not been read by the type yet
+struct Pending<'a> {
+    rule: &'a BinProtRule,
+    // module path of the most recently resolved reference, used to find custom encodings
+    module_path: Option<&'a str>,
+    layout_path: Vec<PathSegment>,
+    // set if this is the version byte of a versioned type
+    version: Option<u16>,
+}
+
+impl<'a> Pending<'a> {
+    fn new(
+        rule: &'a BinProtRule,
+        module_path: Option<&'a str>,
+        layout_path: Vec<PathSegment>,
+    ) -> Self {
+        Self {
+            rule,
+            module_path,
+            layout_path,
+            version: None,
+        }
+    }
+
+    fn child(&self, rule: &'a BinProtRule, segment: PathSegment) -> Self {
+        let mut layout_path = self.layout_path.clone();
+        layout_path.push(segment);
+        Self::new(rule, self.module_path, layout_path)
+    }
+
+    fn describe(&self) -> String {
+        match &self.version {
+            Some(version) => format!(
+                "version byte {} of {} at {}",
+                version,
+                self.module_path.unwrap_or("<root>"),
+                ErrorPath(self.layout_path.clone())
+            ),
+            None => format!(
+                "{} at {}",
+                describe(self.rule),
+                ErrorPath(self.layout_path.clone())
+            ),
+        }
+    }
+}
+
+fn is_integer(rule: &BinProtRule) -> bool {
+    matches!(
+        rule,
+        BinProtRule::Int | BinProtRule::Int32 | BinProtRule::Int64 | BinProtRule::NativeInt
+    )
+}
+
+/// An enum found at some path in the type and the variants of it that have been checked
+struct EnumSite {
+    path: Vec<PathSegment>,
+    visited: Vec<bool>,
+}
+
+impl EnumSite {
+    fn is_complete(&self) -> bool {
+        self.visited.iter().all(|v| *v)
+    }
+}
+
+#[derive(Default)]
+struct State<'a> {
+    // path in the type being checked
+    path: Vec<PathSegment>,
+    // values of the layout still to be read, the next one last.
+    // Options, list elements and constructor arguments are read in a scope of their own
+    scopes: Vec<Vec<Pending<'a>>>,
+    enums: Vec<EnumSite>,
+    // if the last walk visited any variant for the first time
+    progress: bool,
+}
+
+impl<'a> State<'a> {
+    fn mismatch(&self, expected: impl Into<String>, found: impl Into<String>) -> Error {
+        Error::TypeLayoutMismatch {
+            path: ErrorPath(self.path.clone()),
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    // Errors raised by the type itself, such as a visitor rejecting the placeholder values, are
+    // reported at the path where the walk stopped
+    fn divergence(&self, e: Error) -> Error {
+        match e {
+            Error::TypeLayoutMismatch { .. } => e,
+            e => self.mismatch("a type that can be read from the layout", e.to_string()),
+        }
+    }
+
+    fn push_scope(&mut self, mut pending: Vec<Pending<'a>>) {
+        pending.reverse();
+        self.scopes.push(pending);
+    }
+
+    // Everything in a scope must be read by the type
+    fn end_scope(&mut self) -> Result<()> {
+        let mut scope = self.scopes.pop().unwrap_or_default();
+        match scope.pop() {
+            Some(pending) => Err(self.mismatch(pending.describe(), "no more values")),
+            None => Ok(()),
+        }
+    }
+
+    fn push(&mut self, pending: Pending<'a>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(pending)
+        }
+    }
+
+    // Take the next value the type reads, expanding records, tuples and references into their contents
+    fn next(&mut self, found: &str) -> Result<Pending<'a>> {
+        loop {
+            let pending = match self.scopes.last_mut().and_then(|scope| scope.pop()) {
+                Some(pending) => pending,
+                None => return Err(self.mismatch("no more values", found)),
+            };
+            match pending.rule {
+                BinProtRule::Reference(RuleRef::Resolved(payload)) => self.push(Pending {
+                    rule: &payload.ref_rule,
+                    module_path: Some(&payload.source_module_path),
+                    ..pending
+                }),
+                BinProtRule::Record(fields) => {
+                    let version = versioned_inner(pending.rule).map(find_version);
+                    for field in fields.iter().rev() {
+                        let mut child = pending.child(
+                            &field.field_rule,
+                            PathSegment::Field(field.field_name.clone()),
+                        );
+                        if field.field_name == "version" {
+                            child.version = version;
+                        }
+                        self.push(child);
+                    }
+                }
+                BinProtRule::Tuple(rules) => {
+                    for (i, rule) in rules.iter().enumerate().rev() {
+                        self.push(pending.child(rule, PathSegment::Index(i)));
+                    }
+                }
+                BinProtRule::Custom(rules) => {
+                    let path = pending.module_path.ok_or(Error::LayoutIteratorError)?;
+                    let elements: Vec<&BinProtRule> = match CustomType::for_path(path) {
+                        // vectors are followed by a zero byte terminator
+                        Some(CustomType::Vector(len)) => {
+                            let element = rules.first().ok_or(Error::LayoutIteratorError)?;
+                            (0..len)
+                                .map(|_| element)
+                                .chain(std::iter::once(&UNIT))
+                                .collect()
+                        }
+                        Some(CustomType::BigInt) => (0..32).map(|_| &CHAR).collect(),
+                        None => {
+                            return Err(Error::UnknownCustomType {
+                                typ: path.to_string(),
+                            })
+                        }
+                    };
+                    for (i, rule) in elements.into_iter().enumerate().rev() {
+                        self.push(pending.child(rule, PathSegment::Index(i)));
+                    }
+                }
+                _ => return self.check_version(pending, found),
+            }
+        }
+    }
+
+    // The version byte of the layout must be read into a field named version
+    fn check_version(&self, pending: Pending<'a>, found: &str) -> Result<Pending<'a>> {
+        let version_field = PathSegment::Field("version".to_string());
+        let reads_version = self.path.last() == Some(&version_field);
+        if pending.version.is_some() && !reads_version {
+            Err(self.mismatch(pending.describe(), found))
+        } else if reads_version
+            && pending.version.is_none()
+            && pending.layout_path.last() != Some(&version_field)
+        {
+            Err(self.mismatch(pending.describe(), format!("version field {}", found)))
+        } else {
+            Ok(pending)
+        }
+    }
+
+    // Prefer variants that have not been checked, then variants containing enums that have not been fully checked
+    fn choose_variant(&mut self, variants: &[&str]) -> usize {
+        let site = match self.enums.iter().position(|s| s.path == self.path) {
+            Some(site) => site,
+            None => {
+                self.enums.push(EnumSite {
+                    path: self.path.clone(),
+                    visited: vec![false; variants.len()],
+                });
+                self.enums.len() - 1
+            }
+        };
+        if let Some(index) = self.enums[site].visited.iter().position(|v| !v) {
+            self.enums[site].visited[index] = true;
+            self.progress = true;
+            return index;
+        }
+        (0..variants.len())
+            .find(|index| {
+                let mut prefix = self.path.clone();
+                prefix.push(PathSegment::Variant(variants[*index].to_string()));
+                self.enums
+                    .iter()
+                    .any(|s| !s.is_complete() && s.path.starts_with(&prefix))
+            })
+            .unwrap_or(0)
+    }
+}
+
+struct RuleDeserializer<'s, 'a> {
+    state: &'s mut State<'a>,
+}
+
+impl<'s, 'a> RuleDeserializer<'s, 'a> {
+    fn integer(self, found: &str) -> Result<u16> {
+        let pending = self.state.next(found)?;
+        match pending.rule {
+            rule if is_integer(rule) => Ok(pending.version.unwrap_or(0)),
+            BinProtRule::Nat0 => Err(self.state.mismatch(
+                pending.describe(),
+                format!("{} (use bin_prot::integers::nat0)", found),
+            )),
+            _ => Err(self.state.mismatch(pending.describe(), found)),
+        }
+    }
+
+    // Reads the elements of a struct or tuple, which take their values from the current scope
+    fn visit_elements<'de, V>(self, segments: Vec<PathSegment>, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(Elements {
+            segments: segments.into_iter(),
+            element: None,
+            state: self.state,
+        })
+    }
+}
+
+macro_rules! deserialize_integer {
+    ($method:ident, $visit:ident, $ty:ty) => {
+        fn $method<V>(self, visitor: V) -> Result<V::Value>
+        where
+            V: Visitor<'de>,
+        {
+            let value = self.integer(stringify!($ty))?;
+            visitor.$visit(value as $ty)
+        }
+    };
+}
+
+impl<'de, 's, 'a> de::Deserializer<'de> for RuleDeserializer<'s, 'a> {
+    type Error = Error;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let found = "a type that requires a self describing format";
+        let pending = self.state.next(found)?;
+        Err(self.state.mismatch(pending.describe(), found))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("bool")? {
+            Pending {
+                rule: BinProtRule::Bool,
+                ..
+            } => visitor.visit_bool(false),
+            pending => Err(self.state.mismatch(pending.describe(), "bool")),
+        }
+    }
+
+    deserialize_integer!(deserialize_i8, visit_i8, i8);
+    deserialize_integer!(deserialize_i16, visit_i16, i16);
+    deserialize_integer!(deserialize_i32, visit_i32, i32);
+    deserialize_integer!(deserialize_i64, visit_i64, i64);
+    deserialize_integer!(deserialize_u16, visit_u16, u16);
+    deserialize_integer!(deserialize_u32, visit_u32, u32);
+    deserialize_integer!(deserialize_u64, visit_u64, u64);
+
+    // u8 is read as a single raw byte
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let found = "u8 read as a single byte";
+        match self.state.next(found)? {
+            Pending {
+                rule: BinProtRule::Char,
+                ..
+            } => visitor.visit_u8(0),
+            pending => Err(self.state.mismatch(pending.describe(), found)),
+        }
+    }
+
+    fn deserialize_f32<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let pending = self.state.next("f32")?;
+        Err(self.state.mismatch(pending.describe(), "f32"))
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("f64")? {
+            Pending {
+                rule: BinProtRule::Float,
+                ..
+            } => visitor.visit_f64(0.0),
+            pending => Err(self.state.mismatch(pending.describe(), "f64")),
+        }
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("char")? {
+            Pending {
+                rule: BinProtRule::Char,
+                ..
+            } => visitor.visit_char('\0'),
+            pending => Err(self.state.mismatch(pending.describe(), "char")),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("string")? {
+            Pending {
+                rule: BinProtRule::String,
+                ..
+            } => visitor.visit_string(String::new()),
+            pending => Err(self.state.mismatch(pending.describe(), "string")),
+        }
+    }
+
+    // Bytes are either a string or the encoding of an integer when using the integers module
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("bytes")? {
+            Pending {
+                rule: BinProtRule::String,
+                ..
+            } => visitor.visit_byte_buf(Vec::new()),
+            Pending { rule, .. } if is_integer(rule) || matches!(rule, BinProtRule::Nat0) => {
+                visitor.visit_bytes(&[0])
+            }
+            pending => Err(self.state.mismatch(pending.describe(), "bytes")),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("option")? {
+            Pending {
+                rule: BinProtRule::Option(inner),
+                module_path,
+                layout_path,
+                ..
+            } => {
+                self.state
+                    .push_scope(vec![Pending::new(inner, module_path, layout_path)]);
+                let value = visitor.visit_some(RuleDeserializer {
+                    state: &mut *self.state,
+                })?;
+                self.state.end_scope()?;
+                Ok(value)
+            }
+            pending => Err(self.state.mismatch(pending.describe(), "option")),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.state.next("unit")? {
+            Pending {
+                rule: BinProtRule::Unit,
+                ..
+            } => visitor.visit_unit(),
+            pending => Err(self.state.mismatch(pending.describe(), "unit")),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // Lists are checked with a single element
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let pending = self.state.next("sequence")?;
+        let element = match pending.rule {
+            BinProtRule::List(element) => pending.child(element, PathSegment::Index(0)),
+            // a Vec<u8> has the same encoding as a string
+            BinProtRule::String => pending.child(&CHAR, PathSegment::Index(0)),
+            _ => return Err(self.state.mismatch(pending.describe(), "sequence")),
+        };
+        visitor.visit_seq(Elements {
+            segments: vec![PathSegment::Index(0)].into_iter(),
+            element: Some(element),
+            state: self.state,
+        })
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.visit_elements((0..len).map(PathSegment::Index).collect(), visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let pending = self.state.next("map")?;
+        Err(self.state.mismatch(pending.describe(), "map"))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let segments = fields
+            .iter()
+            .map(|field| PathSegment::Field(field.to_string()))
+            .collect();
+        self.visit_elements(segments, visitor)
+    }
+
+    // Every variant of the enum is checked against the constructor of the layout with the same index
+    // or, for polyvars, the same tag
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let found = format!("enum {} with {} variants", name, variants.len());
+        let pending = self.state.next(&found)?;
+        let ctors: Vec<(&str, &[BinProtRule])> = match pending.rule {
+            BinProtRule::Polyvar(polyvars) if name == "Polyvar" => {
+                let mut tagged = Vec::new();
+                collect_tagged(polyvars, &mut tagged)?;
+                if let Some(t) = tagged
+                    .iter()
+                    .find(|t| !variants.iter().any(|v| t.hash == caml_hash_variant(v)))
+                {
+                    return Err(self.state.mismatch(
+                        format!("polyvar tag {} of {}", t.polyvar_name, pending.describe()),
+                        found,
+                    ));
+                }
+                variants
+                    .iter()
+                    .map(|variant| {
+                        tagged
+                            .iter()
+                            .find(|t| t.hash == caml_hash_variant(variant))
+                            .map(|t| (t.polyvar_name.as_str(), t.polyvar_args.as_slice()))
+                            .ok_or_else(|| {
+                                self.state.mismatch(
+                                    pending.describe(),
+                                    format!("variant {} that is not a tag of the layout", variant),
+                                )
+                            })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            BinProtRule::Sum(summands) if name != "Polyvar" => {
+                if summands.len() != variants.len() {
+                    return Err(self.state.mismatch(
+                        format!("{} ({} constructors)", pending.describe(), summands.len()),
+                        found,
+                    ));
+                }
+                (0..variants.len())
+                    .map(|i| {
+                        summands
+                            .iter()
+                            .find(|s| s.index as usize == i)
+                            .map(|s| (s.ctor_name.as_str(), s.ctor_args.as_slice()))
+                            .ok_or_else(|| {
+                                self.state.mismatch(
+                                    format!("a constructor with index {}", i),
+                                    format!("variant {}", variants[i]),
+                                )
+                            })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            _ => return Err(self.state.mismatch(pending.describe(), found)),
+        };
+
+        let state = self.state;
+        let index = state.choose_variant(variants);
+        let (ctor_name, args) = ctors[index];
+        let mut ctor_path = pending.layout_path.clone();
+        ctor_path.push(PathSegment::Variant(ctor_name.to_string()));
+        state.push_scope(
+            args.iter()
+                .enumerate()
+                .map(|(i, arg)| {
+                    let mut layout_path = ctor_path.clone();
+                    layout_path.push(PathSegment::Index(i));
+                    Pending::new(arg, pending.module_path, layout_path)
+                })
+                .collect(),
+        );
+        state
+            .path
+            .push(PathSegment::Variant(variants[index].to_string()));
+        let value = visitor.visit_enum(VariantChoice {
+            index: index as u32,
+            state: &mut *state,
+        })?;
+        state.end_scope()?;
+        state.path.pop();
+        Ok(value)
+    }
+
+    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::WontImplement)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+struct Elements<'s, 'a> {
+    segments: std::vec::IntoIter<PathSegment>,
+    // list elements are read from a scope of their own
+    element: Option<Pending<'a>>,
+    state: &'s mut State<'a>,
+}
+
+impl<'de, 's, 'a> de::SeqAccess<'de> for Elements<'s, 'a> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let segment = match self.segments.next() {
+            Some(segment) => segment,
+            None => return Ok(None),
+        };
+        let element = self.element.take();
+        let scoped = element.is_some();
+        if let Some(element) = element {
+            self.state.push_scope(vec![element]);
+        }
+        self.state.path.push(segment);
+        let value = seed.deserialize(RuleDeserializer {
+            state: &mut *self.state,
+        })?;
+        if scoped {
+            self.state.end_scope()?;
+        }
+        self.state.path.pop();
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.segments.len())
+    }
+}
+
+struct VariantChoice<'s, 'a> {
+    index: u32,
+    state: &'s mut State<'a>,
+}
+
+impl<'de, 's, 'a> de::EnumAccess<'de> for VariantChoice<'s, 'a> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let de: de::value::U32Deserializer<Error> = self.index.into_deserializer();
+        Ok((seed.deserialize(de)?, self))
+    }
+}
+
+// The arguments of the constructor are in the scope pushed for the variant
+impl<'de, 's, 'a> de::VariantAccess<'de> for VariantChoice<'s, 'a> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(RuleDeserializer { state: self.state })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_tuple(RuleDeserializer { state: self.state }, len, visitor)
+    }
+
+    fn struct_variant<V>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(
+            RuleDeserializer { state: self.state },
+            "",
+            fields,
+            visitor,
+        )
+    }
+}
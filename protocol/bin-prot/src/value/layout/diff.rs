@@ -0,0 +1,396 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Structural diff of two layouts
+//!
+//! Both layouts are walked side by side with references resolved. Record fields, sum constructors
+//! and polyvar tags are matched by name so each change is reported once at the path it was found,
+//! rather than as a cascade of differences after an inserted field. Every change is attributed to the
+//! module path of the innermost reference containing it in the new layout, which names the OCaml type
+//! (and so the serialization type) that has changed.
+//!
+//! A type that is used in several places appears in the layout once for each use. Changes to it are
+//! grouped and reported at the first path they were found along with the number of other paths.
+//!
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::{ErrorPath, PathSegment};
+use crate::value::layout::{
+    collect_tagged, describe, find_version, versioned_inner, BinProtRule, Layout, RecordField,
+    RuleRef, TaggedPolyvar,
+};
+
+/// Compare two layouts and return the changes from `old` to `new` in the order they were found
+pub fn diff_layouts(old: &Layout, new: &Layout) -> Vec<LayoutChange> {
+    let mut differ = Differ::default();
+    if !new.module_path.is_empty() {
+        differ.module = Some((&new.module_path, 0));
+    }
+    differ.diff(&old.bin_prot_rule, &new.bin_prot_rule);
+    differ.changes
+}
+
+/// Compare two rules and return the changes from `old` to `new` in the order they were found
+pub fn diff_rules(old: &BinProtRule, new: &BinProtRule) -> Vec<LayoutChange> {
+    let mut differ = Differ::default();
+    differ.diff(old, new);
+    differ.changes
+}
+
+/// A difference between two layouts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutChange {
+    /// Path to the first place the change was found
+    pub path: ErrorPath,
+    /// Module path of the innermost type containing the change in the new layout
+    pub module_path: Option<String>,
+    /// What has changed
+    pub kind: ChangeKind,
+    /// Number of other paths where the same change was found
+    pub other_paths: usize,
+}
+
+/// The kinds of change between two layouts
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// A record field is only in the new layout
+    FieldAdded(String),
+    /// A record field is only in the old layout
+    FieldRemoved(String),
+    /// The fields in both layouts are encoded in a different order
+    FieldsReordered {
+        /// Order of the fields in the old layout
+        old: Vec<String>,
+        /// Order of the fields in the new layout
+        new: Vec<String>,
+    },
+    /// A sum type constructor is only in the new layout
+    SummandAdded(String),
+    /// A sum type constructor is only in the old layout
+    SummandRemoved(String),
+    /// A sum type constructor is encoded with a different index
+    SummandIndexChanged {
+        /// Name of the constructor
+        name: String,
+        /// Index in the old layout
+        old: i32,
+        /// Index in the new layout
+        new: i32,
+    },
+    /// A polyvar tag is only in the new layout
+    PolyvarTagAdded(String),
+    /// A polyvar tag is only in the old layout
+    PolyvarTagRemoved(String),
+    /// A tuple, fixed length vector or constructor has a different number of elements
+    ArityChanged {
+        /// Number of elements in the old layout
+        old: usize,
+        /// Number of elements in the new layout
+        new: usize,
+    },
+    /// The version byte of a versioned type has changed
+    VersionChanged {
+        /// Version in the old layout
+        old: u16,
+        /// Version in the new layout
+        new: u16,
+    },
+    /// The value is encoded with a different rule
+    RuleChanged {
+        /// Kind of the rule in the old layout
+        old: String,
+        /// Kind of the rule in the new layout
+        new: String,
+    },
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::FieldAdded(name) => write!(f, "field `{}` added", name),
+            ChangeKind::FieldRemoved(name) => write!(f, "field `{}` removed", name),
+            ChangeKind::FieldsReordered { old, new } => write!(
+                f,
+                "fields reordered from ({}) to ({})",
+                old.join(", "),
+                new.join(", ")
+            ),
+            ChangeKind::SummandAdded(name) => write!(f, "constructor `{}` added", name),
+            ChangeKind::SummandRemoved(name) => write!(f, "constructor `{}` removed", name),
+            ChangeKind::SummandIndexChanged { name, old, new } => write!(
+                f,
+                "constructor `{}` index changed from {} to {}",
+                name, old, new
+            ),
+            ChangeKind::PolyvarTagAdded(name) => write!(f, "polyvar tag `{}` added", name),
+            ChangeKind::PolyvarTagRemoved(name) => write!(f, "polyvar tag `{}` removed", name),
+            ChangeKind::ArityChanged { old, new } => {
+                write!(f, "number of elements changed from {} to {}", old, new)
+            }
+            ChangeKind::VersionChanged { old, new } => {
+                write!(f, "version changed from {} to {}", old, new)
+            }
+            ChangeKind::RuleChanged { old, new } if old == new => write!(f, "{} changed", old),
+            ChangeKind::RuleChanged { old, new } => {
+                write!(f, "rule changed from {} to {}", old, new)
+            }
+        }
+    }
+}
+
+impl fmt::Display for LayoutChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path)?;
+        if let Some(module_path) = &self.module_path {
+            write!(f, " ({})", module_path)?;
+        }
+        write!(f, ": {}", self.kind)?;
+        if self.other_paths > 0 {
+            write!(f, " (and {} other paths)", self.other_paths)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Differ<'a> {
+    path: Vec<PathSegment>,
+    // module path of the innermost reference in the new layout and the path length where it was entered
+    module: Option<(&'a str, usize)>,
+    changes: Vec<LayoutChange>,
+    // index into changes keyed by module, path relative to the module and kind
+    seen: HashMap<(Option<&'a str>, String, ChangeKind), usize>,
+}
+
+impl<'a> Differ<'a> {
+    fn diff(&mut self, old: &'a BinProtRule, new: &'a BinProtRule) {
+        match (old, new) {
+            (_, BinProtRule::Reference(RuleRef::Resolved(payload))) => {
+                let outer = self.module;
+                if !payload.source_module_path.is_empty() {
+                    self.module = Some((&payload.source_module_path, self.path.len()));
+                }
+                self.diff(old, &payload.ref_rule);
+                self.module = outer;
+            }
+            (BinProtRule::Reference(RuleRef::Resolved(payload)), _) => {
+                self.diff(&payload.ref_rule, new)
+            }
+            (BinProtRule::Record(old_fields), BinProtRule::Record(new_fields)) => {
+                if let (Some(old_t), Some(new_t)) = (versioned_inner(old), versioned_inner(new)) {
+                    let (old_version, new_version) = (find_version(old_t), find_version(new_t));
+                    if old_version != new_version {
+                        self.change(ChangeKind::VersionChanged {
+                            old: old_version,
+                            new: new_version,
+                        });
+                    }
+                    self.child(PathSegment::Field("t".to_string()), old_t, new_t);
+                    return;
+                }
+                for field in old_fields {
+                    if !new_fields.iter().any(|f| f.field_name == field.field_name) {
+                        self.change(ChangeKind::FieldRemoved(field.field_name.clone()));
+                    }
+                }
+                for field in new_fields {
+                    if !old_fields.iter().any(|f| f.field_name == field.field_name) {
+                        self.change(ChangeKind::FieldAdded(field.field_name.clone()));
+                    }
+                }
+                let common = |fields: &[RecordField], others: &[RecordField]| {
+                    fields
+                        .iter()
+                        .filter(|f| others.iter().any(|o| o.field_name == f.field_name))
+                        .map(|f| f.field_name.clone())
+                        .collect::<Vec<_>>()
+                };
+                let (old_order, new_order) = (
+                    common(old_fields, new_fields),
+                    common(new_fields, old_fields),
+                );
+                if old_order != new_order {
+                    self.change(ChangeKind::FieldsReordered {
+                        old: old_order,
+                        new: new_order,
+                    });
+                }
+                for field in new_fields {
+                    if let Some(old_field) =
+                        old_fields.iter().find(|f| f.field_name == field.field_name)
+                    {
+                        self.child(
+                            PathSegment::Field(field.field_name.clone()),
+                            &old_field.field_rule,
+                            &field.field_rule,
+                        );
+                    }
+                }
+            }
+            (BinProtRule::Sum(old_summands), BinProtRule::Sum(new_summands)) => {
+                for summand in old_summands {
+                    if !new_summands
+                        .iter()
+                        .any(|s| s.ctor_name == summand.ctor_name)
+                    {
+                        self.change(ChangeKind::SummandRemoved(summand.ctor_name.clone()));
+                    }
+                }
+                for summand in new_summands {
+                    match old_summands
+                        .iter()
+                        .find(|s| s.ctor_name == summand.ctor_name)
+                    {
+                        Some(old_summand) => {
+                            if old_summand.index != summand.index {
+                                self.change(ChangeKind::SummandIndexChanged {
+                                    name: summand.ctor_name.clone(),
+                                    old: old_summand.index,
+                                    new: summand.index,
+                                });
+                            }
+                            self.path
+                                .push(PathSegment::Variant(summand.ctor_name.clone()));
+                            self.diff_elements(&old_summand.ctor_args, &summand.ctor_args);
+                            self.path.pop();
+                        }
+                        None => self.change(ChangeKind::SummandAdded(summand.ctor_name.clone())),
+                    }
+                }
+            }
+            (BinProtRule::Polyvar(old_polyvars), BinProtRule::Polyvar(new_polyvars)) => {
+                let (mut old_tags, mut new_tags) = (Vec::new(), Vec::new());
+                if collect_tagged(old_polyvars, &mut old_tags).is_err()
+                    || collect_tagged(new_polyvars, &mut new_tags).is_err()
+                {
+                    self.diff_opaque(old, new);
+                    return;
+                }
+                self.diff_tags(&old_tags, &new_tags);
+            }
+            (BinProtRule::Tuple(old_elements), BinProtRule::Tuple(new_elements)) => {
+                self.diff_elements(old_elements, new_elements)
+            }
+            (BinProtRule::Option(old_inner), BinProtRule::Option(new_inner)) => {
+                self.diff(old_inner, new_inner)
+            }
+            (BinProtRule::List(old_inner), BinProtRule::List(new_inner)) => {
+                self.child(PathSegment::Index(0), old_inner, new_inner)
+            }
+            (BinProtRule::Vec(old_len, old_inner), BinProtRule::Vec(new_len, new_inner)) => {
+                if old_len != new_len {
+                    self.change(ChangeKind::ArityChanged {
+                        old: *old_len,
+                        new: *new_len,
+                    });
+                }
+                self.child(PathSegment::Index(0), old_inner, new_inner)
+            }
+            (BinProtRule::Hashtable(old_entry), BinProtRule::Hashtable(new_entry)) => {
+                self.child(
+                    PathSegment::Index(0),
+                    &old_entry.key_rule,
+                    &new_entry.key_rule,
+                );
+                self.child(
+                    PathSegment::Index(1),
+                    &old_entry.value_rule,
+                    &new_entry.value_rule,
+                );
+            }
+            (BinProtRule::Custom(old_args), BinProtRule::Custom(new_args)) => {
+                self.diff_elements(old_args, new_args)
+            }
+            (
+                BinProtRule::Nat0
+                | BinProtRule::Unit
+                | BinProtRule::Bool
+                | BinProtRule::String
+                | BinProtRule::Char
+                | BinProtRule::Int
+                | BinProtRule::Int32
+                | BinProtRule::Int64
+                | BinProtRule::NativeInt
+                | BinProtRule::Float
+                | BinProtRule::Bigstring,
+                _,
+            ) if describe(old) == describe(new) => {}
+            _ => self.diff_opaque(old, new),
+        }
+    }
+
+    fn diff_tags(&mut self, old_tags: &[&'a TaggedPolyvar], new_tags: &[&'a TaggedPolyvar]) {
+        for tag in old_tags {
+            if !new_tags.iter().any(|t| t.polyvar_name == tag.polyvar_name) {
+                self.change(ChangeKind::PolyvarTagRemoved(tag.polyvar_name.clone()));
+            }
+        }
+        for tag in new_tags {
+            match old_tags.iter().find(|t| t.polyvar_name == tag.polyvar_name) {
+                Some(old_tag) => {
+                    self.path
+                        .push(PathSegment::Variant(tag.polyvar_name.clone()));
+                    self.diff_elements(&old_tag.polyvar_args, &tag.polyvar_args);
+                    self.path.pop();
+                }
+                None => self.change(ChangeKind::PolyvarTagAdded(tag.polyvar_name.clone())),
+            }
+        }
+    }
+
+    fn diff_elements(&mut self, old: &'a [BinProtRule], new: &'a [BinProtRule]) {
+        if old.len() != new.len() {
+            self.change(ChangeKind::ArityChanged {
+                old: old.len(),
+                new: new.len(),
+            });
+            return;
+        }
+        for (i, (old, new)) in old.iter().zip(new).enumerate() {
+            self.child(PathSegment::Index(i), old, new);
+        }
+    }
+
+    // Rules that are not diffed structurally, e.g. type variables and unresolved references
+    fn diff_opaque(&mut self, old: &BinProtRule, new: &BinProtRule) {
+        if format!("{:?}", old) != format!("{:?}", new) {
+            self.change(ChangeKind::RuleChanged {
+                old: describe(old).to_string(),
+                new: describe(new).to_string(),
+            });
+        }
+    }
+
+    fn child(&mut self, segment: PathSegment, old: &'a BinProtRule, new: &'a BinProtRule) {
+        self.path.push(segment);
+        self.diff(old, new);
+        self.path.pop();
+    }
+
+    fn change(&mut self, kind: ChangeKind) {
+        let (module_path, start) = match self.module {
+            Some((module_path, start)) => (Some(module_path), start),
+            None => (None, 0),
+        };
+        let key = (
+            module_path,
+            ErrorPath(self.path[start..].to_vec()).to_string(),
+            kind,
+        );
+        match self.seen.get(&key) {
+            Some(&index) => self.changes[index].other_paths += 1,
+            None => {
+                self.changes.push(LayoutChange {
+                    path: ErrorPath(self.path.clone()),
+                    module_path: module_path.map(str::to_string),
+                    kind: key.2.clone(),
+                    other_paths: 0,
+                });
+                self.seen.insert(key, self.changes.len() - 1);
+            }
+        }
+    }
+}
@@ -10,6 +10,11 @@ use core::convert::TryFrom;
 use serde::{Deserialize, Serialize};
 use serde_json::from_value;
 
+use crate::error::Error;
+
+pub mod codegen;
+pub mod compat;
+pub mod diff;
 mod list_tagged_enum;
 pub(crate) mod traverse;
 
@@ -22,7 +27,9 @@ use list_tagged_enum::ListTaggedEnum;
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Layout {
     layout_loc: String,
-    version_opt: Option<i32>,
+    #[serde(default)]
+    pub(crate) module_path: String,
+    pub(crate) version_opt: Option<i32>,
     type_decl: String,
     bin_io_derived: bool,
     /// Rule defining this layout
@@ -161,7 +168,7 @@ impl TryFrom<ListTaggedEnum> for BinProtRule {
 /// Field of a BinProt record with a name and a value
 pub struct RecordField {
     pub(crate) field_name: String,
-    field_rule: BinProtRule,
+    pub(crate) field_rule: BinProtRule,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -237,10 +244,10 @@ pub struct UnresolvedPayload {
 pub struct ResolvedPayload {
     source_type_decl: String,
     #[serde(default)]
-    source_module_path: String,
+    pub(crate) source_module_path: String,
     #[serde(default)]
     bin_io_derived: bool, // This is present only in later versions of the type layouts. Probably don't trust its value
-    ref_rule: Box<BinProtRule>,
+    pub(crate) ref_rule: Box<BinProtRule>,
 }
 
 impl TryFrom<ListTaggedEnum> for RuleRef {
@@ -258,3 +265,124 @@ impl TryFrom<ListTaggedEnum> for RuleRef {
         }
     }
 }
+
+/// Custom types with an encoding known to the loose deserializer
+/// These are looked up by the module path of the reference that contains them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CustomType {
+    /// Fixed length vector of the given length followed by a zero byte terminator
+    Vector(usize),
+    /// 32 byte big integer read as a tuple of chars
+    BigInt,
+}
+
+impl CustomType {
+    pub(crate) fn for_path(path: &str) -> Option<Self> {
+        match path {
+            "Pickles_type.Vector.Vector2" // the missing 's' on 'types' here is intention due to a bug in layout producing code
+            | "Pickles_types.Vector.Vector2" => Some(Self::Vector(2)), // in case it gets fixed :P
+            "Pickles_types.Vector.Vector4" => Some(Self::Vector(4)),
+            "Pickles_types.Vector.Vector8" => Some(Self::Vector(8)),
+            "Pickles_types.Vector.Vector17" => Some(Self::Vector(17)),
+            "Pickles_types.Vector.Vector18" => Some(Self::Vector(18)),
+            "Ledger_hash0"
+            | "State_hash"
+            | "Pending_coinbase.Stack_hash"
+            | "State_body_hash"
+            | "Pending_coinbase.Hash_builder"
+            | "Snark_params.Make_inner_curve_scalar"
+            | "Snark_params.Tick"
+            | "Epoch_seed"
+            | "Zexe_backend.Zexe_backend_common.Stable.Field"
+            | "Pending_coinbase.Coinbase_stack" => Some(Self::BigInt),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the rule of the `t` field if this rule is a versioned record
+pub(crate) fn versioned_inner(rule: &BinProtRule) -> Option<&BinProtRule> {
+    match rule {
+        BinProtRule::Record(fields) => match fields.as_slice() {
+            [version, t]
+                if version.field_name == "version"
+                    && matches!(version.field_rule, BinProtRule::Int)
+                    && t.field_name == "t" =>
+            {
+                Some(&t.field_rule)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The version of a versioned type is the number of the Stable.V<N> module it wraps
+pub(crate) fn find_version(rule: &BinProtRule) -> u16 {
+    match rule {
+        BinProtRule::Reference(RuleRef::Resolved(payload)) => payload
+            .source_module_path
+            .split('.')
+            .rev()
+            .find_map(|segment| segment.strip_prefix('V')?.parse().ok())
+            .unwrap_or_else(|| match versioned_inner(&payload.ref_rule) {
+                Some(t_rule) => find_version(t_rule),
+                None => find_version(&payload.ref_rule),
+            }),
+        _ => 1,
+    }
+}
+
+/// The name of the kind of a rule as it appears in layout files
+pub(crate) fn describe(rule: &BinProtRule) -> &'static str {
+    match rule {
+        BinProtRule::Nat0 => "Nat0",
+        BinProtRule::Unit => "Unit",
+        BinProtRule::Bool => "Bool",
+        BinProtRule::String => "String",
+        BinProtRule::Char => "Char",
+        BinProtRule::Int => "Int",
+        BinProtRule::Int32 => "Int32",
+        BinProtRule::Int64 => "Int64",
+        BinProtRule::NativeInt => "Native_int",
+        BinProtRule::Float => "Float",
+        BinProtRule::Option(_) => "Option",
+        BinProtRule::Record(_) => "Record",
+        BinProtRule::Tuple(_) => "Tuple",
+        BinProtRule::Sum(_) => "Sum",
+        BinProtRule::Polyvar(_) => "Polyvar",
+        BinProtRule::List(_) => "List",
+        BinProtRule::Hashtable(_) => "Hashtable",
+        BinProtRule::Vec(_, _) => "Vec",
+        BinProtRule::Bigstring => "Bigstring",
+        BinProtRule::Reference(_) => "Reference",
+        BinProtRule::TypeVar(_) => "Type_var",
+        BinProtRule::SelfReference(_) => "Self_reference",
+        BinProtRule::TypeAbstraction(_, _) => "Type_abstraction",
+        BinProtRule::TypeClosure(_, _) => "Type_closure",
+        BinProtRule::Custom(_) | BinProtRule::CustomForPath(_, _) => "Custom",
+    }
+}
+
+/// Collects the tags of a polyvar, including those of the polyvar types it inherits from
+pub(crate) fn collect_tagged<'a>(
+    polyvars: &'a [Polyvar],
+    tagged: &mut Vec<&'a TaggedPolyvar>,
+) -> crate::error::Result<()> {
+    for polyvar in polyvars {
+        match polyvar {
+            Polyvar::Tagged(t) => tagged.push(t),
+            Polyvar::Inherited(rule) => {
+                let mut rule = rule;
+                while let BinProtRule::Reference(RuleRef::Resolved(payload)) = rule {
+                    rule = &payload.ref_rule;
+                }
+                match rule {
+                    BinProtRule::Polyvar(inherited) => collect_tagged(inherited, tagged)?,
+                    _ => return Err(Error::UnimplementedRule),
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -23,7 +23,7 @@ use crate::value::layout::{BinProtRule, RuleRef};
 /// Implements a depth first search of the type tree
 /// defined by a BinProtRule
 pub struct BinProtRuleIterator {
-    pub(crate) stack: Vec<BinProtRule>, // regular stack to implement the DFS
+    pub(crate) stack: Vec<(BinProtRule, usize)>, // regular stack to implement the DFS, with repeat counts
     current_module_path: Option<String>, // holds on to most recent path encountered in traverse
 }
 
@@ -31,7 +31,14 @@ impl Iterator for BinProtRuleIterator {
     type Item = BinProtRule;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let top = self.stack.pop();
+        // a repeated rule stays on the stack until it has been returned the requested number of times
+        let top = match self.stack.last_mut() {
+            Some((rule, n)) if *n > 1 => {
+                *n -= 1;
+                Some(rule.clone())
+            }
+            _ => self.stack.pop().map(|(rule, _)| rule),
+        };
         let r = top.clone();
         match top {
             Some(rule) => {
@@ -42,10 +49,11 @@ impl Iterator for BinProtRuleIterator {
                     }
                     BinProtRule::Record(mut rules) => {
                         self.stack
-                            .extend(rules.drain(0..).map(|field| field.field_rule).rev());
+                            .extend(rules.drain(0..).map(|field| (field.field_rule, 1)).rev());
                     }
                     BinProtRule::Tuple(mut rules) => {
-                        self.stack.extend(rules.drain(0..).rev());
+                        self.stack
+                            .extend(rules.drain(0..).map(|rule| (rule, 1)).rev());
                     }
                     BinProtRule::Sum(_) | BinProtRule::Polyvar(_) => {
                         // don't add to the stack. Add to the branch field instead
@@ -59,7 +67,7 @@ impl Iterator for BinProtRuleIterator {
                             unimplemented!();
                         }
                         RuleRef::Resolved(payload) => {
-                            self.stack.push(*payload.ref_rule);
+                            self.stack.push((*payload.ref_rule, 1));
                             self.current_module_path = Some(payload.source_module_path);
                             return self.next();
                         }
@@ -91,12 +99,15 @@ impl Iterator for BinProtRuleIterator {
 impl BinProtRuleIterator {
     // Drop a custom rule onto the stack
     pub fn push(&mut self, rules: Vec<BinProtRule>) {
-        self.stack.extend(rules);
+        self.stack.extend(rules.into_iter().map(|rule| (rule, 1)));
     }
 
-    // Drop a custom rule onto the stack n times
+    // Drop a custom rule onto the stack n times.
+    // The rule is stored once with its count as n is read from the input and can be huge
     pub fn push_n(&mut self, rule: BinProtRule, n: usize) {
-        self.stack.extend(std::iter::repeat(rule).take(n));
+        if n > 0 {
+            self.stack.push((rule, n));
+        }
     }
 }
 
@@ -106,7 +117,7 @@ impl IntoIterator for BinProtRule {
 
     fn into_iter(self) -> <Self as IntoIterator>::IntoIter {
         BinProtRuleIterator {
-            stack: vec![self],
+            stack: vec![(self, 1)],
             current_module_path: None,
         }
     }
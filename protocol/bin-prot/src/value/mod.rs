@@ -11,6 +11,8 @@ use serde::Deserialize;
 mod enum_data;
 mod index;
 #[cfg(feature = "loose_deserialization")]
+pub mod json;
+#[cfg(feature = "loose_deserialization")]
 pub mod layout;
 pub mod ser;
 mod visitor;
@@ -96,4 +98,24 @@ impl Value {
             panic!("Called inner on a non-option variant {:?}", self)
         }
     }
+
+    /// Name of the variant, for error messages
+    #[cfg(feature = "loose_deserialization")]
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Unit => "Unit",
+            Value::Nat0(_) => "Nat0",
+            Value::Bool(_) => "Bool",
+            Value::String(_) => "String",
+            Value::Char(_) => "Char",
+            Value::Int(_) => "Int",
+            Value::Float(_) => "Float",
+            Value::Option(_) => "Option",
+            Value::Record(_) => "Record",
+            Value::Tuple(_) => "Tuple",
+            Value::Sum { .. } => "Sum",
+            Value::Polyvar { .. } => "Polyvar",
+            Value::List(_) => "List",
+        }
+    }
 }
@@ -0,0 +1,141 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+use bin_prot::error::Error;
+use bin_prot::{
+    from_reader, from_slice, from_slice_strict, to_writer, DecodeLimits, Deserializer,
+    ReadBinProtExt,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct Owned {
+    name: String,
+    data: Vec<u8>,
+    tag: String,
+    n: i64,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+struct Borrowed<'a> {
+    name: &'a str,
+    data: &'a [u8],
+    #[serde(borrow)]
+    tag: Cow<'a, str>,
+    n: i64,
+}
+
+fn owned() -> Owned {
+    Owned {
+        name: "block".to_string(),
+        data: vec![0xde, 0xad, 0xbe, 0xef],
+        tag: "tag".to_string(),
+        n: -300,
+    }
+}
+
+fn is_within(inner: &[u8], outer: &[u8]) -> bool {
+    let outer = outer.as_ptr_range();
+    let inner = inner.as_ptr_range();
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+#[test]
+fn test_borrowed_fields_point_into_input() {
+    let mut bytes = vec![];
+    to_writer(&mut bytes, &owned()).unwrap();
+
+    let result: Borrowed = from_slice_strict(&bytes).expect("Failed to deserialize");
+    assert_eq!(result.name, "block");
+    assert_eq!(result.data, &[0xde, 0xad, 0xbe, 0xef]);
+    assert_eq!(result.n, -300);
+    assert!(is_within(result.name.as_bytes(), &bytes));
+    assert!(is_within(result.data, &bytes));
+    match result.tag {
+        Cow::Borrowed(tag) => assert!(is_within(tag.as_bytes(), &bytes)),
+        other => panic!("Expected a borrowed tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_slice_and_reader_agree() {
+    let mut bytes = vec![];
+    to_writer(&mut bytes, &owned()).unwrap();
+
+    let from_rdr: Owned = from_reader(bytes.as_slice()).unwrap();
+    let from_slc: Owned = from_slice(&bytes).unwrap();
+    assert_eq!(from_rdr, owned());
+    assert_eq!(from_slc, owned());
+}
+
+#[test]
+fn test_reader_cannot_borrow() {
+    let mut bytes = vec![];
+    to_writer(&mut bytes, &owned()).unwrap();
+
+    let result: Result<Borrowed, Error> = from_reader(bytes.as_slice());
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::Custom { .. }
+    ));
+}
+
+#[test]
+fn test_slice_strict_unconsumed() {
+    let mut bytes = vec![];
+    to_writer(&mut bytes, &"abc").unwrap();
+    bytes.extend_from_slice(&[0x00, 0x00]);
+
+    let result: &str = from_slice(&bytes).unwrap();
+    assert_eq!(result, "abc");
+    let result: Result<&str, Error> = from_slice_strict(&bytes);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::StreamNotFullyConsumed(2)
+    ));
+}
+
+#[test]
+fn test_slice_truncated_string() {
+    // length prefix claims 5 bytes but only 3 follow
+    let bytes = [0x05, b'a', b'b', b'c'];
+    let result: Result<&[u8], Error> = from_slice(&bytes);
+    assert!(matches!(result.unwrap_err().root_cause(), Error::Io(_)));
+}
+
+#[test]
+fn test_reader_huge_length() {
+    // nat0 length of 2^40 with no bytes following, must fail without allocating for it
+    // even when the length is not rejected by the limits
+    let bytes = [0xfc, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00];
+    fn unlimited<'de, T: Deserialize<'de>>(bytes: &[u8]) -> Result<T, Error> {
+        let mut de = Deserializer::from_reader(bytes).with_limits(DecodeLimits::UNLIMITED);
+        T::deserialize(&mut de)
+    }
+    let result: Result<String, Error> = unlimited(&bytes);
+    assert!(matches!(result.unwrap_err().root_cause(), Error::Io(_)));
+    let result: Result<Vec<bool>, Error> = unlimited(&bytes);
+    assert!(matches!(result.unwrap_err().root_cause(), Error::Io(_)));
+    let result: Result<HashMap<String, bool>, Error> = unlimited(&bytes);
+    assert!(matches!(result.unwrap_err().root_cause(), Error::Io(_)));
+}
+
+#[test]
+fn test_invalid_utf8() {
+    let bytes = [0x02, 0xff, 0xfe];
+    let invalid = |err: Error| match err.root_cause() {
+        Error::InvalidUtf8 { bytes } => bytes == &[0xff, 0xfe],
+        _ => false,
+    };
+    let result: Result<&str, Error> = from_slice(&bytes);
+    assert!(invalid(result.unwrap_err()));
+    let result: Result<String, Error> = from_slice(&bytes);
+    assert!(invalid(result.unwrap_err()));
+    let result: Result<String, Error> = from_reader(bytes.as_slice());
+    assert!(invalid(result.unwrap_err()));
+    assert!(invalid(bytes.as_slice().bin_read_string().unwrap_err()));
+    let result: &[u8] = from_slice(&bytes).unwrap();
+    assert_eq!(result, &[0xff, 0xfe]);
+}
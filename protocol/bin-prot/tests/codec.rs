@@ -0,0 +1,137 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "codec")]
+mod tests {
+    use bin_prot::codec::BinProtCodec;
+    use bin_prot::error::Error;
+    use bytes::BytesMut;
+    use futures::{SinkExt, StreamExt};
+    use serde::{Deserialize, Serialize};
+    use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Message {
+        id: u64,
+        body: String,
+        peers: Vec<i32>,
+    }
+
+    fn message(id: u64) -> Message {
+        Message {
+            id,
+            body: "hello".repeat(id as usize),
+            peers: (0..id as i32).collect(),
+        }
+    }
+
+    fn encoded(messages: &[Message]) -> BytesMut {
+        let mut codec = BinProtCodec::new();
+        let mut buf = BytesMut::new();
+        for m in messages {
+            codec.encode(m.clone(), &mut buf).unwrap();
+        }
+        buf
+    }
+
+    #[test]
+    fn test_frame_has_length_prefix() {
+        let buf = encoded(&[message(1)]);
+        let mut body = vec![];
+        bin_prot::to_writer(&mut body, &message(1)).unwrap();
+        assert_eq!(buf[..8], (body.len() as u64).to_le_bytes());
+        assert_eq!(buf[8..], body[..]);
+    }
+
+    #[test]
+    fn test_decode_partial_frames() {
+        let messages = [message(1), message(200), message(3)];
+        let buf = encoded(&messages);
+
+        // feed the frames in one byte at a time
+        let mut codec = BinProtCodec::<Message>::new();
+        let mut src = BytesMut::new();
+        let mut decoded = vec![];
+        for b in buf.iter() {
+            src.extend_from_slice(&[*b]);
+            if let Some(m) = codec.decode(&mut src).unwrap() {
+                decoded.push(m);
+            }
+        }
+        assert_eq!(decoded, messages);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_max_frame_size() {
+        let buf = encoded(&[message(100)]);
+        let mut codec = BinProtCodec::<Message>::with_max_frame_size(64);
+
+        // rejected as soon as the prefix is read
+        let mut src = BytesMut::from(&buf[..8]);
+        assert!(matches!(
+            codec.decode(&mut src),
+            Err(Error::FrameTooLarge { max: 64, .. })
+        ));
+
+        let mut dst = BytesMut::new();
+        assert!(matches!(
+            codec.encode(message(100), &mut dst),
+            Err(Error::FrameTooLarge { max: 64, .. })
+        ));
+        assert!(dst.is_empty());
+    }
+
+    #[test]
+    fn test_unconsumed_bytes_in_frame() {
+        let mut buf = encoded(&[message(1)]);
+        buf.extend_from_slice(&[0]);
+        let len = u64::from_le_bytes(buf[..8].try_into().unwrap()) + 1;
+        buf[..8].copy_from_slice(&len.to_le_bytes());
+
+        let mut codec = BinProtCodec::<Message>::new();
+        assert!(matches!(
+            codec.decode(&mut buf).unwrap_err().root_cause(),
+            Error::StreamNotFullyConsumed(1)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_framed_roundtrip() {
+        let messages = vec![message(0), message(5), message(1000)];
+
+        let mut writer = FramedWrite::new(Vec::new(), BinProtCodec::new());
+        for m in &messages {
+            writer.send(m.clone()).await.unwrap();
+        }
+        let bytes = writer.into_inner();
+
+        let reader = FramedRead::new(bytes.as_slice(), BinProtCodec::<Message>::new());
+        let decoded: Vec<Message> = reader.map(Result::unwrap).collect().await;
+        assert_eq!(decoded, messages);
+    }
+
+    #[cfg(feature = "loose_deserialization")]
+    #[test]
+    fn test_layout_codec() {
+        use bin_prot::codec::LayoutCodec;
+        use bin_prot::{BinProtRule, Value};
+
+        let rule: BinProtRule = serde_json::from_str(
+            r#"["Record", [
+                { "field_name": "id", "field_rule": ["Int"] },
+                { "field_name": "body", "field_rule": ["String"] },
+                { "field_name": "peers", "field_rule": ["List", ["Int"]] }
+            ]]"#,
+        )
+        .unwrap();
+        let mut buf = encoded(&[message(2)]);
+
+        let mut codec = LayoutCodec::new(rule);
+        let value = codec.decode(&mut buf).unwrap().unwrap();
+        assert!(matches!(&value, Value::Record(fields) if fields.len() == 3));
+
+        codec.encode(value, &mut buf).unwrap();
+        assert_eq!(buf, encoded(&[message(2)]));
+    }
+}
@@ -14,6 +14,7 @@ macro_rules! bin_prot_test {
         $(
         let mut output = vec![];
         bin_prot::to_writer(&mut output, &$typ).expect("Failed writing bin-prot encoded data");
+        assert_eq!(bin_prot::serialized_size(&$typ).unwrap(), output.len());
         assert_eq!(vec![$($expected,)*], output.into_iter().rev().collect::<Vec<u8>>());
         )*
     };
@@ -113,6 +114,7 @@ pub fn print_janestreet_byte_array<W: Write>(w: &mut W, bytes: &[u8], max_len: u
 pub fn roundtrip_test<'a, T: Serialize + Deserialize<'a> + PartialEq + Debug>(val: T) {
     let mut output = Vec::<u8>::new();
     to_writer(&mut output, &val).unwrap();
+    assert_eq!(serialized_size(&val).unwrap(), output.len());
     let re_val: T = from_reader(std::io::Cursor::new(output.as_slice())).unwrap();
     assert_eq!(val, re_val);
     let re_val: T = from_reader_strict(std::io::Cursor::new(output.as_slice())).unwrap();
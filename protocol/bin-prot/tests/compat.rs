@@ -0,0 +1,258 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "loose_deserialization")]
+mod tests {
+    use bin_prot::error::Error;
+    use bin_prot::value::layout::compat::check_type;
+    use bin_prot::value::layout::BinProtRule;
+    use serde::de::DeserializeOwned;
+    use serde::Deserialize;
+
+    const RULE: &str = r#"
+[
+  "Record",
+  [
+    { "field_name": "version", "field_rule": ["Int"] },
+    {
+      "field_name": "t",
+      "field_rule": [
+        "Reference",
+        [
+          "Resolved",
+          {
+            "source_type_decl": "type t = { count: int ; amount: Amount.t ; kind: kind ; tag: [ `One | `Two of float ] }",
+            "source_module_path": "Test_type.Stable.V2",
+            "ref_rule": [
+              "Record",
+              [
+                { "field_name": "count", "field_rule": ["Int"] },
+                {
+                  "field_name": "amount",
+                  "field_rule": [
+                    "Reference",
+                    [
+                      "Resolved",
+                      {
+                        "source_type_decl": "type t = { version: int ; t: typ }",
+                        "source_module_path": "Amount.Stable.V1",
+                        "ref_rule": [
+                          "Record",
+                          [
+                            { "field_name": "version", "field_rule": ["Int"] },
+                            { "field_name": "t", "field_rule": ["Tuple", [["Int64"], ["Bool"]]] }
+                          ]
+                        ]
+                      }
+                    ]
+                  ]
+                },
+                {
+                  "field_name": "kind",
+                  "field_rule": [
+                    "Sum",
+                    [
+                      { "ctor_name": "Empty", "index": 0, "ctor_args": [] },
+                      { "ctor_name": "Counted", "index": 1, "ctor_args": [["List", ["Int"]], ["Option", ["String"]]] }
+                    ]
+                  ]
+                },
+                {
+                  "field_name": "tag",
+                  "field_rule": [
+                    "Polyvar",
+                    [
+                      ["Tagged", { "polyvar_name": "One", "hash": 3953222, "polyvar_args": [] }],
+                      ["Tagged", { "polyvar_name": "Two", "hash": 4203884, "polyvar_args": [["Float"]] }]
+                    ]
+                  ]
+                }
+              ]
+            ]
+          }
+        ]
+      ]
+    }
+  ]
+]
+"#;
+
+    #[derive(Deserialize)]
+    struct Versioned<T> {
+        #[allow(dead_code)]
+        version: u16,
+        #[allow(dead_code)]
+        t: T,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "Polyvar")]
+    enum Tag {
+        One,
+        Two(f64),
+    }
+
+    #[derive(Deserialize)]
+    enum Kind {
+        Empty,
+        Counted(Vec<i64>, Option<String>),
+    }
+
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    struct Matching {
+        count: i32,
+        amount: Versioned<(u64, bool)>,
+        kind: Kind,
+        tag: Tag,
+    }
+
+    fn check<T: DeserializeOwned>() -> Result<(), String> {
+        let rule: BinProtRule = serde_json::from_str(RULE).unwrap();
+        check_type::<T>(&rule).map_err(|e| {
+            assert!(matches!(e, Error::TypeLayoutMismatch { .. }));
+            e.to_string()
+        })
+    }
+
+    #[test]
+    fn test_matching_type() {
+        check::<Versioned<Matching>>().unwrap();
+    }
+
+    #[test]
+    fn test_regrouped_and_renamed_fields() {
+        // records and tuples are only concatenations of their elements in the binary
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Amount {
+            version: u16,
+            value: u64,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Regrouped {
+            n: i64,
+            amount_and_flag: (Amount, bool),
+            kind: Kind,
+            tag: Tag,
+        }
+
+        check::<Versioned<Regrouped>>().unwrap();
+    }
+
+    #[test]
+    fn test_missing_field() {
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct MissingTag {
+            count: i32,
+            amount: Versioned<(u64, bool)>,
+            kind: Kind,
+        }
+
+        assert_eq!(
+            check::<Versioned<MissingTag>>().unwrap_err(),
+            "Type does not match layout at <root>. Expected: Polyvar at t.tag, found: no more values"
+        );
+    }
+
+    #[test]
+    fn test_wrong_integer_encoding() {
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct ByteCount {
+            count: u8,
+            amount: Versioned<(u64, bool)>,
+            kind: Kind,
+            tag: Tag,
+        }
+
+        assert_eq!(
+            check::<Versioned<ByteCount>>().unwrap_err(),
+            "Type does not match layout at t.count. Expected: Int at t.count, found: u8 read as a single byte"
+        );
+    }
+
+    #[test]
+    fn test_missing_version_byte() {
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Unversioned {
+            count: i32,
+            amount: (u64, bool),
+            kind: Kind,
+            tag: Tag,
+        }
+
+        assert_eq!(
+            check::<Versioned<Unversioned>>().unwrap_err(),
+            "Type does not match layout at t.amount[0]. Expected: version byte 1 of Amount.Stable.V1 at t.amount.version, found: u64"
+        );
+    }
+
+    #[test]
+    fn test_every_variant_is_checked() {
+        #[derive(Deserialize)]
+        enum WrongKind {
+            Empty,
+            Counted(Vec<bool>, Option<String>),
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Wrong {
+            count: i32,
+            amount: Versioned<(u64, bool)>,
+            kind: WrongKind,
+            tag: Tag,
+        }
+
+        assert_eq!(
+            check::<Versioned<Wrong>>().unwrap_err(),
+            "Type does not match layout at t.kind::Counted[0][0]. Expected: Int at t.kind::Counted[0][0], found: bool"
+        );
+    }
+
+    #[test]
+    fn test_missing_variants() {
+        #[derive(Deserialize)]
+        enum OneKind {
+            Empty,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename = "Polyvar")]
+        enum OneTag {
+            One,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct MissingCtor {
+            count: i32,
+            amount: Versioned<(u64, bool)>,
+            kind: OneKind,
+            tag: Tag,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct MissingTag {
+            count: i32,
+            amount: Versioned<(u64, bool)>,
+            kind: Kind,
+            tag: OneTag,
+        }
+
+        assert_eq!(
+            check::<Versioned<MissingCtor>>().unwrap_err(),
+            "Type does not match layout at t.kind. Expected: Sum at t.kind (2 constructors), found: enum OneKind with 1 variants"
+        );
+        assert_eq!(
+            check::<Versioned<MissingTag>>().unwrap_err(),
+            "Type does not match layout at t.tag. Expected: polyvar tag Two of Polyvar at t.tag, found: enum Polyvar with 1 variants"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+use bin_prot::error::{Error, ErrorPath, PathSegment};
+use bin_prot::{from_reader, from_slice, from_slice_strict};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct Outer {
+    version: u8,
+    inner: Vec<Inner>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+enum Inner {
+    Empty,
+    Flag { set: bool },
+    Pair(i64, Option<bool>),
+}
+
+fn segments(err: &Error) -> Vec<PathSegment> {
+    err.path().expect("Error is missing its context").0.clone()
+}
+
+#[test]
+fn test_struct_field_and_variant_path() {
+    let bytes = [
+        0x01, // version
+        0x02, // inner length
+        0x00, // Inner::Empty
+        0x01, // Inner::Flag
+        0x02, // invalid bool
+    ];
+    let err = from_slice::<Outer>(&bytes).unwrap_err();
+
+    assert_eq!(err.offset(), Some(4));
+    assert_eq!(
+        segments(&err),
+        vec![
+            PathSegment::Field("inner".into()),
+            PathSegment::Index(1),
+            PathSegment::Variant("Flag".into()),
+            PathSegment::Field("set".into()),
+        ]
+    );
+    assert!(matches!(
+        err.root_cause(),
+        Error::InvalidByte { byte: 0x02, .. }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Invalid byte for deserializing a bool or option. Expected one of: [0, 1], found: 2 \
+         (at byte offset 4, path inner[1]::Flag.set)"
+    );
+}
+
+#[test]
+fn test_reader_and_slice_report_the_same_offset() {
+    let bytes = [
+        0x01, // version
+        0x01, // inner length
+        0x02, // Inner::Pair
+        0xfe, 0x00, 0x01, // 256 as int16
+        0x05, // invalid option
+    ];
+    let from_rdr = from_reader::<_, Outer>(bytes.as_slice()).unwrap_err();
+    let from_slc = from_slice::<Outer>(&bytes).unwrap_err();
+
+    for err in [from_rdr, from_slc] {
+        assert_eq!(err.offset(), Some(6));
+        assert_eq!(
+            err.path().unwrap().to_string(),
+            "inner[0]::Pair[1]".to_string()
+        );
+    }
+}
+
+#[test]
+fn test_truncated_input() {
+    let bytes = [0x01, 0x03, 0x00];
+    let err = from_slice::<Outer>(&bytes).unwrap_err();
+
+    assert_eq!(err.offset(), Some(3));
+    assert_eq!(
+        segments(&err),
+        vec![PathSegment::Field("inner".into()), PathSegment::Index(1)]
+    );
+    assert!(matches!(err.root_cause(), Error::Io(_)));
+}
+
+#[test]
+fn test_unknown_variant_has_no_variant_segment() {
+    let bytes = [0x01, 0x01, 0x07];
+    let err = from_slice::<Outer>(&bytes).unwrap_err();
+
+    assert_eq!(err.offset(), Some(2));
+    assert_eq!(err.path().unwrap().to_string(), "inner[0]");
+    assert!(matches!(err.root_cause(), Error::Custom { .. }));
+}
+
+#[test]
+fn test_root_errors_have_context() {
+    let err = from_slice::<bool>(&[0x03]).unwrap_err();
+    assert_eq!(err.offset(), Some(0));
+    assert_eq!(err.path(), Some(&ErrorPath::default()));
+    assert_eq!(err.path().unwrap().to_string(), "<root>");
+
+    let err = from_slice_strict::<bool>(&[0x01, 0x00]).unwrap_err();
+    assert_eq!(err.offset(), Some(1));
+    assert!(matches!(err.root_cause(), Error::StreamNotFullyConsumed(1)));
+}
+
+#[cfg(feature = "loose_deserialization")]
+mod loose {
+    use super::*;
+    use bin_prot::value::layout::BinProtRule;
+    use bin_prot::value::Value;
+    use bin_prot::Deserializer;
+
+    const RULE: &str = r#"
+[
+  "Record",
+  [
+    { "field_name": "first", "field_rule": ["Int"] },
+    {
+      "field_name": "second",
+      "field_rule": [
+        "Sum",
+        [
+          { "ctor_name": "A", "index": 0, "ctor_args": [] },
+          { "ctor_name": "B", "index": 1, "ctor_args": [["List", ["Bool"]]] }
+        ]
+      ]
+    }
+  ]
+]
+"#;
+
+    #[test]
+    fn test_record_and_sum_path() {
+        let rule: BinProtRule = serde_json::from_str(RULE).unwrap();
+        let bytes = [
+            0x05, // first
+            0x01, // B
+            0x02, // list length
+            0x01, // true
+            0x07, // invalid bool
+        ];
+        let mut de = Deserializer::from_slice(&bytes).with_layout(&rule);
+        let err = Value::deserialize(&mut de).unwrap_err();
+
+        assert_eq!(err.offset(), Some(4));
+        assert_eq!(err.path().unwrap().to_string(), "second::B[0][1]");
+        assert!(matches!(
+            err.root_cause(),
+            Error::InvalidByte { byte: 0x07, .. }
+        ));
+    }
+}
@@ -79,3 +79,17 @@ fn test_roundtrip_integers() {
         common::roundtrip_test(val);
     }
 }
+
+/// Test that u64 values above i64::MAX are encoded as negative i64s and read back
+#[test]
+fn test_roundtrip_u64_above_i64_max() {
+    for val in [i64::MAX as u64, i64::MAX as u64 + 1, u64::MAX] {
+        common::roundtrip_test(val);
+    }
+    let mut output = vec![];
+    bin_prot::to_writer(&mut output, &u64::MAX).unwrap();
+    assert_eq!(output, vec![0xff, 0xff]);
+    let mut output = vec![];
+    bin_prot::to_writer(&mut output, &(i64::MAX as u64 + 1)).unwrap();
+    assert_eq!(output, vec![0xfc, 0, 0, 0, 0, 0, 0, 0, 0x80]);
+}
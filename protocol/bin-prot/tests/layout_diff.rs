@@ -0,0 +1,109 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "loose_deserialization")]
+mod tests {
+    use bin_prot::value::layout::diff::{diff_rules, ChangeKind};
+    use bin_prot::value::layout::BinProtRule;
+
+    // a versioned type with the given fields and the amount type used in two of them
+    fn rule(version: u16, fields: &str, kind: &str, tag: &str) -> BinProtRule {
+        let amount = format!(
+            r#"["Reference", ["Resolved", {{
+                "source_type_decl": "type t = {{ version: int ; t: typ }}",
+                "source_module_path": "Amount.Stable.V1",
+                "ref_rule": ["Record", [
+                    {{ "field_name": "version", "field_rule": ["Int"] }},
+                    {{ "field_name": "t", "field_rule": ["Reference", ["Resolved", {{
+                        "source_type_decl": "type t = {{ value: int64 }}",
+                        "source_module_path": "Amount.Stable.V{version}",
+                        "ref_rule": ["Record", [{{ "field_name": "value", "field_rule": ["Int64"] }}]]
+                    }}]] }}
+                ]]
+            }}]]"#
+        );
+        let json = format!(
+            r#"["Reference", ["Resolved", {{
+                "source_type_decl": "type t = {{ ... }}",
+                "source_module_path": "Test_type.Stable.V1",
+                "ref_rule": ["Record", [
+                    {fields},
+                    {{ "field_name": "fee", "field_rule": {amount} }},
+                    {{ "field_name": "amount", "field_rule": {amount} }},
+                    {{ "field_name": "kind", "field_rule": ["Sum", [{kind}]] }},
+                    {{ "field_name": "tag", "field_rule": ["Polyvar", [{tag}]] }}
+                ]]
+            }}]]"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    const FIELDS: &str = r#"{ "field_name": "count", "field_rule": ["Int"] },
+        { "field_name": "memo", "field_rule": ["String"] }"#;
+    const KIND: &str = r#"{ "ctor_name": "Empty", "index": 0, "ctor_args": [] },
+        { "ctor_name": "Counted", "index": 1, "ctor_args": [["List", ["Int"]]] }"#;
+    const TAG: &str = r#"["Tagged", { "polyvar_name": "One", "hash": 3953222, "polyvar_args": [] }],
+        ["Tagged", { "polyvar_name": "Two", "hash": 4203884, "polyvar_args": [["Float"]] }]"#;
+
+    fn diff(old: BinProtRule, new: BinProtRule) -> Vec<String> {
+        diff_rules(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_layouts() {
+        assert!(diff(rule(1, FIELDS, KIND, TAG), rule(1, FIELDS, KIND, TAG)).is_empty());
+    }
+
+    #[test]
+    fn test_fields() {
+        let fields = r#"{ "field_name": "memo", "field_rule": ["String"] },
+            { "field_name": "count", "field_rule": ["Int32"] },
+            { "field_name": "nonce", "field_rule": ["Int"] }"#;
+        assert_eq!(
+            diff(rule(1, FIELDS, KIND, TAG), rule(1, fields, KIND, TAG)),
+            vec![
+                "<root> (Test_type.Stable.V1): field `nonce` added",
+                "<root> (Test_type.Stable.V1): fields reordered from (count, memo, fee, amount, kind, tag) to (memo, count, fee, amount, kind, tag)",
+                "count (Test_type.Stable.V1): rule changed from Int to Int32",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_summands_and_tags() {
+        let kind = r#"{ "ctor_name": "Counted", "index": 0, "ctor_args": [["List", ["Int"]], ["Bool"]] },
+            { "ctor_name": "Flagged", "index": 1, "ctor_args": [] }"#;
+        let tag = r#"["Tagged", { "polyvar_name": "Two", "hash": 4203884, "polyvar_args": [["Char"]] }],
+            ["Tagged", { "polyvar_name": "Three", "hash": 237833689, "polyvar_args": [] }]"#;
+        assert_eq!(
+            diff(rule(1, FIELDS, KIND, TAG), rule(1, FIELDS, kind, tag)),
+            vec![
+                "kind (Test_type.Stable.V1): constructor `Empty` removed",
+                "kind (Test_type.Stable.V1): constructor `Counted` index changed from 1 to 0",
+                "kind::Counted (Test_type.Stable.V1): number of elements changed from 1 to 2",
+                "kind (Test_type.Stable.V1): constructor `Flagged` added",
+                "tag (Test_type.Stable.V1): polyvar tag `One` removed",
+                "tag::Two[0] (Test_type.Stable.V1): rule changed from Float to Char",
+                "tag (Test_type.Stable.V1): polyvar tag `Three` added",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_version_changes_are_grouped() {
+        let changes = diff_rules(&rule(1, FIELDS, KIND, TAG), &rule(2, FIELDS, KIND, TAG));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].kind,
+            ChangeKind::VersionChanged { old: 1, new: 2 }
+        );
+        assert_eq!(changes[0].other_paths, 1);
+        assert_eq!(
+            changes[0].to_string(),
+            "fee (Amount.Stable.V1): version changed from 1 to 2 (and 1 other paths)"
+        );
+    }
+}
@@ -38,6 +38,7 @@ mod tests {
             ]))))
         );
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
     }
 
     const RECORD_RULE: &str = r#"
@@ -74,6 +75,7 @@ mod tests {
         // also test using the indexing
         assert_eq!(result["second"]["inner"], Value::Bool(false));
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
     }
 
     #[test]
@@ -137,6 +139,15 @@ mod tests {
             }
         );
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
+
+        let example = vec![0x02, 0x00];
+        let mut de = Deserializer::from_reader(Cursor::new(example.as_slice())).with_layout(&rule);
+        let result: Result<Value, _> = Deserialize::deserialize(&mut de);
+        assert!(matches!(
+            result.unwrap_err().root_cause(),
+            bin_prot::error::Error::InvalidByte { byte: 2, .. }
+        ));
     }
 
     const TAGGED_POLYVAR_RULE: &str = r#"
@@ -179,6 +190,7 @@ mod tests {
             }
         );
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
     }
 
     const NESTED_SUM_RULE: &str = r#"
@@ -219,6 +231,7 @@ mod tests {
             }
         );
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
     }
 
     const OPTION_RULE: &str = r#"
@@ -241,6 +254,7 @@ mod tests {
         assert_eq!(result, Value::Option(None));
 
         test_reserialize(&result, &example_none);
+        test_reserialize_with_layout(&result, &rule, &example_none);
 
         let example_some = vec![0x01, 0x07]; // Some(7)
 
@@ -250,6 +264,7 @@ mod tests {
 
         assert_eq!(result, Value::Option(Some(Box::new(Value::Int(0x07)))));
         test_reserialize(&result, &example_some);
+        test_reserialize_with_layout(&result, &rule, &example_some);
     }
 
     const MULTIPLE_CTOR_ARG_SUM_RULE: &str = r#"
@@ -297,6 +312,7 @@ mod tests {
             }
         );
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
     }
 
     #[test]
@@ -307,6 +323,7 @@ mod tests {
         let mut de = Deserializer::from_reader(Cursor::new(example.as_slice())).with_layout(&rule);
         let result: Value = Deserialize::deserialize(&mut de).expect("Failed to deserialize");
         test_reserialize(&result, &example);
+        test_reserialize_with_layout(&result, &rule, &example);
         let mut output = vec![];
         let value = &result["t"]["t"];
         bin_prot::to_writer(&mut output, value).expect("Failed writing bin-prot encoded data");
@@ -319,6 +336,104 @@ mod tests {
         );
     }
 
+    const SMALL_TAG_POLYVAR_RULE: &str = r#"
+[
+  "Polyvar",
+   [
+    [
+     "Tagged",
+     {
+      "polyvar_name": "Small",
+      "hash": 3,
+      "polyvar_args": [["String"]]
+     }
+    ]
+   ]
+]
+"#;
+
+    #[test]
+    fn test_small_tag_polyvar_and_string_rule() {
+        let rule: BinProtRule = serde_json::from_str(SMALL_TAG_POLYVAR_RULE).unwrap();
+        let example = vec![0x07, 0x00, 0x00, 0x00, 0x02, b'h', b'i']; // Small("hi")
+
+        let mut de = Deserializer::from_reader(Cursor::new(example.as_slice())).with_layout(&rule);
+        let result: Value = Deserialize::deserialize(&mut de).expect("Failed to deserialize");
+        test_reserialize_with_layout(&result, &rule, &example);
+
+        // strings are also accepted as a byte string
+        let value = Value::Polyvar {
+            name: "Small".to_string(),
+            tag: 3,
+            value: Box::new(Value::Tuple(vec![Value::String(b"hi".to_vec())])),
+        };
+        test_reserialize_with_layout(&value, &rule, &example);
+    }
+
+    const LIST_RULE: &str = r#"["List", ["Bool"]]"#;
+
+    #[test]
+    fn test_list_rule() {
+        let rule: BinProtRule = serde_json::from_str(LIST_RULE).unwrap();
+        let example = vec![0x02, 0x01, 0x00];
+
+        let mut de = Deserializer::from_reader(Cursor::new(example.as_slice())).with_layout(&rule);
+        let result: Value = Deserialize::deserialize(&mut de).expect("Failed to deserialize");
+        assert_eq!(
+            result,
+            Value::List(vec![Value::Bool(true), Value::Bool(false)])
+        );
+        test_reserialize_with_layout(&result, &rule, &example);
+
+        // a length of 2^40 is rejected by the default limits
+        let example = vec![0xfc, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01];
+        let mut de = Deserializer::from_reader(Cursor::new(example.as_slice())).with_layout(&rule);
+        let result: Result<Value, _> = Deserialize::deserialize(&mut de);
+        assert!(matches!(
+            result.unwrap_err().root_cause(),
+            bin_prot::error::Error::SeqTooLong { len, .. } if *len == 1 << 40
+        ));
+
+        // and without limits it is not allocated up front, decoding fails at the end of the input
+        let mut de = Deserializer::from_reader(Cursor::new(example.as_slice()))
+            .with_limits(bin_prot::DecodeLimits::UNLIMITED)
+            .with_layout(&rule);
+        let result: Result<Value, _> = Deserialize::deserialize(&mut de);
+        assert!(matches!(
+            result.unwrap_err().root_cause(),
+            bin_prot::error::Error::Io(_)
+        ));
+    }
+
+    #[test]
+    fn test_layout_mismatch() {
+        let rule: BinProtRule = serde_json::from_str(RECORD_RULE).unwrap();
+        let value = Value::Record(vec![
+            ("first".to_string(), Value::Int(5)),
+            (
+                "second".to_string(),
+                Value::Record(vec![("inner".to_string(), Value::Int(0))]),
+            ),
+            ("third".to_string(), Value::Bool(true)),
+        ]);
+
+        let err = bin_prot::to_writer_with_layout(&mut vec![], &value, &rule).unwrap_err();
+        assert_eq!(err.offset(), Some(1));
+        assert_eq!(err.path().unwrap().to_string(), "second.inner");
+        assert!(matches!(
+            err.root_cause(),
+            bin_prot::error::Error::ValueLayoutMismatch { .. }
+        ));
+
+        let rule: BinProtRule = serde_json::from_str(SUM_RULE).unwrap();
+        let value = Value::Sum {
+            name: "two".to_string(),
+            index: 0,
+            value: Box::new(Value::Tuple(vec![Value::Bool(false)])),
+        };
+        assert!(bin_prot::to_writer_with_layout(&mut vec![], &value, &rule).is_err());
+    }
+
     pub fn test_reserialize<T>(val: &T, bytes: &[u8])
     where
         T: Serialize,
@@ -327,4 +442,11 @@ mod tests {
         bin_prot::to_writer(&mut output, val).expect("Failed writing bin-prot encoded data");
         assert_eq!(bytes, output)
     }
+
+    pub fn test_reserialize_with_layout(val: &Value, rule: &BinProtRule, bytes: &[u8]) {
+        let mut output = vec![];
+        bin_prot::to_writer_with_layout(&mut output, val, rule)
+            .expect("Failed writing bin-prot encoded data");
+        assert_eq!(bytes, output)
+    }
 }
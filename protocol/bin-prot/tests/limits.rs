@@ -0,0 +1,190 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+use bin_prot::error::Error;
+use bin_prot::{from_reader, from_slice, to_writer, DecodeLimits, Deserializer};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+fn encode<T: serde::Serialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    to_writer(&mut bytes, value).unwrap();
+    bytes
+}
+
+fn decode<'de, T: Deserialize<'de>>(bytes: &'de [u8], limits: DecodeLimits) -> Result<T, Error> {
+    let mut de = Deserializer::from_slice(bytes).with_limits(limits);
+    T::deserialize(&mut de)
+}
+
+#[test]
+fn test_default_limits() {
+    let limits = DecodeLimits::default();
+    assert_eq!(limits, *Deserializer::from_slice(&[]).limits());
+    assert!(limits.max_seq_len <= limits.max_total_alloc);
+    assert!(limits.max_string_len <= limits.max_total_alloc);
+
+    // a nat0 length of 2^62 fails on the length alone
+    let bytes = [0xfc, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40];
+    let result: Result<Vec<u8>, Error> = from_reader(bytes.as_slice());
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::SeqTooLong { len, .. } if *len == 1 << 62
+    ));
+    let result: Result<String, Error> = from_slice(&bytes);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::StringTooLong { len, .. } if *len == 1 << 62
+    ));
+}
+
+#[test]
+fn test_max_seq_len() {
+    let limits = DecodeLimits {
+        max_seq_len: 3,
+        ..DecodeLimits::default()
+    };
+    let bytes = encode(&vec![1_u8, 2, 3]);
+    let result: Vec<u8> = decode(&bytes, limits).unwrap();
+    assert_eq!(result, vec![1, 2, 3]);
+
+    let bytes = encode(&vec![1_u8, 2, 3, 4]);
+    let result: Result<Vec<u8>, _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::SeqTooLong { len: 4, max: 3 }
+    ));
+
+    // maps are length prefixed key value pairs
+    let bytes = [0x04, 0x01, 0x01, 0x02, 0x02, 0x03, 0x03, 0x04, 0x04];
+    let result: Result<HashMap<u8, u8>, _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::SeqTooLong { len: 4, max: 3 }
+    ));
+}
+
+#[test]
+fn test_max_string_len() {
+    let limits = DecodeLimits {
+        max_string_len: 3,
+        ..DecodeLimits::default()
+    };
+    let bytes = encode(&"abc");
+    let result: &str = decode(&bytes, limits).unwrap();
+    assert_eq!(result, "abc");
+
+    let bytes = encode(&"abcd");
+    let result: Result<String, _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::StringTooLong { len: 4, max: 3 }
+    ));
+    let result: Result<&[u8], _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::StringTooLong { len: 4, max: 3 }
+    ));
+}
+
+#[test]
+fn test_max_total_alloc() {
+    let limits = DecodeLimits {
+        max_total_alloc: 6,
+        ..DecodeLimits::default()
+    };
+    // each length is within the other limits, the sum of 2 + 2 + 2 list elements and
+    // string bytes is the total
+    let value = vec!["ab".to_string(), "cd".to_string()];
+    let bytes = encode(&value);
+    let result: Vec<String> = decode(&bytes, limits).unwrap();
+    assert_eq!(result, value);
+
+    let bytes = encode(&vec!["ab".to_string(), "cde".to_string()]);
+    let result: Result<Vec<String>, _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::AllocationLimitExceeded { max: 6 }
+    ));
+}
+
+#[test]
+fn test_max_depth() {
+    let limits = DecodeLimits {
+        max_depth: 3,
+        ..DecodeLimits::default()
+    };
+    let value = vec![vec![vec![1_u8]]];
+    let bytes = encode(&value);
+    let result: Vec<Vec<Vec<u8>>> = decode(&bytes, limits).unwrap();
+    assert_eq!(result, value);
+
+    let bytes = encode(&vec![value]);
+    let result: Result<Vec<Vec<Vec<Vec<u8>>>>, _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::DepthLimitExceeded { max: 3 }
+    ));
+
+    // tuples and structs count as a level too
+    let bytes = encode(&(((1_u8,),),));
+    let result: Result<(((u8,),),), _> = decode(&bytes, limits);
+    assert!(result.is_ok());
+    let bytes = encode(&((((1_u8,),),),));
+    let result: Result<((((u8,),),),), _> = decode(&bytes, limits);
+    assert!(matches!(
+        result.unwrap_err().root_cause(),
+        Error::DepthLimitExceeded { max: 3 }
+    ));
+}
+
+// layouts need the loose_deserialization feature, the other limits are checked without it
+#[cfg(feature = "loose_deserialization")]
+mod loose {
+    use super::*;
+    use bin_prot::value::layout::BinProtRule;
+    use bin_prot::value::Value;
+
+    #[test]
+    fn test_loose_limits() {
+        let rule: BinProtRule = serde_json::from_str(r#"["List", ["List", ["String"]]]"#).unwrap();
+        let bytes = encode(&vec![vec!["abc".to_string()]]);
+
+        let mut de = Deserializer::from_slice(&bytes).with_layout(&rule);
+        assert!(Value::deserialize(&mut de).is_ok());
+
+        let cases = [
+            (
+                DecodeLimits {
+                    max_string_len: 2,
+                    ..DecodeLimits::default()
+                },
+                Error::StringTooLong { len: 3, max: 2 },
+            ),
+            (
+                DecodeLimits {
+                    max_total_alloc: 4,
+                    ..DecodeLimits::default()
+                },
+                Error::AllocationLimitExceeded { max: 4 },
+            ),
+            (
+                DecodeLimits {
+                    max_depth: 1,
+                    ..DecodeLimits::default()
+                },
+                Error::DepthLimitExceeded { max: 1 },
+            ),
+        ];
+        for (limits, expected) in cases {
+            let mut de = Deserializer::from_slice(&bytes)
+                .with_limits(limits)
+                .with_layout(&rule);
+            let result = Value::deserialize(&mut de);
+            assert_eq!(
+                result.unwrap_err().root_cause().to_string(),
+                expected.to_string()
+            );
+        }
+    }
+}
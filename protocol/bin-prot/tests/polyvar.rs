@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use bin_prot::error::Error;
-use bin_prot::to_writer;
+use bin_prot::{serialized_size, to_writer};
 use bin_prot::Deserializer;
 use serde::{Deserialize, Serialize};
 
@@ -74,6 +74,7 @@ fn test_polyvar_variant_two() {
     let mut re_bytes = vec![];
     to_writer(&mut re_bytes, &result).unwrap();
     assert_eq!(data, re_bytes[..]);
+    assert_eq!(serialized_size(&result).unwrap(), re_bytes.len());
 }
 
 #[test]
@@ -0,0 +1,178 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "loose_deserialization")]
+mod tests {
+    use bin_prot::error::Error;
+    use bin_prot::value::layout::{BinProtRule, Layout};
+    use bin_prot::value::Value;
+    use bin_prot::{from_json_with_layout, to_json_with_layout, Deserializer};
+    use serde::Deserialize;
+    use serde_json::json;
+
+    const RULE: &str = r#"
+[
+  "Record",
+  [
+    { "field_name": "int", "field_rule": ["Int"] },
+    { "field_name": "name", "field_rule": ["String"] },
+    { "field_name": "maybe", "field_rule": ["Option", ["Option", ["Bool"]]] },
+    { "field_name": "pair", "field_rule": ["Tuple", [["Char"], ["Float"]]] },
+    {
+      "field_name": "sums",
+      "field_rule": [
+        "List",
+        [
+          "Sum",
+          [
+            { "ctor_name": "Empty", "index": 0, "ctor_args": [] },
+            { "ctor_name": "One", "index": 1, "ctor_args": [["Int"]] },
+            { "ctor_name": "Two", "index": 2, "ctor_args": [["Bool"], ["Int"]] }
+          ]
+        ]
+      ]
+    },
+    {
+      "field_name": "polyvar",
+      "field_rule": [
+        "Polyvar",
+        [["Tagged", { "polyvar_name": "Tagged", "hash": 3, "polyvar_args": [["String"]] }]]
+      ]
+    }
+  ]
+]
+"#;
+
+    const BLOCK_LAYOUT: &str = include_str!("../../layouts/external_transition.json");
+
+    fn block_rule() -> BinProtRule {
+        let mut deserializer = serde_json::Deserializer::from_str(BLOCK_LAYOUT);
+        deserializer.disable_recursion_limit();
+        let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+        Layout::deserialize(deserializer).unwrap().bin_prot_rule
+    }
+
+    fn decode(rule: &BinProtRule, bytes: &[u8]) -> Value {
+        let mut de = Deserializer::from_slice(bytes).with_layout(rule);
+        Value::deserialize(&mut de).expect("Failed to deserialize")
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let rule: BinProtRule = serde_json::from_str(RULE).unwrap();
+        let bytes = vec![
+            0x05, // int
+            0x02, b'h', b'i', // name
+            0x01, 0x00, // Some(None)
+            0x41, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf8, 0x3f, // ('A', 1.5)
+            0x03, // sums length
+            0x00, // Empty
+            0x01, 0x07, // One(7)
+            0x02, 0x01, 0x08, // Two(true, 8)
+            0x07, 0x00, 0x00, 0x00, 0x02, 0xff, 0xfe, // Tagged("\xff\xfe")
+        ];
+        let value = decode(&rule, &bytes);
+
+        let json = to_json_with_layout(&value, &rule).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "int": 5,
+                "name": "hi",
+                "maybe": [null],
+                "pair": [65, 1.5],
+                "sums": ["Empty", { "One": 7 }, { "Two": [true, 8] }],
+                "polyvar": { "Tagged": [255, 254] },
+            })
+        );
+
+        let from_json = from_json_with_layout(&json, &rule).unwrap();
+        assert_eq!(from_json, value);
+
+        let mut output = vec![];
+        bin_prot::to_writer_with_layout(&mut output, &from_json, &rule).unwrap();
+        assert_eq!(output, bytes);
+    }
+
+    #[test]
+    fn test_edited_json() {
+        let rule: BinProtRule = serde_json::from_str(RULE).unwrap();
+        let json = json!({
+            "int": -1,
+            "name": "",
+            "maybe": null,
+            "pair": [0, 0.0],
+            "sums": [],
+            "polyvar": { "Tagged": "ok" },
+        });
+
+        let value = from_json_with_layout(&json, &rule).unwrap();
+        let mut output = vec![];
+        bin_prot::to_writer_with_layout(&mut output, &value, &rule).unwrap();
+        assert_eq!(decode(&rule, &output), value);
+        assert_eq!(to_json_with_layout(&value, &rule).unwrap(), json);
+    }
+
+    #[test]
+    fn test_json_mismatch() {
+        let rule: BinProtRule = serde_json::from_str(RULE).unwrap();
+        let valid = json!({
+            "int": 0,
+            "name": "",
+            "maybe": null,
+            "pair": [0, 0.0],
+            "sums": [],
+            "polyvar": "Tagged",
+        });
+        // polyvar requires an argument
+        assert!(matches!(
+            from_json_with_layout(&valid, &rule),
+            Err(Error::ValueLayoutMismatch { .. })
+        ));
+
+        let mut json = valid.clone();
+        json["polyvar"] = json!({ "Tagged": "x" });
+        assert!(from_json_with_layout(&json, &rule).is_ok());
+
+        let mut unknown_field = json.clone();
+        unknown_field["extra"] = json!(1);
+        assert!(from_json_with_layout(&unknown_field, &rule).is_err());
+
+        let mut unknown_ctor = json.clone();
+        unknown_ctor["sums"] = json!(["Three"]);
+        assert!(from_json_with_layout(&unknown_ctor, &rule).is_err());
+
+        let mut char_overflow = json;
+        char_overflow["pair"] = json!([256, 0.0]);
+        assert!(from_json_with_layout(&char_overflow, &rule).is_err());
+    }
+
+    #[test]
+    fn test_block_json_roundtrip() {
+        // The block layout has custom rules for the Pickles vectors and the 32 byte bigints
+        let rule = block_rule();
+        let bytes = include_bytes!("fixtures/block");
+        let value = decode(&rule, bytes);
+
+        let json = to_json_with_layout(&value, &rule).unwrap();
+        assert!(count_bigints(&json) > 0);
+        let from_json = from_json_with_layout(&json, &rule).unwrap();
+        assert_eq!(from_json, value);
+
+        let mut output = vec![];
+        bin_prot::to_writer_with_layout(&mut output, &from_json, &rule).unwrap();
+        assert_eq!(output, bytes);
+    }
+
+    // Bigints are written as the hex strings of their 32 bytes
+    fn count_bigints(json: &serde_json::Value) -> usize {
+        match json {
+            serde_json::Value::String(s) => {
+                (s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit())) as usize
+            }
+            serde_json::Value::Array(values) => values.iter().map(count_bigints).sum(),
+            serde_json::Value::Object(map) => map.values().map(count_bigints).sum(),
+            _ => 0,
+        }
+    }
+}
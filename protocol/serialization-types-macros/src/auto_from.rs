@@ -6,363 +6,336 @@
 //!
 
 use crate::*;
-use syn::{punctuated::Punctuated, AttrStyle, Attribute, Field, Fields, Token, Variant};
+use syn::{
+    spanned::Spanned, AttrStyle, Attribute, Data, Fields, GenericParam, Generics, Lit, Member,
+    Meta, NestedMeta, Type,
+};
 
-/// Parse the attributed target type(s)
-pub fn parse_types_from_attr(attributes: &[Attribute]) -> Vec<proc_macro2::TokenStream> {
-    let mut target_types: Vec<proc_macro2::TokenStream> = Vec::new();
-    for attr in attributes {
-        if let AttrStyle::Outer = attr.style {
-            if let Some(attr_id) = attr.path.get_ident() {
-                if &attr_id.to_string() == "auto_from" {
-                    if let Ok(args) = attr.parse_args::<proc_macro2::TokenStream>() {
-                        target_types.push(args.clone());
-                    }
-                }
-            }
-        };
+const ATTR: &str = "auto_from";
+
+/// Options given with `#[auto_from(...)]` on a field or a variant
+#[derive(Default)]
+struct FieldOptions {
+    rename: Option<syn::Ident>,
+    with: Option<syn::Path>,
+    skip: bool,
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        _ => Err(syn::Error::new(lit.span(), "expected a string literal")),
     }
-    target_types
 }
 
-/// Codegen for structs with named fields
-pub fn auto_from_for_struct_with_named_fields(
-    ident: &proc_macro2::Ident,
-    target_types: &[proc_macro2::TokenStream],
-    named: Punctuated<Field, Token![,]>,
-) -> Option<TokenStream> {
-    let mut field_idents = Vec::new();
-    let mut vec_field_idents = Vec::new();
-    let mut option_field_idents = Vec::new();
-    let mut boxed_field_idents = Vec::new();
-    'outer: for f in named {
-        if let Some(ident) = f.ident {
-            if let syn::Type::Path(type_path) = f.ty {
-                for seg in type_path.path.segments {
-                    match seg.ident.to_string().as_str() {
-                        "Vec" => {
-                            vec_field_idents.push(ident);
-                            continue 'outer;
-                        }
-                        "Option" => {
-                            option_field_idents.push(ident);
-                            continue 'outer;
-                        }
-                        "Box" => {
-                            boxed_field_idents.push(ident);
-                            continue 'outer;
-                        }
-                        _ => {}
-                    };
+fn parse_field_options(attributes: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in attributes.iter().filter(|a| a.path.is_ident(ATTR)) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(syn::Error::new(meta.span(), "expected #[auto_from(...)]")),
+        };
+        for meta in list.nested {
+            match &meta {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => options.skip = true,
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    options.rename = Some(syn::parse_str(&lit_str(&nv.lit)?)?)
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") => {
+                    options.with = Some(syn::parse_str(&lit_str(&nv.lit)?)?)
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        meta.span(),
+                        "unknown auto_from option, expected one of \
+                        `rename = \"...\"`, `with = \"...\"`, `skip`",
+                    ))
                 }
             }
-            field_idents.push(ident);
         }
     }
-    if field_idents.is_empty()
-        && vec_field_idents.is_empty()
-        && option_field_idents.is_empty()
-        && boxed_field_idents.is_empty()
-    {
-        return None;
-    }
-    let mut output = TokenStream::default();
+    Ok(options)
+}
 
-    for target_type in target_types {
-        let ts: TokenStream = quote! {
-            impl ::std::convert::From<#ident> for #target_type {
-                fn from(item: #ident) -> Self {
-                    Self {
-                        #(#field_idents: item.#field_idents.into(),) *
-                        #(#vec_field_idents: item.#vec_field_idents.into_iter().map(::std::convert::Into::into).collect(),) *
-                        #(#option_field_idents: item.#option_field_idents.map(::std::convert::Into::into),) *
-                        #(#boxed_field_idents: ::std::boxed::Box::new((*item.#boxed_field_idents).into()),) *
-                    }
-                }
-            }
+/// Parse the attributed target type(s)
+pub fn parse_types_from_attr(attributes: &[Attribute]) -> syn::Result<Vec<Type>> {
+    attributes
+        .iter()
+        .filter(|attr| matches!(attr.style, AttrStyle::Outer) && attr.path.is_ident(ATTR))
+        .map(|attr| attr.parse_args::<Type>())
+        .collect()
+}
 
-            impl ::std::convert::From<#target_type> for #ident {
-                fn from(item: #target_type) -> Self {
-                    Self {
-                        #(#field_idents: item.#field_idents.into(),) *
-                        #(#vec_field_idents: item.#vec_field_idents.into_iter().map(::std::convert::Into::into).collect(),) *
-                        #(#option_field_idents: item.#option_field_idents.map(::std::convert::Into::into),) *
-                        #(#boxed_field_idents: ::std::boxed::Box::new((*item.#boxed_field_idents).into()),) *
-                    }
-                }
+// Path of the target type without generic arguments, to refer to
+// its variants in both expressions and patterns
+fn path_without_arguments(ty: &Type) -> syn::Result<syn::Path> {
+    match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            let mut path = type_path.path.clone();
+            for seg in path.segments.iter_mut() {
+                seg.arguments = syn::PathArguments::None;
             }
+            Ok(path)
         }
-        .into();
-        output.extend(ts);
-        output.extend(impl_from_for_versioned(ident, target_type));
+        _ => Err(syn::Error::new(
+            ty.span(),
+            "expected a path to the target type",
+        )),
     }
-
-    Some(output)
 }
 
-/// Codegen for structs with unnamed fields
-pub fn auto_from_for_struct_with_unnamed_fields(
-    ident: &proc_macro2::Ident,
-    target_types: &[proc_macro2::TokenStream],
-    named: Punctuated<Field, Token![,]>,
-) -> Option<TokenStream> {
-    let mut pos_token_stream: Vec<proc_macro2::TokenStream> = Vec::new();
-    'outer: for f in named {
-        if f.ident.is_none() {
-            let pos = proc_macro2::Literal::usize_unsuffixed(pos_token_stream.len());
+/// A field of the annotated type paired with the field of the target type
+struct ConvField {
+    member: Member,
+    target_member: Member,
+    binding: syn::Ident,
+    ty: Type,
+    options: FieldOptions,
+}
 
-            if let syn::Type::Path(type_path) = f.ty {
-                for seg in type_path.path.segments {
-                    match seg.ident.to_string().as_str() {
-                        "Vec" => {
-                            pos_token_stream.push(
-                                quote! {item.#pos.into_iter().map(::std::convert::Into::into).collect()}
-                            );
-                            continue 'outer;
-                        }
-                        "Option" => {
-                            pos_token_stream
-                                .push(quote! {item.#pos.map(::std::convert::Into::into)});
-                            continue 'outer;
-                        }
-                        "Box" => {
-                            pos_token_stream
-                                .push(quote! {::std::boxed::Box::new((*item.#pos).into())});
-                            continue 'outer;
-                        }
-                        _ => {}
-                    };
-                }
+impl ConvField {
+    // Converts `value` with the given conversion function, or into the other type
+    // by mapping [Vec], [Option] and [Box] elements
+    fn convert(&self, value: proc_macro2::TokenStream, with_fn: &str) -> proc_macro2::TokenStream {
+        if let Some(with) = &self.options.with {
+            let with_fn = syn::Ident::new(with_fn, proc_macro2::Span::call_site());
+            return quote! { #with::#with_fn(#value) };
+        }
+        if let Type::Path(type_path) = &self.ty {
+            for seg in type_path.path.segments.iter() {
+                match seg.ident.to_string().as_str() {
+                    "Vec" => {
+                        return quote! { #value.into_iter().map(::std::convert::Into::into).collect() }
+                    }
+                    "Option" => return quote! { #value.map(::std::convert::Into::into) },
+                    "Box" => return quote! { ::std::boxed::Box::new((*#value).into()) },
+                    _ => {}
+                };
             }
-
-            pos_token_stream.push(quote! {item.#pos.into()});
         }
+        quote! { #value.into() }
     }
-    if pos_token_stream.is_empty() {
-        return None;
-    }
-    let mut output = TokenStream::default();
+}
 
-    for target_type in target_types {
-        let ts: TokenStream = quote! {
-            impl ::std::convert::From<#ident> for #target_type {
-                fn from(item: #ident) -> Self {
-                    Self (
-                        #(#pos_token_stream,) *
-                    )
+fn parse_fields(fields: &Fields) -> syn::Result<Vec<ConvField>> {
+    let mut target_index = 0;
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let options = parse_field_options(&f.attrs)?;
+            let member = match &f.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            let target_member = match (&f.ident, &options.rename) {
+                (Some(_), Some(rename)) => Member::Named(rename.clone()),
+                (Some(ident), None) => Member::Named(ident.clone()),
+                (None, Some(rename)) => {
+                    return Err(syn::Error::new(
+                        rename.span(),
+                        "rename is not supported on unnamed fields",
+                    ))
                 }
+                (None, None) => Member::Unnamed(target_index.into()),
+            };
+            if !options.skip {
+                target_index += 1;
             }
+            Ok(ConvField {
+                member,
+                target_member,
+                binding: quote::format_ident!("v{i}"),
+                ty: f.ty.clone(),
+                options,
+            })
+        })
+        .collect()
+}
 
-            impl ::std::convert::From<#target_type> for #ident {
-                fn from(item: #target_type) -> Self {
-                    Self (
-                        #(#pos_token_stream,) *
-                    )
-                }
-            }
-        }
-        .into();
-        output.extend(ts);
-        output.extend(impl_from_for_versioned(ident, target_type));
-    }
+// Struct expression of the target type from the fields of the annotated type
+fn into_target_fields(
+    fields: &[ConvField],
+    value: impl Fn(&ConvField) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let fields = fields.iter().filter(|f| !f.options.skip).map(|f| {
+        let target_member = &f.target_member;
+        let value = f.convert(value(f), "into");
+        quote! { #target_member: #value }
+    });
+    quote! { { #(#fields,)* } }
+}
 
-    Some(output)
+// Struct expression of the annotated type from the fields of the target type,
+// skipped fields are set to their default values
+fn from_target_fields(
+    fields: &[ConvField],
+    value: impl Fn(&ConvField) -> proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let fields = fields.iter().map(|f| {
+        let member = &f.member;
+        let value = if f.options.skip {
+            quote! { ::std::default::Default::default() }
+        } else {
+            f.convert(value(f), "from")
+        };
+        quote! { #member: #value }
+    });
+    quote! { { #(#fields,)* } }
 }
 
-/// Codegen for enums
-pub fn auto_from_for_enum(
-    ident: &proc_macro2::Ident,
-    target_types: &[proc_macro2::TokenStream],
-    variants: Punctuated<Variant, Token![,]>,
-) -> Option<TokenStream> {
-    let mut variant_token_streams: Vec<proc_macro2::TokenStream> =
-        Vec::with_capacity(variants.len());
-    for v in variants {
-        let ident = &v.ident;
-        match v.fields.len() {
-            0 => variant_token_streams.push(quote! {
-                Other::#ident => Self::#ident
-            }),
-            fields_len => match v.fields {
-                Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                    let mut lhs_idents = Vec::with_capacity(fields_len);
-                    let mut rhs_convert = Vec::with_capacity(fields_len);
-                    'outer_unnamed: for (i, f) in unnamed.into_iter().enumerate() {
-                        let ident = proc_macro2::Ident::new(
-                            &format!("v{i}"),
-                            proc_macro2::Span::call_site(),
-                        );
-                        lhs_idents.push(ident.clone());
-                        if let syn::Type::Path(type_path) = f.ty {
-                            for seg in type_path.path.segments {
-                                match seg.ident.to_string().as_str() {
-                                    "Vec" => {
-                                        rhs_convert.push(quote! {#ident.into_iter().map(::std::convert::Into::into).collect()});
-                                        continue 'outer_unnamed;
-                                    }
-                                    "Option" => {
-                                        rhs_convert
-                                            .push(quote! {#ident.map(::std::convert::Into::into)});
-                                        continue 'outer_unnamed;
-                                    }
-                                    "Box" => {
-                                        rhs_convert.push(
-                                            quote! {::std::boxed::Box::new((*#ident).into())},
-                                        );
-                                        continue 'outer_unnamed;
-                                    }
-                                    _ => {}
-                                };
-                            }
-                        }
+// Struct pattern that binds the non-skipped fields
+fn bind_fields(fields: &[ConvField], target: bool) -> proc_macro2::TokenStream {
+    let bindings = fields.iter().filter(|f| !f.options.skip).map(|f| {
+        let member = if target { &f.target_member } else { &f.member };
+        let binding = &f.binding;
+        quote! { #member: #binding }
+    });
+    quote! { { #(#bindings,)* .. } }
+}
 
-                        rhs_convert.push(quote! {#ident.into()});
-                    }
-                    variant_token_streams.push(quote! {
-                        Other::#ident(#(#lhs_idents,) *) => Self::#ident(#(#rhs_convert,) *)
-                    });
-                }
-                Fields::Named(FieldsNamed { named, .. }) => {
-                    let mut lhs_idents = Vec::with_capacity(fields_len);
-                    let mut rhs_convert = Vec::with_capacity(fields_len);
-                    'outer_named: for f in named {
-                        if let Some(ident) = f.ident {
-                            lhs_idents.push(ident.clone());
-                            if let syn::Type::Path(type_path) = f.ty {
-                                for seg in type_path.path.segments {
-                                    match seg.ident.to_string().as_str() {
-                                        "Vec" => {
-                                            rhs_convert.push(quote! {#ident: #ident.into_iter().map(::std::convert::Into::into).collect()});
-                                            continue 'outer_named;
-                                        }
-                                        "Option" => {
-                                            rhs_convert.push(
-                                                    quote! {#ident: #ident.map(::std::convert::Into::into)},
-                                                );
-                                            continue 'outer_named;
-                                        }
-                                        "Box" => {
-                                            rhs_convert.push(
-                                                quote! {#ident: ::std::boxed::Box::new((*#ident).into())},
-                                            );
-                                            continue 'outer_named;
-                                        }
-                                        _ => {}
-                                    };
-                                }
-                            }
-                            rhs_convert.push(quote! {#ident: #ident.into()});
-                        }
-                    }
-                    variant_token_streams.push(quote! {
-                        Other::#ident{#(#lhs_idents,) *} => Self::#ident{#(#rhs_convert,) *}
-                    });
-                }
-                _ => {}
-            },
+/// Codegen for structs and enums
+pub fn impl_auto_from(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let target_types = parse_types_from_attr(&input.attrs)?;
+    let ident = &input.ident;
+    let (into_target, from_target) = match &input.data {
+        Data::Struct(s) => {
+            let fields = parse_fields(&s.fields)?;
+            let into_target = into_target_fields(&fields, |f| {
+                let member = &f.member;
+                quote! { item.#member }
+            });
+            let from_target = from_target_fields(&fields, |f| {
+                let target_member = &f.target_member;
+                quote! { item.#target_member }
+            });
+            (quote! { Self #into_target }, quote! { Self #from_target })
         }
-    }
-
-    if variant_token_streams.is_empty() {
-        return None;
-    }
-    let mut output = TokenStream::default();
-    for target_type in target_types {
-        let ts: TokenStream = quote! {
-            impl ::std::convert::From<#ident> for #target_type {
-                fn from(item: #ident) -> Self {
-                    use #ident as Other;
-                    match item {
-                        #(#variant_token_streams,) *
-                    }
+        Data::Enum(e) => {
+            let mut variants = Vec::with_capacity(e.variants.len());
+            for v in e.variants.iter() {
+                let options = parse_field_options(&v.attrs)?;
+                if options.skip || options.with.is_some() {
+                    return Err(syn::Error::new(
+                        v.span(),
+                        "only rename is supported on variants",
+                    ));
                 }
+                let target_ident = options.rename.unwrap_or_else(|| v.ident.clone());
+                variants.push((v.ident.clone(), target_ident, parse_fields(&v.fields)?));
             }
-
-            impl ::std::convert::From<#target_type> for #ident {
-                fn from(item: #target_type) -> Self {
-                    use #target_type as Other;
-                    match item {
-                        #(#variant_token_streams,) *
-                    }
-                }
+            let binding = |f: &ConvField| {
+                let binding = &f.binding;
+                quote! { #binding }
+            };
+            let into_target_arms = variants.iter().map(|(v, target_v, fields)| {
+                let pattern = bind_fields(fields, false);
+                let value = into_target_fields(fields, binding);
+                quote! { #ident::#v #pattern => Self::#target_v #value }
+            });
+            let from_target_arms = |target: &syn::Path| {
+                let arms = variants.iter().map(|(v, target_v, fields)| {
+                    let pattern = bind_fields(fields, true);
+                    let value = from_target_fields(fields, binding);
+                    quote! { #target::#target_v #pattern => Self::#v #value }
+                });
+                quote! { #(#arms,)* }
+            };
+            let into_target = quote! { match item { #(#into_target_arms,)* } };
+            let from_target = |target: &syn::Path| {
+                let arms = from_target_arms(target);
+                quote! { match item { #arms } }
+            };
+            let mut output = proc_macro2::TokenStream::new();
+            for target_type in &target_types {
+                let from_target = from_target(&path_without_arguments(target_type)?);
+                output.extend(impl_from(input, target_type, &into_target, &from_target));
             }
+            return Ok(output);
         }
-        .into();
-        output.extend(ts);
-        output.extend(impl_from_for_versioned(ident, target_type));
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span(),
+                "AutoFrom cannot be derived for unions",
+            ))
+        }
+    };
+    let mut output = proc_macro2::TokenStream::new();
+    for target_type in &target_types {
+        output.extend(impl_from(input, target_type, &into_target, &from_target));
     }
-
-    Some(output)
+    Ok(output)
 }
 
-/// Codegen for versioned target type(s)
-fn impl_from_for_versioned(
-    ident: &proc_macro2::Ident,
-    target_type: &proc_macro2::TokenStream,
-) -> TokenStream {
-    quote! {
-        impl<const V: u16> ::std::convert::From<#ident> for ::versioned::Versioned<#target_type, V> {
-            #[inline]
-            fn from(t: #ident) -> Self {
-                let t: #target_type = t.into();
-                t.into()
-            }
-        }
-
-        impl<const V: u16> ::std::convert::From<::versioned::Versioned<#target_type, V>> for #ident {
-            #[inline]
-            fn from(t: ::versioned::Versioned<#target_type, V>) -> Self {
-                let (t,): (#target_type,) = t.into();
-                t.into()
+// `From` impls in both directions between the annotated type and the target type
+fn impl_from(
+    input: &DeriveInput,
+    target_type: &Type,
+    into_target: &proc_macro2::TokenStream,
+    from_target: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut output = quote! {
+        impl #impl_generics ::std::convert::From<#ident #ty_generics> for #target_type #where_clause {
+            fn from(item: #ident #ty_generics) -> Self {
+                #into_target
             }
         }
 
-        impl<const V1: u16, const V2: u16> ::std::convert::From<#ident> for ::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2> {
-            #[inline]
-            fn from(t: #ident) -> Self {
-                let t: #target_type = t.into();
-                t.into()
-            }
-        }
-
-        impl<const V1: u16, const V2: u16> ::std::convert::From<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>> for #ident {
-            #[inline]
-            fn from(t: ::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>) -> Self {
-                let (t,): (#target_type,) = t.into();
-                t.into()
-            }
-        }
-
-        impl<const V1: u16, const V2: u16, const V3: u16> ::std::convert::From<#ident> for ::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>, V3> {
-            #[inline]
-            fn from(t: #ident) -> Self {
-                let t: #target_type = t.into();
-                t.into()
+        impl #impl_generics ::std::convert::From<#target_type> for #ident #ty_generics #where_clause {
+            fn from(item: #target_type) -> Self {
+                #from_target
             }
         }
+    };
+    output.extend(impl_from_for_versioned(input, target_type));
+    output
+}
 
-        impl<const V1: u16, const V2: u16, const V3: u16> ::std::convert::From<::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>, V3>> for #ident {
-            #[inline]
-            fn from(t: ::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>, V3>) -> Self {
-                let (t,): (#target_type,) = t.into();
-                t.into()
-            }
-        }
+// Generics of the annotated type with the given const version parameters appended
+fn generics_with_versions(generics: &Generics, versions: &[&str]) -> Generics {
+    let mut generics = generics.clone();
+    for v in versions {
+        let v = syn::Ident::new(v, proc_macro2::Span::call_site());
+        generics
+            .params
+            .push(GenericParam::Const(syn::parse_quote! { const #v: u16 }));
+    }
+    generics
+}
 
-        impl<const V1: u16, const V2: u16, const V3: u16, const V4: u16> ::std::convert::From<#ident> for ::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>, V3>, V4> {
-            #[inline]
-            fn from(t: #ident) -> Self {
-                let t: #target_type = t.into();
-                t.into()
+/// Codegen for versioned target type(s)
+fn impl_from_for_versioned(input: &DeriveInput, target_type: &Type) -> proc_macro2::TokenStream {
+    let ident = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let ident = quote! { #ident #ty_generics };
+    let mut output = proc_macro2::TokenStream::new();
+    let mut versioned = quote! { #target_type };
+    let mut versions = Vec::new();
+    for v in ["V1", "V2", "V3", "V4"] {
+        let v_ident = syn::Ident::new(v, proc_macro2::Span::call_site());
+        versioned = quote! { ::versioned::Versioned<#versioned, #v_ident> };
+        versions.push(v);
+        let generics = generics_with_versions(&input.generics, &versions);
+        let (impl_generics, _, _) = generics.split_for_impl();
+        output.extend(quote! {
+            impl #impl_generics ::std::convert::From<#ident> for #versioned #where_clause {
+                #[inline]
+                fn from(t: #ident) -> Self {
+                    let t: #target_type = t.into();
+                    t.into()
+                }
             }
-        }
 
-        impl<const V1: u16, const V2: u16, const V3: u16, const V4: u16> ::std::convert::From<::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>, V3>, V4>> for #ident {
-            #[inline]
-            fn from(t: ::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<::versioned::Versioned<#target_type, V1>, V2>, V3>, V4>) -> Self {
-                let (t,): (#target_type,) = t.into();
-                t.into()
+            impl #impl_generics ::std::convert::From<#versioned> for #ident #where_clause {
+                #[inline]
+                fn from(t: #versioned) -> Self {
+                    let (t,): (#target_type,) = t.into();
+                    t.into()
+                }
             }
-        }
-    }.into()
+        });
+    }
+    output
 }
@@ -0,0 +1,240 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Utilities for implementing FromGraphQLJson and ToGraphQLJson
+//!
+
+use crate::*;
+use syn::{spanned::Spanned, Attribute, Data, Fields, Lit, Meta, NestedMeta};
+
+const ATTR: &str = "graphql";
+
+/// Options given with `#[graphql(...)]` on the struct
+pub struct StructOptions {
+    krate: syn::Path,
+}
+
+impl Default for StructOptions {
+    fn default() -> Self {
+        Self {
+            krate: syn::parse_quote!(::mina_rs_base),
+        }
+    }
+}
+
+/// Options given with `#[graphql(...)]` on a field
+#[derive(Default)]
+struct FieldOptions {
+    name: Option<String>,
+    flatten: bool,
+    skip: bool,
+    default: Option<syn::Path>,
+    with: Option<syn::Path>,
+}
+
+/// Convert a snake_case field name into the camelCase name used by the GraphQL API
+pub fn camel_case(ident: &str) -> String {
+    let mut output = String::with_capacity(ident.len());
+    let mut upper = false;
+    for c in ident.trim_start_matches("r#").chars() {
+        if c == '_' {
+            upper = !output.is_empty();
+        } else if upper {
+            output.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        _ => Err(syn::Error::new(lit.span(), "expected a string literal")),
+    }
+}
+
+fn nested_metas(attributes: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut metas = Vec::new();
+    for attr in attributes.iter().filter(|a| a.path.is_ident(ATTR)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => return Err(syn::Error::new(meta.span(), "expected #[graphql(...)]")),
+        }
+    }
+    Ok(metas)
+}
+
+/// Parse the options on the struct
+pub fn parse_struct_options(attributes: &[Attribute]) -> syn::Result<StructOptions> {
+    let mut options = StructOptions::default();
+    for meta in nested_metas(attributes)? {
+        match &meta {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("crate") => {
+                options.krate = syn::parse_str(&lit_str(&nv.lit)?)?
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "unknown graphql option, expected `crate = \"...\"`",
+                ))
+            }
+        }
+    }
+    Ok(options)
+}
+
+fn parse_field_options(attributes: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for meta in nested_metas(attributes)? {
+        match &meta {
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("flatten") => options.flatten = true,
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => options.skip = true,
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                options.name = Some(lit_str(&nv.lit)?)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("default") => {
+                options.default = Some(syn::parse_str(&lit_str(&nv.lit)?)?)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("with") => {
+                options.with = Some(syn::parse_str(&lit_str(&nv.lit)?)?)
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    "unknown graphql option, expected one of \
+                        `name = \"...\"`, `flatten`, `skip`, `default = \"...\"`, `with = \"...\"`",
+                ))
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// A field of the struct with its `#[graphql(...)]` options
+pub struct GraphQLField {
+    ident: syn::Ident,
+    options: FieldOptions,
+}
+
+impl GraphQLField {
+    // Json key(s) of the field, nested keys are separated by `.`
+    fn name(&self) -> String {
+        self.options
+            .name
+            .clone()
+            .unwrap_or_else(|| camel_case(&self.ident.to_string()))
+    }
+
+    // Whether the field is read from / written to json at all
+    fn is_skipped(&self) -> bool {
+        self.options.skip || self.options.default.is_some()
+    }
+}
+
+/// Parse the named fields of a struct and their options
+pub fn parse_fields(input: &DeriveInput, derive: &str) -> syn::Result<Vec<GraphQLField>> {
+    let message = format!("{derive} can only be derived for structs with named fields");
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => &named.named,
+            fields => return Err(syn::Error::new(fields.span(), message)),
+        },
+        _ => return Err(syn::Error::new(input.ident.span(), message)),
+    };
+    fields
+        .iter()
+        .map(|f| {
+            Ok(GraphQLField {
+                ident: f.ident.clone().expect("named field"),
+                options: parse_field_options(&f.attrs)?,
+            })
+        })
+        .collect()
+}
+
+/// Codegen for FromGraphQLJson
+pub fn impl_from_graphql_json(
+    input: &DeriveInput,
+    options: &StructOptions,
+    fields: &[GraphQLField],
+) -> proc_macro2::TokenStream {
+    let krate = &options.krate;
+    let parse = |with: &Option<syn::Path>| match with {
+        Some(with) => quote! { #with::from_graphql_json },
+        None => quote! { #krate::from_graphql_json::FromGraphQLJson::from_graphql_json },
+    };
+    let field_values = fields.iter().map(|f| {
+        let ident = &f.ident;
+        let value = if let Some(default) = &f.options.default {
+            quote! { #default() }
+        } else if f.options.skip {
+            quote! { ::std::default::Default::default() }
+        } else if f.options.flatten {
+            let parse = parse(&f.options.with);
+            quote! { #parse(json)? }
+        } else {
+            let parse = parse(&f.options.with);
+            let name = f.name();
+            let keys = name.split('.');
+            quote! {
+                #krate::from_graphql_json::with_json_path(
+                    #parse(&json #([#keys])*),
+                    #name,
+                )?
+            }
+        };
+        quote! { #ident: #value }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #krate::from_graphql_json::FromGraphQLJson for #ident #ty_generics #where_clause {
+            fn from_graphql_json(json: &::serde_json::Value) -> ::anyhow::Result<Self> {
+                ::std::result::Result::Ok(Self {
+                    #(#field_values,)*
+                })
+            }
+        }
+    }
+}
+
+/// Codegen for ToGraphQLJson
+pub fn impl_to_graphql_json(
+    input: &DeriveInput,
+    options: &StructOptions,
+    fields: &[GraphQLField],
+) -> proc_macro2::TokenStream {
+    let krate = &options.krate;
+    let inserts = fields.iter().filter(|f| !f.is_skipped()).map(|f| {
+        let ident = &f.ident;
+        let value = match &f.options.with {
+            Some(with) => quote! { #with::to_graphql_json(&self.#ident) },
+            None => quote! {
+                #krate::to_graphql_json::ToGraphQLJson::to_graphql_json(&self.#ident)
+            },
+        };
+        if f.options.flatten {
+            quote! { #krate::to_graphql_json::merge_json_object(&mut json, #value); }
+        } else {
+            let name = f.name();
+            quote! { #krate::to_graphql_json::insert_json_path(&mut json, #name, #value); }
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #krate::to_graphql_json::ToGraphQLJson for #ident #ty_generics #where_clause {
+            fn to_graphql_json(&self) -> ::serde_json::Value {
+                let mut json = ::serde_json::Map::new();
+                #(#inserts)*
+                ::serde_json::Value::Object(json)
+            }
+        }
+    }
+}
@@ -65,59 +65,119 @@
 #![deny(missing_docs)]
 
 mod auto_from;
+mod graphql;
+mod roinput;
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, DataEnum, DeriveInput, FieldsNamed, FieldsUnnamed};
+use syn::{parse_macro_input, DeriveInput};
 
 /// A derive macro that automatically implements [From] trait between the annotated type
 /// and types including the attributed target type(s) and their versioned types,
 /// when the target type has identical field names with the annotated one, and each pair of the fields
 /// are convertible between each other
+///
+/// Generic parameters of the annotated type can be used in the target type(s),
+/// e.g. `#[auto_from(Target<T>)]`
+///
+/// Supported `#[auto_from(...)]` attributes on a field
+/// - `rename = "..."` names the field of the target type
+/// - `with = "module"` converts the field with `module::into`, a `fn(T) -> U` into the field
+///   of the target type, and back with `module::from`, a `fn(U) -> T`
+/// - `skip` leaves the field out of the target type, and sets it to its [Default] value
+///   when converting from the target type
+///
+/// `rename` is also supported on enum variants
 #[proc_macro_derive(AutoFrom, attributes(auto_from))]
 pub fn auto_from_macro(input: TokenStream) -> TokenStream {
-    let DeriveInput {
-        attrs, ident, data, ..
-    } = parse_macro_input!(input);
+    let input: DeriveInput = parse_macro_input!(input);
+    auto_from::impl_auto_from(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    let target_types: Vec<proc_macro2::TokenStream> =
-        auto_from::parse_types_from_attr(attrs.as_slice());
+/// A derive macro that implements `proof_systems::ToChunkedROInput` by appending
+/// the fields of a struct in declaration order.
+///
+/// Supported `#[roinput(...)]` attributes
+/// - on the struct: `reverse` appends the fields in reverse declaration order
+/// - on a field: `skip` leaves the field out, `wrapper = "Path"` appends `Path(&self.field)`
+///   instead of the field itself, `bits = N` truncates the packed elements of the field to N bits
+#[proc_macro_derive(ToChunkedROInput, attributes(roinput))]
+pub fn to_chunked_roinput_macro(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    roinput::parse_struct_options(&input.attrs)
+        .and_then(|options| roinput::parse_fields(&input.data, &options))
+        .map(|fields| roinput::impl_to_chunked_roinput(&input, &fields))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    if target_types.is_empty() {
-        return Default::default();
-    }
+/// A derive macro that implements `mina_hasher::Hashable` by appending
+/// the fields of a struct in declaration order.
+///
+/// Supported `#[roinput(...)]` attributes
+/// - on the struct: `domain = "..."` sets the domain string, `chunked` hashes the
+///   `ToChunkedROInput` of the struct instead of appending each field as a hashable,
+///   `reverse` appends the fields in reverse declaration order
+/// - on a field: `skip` and `wrapper = "Path"`, see [ToChunkedROInput]
+#[proc_macro_derive(Hashable, attributes(roinput))]
+pub fn hashable_macro(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    roinput::parse_struct_options(&input.attrs)
+        .and_then(|options| {
+            let fields = roinput::parse_fields(&input.data, &options)?;
+            roinput::impl_hashable(&input, &options, &fields)
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    match data {
-        syn::Data::Struct(s) => match s.fields {
-            syn::Fields::Named(FieldsNamed { named, .. }) => {
-                if let Some(ts) = auto_from::auto_from_for_struct_with_named_fields(
-                    &ident,
-                    target_types.as_slice(),
-                    named,
-                ) {
-                    return ts;
-                }
-            }
-            syn::Fields::Unnamed(FieldsUnnamed { unnamed, .. }) => {
-                if let Some(ts) = auto_from::auto_from_for_struct_with_unnamed_fields(
-                    &ident,
-                    target_types.as_slice(),
-                    unnamed,
-                ) {
-                    return ts;
-                }
-            }
-            _ => unimplemented!(),
-        },
-        syn::Data::Enum(DataEnum { variants, .. }) => {
-            if let Some(ts) =
-                auto_from::auto_from_for_enum(&ident, target_types.as_slice(), variants)
-            {
-                return ts;
-            }
-        }
-        _ => unimplemented!(),
-    };
+/// A derive macro that implements `mina_rs_base::from_graphql_json::FromGraphQLJson`
+/// for a struct with named fields, by reading each field from the GraphQL json key
+/// with the camelCase name of the field.
+///
+/// Every field is required: a field that is missing from the json or has the wrong type is an
+/// error, only `Option` fields accept a missing key or `null`. For example a block without
+/// `hasAncestorInSameCheckpointWindow` fails to parse, it is not read as `false`.
+/// Errors report the json path of the field that fails to deserialize.
+///
+/// Supported `#[graphql(...)]` attributes on the struct
+/// - `crate = "path"` is the path of the crate that defines the traits, `::mina_rs_base` by default,
+///   `"crate"` within mina-rs-base itself
+///
+/// Supported `#[graphql(...)]` attributes on a field
+/// - `name = "..."` overrides the json key, nested keys are separated by `.`, e.g. `"balance.total"`
+/// - `flatten` reads the field from the json object of the struct itself
+/// - `with = "module"` parses the field with `module::from_graphql_json`, a
+///   `fn(&serde_json::Value) -> anyhow::Result<T>`
+/// - `skip` sets the field to its [Default] value, `default = "path"` calls `fn() -> T` instead
+#[proc_macro_derive(FromGraphQLJson, attributes(graphql))]
+pub fn from_graphql_json_macro(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    graphql::parse_struct_options(&input.attrs)
+        .and_then(|options| {
+            let fields = graphql::parse_fields(&input, "FromGraphQLJson")?;
+            Ok(graphql::impl_from_graphql_json(&input, &options, &fields))
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    Default::default()
+/// A derive macro that implements `mina_rs_base::to_graphql_json::ToGraphQLJson`,
+/// the reverse of [FromGraphQLJson], taking the same `#[graphql(...)]` attributes.
+///
+/// A field with `with = "module"` is written with `module::to_graphql_json`,
+/// a `fn(&T) -> serde_json::Value`, fields with `skip` or `default` are left out,
+/// and a `flatten` field must be written as a json object.
+#[proc_macro_derive(ToGraphQLJson, attributes(graphql))]
+pub fn to_graphql_json_macro(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    graphql::parse_struct_options(&input.attrs)
+        .and_then(|options| {
+            let fields = graphql::parse_fields(&input, "ToGraphQLJson")?;
+            Ok(graphql::impl_to_graphql_json(&input, &options, &fields))
+        })
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
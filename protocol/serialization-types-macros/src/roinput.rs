@@ -0,0 +1,213 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Utilities for implementing Hashable and ToChunkedROInput
+//!
+
+use crate::*;
+use syn::{spanned::Spanned, Attribute, Data, Fields, Lit, Meta, NestedMeta};
+
+const ATTR: &str = "roinput";
+
+/// Options given with `#[roinput(...)]` on the struct
+#[derive(Default)]
+pub struct StructOptions {
+    /// Domain string of the hash
+    pub domain: Option<String>,
+    /// Hash the chunked random oracle input instead of appending fields as hashables
+    pub chunked: bool,
+    /// Append the fields in reverse declaration order
+    pub reverse: bool,
+}
+
+/// Options given with `#[roinput(...)]` on a field
+#[derive(Default)]
+struct FieldOptions {
+    skip: bool,
+    wrapper: Option<syn::Path>,
+    bits: Option<u32>,
+}
+
+/// A field to append to the random oracle input
+pub struct InputField {
+    member: syn::Member,
+    wrapper: Option<syn::Path>,
+    bits: Option<u32>,
+    span: proc_macro2::Span,
+}
+
+impl InputField {
+    // Expression of the value to append
+    fn value(&self) -> proc_macro2::TokenStream {
+        let member = &self.member;
+        match &self.wrapper {
+            Some(wrapper) => quote! { &#wrapper(&self.#member) },
+            None => quote! { &self.#member },
+        }
+    }
+}
+
+fn nested_metas(attributes: &[Attribute]) -> syn::Result<Vec<NestedMeta>> {
+    let mut metas = Vec::new();
+    for attr in attributes.iter().filter(|a| a.path.is_ident(ATTR)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => metas.extend(list.nested),
+            meta => return Err(syn::Error::new(meta.span(), "expected #[roinput(...)]")),
+        }
+    }
+    Ok(metas)
+}
+
+fn lit_str(lit: &Lit) -> syn::Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        _ => Err(syn::Error::new(lit.span(), "expected a string literal")),
+    }
+}
+
+/// Parse the options on the struct
+pub fn parse_struct_options(attributes: &[Attribute]) -> syn::Result<StructOptions> {
+    let mut options = StructOptions::default();
+    for meta in nested_metas(attributes)? {
+        match &meta {
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("chunked") => options.chunked = true,
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("reverse") => options.reverse = true,
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("domain") => {
+                options.domain = Some(lit_str(&nv.lit)?)
+            }
+            _ => return Err(syn::Error::new(
+                meta.span(),
+                "unknown roinput option, expected one of `chunked`, `reverse`, `domain = \"...\"`",
+            )),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_field_options(attributes: &[Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for meta in nested_metas(attributes)? {
+        match &meta {
+            NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => options.skip = true,
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("wrapper") => {
+                options.wrapper = Some(syn::parse_str(&lit_str(&nv.lit)?)?)
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("bits") => match &nv.lit {
+                Lit::Int(i) => options.bits = Some(i.base10_parse()?),
+                lit => return Err(syn::Error::new(lit.span(), "expected an integer")),
+            },
+            _ => return Err(syn::Error::new(
+                meta.span(),
+                "unknown roinput option, expected one of `skip`, `wrapper = \"...\"`, `bits = N`",
+            )),
+        }
+    }
+    Ok(options)
+}
+
+/// Collect the fields to append in order, leaving out skipped fields
+pub fn parse_fields(data: &Data, options: &StructOptions) -> syn::Result<Vec<InputField>> {
+    let fields = match data {
+        Data::Struct(s) => &s.fields,
+        Data::Enum(e) => {
+            return Err(syn::Error::new(
+                e.enum_token.span,
+                "roinput can only be derived for structs",
+            ))
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span,
+                "roinput can only be derived for structs",
+            ))
+        }
+    };
+    let mut input_fields = Vec::new();
+    for (i, f) in fields.iter().enumerate() {
+        let field_options = parse_field_options(&f.attrs)?;
+        if field_options.skip {
+            continue;
+        }
+        let member = match (&f.ident, fields) {
+            (Some(ident), Fields::Named(_)) => syn::Member::Named(ident.clone()),
+            _ => syn::Member::Unnamed(i.into()),
+        };
+        input_fields.push(InputField {
+            member,
+            wrapper: field_options.wrapper,
+            bits: field_options.bits,
+            span: f.span(),
+        });
+    }
+    if options.reverse {
+        input_fields.reverse();
+    }
+    Ok(input_fields)
+}
+
+/// Codegen for ToChunkedROInput
+pub fn impl_to_chunked_roinput(
+    input: &DeriveInput,
+    fields: &[InputField],
+) -> proc_macro2::TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let appends = fields.iter().map(|f| {
+        let value = f.value();
+        match f.bits {
+            Some(bits) => quote! { .append_chunked_packed(#value, #bits) },
+            None => quote! { .append_chunked(#value) },
+        }
+    });
+    quote! {
+        impl #impl_generics ::proof_systems::ToChunkedROInput for #ident #ty_generics #where_clause {
+            fn to_chunked_roinput(&self) -> ::proof_systems::ChunkedROInput {
+                ::proof_systems::ChunkedROInput::new()
+                    #(#appends)*
+            }
+        }
+    }
+}
+
+/// Codegen for Hashable
+pub fn impl_hashable(
+    input: &DeriveInput,
+    options: &StructOptions,
+    fields: &[InputField],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = if options.chunked {
+        quote! { ::proof_systems::ToChunkedROInput::roinput(self) }
+    } else {
+        if let Some(f) = fields.iter().find(|f| f.bits.is_some()) {
+            return Err(syn::Error::new(
+                f.span,
+                "`bits` is only supported with #[roinput(chunked)]",
+            ));
+        }
+        let values = fields.iter().map(InputField::value);
+        quote! {
+            ::proof_systems::mina_hasher::ROInput::new()
+                #(.append_hashable(#values))*
+        }
+    };
+    let domain = match &options.domain {
+        Some(domain) => quote! { Some(#domain.into()) },
+        None => quote! { None },
+    };
+    Ok(quote! {
+        impl #impl_generics ::proof_systems::mina_hasher::Hashable for #ident #ty_generics #where_clause {
+            type D = ();
+
+            fn to_roinput(&self) -> ::proof_systems::mina_hasher::ROInput {
+                #body
+            }
+
+            fn domain_string(_: Self::D) -> Option<String> {
+                #domain
+            }
+        }
+    })
+}
@@ -0,0 +1,169 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use mina_serialization_types_macros::*;
+
+    #[derive(Debug, Clone, Eq, PartialEq, derive_more::From, derive_more::Into)]
+    struct I64(pub i64);
+
+    mod negate {
+        pub fn into(v: i64) -> super::I64 {
+            super::I64(-v)
+        }
+
+        pub fn from(v: super::I64) -> i64 {
+            -v.0
+        }
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq, AutoFrom)]
+    #[auto_from(Bar)]
+    struct Foo {
+        f1: i64,
+        #[auto_from(rename = "renamed")]
+        f2: i64,
+        #[auto_from(with = "negate")]
+        f3: i64,
+        #[auto_from(skip)]
+        f4: Vec<i64>,
+        f5: Option<i64>,
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    struct Bar {
+        f1: I64,
+        renamed: I64,
+        f3: I64,
+        f5: Option<I64>,
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq, AutoFrom)]
+    #[auto_from(BarTuple)]
+    struct FooTuple(
+        i64,
+        #[auto_from(skip)] bool,
+        #[auto_from(with = "negate")] i64,
+    );
+
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    struct BarTuple(I64, I64);
+
+    #[derive(Debug, Clone, Eq, PartialEq, AutoFrom)]
+    #[auto_from(BarGeneric<T>)]
+    struct FooGeneric<T: Clone> {
+        data: T,
+        list: Vec<i64>,
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    struct BarGeneric<T> {
+        data: T,
+        list: Vec<I64>,
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq, AutoFrom)]
+    #[auto_from(BarEnum<T>)]
+    enum FooEnum<T> {
+        V1,
+        #[auto_from(rename = "Renamed")]
+        V2(T),
+        V3 {
+            #[auto_from(rename = "renamed")]
+            f1: i64,
+            #[auto_from(skip)]
+            f2: Option<i64>,
+            #[auto_from(with = "negate")]
+            f3: i64,
+        },
+    }
+
+    #[derive(Debug, Clone, Eq, PartialEq)]
+    enum BarEnum<T> {
+        V1,
+        Renamed(T),
+        V3 { renamed: I64, f3: I64 },
+    }
+
+    #[test]
+    fn struct_with_attributes_roundtrip() {
+        let foo = Foo {
+            f1: 1,
+            f2: 2,
+            f3: 3,
+            f4: vec![4],
+            f5: Some(5),
+        };
+        let bar: Bar = foo.clone().into();
+        assert_eq!(
+            bar,
+            Bar {
+                f1: I64(1),
+                renamed: I64(2),
+                f3: I64(-3),
+                f5: Some(I64(5)),
+            }
+        );
+        let foo_from_bar: Foo = bar.into();
+        assert_eq!(foo_from_bar, Foo { f4: vec![], ..foo });
+
+        let foo_from_versioned: Foo = ::versioned::Versioned::<Bar, 1>::from(foo.clone()).into();
+        assert_eq!(foo_from_versioned, Foo { f4: vec![], ..foo });
+    }
+
+    #[test]
+    fn tuple_struct_with_attributes_roundtrip() {
+        let foo = FooTuple(1, true, 2);
+        let bar: BarTuple = foo.into();
+        assert_eq!(bar, BarTuple(I64(1), I64(-2)));
+        let foo_from_bar: FooTuple = bar.into();
+        assert_eq!(foo_from_bar, FooTuple(1, false, 2));
+    }
+
+    #[test]
+    fn generic_struct_roundtrip() {
+        let foo = FooGeneric {
+            data: "data".to_string(),
+            list: vec![1, 2],
+        };
+        let bar: BarGeneric<String> = foo.clone().into();
+        assert_eq!(
+            bar,
+            BarGeneric {
+                data: "data".to_string(),
+                list: vec![I64(1), I64(2)],
+            }
+        );
+        let foo_from_bar: FooGeneric<String> = bar.into();
+        assert_eq!(foo_from_bar, foo);
+
+        let bar_v1: ::versioned::Versioned<BarGeneric<String>, 1> = foo.clone().into();
+        let foo_from_bar_v1: FooGeneric<String> = bar_v1.into();
+        assert_eq!(foo_from_bar_v1, foo);
+    }
+
+    #[test]
+    fn generic_enum_with_attributes_roundtrip() {
+        for (foo, expected) in [
+            (FooEnum::V1, BarEnum::V1),
+            (FooEnum::V2(7_u8), BarEnum::Renamed(7_u8)),
+            (
+                FooEnum::V3 {
+                    f1: 1,
+                    f2: None,
+                    f3: 3,
+                },
+                BarEnum::V3 {
+                    renamed: I64(1),
+                    f3: I64(-3),
+                },
+            ),
+        ] {
+            let bar: BarEnum<u8> = foo.clone().into();
+            assert_eq!(bar, expected);
+            let foo_from_bar: FooEnum<u8> = bar.into();
+            assert_eq!(foo_from_bar, foo);
+        }
+    }
+}
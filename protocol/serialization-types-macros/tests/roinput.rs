@@ -0,0 +1,161 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use proof_systems::{
+        mina_hasher::{create_kimchi, create_legacy, Hashable, Hasher, ROInput},
+        ChunkedROInput, ToChunkedROInput,
+    };
+
+    #[derive(Debug, Clone, Copy)]
+    struct U32(u32);
+
+    impl Hashable for U32 {
+        type D = ();
+
+        fn to_roinput(&self) -> ROInput {
+            ROInput::new().append_u32(self.0)
+        }
+
+        fn domain_string(_: Self::D) -> Option<String> {
+            None
+        }
+    }
+
+    impl ToChunkedROInput for U32 {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new().append_u32(self.0)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    struct U64(u64);
+
+    impl ToChunkedROInput for U64 {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new().append_u64(self.0)
+        }
+    }
+
+    struct BoolWrapper<'a>(&'a bool);
+
+    impl ToChunkedROInput for BoolWrapper<'_> {
+        fn to_chunked_roinput(&self) -> ChunkedROInput {
+            ChunkedROInput::new().append_bool(*self.0)
+        }
+    }
+
+    #[derive(Debug, Clone, mina_serialization_types_macros::Hashable)]
+    #[roinput(domain = "Legacy")]
+    struct Legacy {
+        a: U32,
+        #[roinput(skip)]
+        _skipped: bool,
+        b: U32,
+    }
+
+    #[derive(
+        Debug,
+        Clone,
+        mina_serialization_types_macros::Hashable,
+        mina_serialization_types_macros::ToChunkedROInput,
+    )]
+    #[roinput(chunked, reverse, domain = "Chunked")]
+    struct Chunked {
+        a: U32,
+        #[roinput(bits = 32)]
+        b: U64,
+        #[roinput(wrapper = "BoolWrapper")]
+        c: bool,
+        #[roinput(skip)]
+        _skipped: String,
+    }
+
+    #[derive(
+        Debug,
+        Clone,
+        mina_serialization_types_macros::Hashable,
+        mina_serialization_types_macros::ToChunkedROInput,
+    )]
+    #[roinput(chunked)]
+    struct Generic<T: ToChunkedROInput>(T, U32);
+
+    #[test]
+    fn derive_legacy_hashable() {
+        let v = Legacy {
+            a: U32(1),
+            _skipped: true,
+            b: U32(2),
+        };
+        assert_eq!(Legacy::domain_string(()), Some("Legacy".into()));
+
+        struct Expected(Legacy);
+
+        impl Hashable for Expected {
+            type D = ();
+
+            fn to_roinput(&self) -> ROInput {
+                ROInput::new()
+                    .append_hashable(&self.0.a)
+                    .append_hashable(&self.0.b)
+            }
+
+            fn domain_string(_: Self::D) -> Option<String> {
+                Some("Legacy".into())
+            }
+        }
+
+        assert_eq!(
+            create_legacy(()).hash(&v),
+            create_legacy(()).hash(&Expected(v))
+        );
+    }
+
+    #[test]
+    fn derive_chunked() {
+        let v = Chunked {
+            a: U32(1),
+            b: U64(u32::MAX as u64 + 3),
+            c: true,
+            _skipped: "skipped".into(),
+        };
+        assert_eq!(Chunked::domain_string(()), Some("Chunked".into()));
+        assert_eq!(
+            v.to_chunked_roinput(),
+            ChunkedROInput::new()
+                .append_bool(true)
+                .append_u32(2)
+                .append_u32(1)
+        );
+
+        struct Expected(Chunked);
+
+        impl Hashable for Expected {
+            type D = ();
+
+            fn to_roinput(&self) -> ROInput {
+                self.0.roinput()
+            }
+
+            fn domain_string(_: Self::D) -> Option<String> {
+                Some("Chunked".into())
+            }
+        }
+
+        assert_eq!(
+            create_kimchi(()).hash(&v),
+            create_kimchi(()).hash(&Expected(v))
+        );
+    }
+
+    #[test]
+    fn derive_generic_tuple_struct() {
+        let v = Generic(U64(3), U32(4));
+        assert_eq!(Generic::<U64>::domain_string(()), None);
+        assert_eq!(
+            v.to_chunked_roinput(),
+            ChunkedROInput::new().append_u64(3).append_u32(4)
+        );
+    }
+}
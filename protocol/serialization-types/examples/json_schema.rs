@@ -0,0 +1,12 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prints the JSON Schema of the json blocks, which includes the schemas of all the nested json types.
+//! Run with `cargo run -p mina-serialization-types --features json_schema --example json_schema`
+
+use mina_serialization_types::json::ExternalTransitionJson;
+
+fn main() {
+    let schema = schemars::schema_for!(ExternalTransitionJson);
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
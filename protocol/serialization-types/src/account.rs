@@ -14,6 +14,7 @@ use versioned::Versioned;
 
 /// An account as is serialized into the Mina ledger database stores
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct Account {
     /// Account public key
     pub public_key: PublicKey2V1,
@@ -45,6 +46,7 @@ pub type AccountV1 = Versioned<Versioned<Versioned<Account, 1>, 1>, 1>;
 
 /// An account as is serialized into the Mina ledger database stores (unversioned)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct AccountV0 {
     /// Account public key
     pub public_key: CompressedCurvePoint,
@@ -77,6 +79,7 @@ pub struct AccountV0 {
 
 /// FIXME: Need to learn exactly what this is..
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum TokenPermissions {
     /// Account does not own a token
     NotOwned {
@@ -95,6 +98,7 @@ pub type TokenPermissionsV1 = Versioned<Versioned<TokenPermissions, 1>, 1>;
 
 /// Permissions associated with the account
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PermissionsLegacy {
     /// If the account can stake
     pub stake: bool,
@@ -114,6 +118,7 @@ pub struct PermissionsLegacy {
 
 /// Permissions associated with the account
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct Permissions {
     /// Permission required to edit state
     pub edit_state: AuthRequired,
@@ -144,6 +149,7 @@ pub type PermissionsV1 = Versioned<Versioned<PermissionsLegacy, 1>, 1>;
 
 /// The level of auth required to perform a particular action with an account
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum AuthRequired {
     /// None required
     None,
@@ -166,6 +172,7 @@ pub type AuthRequiredV1 = Versioned<AuthRequired, 1>;
 /// Timing information for an account with regard to when its balance is accessable
 /// This is to allow vesting from an initial genesis allocation
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum Timing {
     /// Account does not have any timing limitations
     Untimed,
@@ -175,6 +182,7 @@ pub enum Timing {
 
 /// TODO
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum TimingV0 {
     /// Account does not have any timing limitations
     Untimed,
@@ -185,6 +193,7 @@ pub enum TimingV0 {
 /// Payload for the timing variant Timed
 /// Needs its own version byte
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct TimedData {
     /// Initial balance for the account
     pub initial_minimum_balance: AmountV1,
@@ -200,6 +209,7 @@ pub struct TimedData {
 
 /// TODO
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct TimedDataV0 {
     /// Initial balance for the account
     pub initial_minimum_balance: u64,
@@ -219,3 +229,10 @@ pub type TimedDataV1 = Versioned<TimedData, 1>;
 
 /// Timing information for an account with regard to when its balance is accessable (v1)
 pub type TimingV1 = Versioned<Versioned<Timing, 1>, 1>;
+
+/// Account identifier, the public key and the token ID of the account
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountId(pub PublicKeyV1, pub TokenIdV1);
+
+/// Account identifier (v1)
+pub type AccountIdV1 = Versioned<AccountId, 1>;
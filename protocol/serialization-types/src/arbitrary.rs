@@ -0,0 +1,103 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! [proptest] strategies for the bin-prot types, enabled with the `arbitrary` feature
+//!
+//! Most types derive [Arbitrary], the strategies here are used for the values
+//! that must be valid beyond their shape to survive the conversions into json or base types.
+//!
+
+use crate::{
+    bulletproof_challenges::BulletproofChallenges,
+    common::ByteVec,
+    field_and_curve_elements::{
+        ECPointVec, FieldElement, FieldElementVec, FiniteECPointPairVec, FiniteECPointVec,
+    },
+    proof_messages::ProofMessageWithoutDegreeBoundList,
+    signatures::{CompressedCurvePoint, SignatureV1},
+    v1::{ByteVecV1, CharV1},
+};
+use proof_systems::mina_signer::{Keypair, ScalarField, SecKey};
+use proof_systems::o1_utils::FieldHelpers;
+use proptest::prelude::*;
+
+/// Maximum length of generated lists, kept small as proofs nest many of them
+pub const MAX_VEC_LEN: usize = 4;
+
+/// Strategy for lists of at most [MAX_VEC_LEN] elements
+pub fn vec<T: Arbitrary>() -> impl Strategy<Value = Vec<T>> {
+    prop::collection::vec(any::<T>(), 0..=MAX_VEC_LEN)
+}
+
+/// Strategy for field elements below 2^254, so that they are valid
+/// elements of both the base field and the scalar field
+pub fn field_element() -> impl Strategy<Value = FieldElement> {
+    any::<FieldElement>().prop_map(|mut bytes| {
+        bytes[31] &= 0x3f;
+        bytes
+    })
+}
+
+/// Strategy for ascii characters, the json encoding of [CharV1] is a string
+pub fn char() -> impl Strategy<Value = CharV1> {
+    (0..0x80_u8).prop_map(Into::into)
+}
+
+/// Strategy for ascii byte vectors, the json encoding of [ByteVecV1] is a string
+pub fn ascii_byte_vec() -> impl Strategy<Value = ByteVecV1> {
+    prop::collection::vec(0..0x80_u8, 0..64).prop_map(|bytes| ByteVec(bytes).into())
+}
+
+// A compressed point is only valid when its x coordinate is on the curve,
+// so public keys are derived from random secret keys
+impl Arbitrary for CompressedCurvePoint {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        field_element()
+            .prop_filter_map("Invalid secret key", |bytes| {
+                let scalar = ScalarField::from_bytes(&bytes).ok()?;
+                let keypair = Keypair::from_secret_key(SecKey::new(scalar)).ok()?;
+                Some((&keypair.public.into_compressed()).into())
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for SignatureV1 {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (field_element(), field_element())
+            .prop_map(|signature| SignatureV1(signature.into()))
+            .boxed()
+    }
+}
+
+// Lists of the proofs, which would otherwise be up to 100 elements each
+macro_rules! impl_arbitrary_for_vec_newtype {
+    ($($t:ty),*) => {
+        $(
+            impl Arbitrary for $t {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                    vec().prop_map(Self).boxed()
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_for_vec_newtype!(
+    BulletproofChallenges,
+    FieldElementVec,
+    FiniteECPointVec,
+    FiniteECPointPairVec,
+    ECPointVec,
+    ProofMessageWithoutDegreeBoundList
+);
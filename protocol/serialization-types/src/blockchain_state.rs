@@ -12,6 +12,7 @@ use versioned::Versioned;
 
 /// Mina blockchain state struct
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct BlockchainState {
     /// Hash of the proposed next state of the blockchain
     pub staged_ledger_hash: StagedLedgerHashV1,
@@ -30,6 +31,7 @@ pub type BlockchainStateV1 = Versioned<Versioned<BlockchainState, 1>, 1>;
 
 /// Mina blockchain state struct (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(BlockchainState)]
 pub struct BlockchainStateJson {
     /// Hash of the proposed next state of the blockchain
@@ -46,6 +48,7 @@ pub struct BlockchainStateJson {
 
 /// Staged ledger hash structure
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct StagedLedgerHash {
     pub non_snark: NonSnarkStagedLedgerHashV1,
     pub pending_coinbase_hash: Hash2V1,
@@ -56,6 +59,7 @@ pub type StagedLedgerHashV1 = Versioned<Versioned<StagedLedgerHash, 1>, 1>;
 
 /// Staged ledger hash structure (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(StagedLedgerHash)]
 pub struct StagedLedgerHashJson {
     pub non_snark: NonSnarkStagedLedgerHashJson,
@@ -64,6 +68,7 @@ pub struct StagedLedgerHashJson {
 
 /// Non-snarked ledger hash
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct NonSnarkStagedLedgerHash {
     pub ledger_hash: HashV1,
     pub aux_hash: ByteVecV1,
@@ -75,6 +80,7 @@ pub type NonSnarkStagedLedgerHashV1 = Versioned<NonSnarkStagedLedgerHash, 1>;
 
 /// Non-snarked ledger hash (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(NonSnarkStagedLedgerHash)]
 pub struct NonSnarkStagedLedgerHashJson {
     pub ledger_hash: LedgerHashV1Json,
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use versioned::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct BulletproofChallenge {
     pub prechallenge: BulletproofPreChallengeV1,
 }
@@ -18,6 +19,7 @@ pub struct BulletproofChallenge {
 pub type BulletproofChallengeV1 = Versioned<BulletproofChallenge, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(BulletproofChallenge)]
 pub struct BulletproofChallengeJson {
     pub prechallenge: BulletproofPreChallengeJson,
@@ -29,10 +31,12 @@ pub struct BulletproofChallenges(pub Vec<BulletproofChallengeTuple18V1>);
 pub type BulletproofChallengesV1 = Versioned<BulletproofChallenges, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(BulletproofChallenges)]
 pub struct BulletproofChallengesJson(pub Vec<BulletproofChallengeTuple18Json>);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofStateBulletproofChallenges(
     pub BulletproofChallengeTuple17V1,
     pub BulletproofChallengeTuple17V1,
@@ -42,6 +46,7 @@ pub struct ProofStateBulletproofChallenges(
 pub type ProofStateBulletproofChallengesV1 = Versioned<ProofStateBulletproofChallenges, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofStateBulletproofChallenges)]
 pub struct ProofStateBulletproofChallengesJson(
     pub BulletproofChallengeTuple17Json,
@@ -51,6 +56,7 @@ pub struct ProofStateBulletproofChallengesJson(
 
 // TODO - see if this can be rewritten with const generics over an array
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct BulletproofChallengeTuple17(
     pub BulletproofChallengeV1,
     pub BulletproofChallengeV1,
@@ -76,6 +82,7 @@ pub type BulletproofChallengeTuple17V1 =
     Versioned<Versioned<Versioned<BulletproofChallengeTuple17, 1>, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(BulletproofChallengeTuple17)]
 pub struct BulletproofChallengeTuple17Json(
     pub BulletproofChallengeJson,
@@ -100,6 +107,7 @@ pub struct BulletproofChallengeTuple17Json(
 
 // TODO - see if this can be rewritten with const generics over an array
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct BulletproofChallengeTuple18(
     pub BulletproofChallengeV1,
     pub BulletproofChallengeV1,
@@ -125,6 +133,7 @@ pub struct BulletproofChallengeTuple18(
 pub type BulletproofChallengeTuple18V1 = Versioned<Versioned<BulletproofChallengeTuple18, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(BulletproofChallengeTuple18)]
 pub struct BulletproofChallengeTuple18Json(
     pub BulletproofChallengeJson,
@@ -149,6 +158,7 @@ pub struct BulletproofChallengeTuple18Json(
 );
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum BulletproofPreChallenge {
     ScalarChallenge(ScalarChallengeVector2V1),
 }
@@ -156,6 +166,7 @@ pub enum BulletproofPreChallenge {
 pub type BulletproofPreChallengeV1 = Versioned<BulletproofPreChallenge, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum BulletproofPreChallengeJsonProxy {
     #[serde(rename = "Scalar_challenge")]
     ScalarChallenge(ScalarChallengeVector2Json),
@@ -172,12 +183,20 @@ impl_mina_enum_json_serde_with_option!(
     BulletproofPreChallengeJsonProxy,
     false
 );
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema_with_option!(
+    BulletproofPreChallengeJson,
+    BulletproofPreChallengeJsonProxy,
+    false
+);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ScalarChallengeVector2(pub Hex64V1, pub Hex64V1, pub ());
 
 pub type ScalarChallengeVector2V1 = Versioned<ScalarChallengeVector2, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ScalarChallengeVector2)]
 pub struct ScalarChallengeVector2Json(pub I64, pub I64, #[serde(skip)] pub ());
@@ -87,6 +87,7 @@ impl<'de> Deserialize<'de> for U64Json {
 #[derive(
     Clone, Debug, Eq, PartialEq, Serialize, Deserialize, derive_more::From, derive_more::Into,
 )]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct I64(pub i64);
 
 /// u64 wrapper (json)
@@ -211,6 +212,7 @@ pub type BigInt256 = [u8; 32];
 
 /// Wrapper of Vec<u8>
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, derive_more::From)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ByteVec(pub Vec<u8>);
 
 impl_from_versioned!(ByteVec);
@@ -419,12 +421,15 @@ impl<const VERSION_BYTE: u8> From<Hash2V1> for HashV1Json<VERSION_BYTE> {
 /// base58 string representation of a ledger hash
 pub type LedgerHashV1Json = HashV1Json<{ version_bytes::LEDGER_HASH }>;
 
-/// base58 string representation of a chain hash
-pub type ChainHashV1Json = LedgerHashV1Json;
-
 /// base58 string representation of a coinbase hash
 pub type CoinBaseHashV1Json = HashV1Json<{ version_bytes::COINBASE_HASH }>;
 
+/// base58 string representation of a receipt chain hash
+pub type ReceiptChainHashV1Json = HashV1Json<{ version_bytes::RECEIPT_CHAIN_HASH }>;
+
+/// base58 string representation of a receipt chain hash of an account
+pub type ChainHashV1Json = ReceiptChainHashV1Json;
+
 /// base58 string representation of a coinbase hash
 pub type EpochSeedHashV1Json = HashV1Json<{ version_bytes::EPOCH_SEED }>;
 
@@ -443,6 +448,10 @@ pub type CoinBaseStackHashV1Json = HashV1Json<{ version_bytes::COINBASE_STACK_HA
 /// base58 string representation of a vrf output hash
 pub type VrfOutputHashV1Json = HashV1Json<{ version_bytes::VRF_TRUNCATED_OUTPUT }>;
 
+/// base58 string representation of a transaction hash
+pub type TransactionHashV1Json =
+    Base58EncodableVersionedType<{ version_bytes::TRANSACTION_HASH }, ByteVecV1>;
+
 /// base58 string representation of a aux hash
 pub type AuxHashJson = Base58EncodableType<{ version_bytes::STAGED_LEDGER_HASH_AUX_HASH }, Vec<u8>>;
 
@@ -17,6 +17,7 @@ use versioned::*;
 
 /// Wrapper struct for the output for a VRF
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct VrfOutputTruncated(pub Vec<u8>);
 
 /// Wrapper struct for the output for a VRF, with version
@@ -94,6 +95,7 @@ impl<'de> Deserialize<'de> for VrfOutputTruncatedBase58Json {
 ///
 /// Samasika prepares the past for the future! This future state is stored in the next_epoch_data field.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ConsensusState {
     /// Height of block
     pub blockchain_length: LengthV1,
@@ -102,6 +104,7 @@ pub struct ConsensusState {
     /// Minimum window density oberved on the chain
     pub min_window_density: LengthV1,
     /// Current sliding window of densities
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::arbitrary::vec()"))]
     pub sub_window_densities: Vec<LengthV1>,
     /// Additional VRS output from leader (for seeding Random Oracle)
     pub last_vrf_output: VrfOutputTruncatedV1,
@@ -132,6 +135,7 @@ pub type ConsensusStateV1 = Versioned<Versioned<ConsensusState, 1>, 1>;
 
 /// json protocol version of the consensus state
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ConsensusState)]
 pub struct ConsensusStateJson {
     /// Height of block
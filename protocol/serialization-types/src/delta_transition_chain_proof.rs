@@ -9,9 +9,11 @@ use serde::{Deserialize, Serialize};
 
 /// Proof that the block was produced within the allotted slot time
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct DeltaTransitionChainProof(pub HashV1, pub Vec<HashV1>);
 
 /// Proof that the block was produced within the allotted slot time (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(DeltaTransitionChainProof)]
 pub struct DeltaTransitionChainProofJson(pub LedgerHashV1Json, pub Vec<LedgerHashV1Json>);
@@ -15,6 +15,7 @@ use versioned::*;
 
 /// Epoch Ledger
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct EpochLedger {
     /// A unique identifier of the EpochLedger
     pub hash: HashV1,
@@ -27,6 +28,7 @@ pub type EpochLedgerV1 = Versioned<Versioned<EpochLedger, 1>, 1>;
 
 /// Epoch Ledger (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(EpochLedger)]
 pub struct EpochLedgerJson {
     /// A unique identifier of the EpochLedger
@@ -37,6 +39,7 @@ pub struct EpochLedgerJson {
 
 /// Epoch data
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct EpochData {
     /// Epoch Ledger, contains ledger related data for the epoch
     pub ledger: EpochLedgerV1,
@@ -55,6 +58,7 @@ pub type EpochDataV1 = Versioned<Versioned<EpochData, 1>, 1>;
 
 /// Epoch data (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(EpochData)]
 pub struct EpochDataJson {
     /// Epoch Ledger, contains ledger related data for the epoch
@@ -21,6 +21,7 @@ use versioned::*;
 
 /// This structure represents a mina block received from an external block producer
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ExternalTransition {
     /// The blockchain state, including consensus and the ledger
     pub protocol_state: ProtocolStateV1,
@@ -46,6 +47,7 @@ pub type ExternalTransitionV1 = Versioned<ExternalTransition, 1>;
 /// This structure represents a mina block received from an external block producer
 /// that is convertible from / to the mina specific json representation
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ExternalTransition)]
 pub struct ExternalTransitionJson {
     /// The blockchain state, including consensus and the ledger
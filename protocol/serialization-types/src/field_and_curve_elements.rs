@@ -26,16 +26,19 @@ pub type FieldElementVecV1 = Versioned<FieldElementVec, 1>;
 
 /// Vector of finite field elements (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FieldElementVec)]
 pub struct FieldElementVecJson(pub Vec<FieldElementJson>);
 
 /// An elliptic curve point defined over a base field with elements that fit in a BigInt256
 /// This is a Finite elliptic curve point as this type cannot be used to encode the point-at-infinity
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct FiniteECPoint(pub FieldElement, pub FieldElement);
 
 /// A finite EC point (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FiniteECPoint)]
 pub struct FiniteECPointJson(pub FieldElementJson, pub FieldElementJson);
 
@@ -48,15 +51,18 @@ pub type FiniteECPointVecV1 = Versioned<FiniteECPointVec, 1>;
 
 /// Vector of finite EC points (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FiniteECPointVec)]
 pub struct FiniteECPointVecJson(pub Vec<FiniteECPointJson>);
 
 /// Pair if finite EC Points
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct FiniteECPointPair(pub FiniteECPoint, pub FiniteECPoint);
 
 /// Pair if finite EC Points (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FiniteECPointPair)]
 pub struct FiniteECPointPairJson(pub FiniteECPointJson, pub FiniteECPointJson);
 
@@ -69,12 +75,14 @@ pub type FiniteECPointPairVecV1 = Versioned<FiniteECPointPairVec, 1>;
 
 /// Vector of 2-tuples of finite EC points (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FiniteECPointPairVec)]
 pub struct FiniteECPointPairVecJson(pub Vec<FiniteECPointPairJson>);
 
 /// Elliptic curve point that can either be the coordinates of a point on the curve
 /// OR it can be the point-at-infinity
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, SmartDefault)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum ECPoint {
     /// The point at infinity
     #[default]
@@ -90,6 +98,7 @@ pub type ECPointV1 = Versioned<ECPoint, 1>;
 /// Elliptic curve point that can either be the coordinates of a point on the curve
 /// OR it can be the point-at-infinity (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, SmartDefault)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum ECPointJsonProxy {
     /// The point at infinity
     #[default]
@@ -112,6 +121,8 @@ pub enum ECPointJson {
 }
 
 impl_mina_enum_json_serde_with_option!(ECPointJson, ECPointJsonProxy, false);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema_with_option!(ECPointJson, ECPointJsonProxy, false);
 
 /// Vector of EC points
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
@@ -122,6 +133,7 @@ pub type ECPointVecV1 = Versioned<ECPointVec, 1>;
 
 /// Vector of EC points (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ECPointVec)]
 pub struct ECPointVecJson(pub Vec<ECPointJson>);
 
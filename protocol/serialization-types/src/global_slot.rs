@@ -14,6 +14,7 @@ use crate::{
 
 /// A global slot
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct GlobalSlot {
     /// The global slot number of a chain or block
     pub slot_number: GlobalSlotNumberV1,
@@ -26,6 +27,7 @@ pub type GlobalSlotV1 = Versioned<Versioned<GlobalSlot, 1>, 1>;
 
 /// A global slot (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(GlobalSlot)]
 pub struct GlobalSlotJson {
     /// The global slot number of a chain or block
@@ -0,0 +1,278 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! [JsonSchema] implementations for the json types, enabled with the `json_schema` feature
+//!
+//! Most json types derive [JsonSchema], the implementations here are for the types
+//! with custom string encodings such as integers in decimal strings and base58 or base64 hashes.
+//! The list tagged enums implement it with [crate::impl_mina_enum_json_schema].
+//!
+//! The schema of a type is generated with `schemars::schema_for!(ExternalTransitionJson)`
+//!
+
+use crate::{
+    common::{
+        Base58EncodableType, Base58EncodableVersionedType, ByteVecJson, CharJson, DecimalJson,
+        U32Json, U64Json,
+    },
+    consensus_state::{VrfOutputTruncatedBase58Json, VrfOutputTruncatedBase64Json},
+    field_and_curve_elements::FieldElementJson,
+    protocol_state_proof::ProtocolStateProofBase64Json,
+    signatures::{PublicKeyJson, SignatureJson},
+    staged_ledger_diff::SignedCommandMemoJson,
+};
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{
+        ArrayValidation, InstanceType, Metadata, Schema, SchemaObject, SingleOrVec,
+        StringValidation, SubschemaValidation,
+    },
+    JsonSchema,
+};
+
+/// Pattern of base58 strings, in the bitcoin alphabet used by mina
+pub const BASE58_PATTERN: &str = "^[1-9A-HJ-NP-Za-km-z]+$";
+
+/// Pattern of url safe base64 strings
+pub const BASE64_URL_SAFE_PATTERN: &str = "^[A-Za-z0-9_-]*={0,2}$";
+
+/// Pattern of unsigned integers in decimal strings
+pub const UNSIGNED_PATTERN: &str = "^[0-9]+$";
+
+/// Pattern of currency amounts in decimal strings, with at most 9 decimals
+pub const DECIMAL_PATTERN: &str = r"^[0-9]+(\.[0-9]{1,9})?$";
+
+/// Pattern of field elements in hex strings
+pub const FIELD_ELEMENT_PATTERN: &str = "^(0x)?[0-9a-fA-F]{64}$";
+
+/// Pattern of base58 public keys
+pub const PUBLIC_KEY_PATTERN: &str = "^B62[1-9A-HJ-NP-Za-km-z]{52}$";
+
+/// Schema of a string with the given description and validation
+pub fn string_schema(description: impl Into<String>, validation: StringValidation) -> Schema {
+    SchemaObject {
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.into()),
+            ..Default::default()
+        })),
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(validation)),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Validation of strings matching `pattern`
+pub fn pattern(pattern: &str) -> StringValidation {
+    StringValidation {
+        pattern: Some(pattern.into()),
+        ..Default::default()
+    }
+}
+
+/// Schema of an enum in the list tagged json format of [crate::impl_mina_enum_json_serde],
+/// built from the schema of the externally tagged enum it is converted from.
+/// Unit variants become `["Variant"]` and the other variants `["Variant", value]`,
+/// with the elements of array values following the tag instead when `expand_array` is set
+pub fn list_tagged_enum_schema(gen: &SchemaGenerator, proxy: Schema, expand_array: bool) -> Schema {
+    let mut proxy = proxy.into_object();
+    let variants = match proxy.subschemas.as_mut().and_then(|s| s.one_of.take()) {
+        Some(variants) => variants,
+        None => vec![proxy.into()],
+    };
+    let mut list_tagged = Vec::new();
+    for variant in variants {
+        let variant = variant.into_object();
+        // unit variants are strings, the others objects with the variant name as the only property
+        if let Some(names) = variant.enum_values {
+            list_tagged.extend(
+                names
+                    .into_iter()
+                    .map(|name| tuple_schema(vec![tag(name)], None)),
+            );
+        } else if let Some(object) = variant.object {
+            for (name, value) in object.properties {
+                list_tagged.push(list_tagged_variant(gen, name, value, expand_array));
+            }
+        }
+    }
+    match list_tagged.len() {
+        1 => list_tagged.remove(0),
+        _ => SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                one_of: Some(list_tagged),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into(),
+    }
+}
+
+fn list_tagged_variant(
+    gen: &SchemaGenerator,
+    name: String,
+    value: Schema,
+    expand_array: bool,
+) -> Schema {
+    if expand_array {
+        if let Schema::Object(SchemaObject {
+            array: Some(array), ..
+        }) = gen.dereference(&value).unwrap_or(&value)
+        {
+            match &array.items {
+                // tuples, the elements follow the tag
+                Some(SingleOrVec::Vec(items)) => {
+                    let mut elements = vec![tag(name.into())];
+                    elements.extend(items.iter().cloned());
+                    return tuple_schema(elements, None);
+                }
+                // lists, any number of elements follow the tag
+                Some(SingleOrVec::Single(item)) => {
+                    return tuple_schema(vec![tag(name.into())], Some(*item.clone()))
+                }
+                None => {}
+            }
+        }
+    }
+    tuple_schema(vec![tag(name.into()), value], None)
+}
+
+fn tag(name: serde_json::Value) -> Schema {
+    SchemaObject {
+        const_value: Some(name),
+        ..Default::default()
+    }
+    .into()
+}
+
+// An array of the given elements, followed by any number of `rest` elements when given
+fn tuple_schema(elements: Vec<Schema>, rest: Option<Schema>) -> Schema {
+    let len = elements.len() as u32;
+    SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(elements.into()),
+            max_items: rest.is_none().then_some(len),
+            min_items: Some(len),
+            additional_items: rest.map(Box::new),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+macro_rules! impl_string_json_schema {
+    ($ty:ty, $description:literal, $validation:expr) => {
+        impl JsonSchema for $ty {
+            fn schema_name() -> String {
+                stringify!($ty).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                string_schema($description, $validation)
+            }
+        }
+    };
+}
+
+impl_string_json_schema!(
+    U32Json,
+    "u32 in a decimal string",
+    pattern(UNSIGNED_PATTERN)
+);
+impl_string_json_schema!(
+    U64Json,
+    "u64 in a decimal string",
+    pattern(UNSIGNED_PATTERN)
+);
+impl_string_json_schema!(
+    DecimalJson,
+    "Amount in a decimal string of whole mina and at most 9 decimals",
+    pattern(DECIMAL_PATTERN)
+);
+impl_string_json_schema!(
+    CharJson,
+    "Single character",
+    StringValidation {
+        max_length: Some(1),
+        min_length: Some(1),
+        ..Default::default()
+    }
+);
+impl_string_json_schema!(ByteVecJson, "Bytes as a string", Default::default());
+impl_string_json_schema!(
+    FieldElementJson,
+    "Field element in a hex string",
+    pattern(FIELD_ELEMENT_PATTERN)
+);
+impl_string_json_schema!(
+    PublicKeyJson,
+    "Compressed public key in a base58 address",
+    pattern(PUBLIC_KEY_PATTERN)
+);
+impl_string_json_schema!(
+    SignatureJson,
+    "Signature in a base58 string",
+    pattern(BASE58_PATTERN)
+);
+impl_string_json_schema!(
+    SignedCommandMemoJson,
+    "Memo in a base58 string",
+    pattern(BASE58_PATTERN)
+);
+impl_string_json_schema!(
+    VrfOutputTruncatedBase58Json,
+    "Truncated vrf output in a base58 string",
+    pattern(BASE58_PATTERN)
+);
+impl_string_json_schema!(
+    VrfOutputTruncatedBase64Json,
+    "Truncated vrf output in a url safe base64 string",
+    pattern(BASE64_URL_SAFE_PATTERN)
+);
+impl_string_json_schema!(
+    ProtocolStateProofBase64Json,
+    "Bin-prot encoded protocol state proof in a url safe base64 string",
+    pattern(BASE64_URL_SAFE_PATTERN)
+);
+
+// The hashes are inlined as the version byte is not part of the type name
+
+impl<const VERSION_BYTE: u8, T> JsonSchema for Base58EncodableType<VERSION_BYTE, T> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("Base58EncodableType_{VERSION_BYTE}")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            format!("Bytes in a base58check string with version byte {VERSION_BYTE}"),
+            pattern(BASE58_PATTERN),
+        )
+    }
+}
+
+impl<const VERSION_BYTE: u8, T> JsonSchema for Base58EncodableVersionedType<VERSION_BYTE, T> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("Base58EncodableVersionedType_{VERSION_BYTE}")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(
+            format!(
+                "Bin-prot encoded value in a base58check string with version byte {VERSION_BYTE}"
+            ),
+            pattern(BASE58_PATTERN),
+        )
+    }
+}
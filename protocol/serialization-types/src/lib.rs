@@ -16,6 +16,8 @@
 #![deny(missing_docs)]
 
 pub mod account;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
 pub mod blockchain_state;
 pub mod bulletproof_challenges;
 pub mod common;
@@ -26,6 +28,8 @@ pub mod errors;
 pub mod external_transition;
 pub mod field_and_curve_elements;
 pub mod global_slot;
+#[cfg(feature = "json_schema")]
+pub mod json_schema;
 pub mod macros;
 pub mod opening_proof;
 pub mod proof_evaluations;
@@ -35,9 +39,13 @@ pub mod protocol_state;
 pub mod protocol_state_body;
 pub mod protocol_state_proof;
 pub mod protocol_version;
+pub mod scan_state;
 pub mod signatures;
 pub mod snark_work;
+pub mod sparse_ledger;
 pub mod staged_ledger_diff;
+pub mod transaction_logic;
+pub mod verification_key;
 pub mod version_bytes;
 
 mod type_annotations;
@@ -51,7 +59,7 @@ pub mod unversioned {
 /// Version 1 serialization types for the Mina protocol
 pub mod v1 {
     pub use super::account::{
-        AccountV1, AuthRequiredV1, PermissionsV1, TimingV1, TokenPermissionsV1,
+        AccountIdV1, AccountV1, AuthRequiredV1, PermissionsV1, TimingV1, TokenPermissionsV1,
     };
     pub use super::blockchain_state::{
         BlockchainStateV1, NonSnarkStagedLedgerHashV1, StagedLedgerHashV1,
@@ -89,11 +97,18 @@ pub mod v1 {
         ProofV1, ProtocolStateProofV1, ShiftedValueV1, SpongeDigestBeforeEvaluationsV1,
     };
     pub use super::protocol_version::ProtocolVersionV1;
+    pub use super::scan_state::{
+        BaseJobRecordV1, BaseJobV1, BaseNodeV1, InitStackV1, JobStatusV1,
+        LedgerProofWithSokMessageV1, MergeJobRecordV1, MergeJobV1, MergeNodeV1, NonEmptyListV1,
+        ParallelScanStateV1, ScanTreeV1, SequenceNumberV1, SokMessageV1, TransactionScanStateV1,
+        TransactionWithWitnessV1, WeightV1,
+    };
     pub use super::signatures::{PublicKey2V1, PublicKeyV1, SignatureV1};
     pub use super::snark_work::{
         FeeExcessPairV1, LedgerProofV1, OneORTwoV1, PendingCoinbaseStackStateV1, PendingCoinbaseV1,
         SgnTypeV1, SignedV1, StateStackV1, StatementV1, TransactionSnarkV1, TransactionSnarkWorkV1,
     };
+    pub use super::sparse_ledger::{SparseLedgerTreeV1, SparseLedgerV1};
     pub use super::staged_ledger_diff::{
         CoinBaseBalanceDataV1, CoinBaseFeeTransferV1, CoinBaseV1, FeeTransferBalanceDataV1,
         InternalCommandBalanceDataV1, PaymentPayloadV1, SignedCommandFeeTokenV1,
@@ -103,8 +118,18 @@ pub mod v1 {
         TransactionStatusBalanceDataV1, TransactionStatusFailedTypeV1, TransactionStatusV1,
         UserCommandV1, UserCommandWithStatusV1,
     };
+    pub use super::transaction_logic::{
+        CoinbaseUndoV1, CoinbaseV1, CommandUndoV1, FeeTransferSingleV1, FeeTransferUndoV1,
+        FeeTransferV1, SignedCommandUndoBodyV1, SignedCommandUndoCommonV1, SignedCommandUndoV1,
+        SignedCommandWithStatusV1, TransactionUndoV1, TransactionUndoVaryingV1,
+    };
+    pub use super::verification_key::{
+        DomainV1, DomainsV1, PlonkVerificationKeyEvalsV1, VerificationKeyDataV1, VerificationKeyV1,
+    };
 }
 
+pub mod v2;
+
 /// json serialization types for the Mina protocol
 pub mod json {
     use super::*;
@@ -118,7 +143,8 @@ pub mod json {
     pub use common::{
         AuxHashJson, ByteVecJson, ChainHashV1Json, CharJson, CoinBaseHashV1Json,
         EpochSeedHashV1Json, HashV1Json, LedgerHashV1Json, PendingCoinbaseAuxHashJson,
-        StateBodyHashV1Json, StateHashV1Json, VrfOutputHashV1Json,
+        ReceiptChainHashV1Json, StateBodyHashV1Json, StateHashV1Json, TransactionHashV1Json,
+        VrfOutputHashV1Json,
     };
     pub use consensus_state::{
         ConsensusStateJson, VrfOutputTruncatedBase58Json, VrfOutputTruncatedBase64Json,
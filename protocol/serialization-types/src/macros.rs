@@ -136,3 +136,34 @@ macro_rules! impl_mina_enum_json_serde {
         impl_mina_enum_json_serde_with_option!($t, $tp, true);
     };
 }
+
+/// Implement `schemars::JsonSchema` for an enum in the list tagged json serde format
+/// of [impl_mina_enum_json_serde_with_option], from the schema of the externally tagged
+/// enum it is converted from
+#[macro_export]
+macro_rules! impl_mina_enum_json_schema_with_option {
+    ($t:ty, $tp:ty, $ser_expand_array:expr) => {
+        impl ::schemars::JsonSchema for $t {
+            fn schema_name() -> String {
+                stringify!($t).into()
+            }
+
+            fn json_schema(
+                gen: &mut ::schemars::gen::SchemaGenerator,
+            ) -> ::schemars::schema::Schema {
+                let proxy = <$tp as ::schemars::JsonSchema>::json_schema(gen);
+                $crate::json_schema::list_tagged_enum_schema(gen, proxy, $ser_expand_array)
+            }
+        }
+    };
+}
+
+/// Implement `schemars::JsonSchema` for an enum in the list tagged json serde format
+/// of [impl_mina_enum_json_serde], from the schema of the externally tagged
+/// enum it is converted from
+#[macro_export]
+macro_rules! impl_mina_enum_json_schema {
+    ($t:ty, $tp:ty) => {
+        impl_mina_enum_json_schema_with_option!($t, $tp, true);
+    };
+}
@@ -15,6 +15,7 @@ use serde::{Deserialize, Serialize};
 use versioned::Versioned;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct OpeningProof {
     pub lr: FiniteECPointPairVecV1,
     pub z_1: FieldElement,
@@ -26,6 +27,7 @@ pub struct OpeningProof {
 pub type OpeningProofV1 = Versioned<OpeningProof, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(OpeningProof)]
 pub struct OpeningProofJson {
     pub lr: FiniteECPointPairVecJson,
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use versioned::Versioned;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofEvaluations {
     pub l: FieldElementVecV1,
     pub r: FieldElementVecV1,
@@ -25,6 +26,7 @@ pub struct ProofEvaluations {
 pub type ProofEvaluationsV1 = Versioned<ProofEvaluations, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofEvaluations)]
 pub struct ProofEvaluationsJson {
     pub l: FieldElementVecJson,
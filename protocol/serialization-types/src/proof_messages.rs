@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use versioned::Versioned;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofMessages {
     pub l_comm: ProofMessageWithoutDegreeBoundListV1,
     pub r_comm: ProofMessageWithoutDegreeBoundListV1,
@@ -22,6 +23,7 @@ pub struct ProofMessages {
 pub type ProofMessagesV1 = Versioned<ProofMessages, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofMessages)]
 pub struct ProofMessagesJson {
     pub l_comm: ProofMessageWithoutDegreeBoundListJson,
@@ -38,10 +40,12 @@ pub type ProofMessageWithoutDegreeBoundListV1 =
     Versioned<Versioned<ProofMessageWithoutDegreeBoundList, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofMessageWithoutDegreeBoundList)]
 pub struct ProofMessageWithoutDegreeBoundListJson(pub Vec<FiniteECPointJson>);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofMessageWithDegreeBound {
     pub unshifted: ECPointVecV1,
     pub shifted: ECPointV1,
@@ -50,6 +54,7 @@ pub struct ProofMessageWithDegreeBound {
 pub type ProofMessageWithDegreeBoundV1 = Versioned<ProofMessageWithDegreeBound, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofMessageWithDegreeBound)]
 pub struct ProofMessageWithDegreeBoundJson {
     pub unshifted: ECPointVecJson,
@@ -15,6 +15,7 @@ use crate::{
 
 /// Constants that define the consensus parameters
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProtocolConstants {
     /// Point of finality (number of confirmations)
     pub k: LengthV1,
@@ -33,6 +34,7 @@ pub type ProtocolConstantsV1 = Versioned<Versioned<ProtocolConstants, 1>, 1>;
 
 /// Constants that define the consensus parameters (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProtocolConstants)]
 pub struct ProtocolConstantsJson {
     /// Point of finality (number of confirmations)
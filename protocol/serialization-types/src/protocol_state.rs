@@ -10,6 +10,7 @@ use versioned::*;
 
 /// This structure can be thought of like the block header. It contains the most essential information of a block.
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProtocolState {
     /// Commitment to previous block (hash of previous protocol state hash and body hash)
     pub previous_state_hash: HashV1,
@@ -22,6 +23,7 @@ pub type ProtocolStateV1 = Versioned<Versioned<ProtocolState, 1>, 1>;
 
 /// This structure can be thought of like the block header. It contains the most essential information of a block. (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProtocolState)]
 pub struct ProtocolStateJson {
     /// Commitment to previous block (hash of previous protocol state hash and body hash)
@@ -10,6 +10,7 @@ use versioned::*;
 
 /// Body of the protocol state
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProtocolStateBody {
     /// Genesis protocol state hash (used for hardforks)
     pub genesis_state_hash: HashV1,
@@ -26,6 +27,7 @@ pub type ProtocolStateBodyV1 = Versioned<Versioned<ProtocolStateBody, 1>, 1>;
 
 /// Body of the protocol state (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProtocolStateBody)]
 pub struct ProtocolStateBodyJson {
     /// Genesis protocol state hash (used for hardforks)
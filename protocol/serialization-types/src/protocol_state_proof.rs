@@ -12,6 +12,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use versioned::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 /// SNARK proof of the protocol state at some point in time
 pub struct ProtocolStateProof {
     pub statement: ProofStatementV1,
@@ -25,6 +26,7 @@ pub type ProtocolStateProofV1 =
     Versioned<Versioned<Versioned<Versioned<ProtocolStateProof, 1>, 1>, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProtocolStateProof)]
 /// SNARK proof of the protocol state at some point in time (json)
 pub struct ProtocolStateProofJson {
@@ -66,6 +68,7 @@ impl<'de> Deserialize<'de> for ProtocolStateProofBase64Json {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofStatement {
     pub proof_state: ProofStateV1,
     pub pass_through: PairingBasedV1,
@@ -74,6 +77,7 @@ pub struct ProofStatement {
 pub type ProofStatementV1 = Versioned<Versioned<ProofStatement, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofStatement)]
 pub struct ProofStatementJson {
     pub proof_state: ProofStateJson,
@@ -81,6 +85,7 @@ pub struct ProofStatementJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofState {
     pub deferred_values: ProofStateDeferredValuesV1,
     pub sponge_digest_before_evaluations: SpongeDigestBeforeEvaluationsV1,
@@ -90,6 +95,7 @@ pub struct ProofState {
 pub type ProofStateV1 = Versioned<ProofState, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofState)]
 pub struct ProofStateJson {
     pub deferred_values: ProofStateDeferredValuesJson,
@@ -98,18 +104,21 @@ pub struct ProofStateJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofStateDeferredValues {
     pub plonk: PlonkV1,
     pub combined_inner_product: ShiftedValueV1,
     pub b: ShiftedValueV1,
     pub xi: BulletproofPreChallengeV1,
     pub bulletproof_challenges: BulletproofChallengeTuple18V1,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::arbitrary::char()"))]
     pub which_branch: CharV1,
 }
 
 pub type ProofStateDeferredValuesV1 = Versioned<ProofStateDeferredValues, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofStateDeferredValues)]
 pub struct ProofStateDeferredValuesJson {
     pub plonk: PlonkJson,
@@ -121,6 +130,7 @@ pub struct ProofStateDeferredValuesJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct Plonk {
     pub alpha: BulletproofPreChallengeV1,
     pub beta: ScalarChallengeVector2V1,
@@ -131,6 +141,7 @@ pub struct Plonk {
 pub type PlonkV1 = Versioned<Plonk, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(Plonk)]
 pub struct PlonkJson {
     pub alpha: BulletproofPreChallengeJson,
@@ -140,6 +151,7 @@ pub struct PlonkJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum ShiftedValue {
     ShiftedValue(BigInt256),
 }
@@ -147,6 +159,7 @@ pub enum ShiftedValue {
 pub type ShiftedValueV1 = Versioned<ShiftedValue, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum ShiftedValueJsonProxy {
     #[serde(rename = "Shifted_value")]
     ShiftedValue(FieldElementJson),
@@ -159,8 +172,11 @@ pub enum ShiftedValueJson {
     ShiftedValue(FieldElementJson),
 }
 impl_mina_enum_json_serde!(ShiftedValueJson, ShiftedValueJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(ShiftedValueJson, ShiftedValueJsonProxy);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct SpongeDigestBeforeEvaluations(
     pub Hex64V1,
     pub Hex64V1,
@@ -173,6 +189,7 @@ pub type SpongeDigestBeforeEvaluationsV1 =
     Versioned<Versioned<SpongeDigestBeforeEvaluations, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(SpongeDigestBeforeEvaluations)]
 pub struct SpongeDigestBeforeEvaluationsJson(
     pub I64,
@@ -183,6 +200,7 @@ pub struct SpongeDigestBeforeEvaluationsJson(
 );
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofStatePairingBased {
     pub sg: FiniteECPoint,
     pub old_bulletproof_challenges: ProofStateBulletproofChallengesV1,
@@ -191,6 +209,7 @@ pub struct ProofStatePairingBased {
 pub type ProofStatePairingBasedV1 = Versioned<ProofStatePairingBased, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofStatePairingBased)]
 pub struct ProofStatePairingBasedJson {
     pub sg: FiniteECPointJson,
@@ -198,6 +217,7 @@ pub struct ProofStatePairingBasedJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PairingBased {
     pub app_state: (),
     pub sg: FiniteECPointVecV1,
@@ -207,6 +227,7 @@ pub struct PairingBased {
 pub type PairingBasedV1 = Versioned<PairingBased, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(PairingBased)]
 pub struct PairingBasedJson {
     pub app_state: (),
@@ -215,24 +236,29 @@ pub struct PairingBasedJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PrevEvals(pub ProofEvaluationsV1, pub ProofEvaluationsV1);
 
 pub type PrevEvalsV1 = Versioned<PrevEvals, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(PrevEvals)]
 pub struct PrevEvalsJson(pub ProofEvaluationsJson, pub ProofEvaluationsJson);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PrevXHat(pub FiniteECPoint);
 
 pub type PrevXHatV1 = Versioned<PrevXHat, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(PrevXHat)]
 pub struct PrevXHatJson(pub FiniteECPointJson);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct Proof {
     pub messages: ProofMessagesV1,
     pub openings: ProofOpeningsV1,
@@ -241,6 +267,7 @@ pub struct Proof {
 pub type ProofV1 = Versioned<Versioned<Proof, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(Proof)]
 pub struct ProofJson {
     pub messages: ProofMessagesJson,
@@ -248,13 +275,16 @@ pub struct ProofJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofOpeningsEvals(pub ProofEvaluationsV1, pub ProofEvaluationsV1);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofOpeningsEvals)]
 pub struct ProofOpeningsEvalsJson(pub ProofEvaluationsJson, pub ProofEvaluationsJson);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct ProofOpenings {
     pub proof: OpeningProofV1,
     pub evals: ProofOpeningsEvals,
@@ -263,6 +293,7 @@ pub struct ProofOpenings {
 pub type ProofOpeningsV1 = Versioned<ProofOpenings, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(ProofOpenings)]
 pub struct ProofOpeningsJson {
     pub proof: OpeningProofJson,
@@ -9,6 +9,8 @@ use versioned::Versioned;
 
 /// Defines a version of the Mina protocol in semver format
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, SmartDefault)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct ProtocolVersion {
     /// Major version number
     #[default(2)]
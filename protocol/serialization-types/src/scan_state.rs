@@ -0,0 +1,255 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types of the transaction snark scan state, as stored in staged ledger snapshots
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/parallel_scan/parallel_scan.ml>
+//! and <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/transaction_snark_scan_state/transaction_snark_scan_state.ml>
+//!
+//! FIXME: No test coverage against captured snapshots yet, the layouts follow the OCaml definitions
+#![allow(missing_docs)]
+
+use crate::{sparse_ledger::SparseLedgerV1, transaction_logic::TransactionUndoV1, v1::*};
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::marker::PhantomData;
+use versioned::Versioned;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum JobStatus {
+    Todo,
+    Done,
+}
+
+pub type JobStatusV1 = Versioned<JobStatus, 1>;
+
+pub type SequenceNumberV1 = Versioned<u64, 1>;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Weight {
+    pub base: u64,
+    pub merge: u64,
+}
+
+pub type WeightV1 = Versioned<Weight, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BaseJobRecord<B> {
+    pub job: B,
+    pub seq_no: SequenceNumberV1,
+    pub status: JobStatusV1,
+}
+
+pub type BaseJobRecordV1<B> = Versioned<BaseJobRecord<B>, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum BaseJob<B> {
+    Empty,
+    Full(BaseJobRecordV1<B>),
+}
+
+pub type BaseJobV1<B> = Versioned<BaseJob<B>, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BaseNode<B>(pub WeightV1, pub BaseJobV1<B>);
+
+pub type BaseNodeV1<B> = Versioned<BaseNode<B>, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MergeJobRecord<M> {
+    pub left: M,
+    pub right: M,
+    pub seq_no: SequenceNumberV1,
+    pub status: JobStatusV1,
+}
+
+pub type MergeJobRecordV1<M> = Versioned<MergeJobRecord<M>, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum MergeJob<M> {
+    Empty,
+    Part(M),
+    Full(MergeJobRecordV1<M>),
+}
+
+pub type MergeJobV1<M> = Versioned<MergeJob<M>, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MergeNode<M>(pub (WeightV1, WeightV1), pub MergeJobV1<M>);
+
+pub type MergeNodeV1<M> = Versioned<MergeNode<M>, 1>;
+
+/// Complete binary tree of jobs, merge nodes are in breadth first order from the root
+/// and base nodes are from left to right.
+///
+/// The OCaml type is the nested `Leaf of 'base | Node of {depth; value; sub_tree}`
+/// where the value and the subtree of each level hold pairs of the level above,
+/// so each level is written as the tag `1`, its depth and all its nodes from left to right,
+/// followed by the tag `0` and all the base nodes
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScanTree<M, B> {
+    pub merge_nodes: Vec<MergeNodeV1<M>>,
+    pub base_nodes: Vec<BaseNodeV1<B>>,
+}
+
+pub type ScanTreeV1<M, B> = Versioned<ScanTree<M, B>, 1>;
+
+// Trees deeper than this cannot be allocated anyway
+const MAX_SCAN_TREE_DEPTH: u64 = 32;
+
+impl<M, B> Serialize for ScanTree<M, B>
+where
+    M: Serialize,
+    B: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let depth = self.base_nodes.len().trailing_zeros() as usize;
+        let mut tuple = serializer
+            .serialize_tuple(2 * depth + 1 + self.merge_nodes.len() + self.base_nodes.len())?;
+        let mut merge_nodes = self.merge_nodes.iter();
+        for level in 0..depth {
+            tuple.serialize_element(&1_u8)?;
+            tuple.serialize_element(&(level as u64))?;
+            for node in merge_nodes.by_ref().take(1 << level) {
+                tuple.serialize_element(node)?;
+            }
+        }
+        tuple.serialize_element(&0_u8)?;
+        for node in &self.base_nodes {
+            tuple.serialize_element(node)?;
+        }
+        tuple.end()
+    }
+}
+
+struct ScanTreeVisitor<M, B>(PhantomData<(M, B)>);
+
+fn next<'de, A, T>(seq: &mut A) -> Result<T, A::Error>
+where
+    A: SeqAccess<'de>,
+    T: Deserialize<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| A::Error::custom("unexpected end of scan tree"))
+}
+
+impl<'de, M, B> Visitor<'de> for ScanTreeVisitor<M, B>
+where
+    M: Deserialize<'de>,
+    B: Deserialize<'de>,
+{
+    type Value = ScanTree<M, B>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a scan tree")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut merge_nodes = Vec::new();
+        let mut depth = 0;
+        loop {
+            match next(&mut seq)? {
+                0_u8 => break,
+                1_u8 => {
+                    let level: u64 = next(&mut seq)?;
+                    if level != depth || level >= MAX_SCAN_TREE_DEPTH {
+                        return Err(A::Error::custom(format!(
+                            "unexpected scan tree depth {level}, expected {depth}"
+                        )));
+                    }
+                    for _ in 0..1_u64 << level {
+                        merge_nodes.push(next(&mut seq)?);
+                    }
+                    depth += 1;
+                }
+                tag => {
+                    return Err(A::Error::custom(format!(
+                        "unexpected scan tree variant {tag}"
+                    )))
+                }
+            }
+        }
+        let mut base_nodes = Vec::new();
+        for _ in 0..1_u64 << depth {
+            base_nodes.push(next(&mut seq)?);
+        }
+        Ok(ScanTree {
+            merge_nodes,
+            base_nodes,
+        })
+    }
+}
+
+impl<'de, M, B> Deserialize<'de> for ScanTree<M, B>
+where
+    M: Deserialize<'de>,
+    B: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(usize::MAX, ScanTreeVisitor(PhantomData))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NonEmptyList<T>(pub T, pub Vec<T>);
+
+pub type NonEmptyListV1<T> = Versioned<NonEmptyList<T>, 1>;
+
+/// Trees are ordered from the newest to the oldest
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ParallelScanState<M, B> {
+    pub trees: NonEmptyListV1<ScanTreeV1<M, B>>,
+    pub acc: Option<(M, Vec<B>)>,
+    pub curr_job_seq_no: SequenceNumberV1,
+    pub max_base_jobs: u64,
+    pub delay: u64,
+}
+
+pub type ParallelScanStateV1<M, B> = Versioned<ParallelScanState<M, B>, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SokMessage {
+    pub fee: AmountV1,
+    pub prover: PublicKeyV1,
+}
+
+pub type SokMessageV1 = Versioned<SokMessage, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LedgerProofWithSokMessage(pub LedgerProofV1, pub SokMessageV1);
+
+pub type LedgerProofWithSokMessageV1 = Versioned<LedgerProofWithSokMessage, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum InitStack {
+    Base(PendingCoinbaseV1),
+    Merge,
+}
+
+pub type InitStackV1 = Versioned<InitStack, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TransactionWithWitness {
+    pub transaction_with_info: TransactionUndoV1,
+    // State hash and state body hash
+    pub state_hash: (HashV1, HashV1),
+    pub statement: StatementV1,
+    pub init_stack: InitStackV1,
+    pub ledger_witness: SparseLedgerV1,
+}
+
+pub type TransactionWithWitnessV1 = Versioned<TransactionWithWitness, 1>;
+
+pub type TransactionScanStateV1 =
+    Versioned<ParallelScanStateV1<LedgerProofWithSokMessageV1, TransactionWithWitnessV1>, 1>;
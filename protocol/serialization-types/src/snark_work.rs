@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use versioned::Versioned;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct TransactionSnarkWork {
     // Versioned 1 byte
     pub fee: AmountV1,
@@ -20,6 +21,7 @@ pub struct TransactionSnarkWork {
 pub type TransactionSnarkWorkV1 = Versioned<TransactionSnarkWork, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(TransactionSnarkWork)]
 pub struct TransactionSnarkWorkJson {
     // Versioned 1 byte
@@ -29,6 +31,7 @@ pub struct TransactionSnarkWorkJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 #[serde(rename = "Polyvar")]
 pub enum OneORTwo {
     // Versioned 1 byte
@@ -41,6 +44,7 @@ pub enum OneORTwo {
 pub type OneORTwoV1 = Versioned<OneORTwo, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum OneORTwoJsonProxy {
     One(Box<TransactionSnarkJson>),
     Two(Box<TransactionSnarkJson>, Box<TransactionSnarkJson>),
@@ -55,10 +59,13 @@ pub enum OneORTwoJson {
 }
 
 impl_mina_enum_json_serde!(OneORTwoJson, OneORTwoJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(OneORTwoJson, OneORTwoJsonProxy);
 
 pub type LedgerProofV1 = Versioned<TransactionSnarkV1, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct TransactionSnark {
     pub statement: StatementV1,
     pub transaction_snark_proof: ProtocolStateProofV1,
@@ -67,6 +74,7 @@ pub struct TransactionSnark {
 pub type TransactionSnarkV1 = Versioned<TransactionSnark, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(TransactionSnark)]
 pub struct TransactionSnarkJson {
     pub statement: StatementJson,
@@ -76,6 +84,7 @@ pub struct TransactionSnarkJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct Statement {
     // Versioned 2 byte
     pub source: HashV1,
@@ -85,12 +94,17 @@ pub struct Statement {
     pub fee_excess: FeeExcessPairV1,
     pub next_available_token_before: TokenIdV1,
     pub next_available_token_after: TokenIdV1,
+    #[cfg_attr(
+        feature = "arbitrary",
+        proptest(strategy = "crate::arbitrary::ascii_byte_vec()")
+    )]
     pub sok_digest: ByteVecV1,
 }
 
 pub type StatementV1 = Versioned<Versioned<Statement, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(Statement)]
 pub struct StatementJson {
     // Versioned 2 byte
@@ -105,6 +119,7 @@ pub struct StatementJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PendingCoinbaseStackState {
     // Versioned 2 byte
     pub source: PendingCoinbaseV1,
@@ -114,6 +129,7 @@ pub struct PendingCoinbaseStackState {
 pub type PendingCoinbaseStackStateV1 = Versioned<Versioned<PendingCoinbaseStackState, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(PendingCoinbaseStackState)]
 pub struct PendingCoinbaseStackStateJson {
     // Versioned 2 byte
@@ -122,6 +138,7 @@ pub struct PendingCoinbaseStackStateJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PendingCoinbase {
     // Versioned 2 byte
     pub data_stack: HashV1,
@@ -131,6 +148,7 @@ pub struct PendingCoinbase {
 pub type PendingCoinbaseV1 = Versioned<Versioned<PendingCoinbase, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(PendingCoinbase)]
 pub struct PendingCoinbaseJson {
     #[serde(rename = "data")]
@@ -140,6 +158,7 @@ pub struct PendingCoinbaseJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct StateStack {
     // Versioned 2 byte
     pub init: HashV1,
@@ -149,6 +168,7 @@ pub struct StateStack {
 pub type StateStackV1 = Versioned<Versioned<StateStack, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(StateStack)]
 pub struct StateStackJson {
     // Versioned 2 byte
@@ -157,12 +177,14 @@ pub struct StateStackJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct FeeExcess {
     pub token: TokenIdV1,
     pub amount: SignedV1,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FeeExcess)]
 pub struct FeeExcessJson {
     pub token: U64Json,
@@ -170,15 +192,18 @@ pub struct FeeExcessJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct FeeExcessPair(pub FeeExcess, pub FeeExcess);
 
 pub type FeeExcessPairV1 = Versioned<Versioned<FeeExcessPair, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FeeExcessPair)]
 pub struct FeeExcessPairJson(pub FeeExcessJson, pub FeeExcessJson);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct Signed {
     // Versioned 1 byte
     pub magnitude: AmountV1,
@@ -188,6 +213,7 @@ pub struct Signed {
 pub type SignedV1 = Versioned<Signed, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(Signed)]
 pub struct SignedJson {
     // Versioned 1 byte
@@ -196,6 +222,7 @@ pub struct SignedJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum SgnType {
     // Versioned 1 byte
     Pos,
@@ -205,6 +232,7 @@ pub enum SgnType {
 pub type SgnTypeV1 = Versioned<SgnType, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub enum SgnTypeJsonProxy {
     Pos,
     Neg,
@@ -219,3 +247,5 @@ pub enum SgnTypeJson {
 }
 
 impl_mina_enum_json_serde!(SgnTypeJson, SgnTypeJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(SgnTypeJson, SgnTypeJsonProxy);
@@ -0,0 +1,32 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sparse merkle ledger that holds the accounts a transaction touches
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/sparse_ledger_lib/sparse_ledger.ml>
+//!
+//! FIXME: No test coverage against captured snapshots yet, the layouts follow the OCaml definitions
+#![allow(missing_docs)]
+
+use crate::{account::AccountIdV1, v1::*};
+use serde::{Deserialize, Serialize};
+use versioned::Versioned;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SparseLedgerTree {
+    Account(Box<AccountV1>),
+    Hash(HashV1),
+    Node(HashV1, Box<SparseLedgerTree>, Box<SparseLedgerTree>),
+}
+
+pub type SparseLedgerTreeV1 = Versioned<SparseLedgerTree, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SparseLedger {
+    // Account ids and their indexes in the ledger
+    pub indexes: Vec<(AccountIdV1, u64)>,
+    pub depth: u64,
+    pub tree: SparseLedgerTreeV1,
+    pub next_available_token: TokenIdV1,
+}
+
+pub type SparseLedgerV1 = Versioned<Versioned<SparseLedger, 1>, 1>;
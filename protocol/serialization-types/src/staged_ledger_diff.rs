@@ -13,6 +13,7 @@ use smart_default::SmartDefault;
 use versioned::*;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 /// Top level wrapper type for a StagedLedgerDiff
 pub struct StagedLedgerDiff {
     pub diff: StagedLedgerDiffTupleV1,
@@ -23,17 +24,20 @@ pub type StagedLedgerDiffV1 = Versioned<StagedLedgerDiff, 1>;
 
 /// Top level wrapper type for a StagedLedgerDiff (json)
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(StagedLedgerDiff)]
 pub struct StagedLedgerDiffJson {
     pub diff: StagedLedgerDiffTupleJson,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct StagedLedgerDiffTuple(pub StagedLedgerPreDiffV1, pub Option<StagedLedgerPreDiffV1>);
 
 pub type StagedLedgerDiffTupleV1 = Versioned<StagedLedgerDiffTuple, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(StagedLedgerDiffTuple)]
 pub struct StagedLedgerDiffTupleJson(
     pub StagedLedgerPreDiffJson,
@@ -41,16 +45,21 @@ pub struct StagedLedgerDiffTupleJson(
 );
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct StagedLedgerPreDiff {
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::arbitrary::vec()"))]
     pub completed_works: Vec<TransactionSnarkWorkV1>,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::arbitrary::vec()"))]
     pub commands: Vec<UserCommandWithStatusV1>,
     pub coinbase: CoinBaseV1,
+    #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::arbitrary::vec()"))]
     pub internal_command_balances: Vec<InternalCommandBalanceDataV1>,
 }
 
 pub type StagedLedgerPreDiffV1 = Versioned<Versioned<StagedLedgerPreDiff, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(StagedLedgerPreDiff)]
 pub struct StagedLedgerPreDiffJson {
     pub completed_works: Vec<TransactionSnarkWorkJson>,
@@ -60,6 +69,7 @@ pub struct StagedLedgerPreDiffJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct UserCommandWithStatus {
     pub data: UserCommandV1,
     pub status: TransactionStatusV1,
@@ -68,6 +78,7 @@ pub struct UserCommandWithStatus {
 pub type UserCommandWithStatusV1 = Versioned<UserCommandWithStatus, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(UserCommandWithStatus)]
 pub struct UserCommandWithStatusJson {
     pub data: UserCommandJson,
@@ -75,6 +86,7 @@ pub struct UserCommandWithStatusJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum UserCommand {
     SignedCommand(SignedCommandV1),
     // FIXME: other variants are not covered by current test block
@@ -83,6 +95,7 @@ pub enum UserCommand {
 pub type UserCommandV1 = Versioned<Versioned<UserCommand, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum UserCommandJsonProxy {
     #[serde(rename = "Signed_command")]
     SignedCommand(SignedCommandJson),
@@ -96,8 +109,11 @@ pub enum UserCommandJson {
 }
 
 impl_mina_enum_json_serde!(UserCommandJson, UserCommandJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(UserCommandJson, UserCommandJsonProxy);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct SignedCommand {
     pub payload: SignedCommandPayloadV1,
     pub signer: PublicKey2V1,
@@ -107,6 +123,7 @@ pub struct SignedCommand {
 pub type SignedCommandV1 = Versioned<Versioned<SignedCommand, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(SignedCommand)]
 pub struct SignedCommandJson {
     pub payload: SignedCommandPayloadJson,
@@ -115,6 +132,7 @@ pub struct SignedCommandJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct SignedCommandPayload {
     pub common: SignedCommandPayloadCommonV1,
     pub body: SignedCommandPayloadBodyV1,
@@ -123,6 +141,7 @@ pub struct SignedCommandPayload {
 pub type SignedCommandPayloadV1 = Versioned<Versioned<SignedCommandPayload, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(SignedCommandPayload)]
 pub struct SignedCommandPayloadJson {
     pub common: SignedCommandPayloadCommonJson,
@@ -130,6 +149,7 @@ pub struct SignedCommandPayloadJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct SignedCommandPayloadCommon {
     pub fee: AmountV1,
     pub fee_token: SignedCommandFeeTokenV1,
@@ -143,6 +163,7 @@ pub type SignedCommandPayloadCommonV1 =
     Versioned<Versioned<Versioned<SignedCommandPayloadCommon, 1>, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(SignedCommandPayloadCommon)]
 pub struct SignedCommandPayloadCommonJson {
     pub fee: DecimalJson,
@@ -154,6 +175,7 @@ pub struct SignedCommandPayloadCommonJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum SignedCommandPayloadBody {
     PaymentPayload(PaymentPayloadV1),
     StakeDelegation(StakeDelegationV1),
@@ -163,6 +185,7 @@ pub enum SignedCommandPayloadBody {
 pub type SignedCommandPayloadBodyV1 = Versioned<Versioned<SignedCommandPayloadBody, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum SignedCommandPayloadBodyJsonProxy {
     #[serde(rename = "Payment")]
     PaymentPayload(PaymentPayloadJson),
@@ -183,8 +206,15 @@ impl_mina_enum_json_serde_with_option!(
     SignedCommandPayloadBodyJsonProxy,
     false
 );
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema_with_option!(
+    SignedCommandPayloadBodyJson,
+    SignedCommandPayloadBodyJsonProxy,
+    false
+);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct PaymentPayload {
     pub source_pk: PublicKeyV1,
     pub receiver_pk: PublicKeyV1,
@@ -195,6 +225,7 @@ pub struct PaymentPayload {
 pub type PaymentPayloadV1 = Versioned<Versioned<PaymentPayload, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(PaymentPayload)]
 pub struct PaymentPayloadJson {
     pub source_pk: PublicKeyJson,
@@ -204,6 +235,7 @@ pub struct PaymentPayloadJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum StakeDelegation {
     SetDelegate {
         delegator: PublicKeyV1,
@@ -214,6 +246,7 @@ pub enum StakeDelegation {
 pub type StakeDelegationV1 = Versioned<StakeDelegation, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum StakeDelegationJsonProxy {
     #[serde(rename = "Set_delegate")]
     SetDelegate {
@@ -233,10 +266,13 @@ pub enum StakeDelegationJson {
 }
 
 impl_mina_enum_json_serde!(StakeDelegationJson, StakeDelegationJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(StakeDelegationJson, StakeDelegationJsonProxy);
 
 pub type SignedCommandFeeTokenV1 = Versioned<Versioned<Versioned<u64, 1>, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct SignedCommandMemo(pub Vec<u8>);
 
 pub type SignedCommandMemoV1 = Versioned<SignedCommandMemo, 1>;
@@ -276,12 +312,14 @@ impl<'de> Deserialize<'de> for SignedCommandMemoJson {
 pub type SnappCommand = Versioned<Versioned<(), 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum TransactionStatus {
     Applied(
         TransactionStatusAuxiliaryDataV1,
         TransactionStatusBalanceDataV1,
     ),
     Failed(
+        #[cfg_attr(feature = "arbitrary", proptest(strategy = "crate::arbitrary::vec()"))]
         Vec<TransactionStatusFailedTypeV1>,
         TransactionStatusBalanceDataV1,
     ),
@@ -290,6 +328,7 @@ pub enum TransactionStatus {
 pub type TransactionStatusV1 = Versioned<TransactionStatus, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum TransactionStatusJsonProxy {
     Applied(
         TransactionStatusAuxiliaryDataJson,
@@ -316,8 +355,11 @@ pub enum TransactionStatusJson {
 }
 
 impl_mina_enum_json_serde!(TransactionStatusJson, TransactionStatusJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(TransactionStatusJson, TransactionStatusJsonProxy);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct TransactionStatusAuxiliaryData {
     pub fee_payer_account_creation_fee_paid: Option<AmountV1>,
     pub receiver_account_creation_fee_paid: Option<AmountV1>,
@@ -327,6 +369,7 @@ pub struct TransactionStatusAuxiliaryData {
 pub type TransactionStatusAuxiliaryDataV1 = Versioned<TransactionStatusAuxiliaryData, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(TransactionStatusAuxiliaryData)]
 pub struct TransactionStatusAuxiliaryDataJson {
     pub fee_payer_account_creation_fee_paid: Option<U64Json>,
@@ -335,6 +378,7 @@ pub struct TransactionStatusAuxiliaryDataJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum TransactionStatusFailedType {
     #[serde(rename = "Predicate")]
     Predicate,
@@ -370,6 +414,7 @@ pub enum TransactionStatusFailedType {
 pub type TransactionStatusFailedTypeV1 = Versioned<TransactionStatusFailedType, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum TransactionStatusFailedTypeJsonProxy {
     #[serde(rename = "Predicate")]
     Predicate,
@@ -404,6 +449,7 @@ enum TransactionStatusFailedTypeJsonProxy {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(TransactionStatusFailedType)]
 #[auto_from(TransactionStatusFailedTypeJsonProxy)]
 pub enum TransactionStatusFailedTypeJson {
@@ -440,6 +486,7 @@ pub enum TransactionStatusFailedTypeJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct TransactionStatusBalanceData {
     pub fee_payer_balance: Option<ExtendedU64_3>,
     pub source_balance: Option<ExtendedU64_3>,
@@ -449,6 +496,7 @@ pub struct TransactionStatusBalanceData {
 pub type TransactionStatusBalanceDataV1 = Versioned<TransactionStatusBalanceData, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(TransactionStatusBalanceData)]
 pub struct TransactionStatusBalanceDataJson {
     pub fee_payer_balance: Option<U64Json>,
@@ -457,6 +505,7 @@ pub struct TransactionStatusBalanceDataJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, SmartDefault)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum CoinBase {
     #[default]
     Zero,
@@ -467,6 +516,7 @@ pub enum CoinBase {
 pub type CoinBaseV1 = Versioned<CoinBase, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, SmartDefault)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum CoinBaseJsonProxy {
     #[default]
     Zero,
@@ -491,8 +541,11 @@ pub enum CoinBaseJson {
 }
 
 impl_mina_enum_json_serde!(CoinBaseJson, CoinBaseJsonProxy);
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(CoinBaseJson, CoinBaseJsonProxy);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct CoinBaseFeeTransfer {
     pub receiver_pk: PublicKeyV1,
     pub fee: ExtendedU64_2,
@@ -501,6 +554,7 @@ pub struct CoinBaseFeeTransfer {
 pub type CoinBaseFeeTransferV1 = Versioned<Versioned<CoinBaseFeeTransfer, 1>, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(CoinBaseFeeTransfer)]
 pub struct CoinBaseFeeTransferJson {
     pub receiver_pk: PublicKeyJson,
@@ -508,6 +562,7 @@ pub struct CoinBaseFeeTransferJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub enum InternalCommandBalanceData {
     CoinBase(CoinBaseBalanceDataV1),
     FeeTransfer(FeeTransferBalanceDataV1),
@@ -516,6 +571,7 @@ pub enum InternalCommandBalanceData {
 pub type InternalCommandBalanceDataV1 = Versioned<InternalCommandBalanceData, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 enum InternalCommandBalanceDataJsonProxy {
     #[serde(rename = "Coinbase")]
     CoinBase(CoinBaseBalanceDataJson),
@@ -535,8 +591,14 @@ impl_mina_enum_json_serde!(
     InternalCommandBalanceDataJson,
     InternalCommandBalanceDataJsonProxy
 );
+#[cfg(feature = "json_schema")]
+impl_mina_enum_json_schema!(
+    InternalCommandBalanceDataJson,
+    InternalCommandBalanceDataJsonProxy
+);
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct CoinBaseBalanceData {
     pub coinbase_receiver_balance: ExtendedU64_3,
     // FIXME: No test coverage yet
@@ -546,6 +608,7 @@ pub struct CoinBaseBalanceData {
 pub type CoinBaseBalanceDataV1 = Versioned<CoinBaseBalanceData, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(CoinBaseBalanceData)]
 pub struct CoinBaseBalanceDataJson {
     pub coinbase_receiver_balance: U64Json,
@@ -553,6 +616,7 @@ pub struct CoinBaseBalanceDataJson {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(proptest_derive::Arbitrary))]
 pub struct FeeTransferBalanceData {
     pub receiver1_balance: ExtendedU64_3,
     // FIXME: No test coverage yet
@@ -562,6 +626,7 @@ pub struct FeeTransferBalanceData {
 pub type FeeTransferBalanceDataV1 = Versioned<FeeTransferBalanceData, 1>;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, AutoFrom)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[auto_from(FeeTransferBalanceData)]
 pub struct FeeTransferBalanceDataJson {
     pub receiver1_balance: U64Json,
@@ -0,0 +1,130 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transactions applied to the staged ledger along with the data to undo them
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/transaction_logic/mina_transaction_logic.ml>
+//!
+//! FIXME: No test coverage against captured snapshots yet, the layouts follow the OCaml definitions
+#![allow(missing_docs)]
+
+use crate::{account::AccountIdV1, v1::*};
+use serde::{Deserialize, Serialize};
+use versioned::Versioned;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeeTransferSingle {
+    pub receiver_pk: PublicKeyV1,
+    pub fee: AmountV1,
+    pub fee_token: TokenIdV1,
+}
+
+pub type FeeTransferSingleV1 = Versioned<FeeTransferSingle, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename = "Polyvar")]
+pub enum FeeTransfer {
+    #[serde(rename = "One")]
+    One(FeeTransferSingleV1),
+    #[serde(rename = "Two")]
+    Two(FeeTransferSingleV1, FeeTransferSingleV1),
+}
+
+pub type FeeTransferV1 = Versioned<FeeTransfer, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Coinbase {
+    pub receiver: PublicKeyV1,
+    pub amount: AmountV1,
+    pub fee_transfer: Option<CoinBaseFeeTransferV1>,
+}
+
+pub type CoinbaseV1 = Versioned<Coinbase, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandWithStatus {
+    pub data: SignedCommandV1,
+    pub status: TransactionStatusV1,
+}
+
+pub type SignedCommandWithStatusV1 = Versioned<SignedCommandWithStatus, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandUndoCommon {
+    pub user_command: SignedCommandWithStatusV1,
+    pub previous_receipt_chain_hash: HashV1,
+    pub fee_payer_timing: TimingV1,
+    pub source_timing: Option<TimingV1>,
+}
+
+pub type SignedCommandUndoCommonV1 = Versioned<SignedCommandUndoCommon, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SignedCommandUndoBody {
+    Payment {
+        previous_empty_accounts: Vec<AccountIdV1>,
+    },
+    StakeDelegation {
+        previous_delegate: Option<PublicKeyV1>,
+    },
+    CreateNewToken {
+        created_token: TokenIdV1,
+    },
+    CreateTokenAccount,
+    MintTokens,
+    Failed,
+}
+
+pub type SignedCommandUndoBodyV1 = Versioned<SignedCommandUndoBody, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandUndo {
+    pub common: SignedCommandUndoCommonV1,
+    pub body: SignedCommandUndoBodyV1,
+}
+
+pub type SignedCommandUndoV1 = Versioned<SignedCommandUndo, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum CommandUndo {
+    SignedCommand(SignedCommandUndoV1),
+    // FIXME: Snapp commands are not covered, same as UserCommand
+}
+
+pub type CommandUndoV1 = Versioned<CommandUndo, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeeTransferUndo {
+    pub fee_transfer: FeeTransferV1,
+    pub previous_empty_accounts: Vec<AccountIdV1>,
+    pub receiver_timing: TimingV1,
+    pub balances: FeeTransferBalanceDataV1,
+}
+
+pub type FeeTransferUndoV1 = Versioned<FeeTransferUndo, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CoinbaseUndo {
+    pub coinbase: CoinbaseV1,
+    pub previous_empty_accounts: Vec<AccountIdV1>,
+    pub receiver_timing: TimingV1,
+    pub balances: CoinBaseBalanceDataV1,
+}
+
+pub type CoinbaseUndoV1 = Versioned<CoinbaseUndo, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TransactionUndoVarying {
+    Command(Box<CommandUndoV1>),
+    FeeTransfer(FeeTransferUndoV1),
+    Coinbase(CoinbaseUndoV1),
+}
+
+pub type TransactionUndoVaryingV1 = Versioned<TransactionUndoVarying, 1>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TransactionUndo {
+    pub previous_hash: HashV1,
+    pub varying: TransactionUndoVaryingV1,
+}
+
+pub type TransactionUndoV1 = Versioned<TransactionUndo, 1>;
@@ -0,0 +1,36 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Berkeley block, which replaces the external transition
+
+use super::{common::*, proof::*, protocol_state::*, staged_ledger_diff::*};
+use crate::protocol_version::ProtocolVersion;
+use serde::{Deserialize, Serialize};
+
+/// This structure represents a mina block received from an external block producer (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BlockV2 {
+    /// Header of the block
+    pub header: HeaderV2,
+    /// Body of the block
+    pub body: StagedLedgerDiffBodyV2,
+}
+
+/// Berkeley blocks replace external transitions of the [v1](crate::v1) protocol
+pub type ExternalTransitionV2 = BlockV2;
+
+/// Header of a Berkeley block, the body is committed to by
+/// [BodyReferenceV2](super::BodyReferenceV2) in the blockchain state (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct HeaderV2 {
+    /// The blockchain state, including consensus and the ledger
+    pub protocol_state: ProtocolStateV2,
+    /// Proof that the protocol state and entire history of the chain is valid
+    pub protocol_state_proof: ProtocolStateProofV2,
+    /// Proof that the block was produced within the allotted slot time
+    pub delta_block_chain_proof: (HashV2, Vec<HashV2>),
+    /// Current protocol version
+    pub current_protocol_version: ProtocolVersion,
+    /// Proposed protocol version
+    pub proposed_protocol_version_opt: Option<ProtocolVersion>,
+}
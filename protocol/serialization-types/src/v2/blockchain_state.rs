@@ -0,0 +1,105 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types related to the Berkeley blockchain state
+
+use super::{common::*, staged_ledger_diff::TransactionFailureV2};
+use crate::field_and_curve_elements::FieldElement;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Mina blockchain state struct (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BlockchainStateV2 {
+    /// Hash of the proposed next state of the blockchain
+    pub staged_ledger_hash: StagedLedgerHashV2,
+    /// Hash of the genesis state
+    pub genesis_ledger_hash: HashV2,
+    /// Registers of the most recently proven state of the blockchain
+    pub registers: BlockchainStateRegistersV2,
+    /// Timestamps for blocks
+    pub timestamp: BlockTimeV2,
+    /// Blake2 digest of the block body
+    pub body_reference: BodyReferenceV2,
+}
+
+/// Staged ledger hash structure (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StagedLedgerHashV2 {
+    /// Hash of the non-snarked part of the staged ledger
+    pub non_snark: NonSnarkStagedLedgerHashV2,
+    /// Merkle root of the pending coinbase collection
+    pub pending_coinbase_hash: HashV2,
+}
+
+/// Non-snarked ledger hash (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NonSnarkStagedLedgerHashV2 {
+    /// Merkle root of the staged ledger
+    pub ledger_hash: HashV2,
+    /// Hash of the scan state
+    pub aux_hash: Vec<u8>,
+    /// Hash of the pending coinbase collection metadata
+    pub pending_coinbase_aux: Vec<u8>,
+}
+
+/// Mina blockchain state registers (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BlockchainStateRegistersV2 {
+    /// Snarked ledger hash
+    pub ledger: HashV2,
+    /// Pending coinbase stack, always unit in the blockchain state
+    pub pending_coinbase_stack: (),
+    /// Local state of the zkApp command being applied
+    pub local_state: LocalStateV2,
+}
+
+/// Local state of the zkApp transaction logic (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LocalStateV2 {
+    /// Hash of the current stack frame
+    pub stack_frame: FieldElement,
+    /// Hash of the call stack
+    pub call_stack: FieldElement,
+    /// Commitment to the transaction
+    pub transaction_commitment: FieldElement,
+    /// Commitment to the transaction including the fee payer
+    pub full_transaction_commitment: FieldElement,
+    /// Token ID of the current account update
+    pub token_id: TokenIdV2,
+    /// Fee excess accumulated so far
+    pub excess: SignedAmountV2,
+    /// Hash of the ledger being modified
+    pub ledger: HashV2,
+    /// Whether the transaction has succeeded so far
+    pub success: bool,
+    /// Index of the current account update
+    pub account_update_index: u32,
+    /// Failures of each account update
+    pub failure_status_tbl: Vec<Vec<TransactionFailureV2>>,
+}
+
+/// Mina block body reference, blake2 256-bit digest encoded as a string (v2)
+#[derive(Clone, Debug, Default, Eq, PartialEq, derive_more::From, derive_more::Into)]
+pub struct BodyReferenceV2(pub [u8; 32]);
+
+impl Serialize for BodyReferenceV2 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for BodyReferenceV2 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let len = bytes.len();
+        Ok(Self(bytes.try_into().map_err(|_| {
+            <D::Error as serde::de::Error>::invalid_length(len, &"32 bytes")
+        })?))
+    }
+}
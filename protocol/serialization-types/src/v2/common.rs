@@ -0,0 +1,76 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Some basic types used throughout the Berkeley types
+
+use crate::{
+    common::BigInt256,
+    field_and_curve_elements::{FieldElement, InnerCurveScalar},
+    signatures::CompressedCurvePoint,
+    snark_work::SgnType,
+};
+use serde::{Deserialize, Serialize};
+
+/// 32 bytes representing a hash of some kind, which is a field element (v2)
+pub type HashV2 = BigInt256;
+
+/// Token ID, which is a field element since Berkeley (v2)
+pub type TokenIdV2 = BigInt256;
+
+/// u64 representing an amount of currency (v2)
+pub type AmountV2 = u64;
+
+/// u64 representing a fee (v2)
+pub type FeeV2 = u64;
+
+/// u64 representing a block time (v2)
+pub type BlockTimeV2 = u64;
+
+/// u32 representing a length (v2)
+pub type LengthV2 = u32;
+
+/// u32 representing a slot number (v2)
+pub type GlobalSlotNumberV2 = u32;
+
+/// u32 representing an account nonce (v2)
+pub type AccountNonceV2 = u32;
+
+/// Compressed public key (v2)
+pub type PublicKeyV2 = CompressedCurvePoint;
+
+/// Schnorr signature (v2)
+pub type SignatureV2 = (FieldElement, InnerCurveScalar);
+
+/// Signed amount of currency (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedAmountV2 {
+    /// Absolute value of the amount
+    pub magnitude: AmountV2,
+    /// Sign of the amount
+    pub sgn: SgnType,
+}
+
+/// Memo of a user command, 34 bytes (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MemoV2(pub Vec<u8>);
+
+/// Fixed length vector of 2 elements, terminated by a unit as pickles vectors are
+pub type Vector2<T> = ([T; 2], ());
+
+/// Fixed length vector of 4 elements, terminated by a unit as pickles vectors are
+pub type Vector4<T> = ([T; 4], ());
+
+/// Fixed length vector of 6 elements, terminated by a unit as pickles vectors are
+pub type Vector6<T> = ([T; 6], ());
+
+/// Fixed length vector of 7 elements, terminated by a unit as pickles vectors are
+pub type Vector7<T> = ([T; 7], ());
+
+/// Fixed length vector of 8 elements, terminated by a unit as pickles vectors are
+pub type Vector8<T> = ([T; 8], ());
+
+/// Fixed length vector of 15 elements, terminated by a unit as pickles vectors are
+pub type Vector15<T> = ([T; 15], ());
+
+/// Fixed length vector of 16 elements, terminated by a unit as pickles vectors are
+pub type Vector16<T> = ([T; 16], ());
@@ -0,0 +1,93 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types related to the Berkeley consensus state
+
+use super::common::*;
+use crate::consensus_state::VrfOutputTruncated;
+use serde::{Deserialize, Serialize};
+
+/// This structure encapsulates the succinct state of the consensus protocol (v2)
+///
+/// See [ConsensusState](crate::consensus_state::ConsensusState) for details
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ConsensusStateV2 {
+    /// Height of block
+    pub blockchain_length: LengthV2,
+    /// Epoch number
+    pub epoch_count: LengthV2,
+    /// Minimum window density oberved on the chain
+    pub min_window_density: LengthV2,
+    /// Current sliding window of densities
+    pub sub_window_densities: Vec<LengthV2>,
+    /// Additional VRS output from leader (for seeding Random Oracle)
+    pub last_vrf_output: VrfOutputTruncated,
+    /// Total supply of currency
+    pub total_currency: AmountV2,
+    /// Current global slot number relative to the current hard fork
+    pub curr_global_slot: GlobalSlotV2,
+    /// Absolute global slot number since genesis
+    pub global_slot_since_genesis: GlobalSlotNumberV2,
+    /// Epoch data for previous epoch
+    pub staking_epoch_data: EpochDataV2,
+    /// Epoch data for current epoch
+    pub next_epoch_data: EpochDataV2,
+    /// If the block has an ancestor in the same checkpoint window
+    pub has_ancestor_in_same_checkpoint_window: bool,
+    /// Compressed public key of winning account
+    pub block_stake_winner: PublicKeyV2,
+    /// Compressed public key of the block producer
+    pub block_creator: PublicKeyV2,
+    /// Compresed public key of account receiving the block reward
+    pub coinbase_receiver: PublicKeyV2,
+    /// true if block_stake_winner has no locked tokens, false otherwise
+    pub supercharge_coinbase: bool,
+}
+
+/// Global slot (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GlobalSlotV2 {
+    /// The global slot number of a chain or block
+    pub slot_number: GlobalSlotNumberV2,
+    /// Number of slots per epoch
+    pub slots_per_epoch: LengthV2,
+}
+
+/// Epoch ledger (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochLedgerV2 {
+    /// A unique identifier of the EpochLedger
+    pub hash: HashV2,
+    /// The total currency in circulation after the block was produced
+    pub total_currency: AmountV2,
+}
+
+/// Epoch data (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochDataV2 {
+    /// Epoch Ledger, contains ledger related data for the epoch
+    pub ledger: EpochLedgerV2,
+    ///  Initialize the random number generator
+    pub seed: HashV2,
+    /// State hash of first block of epoch
+    pub start_checkpoint: HashV2,
+    /// State hash of last known block in the first 2/3 of epoch (excluding the current state)
+    pub lock_checkpoint: HashV2,
+    /// Length of an epoch
+    pub epoch_length: LengthV2,
+}
+
+/// Constants that define the consensus parameters (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProtocolConstantsV2 {
+    /// Point of finality (number of confirmations)
+    pub k: LengthV2,
+    /// Number of slots per epoch
+    pub slots_per_epoch: LengthV2,
+    /// No of slots in a sub-window = 7
+    pub slots_per_sub_window: LengthV2,
+    /// Maximum permissable delay of packets (in slots after the current)
+    pub delta: LengthV2,
+    /// Timestamp of genesis block in unixtime
+    pub genesis_state_timestamp: BlockTimeV2,
+}
@@ -0,0 +1,27 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Version 2 (Berkeley) serialization types for the Mina protocol
+//!
+//! Unlike the [v1](crate::v1) types, Berkeley types do not carry nested version bytes,
+//! every type here is encoded as is by bin-prot. Types are named after the stable version
+//! of the corresponding module in the OCaml implementation.
+//!
+
+mod block;
+pub use block::*;
+mod blockchain_state;
+pub use blockchain_state::*;
+mod common;
+pub use common::*;
+mod consensus_state;
+pub use consensus_state::*;
+mod proof;
+pub use proof::*;
+mod protocol_state;
+pub use protocol_state::*;
+mod staged_ledger_diff;
+pub use staged_ledger_diff::*;
+mod zkapp_command;
+pub use zkapp_command::*;
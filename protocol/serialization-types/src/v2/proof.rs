@@ -0,0 +1,172 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types of the Berkeley pickles proof, which wraps a kimchi proof
+
+#![allow(missing_docs)] // Don't actually know what many of the types fields are for yet
+
+use super::common::*;
+use crate::field_and_curve_elements::{FieldElement, FiniteECPoint};
+use serde::{Deserialize, Serialize};
+use smart_default::SmartDefault;
+
+/// Pickles proof with at most 2 previous proofs verified, used for both
+/// the protocol state proof and the ledger proofs (v2)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProtocolStateProofV2 {
+    pub statement: ProofStatementV2,
+    pub prev_evals: AllEvalsV2,
+    pub proof: ProofV2,
+}
+
+/// 128-bit challenge as 2 64-bit limbs (v2)
+pub type ChallengeV2 = Vector2<i64>;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ScalarChallengeV2 {
+    pub inner: ChallengeV2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BulletproofChallengeV2 {
+    pub prechallenge: ScalarChallengeV2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofStatementV2 {
+    pub proof_state: ProofStateV2,
+    pub messages_for_next_step_proof: MessagesForNextStepProofV2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofStateV2 {
+    pub deferred_values: ProofStateDeferredValuesV2,
+    pub sponge_digest_before_evaluations: Vector4<i64>,
+    pub messages_for_next_wrap_proof: MessagesForNextWrapProofV2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofStateDeferredValuesV2 {
+    pub plonk: PlonkV2,
+    pub combined_inner_product: ShiftedValueV2,
+    pub b: ShiftedValueV2,
+    pub xi: ScalarChallengeV2,
+    pub bulletproof_challenges: Vector16<BulletproofChallengeV2>,
+    pub branch_data: BranchDataV2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PlonkV2 {
+    pub alpha: ScalarChallengeV2,
+    pub beta: ChallengeV2,
+    pub gamma: ChallengeV2,
+    pub zeta: ScalarChallengeV2,
+    pub joint_combiner: Option<ScalarChallengeV2>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, SmartDefault)]
+pub enum ShiftedValueV2 {
+    #[default]
+    ShiftedValue(FieldElement),
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BranchDataV2 {
+    pub proofs_verified: ProofsVerifiedV2,
+    pub domain_log2: u8,
+}
+
+/// Number of previous proofs a pickles proof verifies (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, SmartDefault)]
+pub enum ProofsVerifiedV2 {
+    #[default]
+    N0,
+    N1,
+    N2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MessagesForNextWrapProofV2 {
+    pub challenge_polynomial_commitment: FiniteECPoint,
+    pub old_bulletproof_challenges: Vector2<Vector15<BulletproofChallengeV2>>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct MessagesForNextStepProofV2 {
+    pub app_state: (),
+    pub challenge_polynomial_commitments: Vec<FiniteECPoint>,
+    pub old_bulletproof_challenges: Vec<Vector16<BulletproofChallengeV2>>,
+}
+
+/// Evaluations of a polynomial at zeta and zeta * omega (v2)
+pub type PointEvaluationsV2 = (Vec<FieldElement>, Vec<FieldElement>);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AllEvalsV2 {
+    pub evals: EvalsWithPublicInputV2,
+    pub ft_eval1: FieldElement,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EvalsWithPublicInputV2 {
+    pub public_input: PointEvaluationsV2,
+    pub evals: ProofEvaluationsV2,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofEvaluationsV2 {
+    pub w: Vector15<PointEvaluationsV2>,
+    pub z: PointEvaluationsV2,
+    pub s: Vector6<PointEvaluationsV2>,
+    pub generic_selector: PointEvaluationsV2,
+    pub poseidon_selector: PointEvaluationsV2,
+    pub lookup: Option<LookupEvaluationsV2>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LookupEvaluationsV2 {
+    pub sorted: Vec<PointEvaluationsV2>,
+    pub aggreg: PointEvaluationsV2,
+    pub table: PointEvaluationsV2,
+    pub runtime: Option<PointEvaluationsV2>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofV2 {
+    pub messages: ProofMessagesV2,
+    pub openings: ProofOpeningsV2,
+}
+
+/// Polynomial commitment, split into chunks (v2)
+pub type PolyCommV2 = Vec<FiniteECPoint>;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofMessagesV2 {
+    pub w_comm: Vector15<PolyCommV2>,
+    pub z_comm: PolyCommV2,
+    pub t_comm: PolyCommV2,
+    pub lookup: Option<LookupCommitmentsV2>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LookupCommitmentsV2 {
+    pub sorted: Vec<PolyCommV2>,
+    pub aggreg: PolyCommV2,
+    pub runtime: Option<PolyCommV2>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProofOpeningsV2 {
+    pub proof: OpeningProofV2,
+    pub evals: ProofEvaluationsV2,
+    pub ft_eval1: FieldElement,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct OpeningProofV2 {
+    pub lr: Vec<(FiniteECPoint, FiniteECPoint)>,
+    pub z_1: FieldElement,
+    pub z_2: FieldElement,
+    pub delta: FiniteECPoint,
+    pub challenge_polynomial_commitment: FiniteECPoint,
+}
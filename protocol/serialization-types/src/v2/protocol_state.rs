@@ -0,0 +1,29 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types related to the Berkeley protocol state
+
+use super::{blockchain_state::*, common::*, consensus_state::*};
+use serde::{Deserialize, Serialize};
+
+/// This structure can be thought of like the block header. It contains the most essential information of a block (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProtocolStateV2 {
+    /// Commitment to previous block (hash of previous protocol state hash and body hash)
+    pub previous_state_hash: HashV2,
+    /// The body of the protocol state
+    pub body: ProtocolStateBodyV2,
+}
+
+/// Body of the protocol state (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProtocolStateBodyV2 {
+    /// Genesis protocol state hash (used for hardforks)
+    pub genesis_state_hash: HashV2,
+    /// Ledger related state
+    pub blockchain_state: BlockchainStateV2,
+    /// Consensus related state
+    pub consensus_state: ConsensusStateV2,
+    /// Consensus constants
+    pub constants: ProtocolConstantsV2,
+}
@@ -0,0 +1,211 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types related to the Berkeley staged ledger diff, the body of a block
+
+#![allow(missing_docs)] // Don't actually know what many of the types fields are for yet
+
+use super::{blockchain_state::LocalStateV2, common::*, proof::*, zkapp_command::*};
+use serde::{Deserialize, Serialize};
+
+/// Body of a Berkeley block (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StagedLedgerDiffBodyV2 {
+    pub staged_ledger_diff: StagedLedgerDiffV2,
+}
+
+/// Diff between the current staged ledger and the proposed next one (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StagedLedgerDiffV2 {
+    pub diff: (StagedLedgerPreDiffV2, Option<StagedLedgerPreDiffV2>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StagedLedgerPreDiffV2 {
+    pub completed_works: Vec<TransactionSnarkWorkV2>,
+    pub commands: Vec<UserCommandWithStatusV2>,
+    pub coinbase: CoinBaseV2,
+    pub internal_command_statuses: Vec<TransactionStatusV2>,
+}
+
+/// Coinbase of a pre diff, the second pre diff never has two fee transfers (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum CoinBaseV2 {
+    Zero,
+    One(Option<CoinBaseFeeTransferV2>),
+    Two(Option<(CoinBaseFeeTransferV2, Option<CoinBaseFeeTransferV2>)>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CoinBaseFeeTransferV2 {
+    pub receiver_pk: PublicKeyV2,
+    pub fee: FeeV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct UserCommandWithStatusV2 {
+    pub data: UserCommandV2,
+    pub status: TransactionStatusV2,
+}
+
+/// User command, either a signed command or a zkApp command (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum UserCommandV2 {
+    SignedCommand(SignedCommandV2),
+    ZkappCommand(ZkappCommandV2),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TransactionStatusV2 {
+    Applied,
+    Failed(Vec<Vec<TransactionFailureV2>>),
+}
+
+/// Reason of a transaction failure (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum TransactionFailureV2 {
+    Predicate,
+    SourceNotPresent,
+    ReceiverNotPresent,
+    AmountInsufficientToCreateAccount,
+    CannotPayCreationFeeInToken,
+    SourceInsufficientBalance,
+    SourceMinimumBalanceViolation,
+    ReceiverAlreadyExists,
+    TokenOwnerNotCaller,
+    Overflow,
+    GlobalExcessOverflow,
+    LocalExcessOverflow,
+    LocalSupplyIncreaseOverflow,
+    GlobalSupplyIncreaseOverflow,
+    SignedCommandOnZkappAccount,
+    ZkappAccountNotPresent,
+    UpdateNotPermittedBalance,
+    UpdateNotPermittedTimingExistingAccount,
+    UpdateNotPermittedDelegate,
+    UpdateNotPermittedAppState,
+    UpdateNotPermittedVerificationKey,
+    UpdateNotPermittedSequenceState,
+    UpdateNotPermittedZkappUri,
+    UpdateNotPermittedTokenSymbol,
+    UpdateNotPermittedPermissions,
+    UpdateNotPermittedNonce,
+    UpdateNotPermittedVotingFor,
+    ZkappCommandReplayCheckFailed,
+    FeePayerNonceMustIncrease,
+    FeePayerMustBeSigned,
+    AccountBalancePreconditionUnsatisfied,
+    AccountNoncePreconditionUnsatisfied,
+    AccountReceiptChainHashPreconditionUnsatisfied,
+    AccountDelegatePreconditionUnsatisfied,
+    AccountSequenceStatePreconditionUnsatisfied,
+    AccountAppStatePreconditionUnsatisfied(i64),
+    AccountProvedStatePreconditionUnsatisfied,
+    AccountIsNewPreconditionUnsatisfied,
+    ProtocolStatePreconditionUnsatisfied,
+    IncorrectNonce,
+    InvalidFeeExcess,
+    Cancelled,
+}
+
+/// Payment or stake delegation signed by the fee payer (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandV2 {
+    pub payload: SignedCommandPayloadV2,
+    pub signer: PublicKeyV2,
+    pub signature: SignatureV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandPayloadV2 {
+    pub common: SignedCommandPayloadCommonV2,
+    pub body: SignedCommandPayloadBodyV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct SignedCommandPayloadCommonV2 {
+    pub fee: FeeV2,
+    pub fee_payer_pk: PublicKeyV2,
+    pub nonce: AccountNonceV2,
+    pub valid_until: GlobalSlotNumberV2,
+    pub memo: MemoV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SignedCommandPayloadBodyV2 {
+    Payment(PaymentPayloadV2),
+    StakeDelegation(StakeDelegationV2),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PaymentPayloadV2 {
+    pub source_pk: PublicKeyV2,
+    pub receiver_pk: PublicKeyV2,
+    pub amount: AmountV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum StakeDelegationV2 {
+    SetDelegate {
+        delegator: PublicKeyV2,
+        new_delegate: PublicKeyV2,
+    },
+}
+
+/// Snark work purchased by the block producer (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TransactionSnarkWorkV2 {
+    pub fee: FeeV2,
+    pub proofs: OneOrTwoV2<LedgerProofV2>,
+    pub prover: PublicKeyV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum OneOrTwoV2<T> {
+    One(Box<T>),
+    Two(Box<T>, Box<T>),
+}
+
+/// Transaction snark proving a ledger transition (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LedgerProofV2 {
+    pub statement: StatementV2,
+    pub proof: ProtocolStateProofV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StatementV2 {
+    pub source: RegistersV2,
+    pub target: RegistersV2,
+    pub supply_increase: SignedAmountV2,
+    pub fee_excess: FeeExcessV2,
+    pub sok_digest: Vec<u8>,
+}
+
+/// Registers of a transaction snark statement (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct RegistersV2 {
+    pub ledger: HashV2,
+    pub pending_coinbase_stack: PendingCoinbaseStackV2,
+    pub local_state: LocalStateV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PendingCoinbaseStackV2 {
+    pub data: HashV2,
+    pub state: StateStackV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StateStackV2 {
+    pub init: HashV2,
+    pub curr: HashV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeeExcessV2 {
+    pub fee_token_l: TokenIdV2,
+    pub fee_excess_l: SignedAmountV2,
+    pub fee_token_r: TokenIdV2,
+    pub fee_excess_r: SignedAmountV2,
+}
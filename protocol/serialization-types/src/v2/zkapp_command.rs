@@ -0,0 +1,245 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Types related to zkApp commands, the user commands introduced in Berkeley
+//! that update a forest of accounts and may be authorized by proofs
+
+#![allow(missing_docs)] // Don't actually know what many of the types fields are for yet
+
+use super::{common::*, proof::*};
+use crate::field_and_curve_elements::{FieldElement, FiniteECPoint};
+use serde::{Deserialize, Serialize};
+
+/// A zkApp command, a fee payer along with a forest of account updates (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ZkappCommandV2 {
+    pub fee_payer: FeePayerV2,
+    pub account_updates: Vec<CallForestNodeV2>,
+    pub memo: MemoV2,
+}
+
+/// Fee payer of a zkApp command, always authorized by a signature (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeePayerV2 {
+    pub body: FeePayerBodyV2,
+    pub authorization: SignatureV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct FeePayerBodyV2 {
+    pub public_key: PublicKeyV2,
+    pub fee: FeeV2,
+    pub valid_until: Option<GlobalSlotNumberV2>,
+    pub nonce: AccountNonceV2,
+}
+
+/// Node of the call forest, stack hashes are not sent over the wire (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CallForestNodeV2 {
+    pub elt: AccountUpdateTreeV2,
+    pub stack_hash: (),
+}
+
+/// An account update along with the account updates it calls (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountUpdateTreeV2 {
+    pub account_update: AccountUpdateV2,
+    pub account_update_digest: (),
+    pub calls: Vec<CallForestNodeV2>,
+}
+
+/// Update of a single account (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountUpdateV2 {
+    pub body: AccountUpdateBodyV2,
+    pub authorization: ControlV2,
+}
+
+/// Authorization of an account update (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum ControlV2 {
+    Proof(Box<ProtocolStateProofV2>),
+    Signature(SignatureV2),
+    NoneGiven,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct AccountUpdateBodyV2 {
+    pub public_key: PublicKeyV2,
+    pub token_id: TokenIdV2,
+    pub update: UpdateV2,
+    pub balance_change: SignedAmountV2,
+    pub increment_nonce: bool,
+    pub events: Vec<Vec<FieldElement>>,
+    pub sequence_events: Vec<Vec<FieldElement>>,
+    pub call_data: FieldElement,
+    pub preconditions: PreconditionsV2,
+    pub use_full_commitment: bool,
+    pub caller: CallTypeV2,
+    pub authorization_kind: AuthorizationKindV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum CallTypeV2 {
+    Call,
+    DelegateCall,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum AuthorizationKindV2 {
+    NoneGiven,
+    Signature,
+    Proof,
+}
+
+/// Either sets a field of the account to the given value or keeps it unchanged (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum SetOrKeepV2<T> {
+    Set(T),
+    Keep,
+}
+
+/// Either checks a precondition against the given value or ignores it (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum OrIgnoreV2<T> {
+    Check(T),
+    Ignore,
+}
+
+/// Closed interval, both bounds are inclusive (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ClosedIntervalV2<T> {
+    pub lower: T,
+    pub upper: T,
+}
+
+/// Precondition that a number lies in a closed interval (v2)
+pub type NumericPreconditionV2<T> = OrIgnoreV2<ClosedIntervalV2<T>>;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct UpdateV2 {
+    pub app_state: Vector8<SetOrKeepV2<FieldElement>>,
+    pub delegate: SetOrKeepV2<PublicKeyV2>,
+    pub verification_key: SetOrKeepV2<VerificationKeyWithHashV2>,
+    pub permissions: SetOrKeepV2<PermissionsV2>,
+    pub zkapp_uri: SetOrKeepV2<Vec<u8>>,
+    pub token_symbol: SetOrKeepV2<Vec<u8>>,
+    pub timing: SetOrKeepV2<TimingInfoV2>,
+    pub voting_for: SetOrKeepV2<HashV2>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKeyWithHashV2 {
+    pub data: VerificationKeyV2,
+    pub hash: FieldElement,
+}
+
+/// Side loaded verification key of a zkApp (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKeyV2 {
+    pub max_proofs_verified: ProofsVerifiedV2,
+    pub actual_wrap_domain_size: ProofsVerifiedV2,
+    pub wrap_index: VerificationKeyWrapIndexV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKeyWrapIndexV2 {
+    pub sigma_comm: Vector7<FiniteECPoint>,
+    pub coefficients_comm: Vector15<FiniteECPoint>,
+    pub generic_comm: FiniteECPoint,
+    pub psm_comm: FiniteECPoint,
+    pub complete_add_comm: FiniteECPoint,
+    pub mul_comm: FiniteECPoint,
+    pub emul_comm: FiniteECPoint,
+    pub endomul_scalar_comm: FiniteECPoint,
+}
+
+/// Authorization required to perform an action on an account (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum AuthRequiredV2 {
+    None,
+    Either,
+    Proof,
+    Signature,
+    Impossible,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PermissionsV2 {
+    pub edit_state: AuthRequiredV2,
+    pub send: AuthRequiredV2,
+    pub receive: AuthRequiredV2,
+    pub set_delegate: AuthRequiredV2,
+    pub set_permissions: AuthRequiredV2,
+    pub set_verification_key: AuthRequiredV2,
+    pub set_zkapp_uri: AuthRequiredV2,
+    pub edit_sequence_state: AuthRequiredV2,
+    pub set_token_symbol: AuthRequiredV2,
+    pub increment_nonce: AuthRequiredV2,
+    pub set_voting_for: AuthRequiredV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TimingInfoV2 {
+    pub initial_minimum_balance: AmountV2,
+    pub cliff_time: GlobalSlotNumberV2,
+    pub cliff_amount: AmountV2,
+    pub vesting_period: GlobalSlotNumberV2,
+    pub vesting_increment: AmountV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PreconditionsV2 {
+    pub network: NetworkPreconditionV2,
+    pub account: AccountPreconditionV2,
+}
+
+/// Preconditions on the protocol state of the block including the account update (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct NetworkPreconditionV2 {
+    pub snarked_ledger_hash: OrIgnoreV2<HashV2>,
+    pub timestamp: NumericPreconditionV2<BlockTimeV2>,
+    pub blockchain_length: NumericPreconditionV2<LengthV2>,
+    pub min_window_density: NumericPreconditionV2<LengthV2>,
+    pub last_vrf_output: (),
+    pub total_currency: NumericPreconditionV2<AmountV2>,
+    pub global_slot_since_hard_fork: NumericPreconditionV2<GlobalSlotNumberV2>,
+    pub global_slot_since_genesis: NumericPreconditionV2<GlobalSlotNumberV2>,
+    pub staking_epoch_data: EpochDataPreconditionV2,
+    pub next_epoch_data: EpochDataPreconditionV2,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochDataPreconditionV2 {
+    pub ledger: EpochLedgerPreconditionV2,
+    pub seed: OrIgnoreV2<HashV2>,
+    pub start_checkpoint: OrIgnoreV2<HashV2>,
+    pub lock_checkpoint: OrIgnoreV2<HashV2>,
+    pub epoch_length: NumericPreconditionV2<LengthV2>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct EpochLedgerPreconditionV2 {
+    pub hash: OrIgnoreV2<HashV2>,
+    pub total_currency: NumericPreconditionV2<AmountV2>,
+}
+
+/// Preconditions on the account being updated (v2)
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum AccountPreconditionV2 {
+    Full(Box<ZkappAccountPreconditionV2>),
+    Nonce(AccountNonceV2),
+    Accept,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ZkappAccountPreconditionV2 {
+    pub balance: NumericPreconditionV2<AmountV2>,
+    pub nonce: NumericPreconditionV2<AccountNonceV2>,
+    pub receipt_chain_hash: OrIgnoreV2<HashV2>,
+    pub delegate: OrIgnoreV2<PublicKeyV2>,
+    pub state: Vector8<OrIgnoreV2<FieldElement>>,
+    pub sequence_state: OrIgnoreV2<FieldElement>,
+    pub proved_state: OrIgnoreV2<bool>,
+    pub is_new: OrIgnoreV2<bool>,
+}
@@ -0,0 +1,101 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Side loaded verification key of a Pickles proof system
+//! see <https://github.com/MinaProtocol/mina/blob/compatible/src/lib/pickles/side_loaded_verification_key.ml>
+//!
+//! Checked against protocol/layouts/verification_key.json, there are no binary fixtures of it yet
+
+use crate::v1::*;
+use serde::{Deserialize, Serialize};
+use versioned::Versioned;
+
+/// Commitments to the polynomials of the constraint system, each one a list of curve points
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct PlonkVerificationKeyEvals {
+    /// Commitment to the first permutation polynomial
+    pub sigma_comm_0: Vec<FiniteECPoint>,
+    /// Commitment to the second permutation polynomial
+    pub sigma_comm_1: Vec<FiniteECPoint>,
+    /// Commitment to the third permutation polynomial
+    pub sigma_comm_2: Vec<FiniteECPoint>,
+    /// Commitment to the left input selector polynomial
+    pub ql_comm: Vec<FiniteECPoint>,
+    /// Commitment to the right input selector polynomial
+    pub qr_comm: Vec<FiniteECPoint>,
+    /// Commitment to the output selector polynomial
+    pub qo_comm: Vec<FiniteECPoint>,
+    /// Commitment to the multiplication selector polynomial
+    pub qm_comm: Vec<FiniteECPoint>,
+    /// Commitment to the constant selector polynomial
+    pub qc_comm: Vec<FiniteECPoint>,
+    /// Commitment to the first round constant polynomial
+    pub rcm_comm_0: Vec<FiniteECPoint>,
+    /// Commitment to the second round constant polynomial
+    pub rcm_comm_1: Vec<FiniteECPoint>,
+    /// Commitment to the third round constant polynomial
+    pub rcm_comm_2: Vec<FiniteECPoint>,
+    /// Commitment to the poseidon selector polynomial
+    pub psm_comm: Vec<FiniteECPoint>,
+    /// Commitment to the EC addition selector polynomial
+    pub add_comm: Vec<FiniteECPoint>,
+    /// Commitment to the first EC variable base scalar multiplication selector polynomial
+    pub mul1_comm: Vec<FiniteECPoint>,
+    /// Commitment to the second EC variable base scalar multiplication selector polynomial
+    pub mul2_comm: Vec<FiniteECPoint>,
+    /// Commitment to the first endoscalar multiplication selector polynomial
+    pub emul1_comm: Vec<FiniteECPoint>,
+    /// Commitment to the second endoscalar multiplication selector polynomial
+    pub emul2_comm: Vec<FiniteECPoint>,
+    /// Commitment to the third endoscalar multiplication selector polynomial
+    pub emul3_comm: Vec<FiniteECPoint>,
+}
+
+/// Commitments to the polynomials of the constraint system (v1)
+pub type PlonkVerificationKeyEvalsV1 = Versioned<PlonkVerificationKeyEvals, 1>;
+
+/// Evaluation domain of a polynomial
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub enum Domain {
+    /// Domain of the 2^n roots of unity, holds n
+    Pow2RootsOfUnity(u64),
+}
+
+/// Evaluation domain of a polynomial (v1)
+pub type DomainV1 = Versioned<Domain, 1>;
+
+/// Domains of a step circuit
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct Domains {
+    /// Domain of the circuit
+    pub h: DomainV1,
+    /// Domain of the public input
+    pub x: DomainV1,
+}
+
+/// Domains of a step circuit (v1)
+pub type DomainsV1 = Versioned<Domains, 1>;
+
+/// Size of the constraint system
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKeyData {
+    /// Number of constraints
+    pub constraints: u64,
+}
+
+/// Size of the constraint system (v1)
+pub type VerificationKeyDataV1 = Versioned<VerificationKeyData, 1>;
+
+/// Side loaded verification key
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct VerificationKey {
+    /// Commitments to the polynomials of the constraint system
+    pub commitments: PlonkVerificationKeyEvalsV1,
+    /// Domains of the step circuits
+    pub step_domains: Vec<DomainsV1>,
+    /// Size of the constraint system
+    pub data: VerificationKeyDataV1,
+}
+
+/// Side loaded verification key (v1)
+pub type VerificationKeyV1 = Versioned<Versioned<VerificationKey, 1>, 1>;
@@ -8,6 +8,10 @@
 //! This adds a unique prefix byte to each type of encoding so they cannot be confused
 //! (e.g. a hash cannot be used as an address). It also adds checksum bytes to the end.
 //!
+//! Only the version bytes of the v1 types are listed, those of the v2 token ids (0x1c),
+//! zkApp commands (0x1a) and verification keys (0x1b) are out of scope until the v2 types
+//! have base58 encodings.
+//!
 
 // TODO: Move the entire version_bytes.rs under crypto crate here
 
@@ -17,6 +21,9 @@ pub const LEDGER_HASH: u8 = 0x05;
 /// base58 version check byte for coinbase hash
 pub const COINBASE_HASH: u8 = 0x0c;
 
+/// base58 version check byte for receipt chain hash, shared with [COINBASE_HASH]
+pub const RECEIPT_CHAIN_HASH: u8 = 0x0c;
+
 /// base58 version check byte for epoch seed hash
 pub const EPOCH_SEED: u8 = 0x0d;
 
@@ -35,6 +42,12 @@ pub const STAGED_LEDGER_HASH_AUX_HASH: u8 = 0x0e;
 /// base58 version check byte for pending coinbase aux hash
 pub const STAGED_LEDGER_HASH_PENDING_COINBASE_AUX: u8 = 0x0f;
 
+/// base58 version check byte for transaction hash
+pub const TRANSACTION_HASH: u8 = 0x12;
+
+/// base58 version check byte for signed command (v1)
+pub const SIGNED_COMMAND_V1: u8 = 0x13;
+
 /// base58 version check byte for user command memo
 pub const USER_COMMAND_MEMO: u8 = 0x14;
 
@@ -46,3 +59,9 @@ pub const COINBASE_STACK_DATA: u8 = 0x17;
 
 /// base58 version check byte for coinbase stack hash
 pub const COINBASE_STACK_HASH: u8 = 0x18;
+
+/// base58 version check byte for private key
+pub const PRIVATE_KEY: u8 = 0x5a;
+
+/// base58 version check byte for public key (compressed non-zero curve point)
+pub const PUBLIC_KEY: u8 = 0xcb;
@@ -0,0 +1,297 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests {
+    use mina_serialization_types::{
+        consensus_state::VrfOutputTruncated, protocol_version::ProtocolVersion,
+        signatures::CompressedCurvePoint, snark_work::SgnType, v2::*,
+    };
+    use pretty_assertions::assert_eq;
+    use serde::{de::DeserializeOwned, Serialize};
+
+    fn roundtrip<T>(t: &T) -> anyhow::Result<Vec<u8>>
+    where
+        T: Serialize + DeserializeOwned + Eq + std::fmt::Debug,
+    {
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, t)?;
+        let recovered: T = bin_prot::from_reader_strict(bytes.as_slice())?;
+        assert_eq!(&recovered, t);
+        Ok(bytes)
+    }
+
+    fn pk(x: u8) -> PublicKeyV2 {
+        CompressedCurvePoint {
+            x: [x; 32],
+            is_odd: false,
+        }
+    }
+
+    fn epoch_data() -> EpochDataV2 {
+        EpochDataV2 {
+            ledger: EpochLedgerV2 {
+                hash: [1; 32],
+                total_currency: 1_000,
+            },
+            seed: [2; 32],
+            start_checkpoint: [3; 32],
+            lock_checkpoint: [4; 32],
+            epoch_length: 5,
+        }
+    }
+
+    fn local_state() -> LocalStateV2 {
+        LocalStateV2 {
+            stack_frame: [1; 32],
+            call_stack: [0; 32],
+            transaction_commitment: [0; 32],
+            full_transaction_commitment: [0; 32],
+            token_id: [1; 32],
+            excess: SignedAmountV2 {
+                magnitude: 0,
+                sgn: SgnType::Pos,
+            },
+            ledger: [2; 32],
+            success: false,
+            account_update_index: 3,
+            failure_status_tbl: vec![vec![], vec![TransactionFailureV2::Cancelled]],
+        }
+    }
+
+    fn protocol_state() -> ProtocolStateV2 {
+        ProtocolStateV2 {
+            previous_state_hash: [1; 32],
+            body: ProtocolStateBodyV2 {
+                genesis_state_hash: [2; 32],
+                blockchain_state: BlockchainStateV2 {
+                    staged_ledger_hash: StagedLedgerHashV2 {
+                        non_snark: NonSnarkStagedLedgerHashV2 {
+                            ledger_hash: [3; 32],
+                            aux_hash: vec![0; 32],
+                            pending_coinbase_aux: vec![0; 32],
+                        },
+                        pending_coinbase_hash: [4; 32],
+                    },
+                    genesis_ledger_hash: [5; 32],
+                    registers: BlockchainStateRegistersV2 {
+                        ledger: [6; 32],
+                        pending_coinbase_stack: (),
+                        local_state: local_state(),
+                    },
+                    timestamp: 1655755201000,
+                    body_reference: BodyReferenceV2([7; 32]),
+                },
+                consensus_state: ConsensusStateV2 {
+                    blockchain_length: 2,
+                    epoch_count: 0,
+                    min_window_density: 77,
+                    sub_window_densities: vec![1, 7, 7],
+                    last_vrf_output: VrfOutputTruncated(vec![8; 32]),
+                    total_currency: 1_000,
+                    curr_global_slot: GlobalSlotV2 {
+                        slot_number: 3,
+                        slots_per_epoch: 7140,
+                    },
+                    global_slot_since_genesis: 3,
+                    staking_epoch_data: epoch_data(),
+                    next_epoch_data: epoch_data(),
+                    has_ancestor_in_same_checkpoint_window: true,
+                    block_stake_winner: pk(9),
+                    block_creator: pk(10),
+                    coinbase_receiver: pk(11),
+                    supercharge_coinbase: true,
+                },
+                constants: ProtocolConstantsV2 {
+                    k: 290,
+                    slots_per_epoch: 7140,
+                    slots_per_sub_window: 7,
+                    delta: 0,
+                    genesis_state_timestamp: 1655755201000,
+                },
+            },
+        }
+    }
+
+    fn signed_command() -> UserCommandV2 {
+        UserCommandV2::SignedCommand(SignedCommandV2 {
+            payload: SignedCommandPayloadV2 {
+                common: SignedCommandPayloadCommonV2 {
+                    fee: 10_000_000,
+                    fee_payer_pk: pk(1),
+                    nonce: 3,
+                    valid_until: 4_000_000,
+                    memo: MemoV2(vec![1, 0]),
+                },
+                body: SignedCommandPayloadBodyV2::Payment(PaymentPayloadV2 {
+                    source_pk: pk(1),
+                    receiver_pk: pk(2),
+                    amount: 1_000_000_000,
+                }),
+            },
+            signer: pk(1),
+            signature: ([1; 32], [2; 32]),
+        })
+    }
+
+    fn zkapp_command() -> UserCommandV2 {
+        let ignore_epoch_data = EpochDataPreconditionV2 {
+            ledger: EpochLedgerPreconditionV2 {
+                hash: OrIgnoreV2::Ignore,
+                total_currency: OrIgnoreV2::Ignore,
+            },
+            seed: OrIgnoreV2::Ignore,
+            start_checkpoint: OrIgnoreV2::Ignore,
+            lock_checkpoint: OrIgnoreV2::Ignore,
+            epoch_length: OrIgnoreV2::Ignore,
+        };
+        let account_update = AccountUpdateV2 {
+            body: AccountUpdateBodyV2 {
+                public_key: pk(3),
+                token_id: [1; 32],
+                update: UpdateV2 {
+                    app_state: (
+                        [
+                            SetOrKeepV2::Set([1; 32]),
+                            SetOrKeepV2::Keep,
+                            SetOrKeepV2::Keep,
+                            SetOrKeepV2::Keep,
+                            SetOrKeepV2::Keep,
+                            SetOrKeepV2::Keep,
+                            SetOrKeepV2::Keep,
+                            SetOrKeepV2::Keep,
+                        ],
+                        (),
+                    ),
+                    delegate: SetOrKeepV2::Keep,
+                    verification_key: SetOrKeepV2::Keep,
+                    permissions: SetOrKeepV2::Keep,
+                    zkapp_uri: SetOrKeepV2::Set(b"https://minaprotocol.com".to_vec()),
+                    token_symbol: SetOrKeepV2::Keep,
+                    timing: SetOrKeepV2::Keep,
+                    voting_for: SetOrKeepV2::Keep,
+                },
+                balance_change: SignedAmountV2 {
+                    magnitude: 100,
+                    sgn: SgnType::Neg,
+                },
+                increment_nonce: false,
+                events: vec![vec![[1; 32], [2; 32]]],
+                sequence_events: vec![],
+                call_data: [0; 32],
+                preconditions: PreconditionsV2 {
+                    network: NetworkPreconditionV2 {
+                        snarked_ledger_hash: OrIgnoreV2::Ignore,
+                        timestamp: OrIgnoreV2::Ignore,
+                        blockchain_length: OrIgnoreV2::Check(ClosedIntervalV2 {
+                            lower: 0,
+                            upper: 100,
+                        }),
+                        min_window_density: OrIgnoreV2::Ignore,
+                        last_vrf_output: (),
+                        total_currency: OrIgnoreV2::Ignore,
+                        global_slot_since_hard_fork: OrIgnoreV2::Ignore,
+                        global_slot_since_genesis: OrIgnoreV2::Ignore,
+                        staking_epoch_data: ignore_epoch_data.clone(),
+                        next_epoch_data: ignore_epoch_data,
+                    },
+                    account: AccountPreconditionV2::Nonce(1),
+                },
+                use_full_commitment: true,
+                caller: CallTypeV2::Call,
+                authorization_kind: AuthorizationKindV2::Proof,
+            },
+            authorization: ControlV2::Proof(Box::default()),
+        };
+        UserCommandV2::ZkappCommand(ZkappCommandV2 {
+            fee_payer: FeePayerV2 {
+                body: FeePayerBodyV2 {
+                    public_key: pk(1),
+                    fee: 10_000_000,
+                    valid_until: None,
+                    nonce: 4,
+                },
+                authorization: ([3; 32], [4; 32]),
+            },
+            account_updates: vec![CallForestNodeV2 {
+                elt: AccountUpdateTreeV2 {
+                    account_update,
+                    account_update_digest: (),
+                    calls: vec![],
+                },
+                stack_hash: (),
+            }],
+            memo: MemoV2(vec![1, 0]),
+        })
+    }
+
+    fn block() -> BlockV2 {
+        BlockV2 {
+            header: HeaderV2 {
+                protocol_state: protocol_state(),
+                protocol_state_proof: Default::default(),
+                delta_block_chain_proof: ([1; 32], vec![[2; 32]]),
+                current_protocol_version: ProtocolVersion::default(),
+                proposed_protocol_version_opt: None,
+            },
+            body: StagedLedgerDiffBodyV2 {
+                staged_ledger_diff: StagedLedgerDiffV2 {
+                    diff: (
+                        StagedLedgerPreDiffV2 {
+                            completed_works: vec![],
+                            commands: vec![
+                                UserCommandWithStatusV2 {
+                                    data: signed_command(),
+                                    status: TransactionStatusV2::Applied,
+                                },
+                                UserCommandWithStatusV2 {
+                                    data: zkapp_command(),
+                                    status: TransactionStatusV2::Failed(vec![
+                                        vec![],
+                                        vec![TransactionFailureV2::AccountNoncePreconditionUnsatisfied],
+                                    ]),
+                                },
+                            ],
+                            coinbase: CoinBaseV2::One(Some(CoinBaseFeeTransferV2 {
+                                receiver_pk: pk(4),
+                                fee: 1,
+                            })),
+                            internal_command_statuses: vec![TransactionStatusV2::Applied],
+                        },
+                        None,
+                    ),
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn body_reference_is_encoded_as_string() -> anyhow::Result<()> {
+        let bytes = roundtrip(&BodyReferenceV2([7; 32]))?;
+        assert_eq!(bytes.len(), 33);
+        assert_eq!(bytes[0], 32);
+        Ok(())
+    }
+
+    #[test]
+    fn protocol_state_roundtrip() -> anyhow::Result<()> {
+        roundtrip(&protocol_state())?;
+        Ok(())
+    }
+
+    #[test]
+    fn user_commands_roundtrip() -> anyhow::Result<()> {
+        roundtrip(&signed_command())?;
+        roundtrip(&zkapp_command())?;
+        Ok(())
+    }
+
+    #[test]
+    fn block_roundtrip() -> anyhow::Result<()> {
+        let bytes = roundtrip(&block())?;
+        // Berkeley types are not prefixed with version bytes
+        let protocol_state_bytes = roundtrip(&protocol_state())?;
+        assert_eq!(&bytes[..protocol_state_bytes.len()], &protocol_state_bytes);
+        Ok(())
+    }
+}
@@ -2,16 +2,26 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use mina_serialization_types::v1::ExternalTransitionV1;
 use test_fixtures::TEST_BLOCKS;
 
 fn criterion_benchmark(c: &mut Criterion) {
+    let block = TEST_BLOCKS
+        .get("3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK.hex")
+        .unwrap();
     c.bench_function(
         "block deserialization: 3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK",
+        |b| b.iter(|| black_box(block).external_transitionv1().unwrap()),
+    );
+    c.bench_function(
+        "block deserialization (reader): 3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK",
         |b| {
-            let block = TEST_BLOCKS
-                .get("3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK.hex")
-                .unwrap();
-            b.iter(|| black_box(block).external_transitionv1().unwrap())
+            b.iter(|| {
+                bin_prot::from_reader_strict::<_, ExternalTransitionV1>(
+                    black_box(block).bytes.as_slice(),
+                )
+                .unwrap()
+            })
         },
     );
 }
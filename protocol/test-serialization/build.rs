@@ -0,0 +1,36 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+use bin_prot::value::layout::codegen::generate_types;
+use bin_prot::Layout;
+use serde::Deserialize;
+use std::{env, fs, path::Path};
+
+const EXTERNAL_TRANSITION_LAYOUT: &str = "../layouts/external_transition.json";
+
+// Generates the types for the external transition layout, the codegen tests compile them
+// and check them against the block fixtures
+fn main() -> anyhow::Result<()> {
+    println!("cargo:rerun-if-changed={}", EXTERNAL_TRANSITION_LAYOUT);
+    let json = fs::read_to_string(EXTERNAL_TRANSITION_LAYOUT)?;
+    // the layout is deeply nested so needs a larger stack than the default thread
+    let code = std::thread::Builder::new()
+        .stack_size(256 * 1024 * 1024)
+        .spawn(move || -> anyhow::Result<String> {
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            deserializer.disable_recursion_limit();
+            let layout = Layout::deserialize(&mut deserializer)?;
+            Ok(generate_types(&layout)?)
+        })?
+        .join()
+        .expect("Failed to generate types")?;
+    // the generated module is included into another one so cannot have inner attributes
+    let code: String = code
+        .lines()
+        .filter(|line| !line.starts_with("//!") && !line.starts_with("#!["))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let out_dir = env::var("OUT_DIR")?;
+    fs::write(Path::new(&out_dir).join("external_transition.rs"), code)?;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the types generated from protocol/layouts/external_transition.json by build.rs
+//! against the block fixtures
+//! Run with `cargo test -p test-serialization codegen`
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use test_fixtures::*;
+
+    #[allow(dead_code, clippy::large_enum_variant)]
+    mod generated {
+        include!(concat!(env!("OUT_DIR"), "/external_transition.rs"));
+    }
+
+    use generated::ExternalTransitionRawVersionedV1;
+
+    fn roundtrip(block: &BlockFixture) {
+        let transition: ExternalTransitionRawVersionedV1 =
+            bin_prot::from_slice_strict(&block.bytes).unwrap_or_else(|e| {
+                panic!(
+                    "Failed to decode {} with the generated types: {}",
+                    block.block_name, e
+                )
+            });
+        let mut output = Vec::new();
+        bin_prot::to_writer(&mut output, &transition).unwrap();
+        assert_eq!(
+            output, block.bytes,
+            "{} is not encoded to the same bytes",
+            block.block_name
+        );
+    }
+
+    #[test]
+    fn generated_types_roundtrip_blocks() {
+        // the generated types are deeply nested so need a larger stack than the default test thread
+        std::thread::Builder::new()
+            .stack_size(256 * 1024 * 1024)
+            .spawn(|| {
+                roundtrip(&GENESIS_BLOCK_MAINNET);
+                for block in TEST_BLOCKS.values() {
+                    roundtrip(block);
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+}
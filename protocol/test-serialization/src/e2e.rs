@@ -46,4 +46,18 @@ mod tests {
             assert_eq!(bytes.as_slice(), block.bytes.as_slice());
         }
     }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_block_serialized_size() {
+        let genesis_blocks = [
+            &*test_fixtures::GENESIS_BLOCK_MAINNET,
+            &*test_fixtures::GENESIS_BLOCK_DEVNET,
+        ];
+        for block in TEST_BLOCKS.values().chain(genesis_blocks) {
+            let versioned = block.external_transitionv1().unwrap();
+            let size = bin_prot::serialized_size(&versioned).unwrap();
+            assert_eq!(size, block.bytes.len(), "{}", block.block_name);
+        }
+    }
 }
@@ -35,6 +35,15 @@ mod tests {
         assert_eq!(genesis_fixture.bytes, output)
     }
 
+    #[test]
+    #[wasm_bindgen_test]
+    fn test_genesis_loose_roundtrip() {
+        let mut output: Vec<u8> = Vec::new();
+        bin_prot::to_writer_with_layout(&mut output, &GENESIS_BLOCK_MAINNET.value, &BLOCK_RULE)
+            .unwrap();
+        assert_eq!(GENESIS_BLOCK_MAINNET.bytes, output)
+    }
+
     #[test]
     #[wasm_bindgen_test]
     fn test_genesis_protocol_state_proof() {
@@ -13,7 +13,6 @@ mod tests {
     use num::BigUint;
     use pretty_assertions::assert_eq;
     use proof_systems::mina_hasher::{create_legacy, Hashable, Hasher};
-    use std::str::FromStr;
     use wasm_bindgen_test::*;
 
     #[test]
@@ -404,24 +403,11 @@ mod tests {
     macro_rules! json_serde_roundtrip {
         ($ty: ty, $ty_json: ty, $path: literal) => {
             (|| {
-                'outer: for (_, mut json) in test_fixtures::JSON_TEST_BLOCKS.iter() {
-                    if $path.len() > 0 {
-                        for p in $path.split('/') {
-                            json = match usize::from_str(p) {
-                                Ok(index) => {
-                                    if let Some(array) = json.as_array() {
-                                        if index >= array.len() {
-                                            continue 'outer;
-                                        }
-                                        &array[index]
-                                    } else {
-                                        panic!("Array expect");
-                                    }
-                                }
-                                _ => &json[p],
-                            };
-                        }
-                    }
+                for (_, json) in test_fixtures::JSON_TEST_BLOCKS.iter() {
+                    let json = match crate::tests::select_json_path(json, $path) {
+                        Some(json) => json,
+                        None => continue,
+                    };
                     let cs: $ty = {
                         let json_string = serde_json::to_string_pretty(json)?;
                         let json: $ty_json = serde_json::from_str(json_string.as_str())
@@ -0,0 +1,153 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validates the json fixtures against the JSON Schemas generated for the json types
+//! Run with `cargo test -p test-serialization json_schema`
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use crate::tests::select_json_path;
+    use jsonschema::JSONSchema;
+    use mina_serialization_types::json::*;
+    use schemars::{schema_for, JsonSchema};
+
+    fn compile<T: JsonSchema>() -> JSONSchema {
+        let schema = serde_json::to_value(schema_for!(T)).unwrap();
+        JSONSchema::compile(&schema).expect("Generated schema is not a valid JSON Schema")
+    }
+
+    // Validates the value at the path of every json fixture that has it against the schema of `T`
+    fn validate<T: JsonSchema>(path: &str) {
+        let schema = compile::<T>();
+        for (file_name, json) in test_fixtures::JSON_TEST_BLOCKS.iter() {
+            if let Some(json) = select_json_path(json, path) {
+                if let Err(errors) = schema.validate(json) {
+                    let errors: Vec<_> = errors
+                        .map(|e| format!("{} at {}", e, e.instance_path))
+                        .collect();
+                    panic!(
+                        "{file_name} {path} does not match the schema of {}:\n{}",
+                        T::schema_name(),
+                        errors.join("\n")
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn consensus_state_json_schema() {
+        validate::<ConsensusStateJson>("protocol_state/body/consensus_state");
+    }
+
+    #[test]
+    fn constants_json_schema() {
+        validate::<ProtocolConstantsJson>("protocol_state/body/constants");
+    }
+
+    #[test]
+    fn blockchain_state_json_schema() {
+        validate::<BlockchainStateJson>("protocol_state/body/blockchain_state");
+    }
+
+    #[test]
+    fn protocol_state_body_json_schema() {
+        validate::<ProtocolStateBodyJson>("protocol_state/body");
+    }
+
+    #[test]
+    fn protocol_state_json_schema() {
+        validate::<ProtocolStateJson>("protocol_state");
+    }
+
+    #[test]
+    fn protocol_state_proof_json_schema() {
+        validate::<ProtocolStateProofBase64Json>("protocol_state_proof");
+    }
+
+    #[test]
+    fn delta_transition_chain_proof_json_schema() {
+        validate::<DeltaTransitionChainProofJson>("delta_transition_chain_proof");
+    }
+
+    #[test]
+    fn completed_works_json_schema() {
+        validate::<TransactionSnarkWorkJson>("staged_ledger_diff/diff/0/completed_works/0");
+    }
+
+    #[test]
+    fn completed_works_proofs_statement_json_schema() {
+        validate::<StatementJson>("staged_ledger_diff/diff/0/completed_works/0/proofs/1/statement");
+    }
+
+    #[test]
+    fn completed_works_proofs_proof_json_schema() {
+        validate::<ProtocolStateProofJson>(
+            "staged_ledger_diff/diff/0/completed_works/0/proofs/1/proof",
+        );
+    }
+
+    #[test]
+    fn commands_json_schema() {
+        validate::<UserCommandWithStatusJson>("staged_ledger_diff/diff/0/commands/0");
+        validate::<UserCommandJson>("staged_ledger_diff/diff/0/commands/2/data");
+        validate::<TransactionStatusJson>("staged_ledger_diff/diff/0/commands/0/status");
+    }
+
+    #[test]
+    fn internal_command_balances_json_schema() {
+        validate::<InternalCommandBalanceDataJson>(
+            "staged_ledger_diff/diff/0/internal_command_balances/0",
+        );
+    }
+
+    #[test]
+    fn coinbase_json_schema() {
+        validate::<CoinBaseJson>("staged_ledger_diff/diff/0/coinbase");
+    }
+
+    #[test]
+    fn staged_ledger_diff_json_schema() {
+        validate::<StagedLedgerDiffJson>("staged_ledger_diff");
+    }
+
+    #[test]
+    fn block_json_schema() {
+        validate::<ExternalTransitionJson>("");
+    }
+
+    #[test]
+    fn json_schema_rejects_invalid_encodings() {
+        let schema = compile::<ExternalTransitionJson>();
+        let (_, block) = test_fixtures::JSON_TEST_BLOCKS.iter().next().unwrap();
+        assert!(schema.is_valid(block));
+
+        let invalid = |path: &str, value: serde_json::Value| {
+            let mut block = block.clone();
+            *block.pointer_mut(path).unwrap() = value;
+            assert!(!schema.is_valid(&block), "{path} should be invalid");
+        };
+        // integers are decimal strings
+        invalid(
+            "/protocol_state/body/consensus_state/blockchain_length",
+            1.into(),
+        );
+        // hashes and public keys are base58
+        invalid("/protocol_state/previous_state_hash", "0x1234".into());
+        invalid(
+            "/protocol_state/body/consensus_state/block_creator",
+            "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ".into(),
+        );
+        // proofs are url safe base64
+        invalid("/protocol_state_proof", "a+b/".into());
+        // enums are list tagged
+        invalid(
+            "/staged_ledger_diff/diff/0/coinbase",
+            serde_json::json!({ "Zero": null }),
+        );
+        invalid(
+            "/staged_ledger_diff/diff/0/coinbase",
+            serde_json::json!(["Three"]),
+        );
+    }
+}
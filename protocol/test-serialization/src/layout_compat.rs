@@ -0,0 +1,183 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the serialization types against the layouts in protocol/layouts
+//! Run with `cargo test -p test-serialization layout_compat`
+
+#[cfg(test)]
+mod tests {
+    use bin_prot::error::{Error, Result};
+    use bin_prot::value::layout::compat::check_type;
+    use bin_prot::Layout;
+    use mina_serialization_types::v1::*;
+    use serde::de::DeserializeOwned;
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+
+    // Layouts that do not have a matching type yet
+    const UNCHECKED_LAYOUTS: &[&str] = &[];
+
+    const CHECKED_LAYOUTS: &[&str] = &[
+        "external_transition.json",
+        "protocol_state.json",
+        "snark_work.json",
+        "verification_key.json",
+    ];
+
+    fn check<T: DeserializeOwned + 'static>(layout: &'static str) -> Result<()> {
+        check_patched::<T>(layout, &[])
+    }
+
+    // Edits the summands of every sum type with the given module path before the check
+    type Patch = (&'static str, fn(&mut Vec<Value>));
+
+    // Checks the layout with the known differences to the type patched out, so that everything
+    // else still has to match
+    fn check_patched<T: DeserializeOwned + 'static>(
+        layout: &'static str,
+        patches: &'static [Patch],
+    ) -> Result<()> {
+        // the layouts are deeply nested so need a larger stack than the default test thread
+        std::thread::Builder::new()
+            .stack_size(256 * 1024 * 1024)
+            .spawn(move || {
+                let mut deserializer = serde_json::Deserializer::from_str(layout);
+                deserializer.disable_recursion_limit();
+                let mut layout = Value::deserialize(&mut deserializer).unwrap();
+                for (module_path, patch) in patches {
+                    let patched = patch_sum(&mut layout, module_path, *patch);
+                    assert!(patched > 0, "{} not found in the layout", module_path);
+                }
+                let layout = Layout::deserialize(layout).unwrap();
+                check_type::<T>(&layout.bin_prot_rule)
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    // Applies the patch to every sum type with the module path, returns how many were patched
+    fn patch_sum(json: &mut Value, module_path: &str, patch: fn(&mut Vec<Value>)) -> usize {
+        let mut patched = 0;
+        if let Value::Object(map) = json {
+            if map.get("source_module_path") == Some(&Value::from(module_path)) {
+                if let Some(Value::Array(summands)) =
+                    map.get_mut("ref_rule").and_then(|rule| rule.get_mut(1))
+                {
+                    patch(summands);
+                    patched += 1;
+                }
+            }
+        }
+        let children: Vec<&mut Value> = match json {
+            Value::Object(map) => map.values_mut().collect(),
+            Value::Array(values) => values.iter_mut().collect(),
+            _ => vec![],
+        };
+        children
+            .into_iter()
+            .map(|v| patch_sum(v, module_path, patch))
+            .sum::<usize>()
+            + patched
+    }
+
+    // Only the last constructor can be removed without changing the indices of the others
+    fn remove_last(summands: &mut Vec<Value>, ctor_name: &str) {
+        let last = summands.pop().unwrap();
+        assert_eq!(last["ctor_name"], ctor_name);
+    }
+
+    fn ctor_args<'a>(summands: &'a mut [Value], ctor_name: &str) -> &'a mut Value {
+        summands
+            .iter_mut()
+            .find(|s| s["ctor_name"] == ctor_name)
+            .map(|s| &mut s["ctor_args"])
+            .unwrap()
+    }
+
+    // Known differences between ExternalTransitionV1 and the layout
+    // FIXME: Remove a patch once the type matches the layout
+    const EXTERNAL_TRANSITION_PATCHES: &[Patch] = &[
+        // UserCommand does not have the Snapp_command variant
+        ("User_command.Poly.Stable.V1", |summands| {
+            remove_last(summands, "Snapp_command")
+        }),
+        // SignedCommandPayloadBody does not have the token command variants
+        (
+            "Signed_command_payload.Body.Binable_arg.Stable.V1",
+            |summands| {
+                remove_last(summands, "Mint_tokens");
+                remove_last(summands, "Create_token_account");
+                remove_last(summands, "Create_new_token");
+            },
+        ),
+        // TransactionStatus::Failed holds a list of failures instead of one
+        ("Transaction_status.Stable.V1", |summands| {
+            let args = ctor_args(summands, "Failed");
+            args[0] = json!(["List", args[0].take()]);
+        }),
+        // CoinBase::Two holds two options instead of an optional pair of a transfer and an option
+        ("Staged_ledger_diff.At_most_two.Stable.V1", |summands| {
+            let args = ctor_args(summands, "Two");
+            let pair = args[0][1][1].take();
+            *args = json!([["Option", pair[0]], pair[1]]);
+        }),
+        // the second pre diff reuses CoinBase that has a Two variant the layout does not have
+        ("Staged_ledger_diff.At_most_one.Stable.V1", |summands| {
+            let one = ctor_args(summands, "One")[0].clone();
+            summands
+                .push(json!({ "ctor_name": "Two", "index": 2, "ctor_args": [one.clone(), one] }));
+        }),
+    ];
+
+    const EXTERNAL_TRANSITION_LAYOUT: &str = include_str!("../../layouts/external_transition.json");
+
+    #[test]
+    fn test_external_transition_layout() {
+        // UserCommandV1 does not have the Snapp_command variant, the check stops there
+        match check::<ExternalTransitionV1>(EXTERNAL_TRANSITION_LAYOUT) {
+            Err(Error::TypeLayoutMismatch { path, .. }) => assert_eq!(
+                path.to_string(),
+                "t.staged_ledger_diff.t.diff.t[0].t.t.commands[0].t.data.t.t"
+            ),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        // everything but the known differences has to match
+        check_patched::<ExternalTransitionV1>(
+            EXTERNAL_TRANSITION_LAYOUT,
+            EXTERNAL_TRANSITION_PATCHES,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_protocol_state_layout() {
+        check::<ProtocolStateV1>(include_str!("../../layouts/protocol_state.json")).unwrap();
+    }
+
+    #[test]
+    fn test_snark_work_layout() {
+        check::<TransactionSnarkWorkV1>(include_str!("../../layouts/snark_work.json")).unwrap();
+    }
+
+    #[test]
+    fn test_verification_key_layout() {
+        check::<VerificationKeyV1>(include_str!("../../layouts/verification_key.json")).unwrap();
+    }
+
+    #[test]
+    fn test_all_layouts_are_listed() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../layouts");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let name = entry.unwrap().file_name().into_string().unwrap();
+            if name.ends_with(".json") {
+                assert!(
+                    CHECKED_LAYOUTS.contains(&name.as_str())
+                        || UNCHECKED_LAYOUTS.contains(&name.as_str()),
+                    "Add a check for {} to layout_compat.rs",
+                    name
+                );
+            }
+        }
+    }
+}
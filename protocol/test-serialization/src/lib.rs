@@ -6,10 +6,14 @@
 #[cfg(all(test, feature = "browser"))]
 wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
+mod codegen;
 mod e2e;
 mod fuzz;
 mod genesis;
 mod json;
+mod json_schema;
+mod layout_compat;
+mod roundtrip;
 #[allow(non_snake_case)]
 mod test_3NKaBJsN1SehD6iJwRwJSFmVzJg5DXSUQVgnMxtH4eer4aF5BrDK;
 
@@ -49,4 +53,24 @@ mod tests {
         }
         val
     }
+
+    pub(crate) fn select_json_path<'a>(
+        json: &'a serde_json::Value,
+        path: impl AsRef<str>,
+    ) -> Option<&'a serde_json::Value> {
+        // pull out the json value corresponding to the path, None if an array index is out of bounds
+        // will panic if the path is invalid
+        let path_ref = path.as_ref();
+        if path_ref.is_empty() {
+            return Some(json);
+        }
+        let mut val = json;
+        for p in path_ref.split('/') {
+            val = match usize::from_str(p) {
+                Ok(index) => val.as_array().expect("Array expected").get(index)?,
+                _ => &val[p],
+            };
+        }
+        Some(val)
+    }
 }
@@ -0,0 +1,197 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//! Property tests that arbitrary values survive bin-prot and json round trips,
+//! and the conversions between the base types and the serialization types
+//! Run with `cargo test -p test-serialization roundtrip`
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use mina_rs_base::types::*;
+    use mina_serialization_types::{json::*, v1::*};
+    use proptest::{prelude::*, test_runner::TestRunner};
+    use serde::{de::DeserializeOwned, Serialize};
+    use std::fmt::Debug;
+
+    // Values nest proofs and lists of commands, so fewer cases than the default keep the tests fast
+    const CASES: u32 = 32;
+
+    // Runs the property test for arbitrary values of `T`, the values are deeply nested
+    // so need a larger stack than the default test thread to be generated and compared
+    fn check<T: Arbitrary + 'static>(test: fn(&T) -> Result<(), TestCaseError>) {
+        std::thread::Builder::new()
+            .stack_size(256 * 1024 * 1024)
+            .spawn(move || {
+                let mut runner = TestRunner::new(ProptestConfig::with_cases(CASES));
+                if let Err(e) = runner.run(&any::<T>(), |t| test(&t)) {
+                    panic!("{}", e);
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    fn binprot_roundtrip<T>(t: &T) -> Result<(), TestCaseError>
+    where
+        T: Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, t)?;
+        let t_from_bytes: T = bin_prot::from_reader_strict(bytes.as_slice())?;
+        prop_assert_eq!(&t_from_bytes, t);
+        Ok(())
+    }
+
+    fn json_roundtrip<T, TJson>(t: &T) -> Result<(), TestCaseError>
+    where
+        T: Clone + From<TJson> + PartialEq + Debug,
+        TJson: From<T> + Serialize + DeserializeOwned,
+    {
+        let json = serde_json::to_string(&TJson::from(t.clone()))?;
+        let t_json: TJson = serde_json::from_str(&json)?;
+        prop_assert_eq!(&T::from(t_json), t);
+        Ok(())
+    }
+
+    fn base_roundtrip<T, TVersioned>(t: &T) -> Result<(), TestCaseError>
+    where
+        T: Clone + From<TVersioned> + PartialEq + Debug,
+        TVersioned: Clone + From<T> + PartialEq + Debug,
+    {
+        let versioned = TVersioned::from(t.clone());
+        let t_from_versioned = T::from(versioned.clone());
+        prop_assert_eq!(&t_from_versioned, t);
+        prop_assert_eq!(TVersioned::from(t_from_versioned), versioned);
+        Ok(())
+    }
+
+    macro_rules! binprot_roundtrip_tests {
+        ($($name:ident: $t:ty,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    check::<$t>(binprot_roundtrip);
+                }
+            )*
+        };
+    }
+
+    macro_rules! json_roundtrip_tests {
+        ($($name:ident: $t:ty => $t_json:ty,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    check::<$t>(|t| {
+                        binprot_roundtrip(t)?;
+                        json_roundtrip::<_, $t_json>(t)
+                    });
+                }
+            )*
+        };
+    }
+
+    macro_rules! base_roundtrip_tests {
+        ($($name:ident: $t:ty => $t_versioned:ty,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    check::<$t>(base_roundtrip::<_, $t_versioned>);
+                }
+            )*
+        };
+    }
+
+    binprot_roundtrip_tests!(
+        account_binprot_roundtrip: AccountV1,
+        epoch_data_binprot_roundtrip: EpochDataV1,
+        global_slot_binprot_roundtrip: GlobalSlotV1,
+    );
+
+    json_roundtrip_tests!(
+        protocol_state_roundtrip: ProtocolStateV1 => ProtocolStateJson,
+        protocol_state_body_roundtrip: ProtocolStateBodyV1 => ProtocolStateBodyJson,
+        blockchain_state_roundtrip: BlockchainStateV1 => BlockchainStateJson,
+        consensus_state_roundtrip: ConsensusStateV1 => ConsensusStateJson,
+        protocol_constants_roundtrip: ProtocolConstantsV1 => ProtocolConstantsJson,
+        protocol_version_roundtrip: ProtocolVersionV1 => ProtocolVersionJson,
+        protocol_state_proof_roundtrip: ProtocolStateProofV1 => ProtocolStateProofJson,
+        protocol_state_proof_base64_roundtrip: ProtocolStateProofV1 => ProtocolStateProofBase64Json,
+        proof_statement_roundtrip: ProofStatementV1 => ProofStatementJson,
+        proof_roundtrip: ProofV1 => ProofJson,
+        proof_messages_roundtrip: ProofMessagesV1 => ProofMessagesJson,
+        proof_openings_roundtrip: ProofOpeningsV1 => ProofOpeningsJson,
+        proof_evaluations_roundtrip: ProofEvaluationsV1 => ProofEvaluationsJson,
+        prev_evals_roundtrip: PrevEvalsV1 => PrevEvalsJson,
+        opening_proof_roundtrip: OpeningProofV1 => OpeningProofJson,
+        staged_ledger_diff_roundtrip: StagedLedgerDiffV1 => StagedLedgerDiffJson,
+        user_command_with_status_roundtrip: UserCommandWithStatusV1 => UserCommandWithStatusJson,
+        signed_command_roundtrip: SignedCommandV1 => SignedCommandJson,
+        coinbase_roundtrip: CoinBaseV1 => CoinBaseJson,
+        internal_command_balance_data_roundtrip: InternalCommandBalanceDataV1 => InternalCommandBalanceDataJson,
+        transaction_snark_work_roundtrip: TransactionSnarkWorkV1 => TransactionSnarkWorkJson,
+        statement_roundtrip: StatementV1 => StatementJson,
+        delta_transition_chain_proof_roundtrip: mina_serialization_types::v1::DeltaTransitionChainProof => DeltaTransitionChainProofJson,
+        public_key_roundtrip: PublicKeyV1 => PublicKeyJson,
+        signature_roundtrip: SignatureV1 => SignatureJson,
+    );
+
+    // The json of a block does not carry the protocol versions, which are set to their defaults
+    fn without_protocol_versions(mut t: ExternalTransitionV1) -> ExternalTransitionV1 {
+        t.t.current_protocol_version = Default::default();
+        t.t.proposed_protocol_version_opt = None;
+        t
+    }
+
+    #[test]
+    fn external_transition_roundtrip() {
+        check::<ExternalTransitionV1>(|t| {
+            binprot_roundtrip(t)?;
+            json_roundtrip::<_, ExternalTransitionJson>(&without_protocol_versions(t.clone()))
+        });
+    }
+
+    // u64 values are written with the Int64 encoding of Mina's UInt64, values above i64::MAX
+    // are negative integers on the wire and have to be read back into the same block
+    #[test]
+    fn external_transition_u64_above_i64_max_roundtrip() -> anyhow::Result<()> {
+        let mut block = test_fixtures::GENESIS_BLOCK_MAINNET.external_transitionv1()?;
+        let body = &mut block.t.protocol_state.t.t.body.t.t;
+        body.blockchain_state.t.t.timestamp.t.t = u64::MAX;
+        body.consensus_state.t.t.total_currency.t.t = i64::MAX as u64 + 1;
+
+        let mut bytes = Vec::new();
+        bin_prot::to_writer(&mut bytes, &block)?;
+        let decoded: ExternalTransitionV1 = bin_prot::from_reader_strict(bytes.as_slice())?;
+        assert_eq!(decoded, block);
+
+        let base = ExternalTransition::from(decoded);
+        let body = &base.protocol_state.body;
+        assert_eq!(body.blockchain_state.timestamp, BlockTime(u64::MAX));
+        assert_eq!(
+            body.consensus_state.total_currency,
+            Amount(i64::MAX as u64 + 1)
+        );
+        assert_eq!(ExternalTransitionV1::from(base), block);
+        Ok(())
+    }
+
+    base_roundtrip_tests!(
+        external_transition_base_roundtrip: ExternalTransition => ExternalTransitionV1,
+        protocol_state_base_roundtrip: ProtocolStateLegacy => ProtocolStateV1,
+        protocol_state_body_base_roundtrip: ProtocolStateBodyLegacy => ProtocolStateBodyV1,
+        blockchain_state_base_roundtrip: BlockchainStateLegacy => BlockchainStateV1,
+        consensus_state_base_roundtrip: ConsensusState => ConsensusStateV1,
+        protocol_constants_base_roundtrip: ProtocolConstants => ProtocolConstantsV1,
+        epoch_data_base_roundtrip: EpochData => EpochDataV1,
+        global_slot_base_roundtrip: GlobalSlot => GlobalSlotV1,
+        protocol_version_base_roundtrip: ProtocolVersion => ProtocolVersionV1,
+        protocol_state_proof_base_roundtrip: ProtocolStateProof => ProtocolStateProofV1,
+        proof_statement_base_roundtrip: ProofStatement => ProofStatementV1,
+        delta_transition_chain_proof_base_roundtrip: mina_rs_base::types::DeltaTransitionChainProof => mina_serialization_types::v1::DeltaTransitionChainProof,
+        staged_ledger_diff_base_roundtrip: StagedLedgerDiff => StagedLedgerDiffV1,
+        user_command_with_status_base_roundtrip: UserCommandWithStatus => UserCommandWithStatusV1,
+        signed_command_base_roundtrip: SignedCommand => SignedCommandV1,
+        transaction_snark_work_base_roundtrip: TransactionSnarkWork => TransactionSnarkWorkV1,
+    );
+}
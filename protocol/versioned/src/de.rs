@@ -0,0 +1,314 @@
+// Copyright 2020 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0
+
+//!
+//! Deserialization of versioned types with checking of the version on the wire
+//!
+//! The version read for a `Versioned<T, V>` is compared with `V`. By default a mismatch is an error,
+//! the check can be relaxed to log a warning instead for a single deserialization with
+//! [deserialize_with_version_check]. Types that accept more than one version implement [MultiVersioned]
+//! (usually via [crate::versioned_enum]) and decode each version into its own inner type. A version
+//! they do not know is always an error.
+//!
+//! Fields other than `version` and `t` are ignored in self-describing formats such as JSON.
+//!
+
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Versioned;
+
+const FIELDS: &[&str] = &["version", "t"];
+
+/// How a version that differs from the expected version is handled when deserializing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCheck {
+    /// Fail with a [VersionMismatch] error
+    Strict,
+    /// Log a warning and decode the inner type anyway
+    Warn,
+}
+
+thread_local! {
+    // check of the deserialization running on this thread, set by deserialize_with_version_check
+    static VERSION_CHECK: Cell<VersionCheck> = const { Cell::new(VersionCheck::Strict) };
+}
+
+/// Deserialize a value from the deserializer, handling version mismatches of the `Versioned`
+/// types within it as given by `check`. Deserialization elsewhere, including on other threads,
+/// stays [VersionCheck::Strict]
+///
+/// ```
+/// use versioned::{deserialize_with_version_check, VersionCheck, Versioned};
+///
+/// let json = r#"{"version":2,"t":7}"#;
+/// assert!(serde_json::from_str::<Versioned<i32, 1>>(json).is_err());
+/// let mut de = serde_json::Deserializer::from_str(json);
+/// let v: Versioned<i32, 1> = deserialize_with_version_check(&mut de, VersionCheck::Warn).unwrap();
+/// assert_eq!(v.t, 7);
+/// ```
+pub fn deserialize_with_version_check<'de, T, D>(
+    deserializer: D,
+    check: VersionCheck,
+) -> Result<T, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    // restores the check of an enclosing call, also when deserialization panics
+    struct Restore(VersionCheck);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            VERSION_CHECK.with(|c| c.set(self.0));
+        }
+    }
+
+    let _restore = Restore(VERSION_CHECK.with(|c| c.replace(check)));
+    T::deserialize(deserializer)
+}
+
+/// The version read for a type is not one of the versions it accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionMismatch {
+    /// Name of the type being deserialized
+    pub type_name: &'static str,
+    /// Versions the type accepts
+    pub expected: Vec<u16>,
+    /// Version that was read
+    pub found: u16,
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unexpected version of {}. Expected: ", self.type_name)?;
+        match self.expected.as_slice() {
+            [version] => write!(f, "{}", version)?,
+            versions => write!(f, "one of {:?}", versions)?,
+        }
+        write!(f, ", found: {}", self.found)
+    }
+}
+
+impl std::error::Error for VersionMismatch {}
+
+fn check_version<T, E: de::Error>(expected: u16, found: u16) -> Result<(), E> {
+    if expected == found {
+        return Ok(());
+    }
+    let mismatch = VersionMismatch {
+        type_name: std::any::type_name::<T>(),
+        expected: vec![expected],
+        found,
+    };
+    match VERSION_CHECK.with(Cell::get) {
+        VersionCheck::Strict => Err(E::custom(mismatch)),
+        VersionCheck::Warn => {
+            log::warn!("{}", mismatch);
+            Ok(())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Version,
+    T,
+    #[serde(other)]
+    Other,
+}
+
+impl<'de, T, const V: u16> Deserialize<'de> for Versioned<T, V>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("Versioned", FIELDS, VersionedVisitor(PhantomData))
+    }
+}
+
+struct VersionedVisitor<T, const V: u16>(PhantomData<T>);
+
+impl<'de, T, const V: u16> Visitor<'de> for VersionedVisitor<T, V>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Versioned<T, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "version {} of {}", V, std::any::type_name::<T>())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let version = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        // checked before the inner type so a mismatch is reported rather than a decoding error
+        check_version::<T, A::Error>(V, version)?;
+        let t = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(Versioned { version, t })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let (mut version, mut t) = (None, None);
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Version if version.is_none() => version = Some(map.next_value()?),
+                Field::T if t.is_none() => t = Some(map.next_value()?),
+                Field::Version => return Err(de::Error::duplicate_field("version")),
+                Field::T => return Err(de::Error::duplicate_field("t")),
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+        check_version::<T, A::Error>(V, version)?;
+        let t = t.ok_or_else(|| de::Error::missing_field("t"))?;
+        Ok(Versioned { version, t })
+    }
+}
+
+/// A type encoded like a `Versioned` wrapper whose inner type depends on the version,
+/// usually implemented for an enum with one variant per version by [crate::versioned_enum]
+pub trait MultiVersioned: Sized {
+    /// The versions that can be decoded
+    const VERSIONS: &'static [u16];
+
+    /// The version this value is encoded with
+    fn version(&self) -> u16;
+
+    /// Deserialize the inner type of the given version, which is one of `VERSIONS`
+    fn deserialize_version<'de, D>(version: u16, deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+
+    /// Serialize the inner type of this value
+    fn serialize_inner<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// Serialize a [MultiVersioned] value as its version followed by its inner value
+pub fn serialize_multi_versioned<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: MultiVersioned,
+    S: Serializer,
+{
+    struct Inner<'a, T>(&'a T);
+
+    impl<'a, T: MultiVersioned> Serialize for Inner<'a, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize_inner(serializer)
+        }
+    }
+
+    let mut state = serializer.serialize_struct("Versioned", 2)?;
+    state.serialize_field("version", &value.version())?;
+    state.serialize_field("t", &Inner(value))?;
+    state.end()
+}
+
+/// Deserialize a [MultiVersioned] value by reading its version and then the inner type of that version
+pub fn deserialize_multi_versioned<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: MultiVersioned,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_struct("Versioned", FIELDS, MultiVersionedVisitor(PhantomData))
+}
+
+fn accepted_version<T: MultiVersioned, E: de::Error>(found: u16) -> Result<u16, E> {
+    if T::VERSIONS.contains(&found) {
+        Ok(found)
+    } else {
+        Err(E::custom(VersionMismatch {
+            type_name: std::any::type_name::<T>(),
+            expected: T::VERSIONS.to_vec(),
+            found,
+        }))
+    }
+}
+
+struct MultiVersionedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: MultiVersioned> Visitor<'de> for MultiVersionedVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "one of versions {:?} of {}",
+            T::VERSIONS,
+            std::any::type_name::<T>()
+        )
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let version = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let version = accepted_version::<T, A::Error>(version)?;
+        seq.next_element_seed(VersionSeed(version, PhantomData))?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        // the inner type depends on the version so it must come before `t`
+        let (mut version, mut value) = (None, None);
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Version if version.is_none() => {
+                    version = Some(accepted_version::<T, A::Error>(map.next_value()?)?)
+                }
+                Field::T if value.is_none() => {
+                    let version = version.ok_or_else(|| de::Error::missing_field("version"))?;
+                    value = Some(map.next_value_seed(VersionSeed(version, PhantomData))?)
+                }
+                Field::Version => return Err(de::Error::duplicate_field("version")),
+                Field::T => return Err(de::Error::duplicate_field("t")),
+                Field::Other => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        version.ok_or_else(|| de::Error::missing_field("version"))?;
+        value.ok_or_else(|| de::Error::missing_field("t"))
+    }
+}
+
+struct VersionSeed<T>(u16, PhantomData<T>);
+
+impl<'de, T: MultiVersioned> DeserializeSeed<'de> for VersionSeed<T> {
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize_version(self.0, deserializer)
+    }
+}
@@ -8,16 +8,31 @@
 //! compatibility if parts of the wire protocol change. This simple wrapper type ensures that this information
 //! is included in the serialized output in an indentical way to the mina reference implementation.
 //!
+//! When deserializing, the version read is checked against the version of the wrapper so data of another
+//! version is rejected instead of being decoded into the wrong type. Use [deserialize_with_version_check] to
+//! only log a warning instead, and [versioned_enum] for types that accept several versions.
+//!
 
 #![deny(warnings)]
 #![deny(missing_docs)]
 
+mod de;
 pub mod macros;
 
-use serde::{Deserialize, Serialize};
+pub use de::{
+    deserialize_multi_versioned, deserialize_with_version_check, serialize_multi_versioned,
+    MultiVersioned, VersionCheck, VersionMismatch,
+};
+
+// used by the macros
+#[doc(hidden)]
+pub use serde;
+
+use serde::Serialize;
 
-/// A generic version wrapper around another type
-#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+/// A generic version wrapper around another type.
+/// Deserialization checks that the version read is `V`, see [VersionCheck]
+#[derive(Debug, Eq, PartialEq, Serialize, Clone)]
 pub struct Versioned<T, const V: u16> {
     /// Version byte to be encoded first when the whole wrapper is serialized
     pub version: u16,
@@ -124,6 +139,21 @@ impl<T, const V1: u16, const V2: u16, const V3: u16, const V4: u16>
     }
 }
 
+/// Generates arbitrary inner values, the version is always `V`
+#[cfg(feature = "arbitrary")]
+impl<T, const V: u16> proptest::arbitrary::Arbitrary for Versioned<T, V>
+where
+    T: proptest::arbitrary::Arbitrary,
+{
+    type Parameters = T::Parameters;
+    type Strategy = proptest::strategy::Map<T::Strategy, fn(T) -> Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+        T::arbitrary_with(args).prop_map(Versioned::new)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +166,112 @@ mod tests {
         assert_eq!(i.version(), 2);
         assert_eq!(i.inner(), i32::default());
     }
+
+    fn to_bytes<T: Serialize>(t: &T) -> Vec<u8> {
+        let mut bytes = vec![];
+        bin_prot::to_writer(&mut bytes, t).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_version_check() {
+        let bytes = to_bytes(&Versioned::<i32, 2>::new(7));
+        let err = bin_prot::from_slice_strict::<Versioned<i32, 1>>(&bytes).unwrap_err();
+        assert_eq!(
+            err.root_cause().to_string(),
+            "Unexpected version of i32. Expected: 1, found: 2"
+        );
+
+        let mut de = bin_prot::Deserializer::from_slice(&bytes);
+        let result: Result<Versioned<i32, 1>, _> =
+            deserialize_with_version_check(&mut de, VersionCheck::Warn);
+        assert_eq!(result.unwrap(), Versioned { version: 2, t: 7 });
+        // the check only applies to that deserialization
+        assert!(bin_prot::from_slice_strict::<Versioned<i32, 1>>(&bytes).is_err());
+        let mut de = bin_prot::Deserializer::from_slice(&bytes);
+        let result: Result<Versioned<i32, 1>, _> =
+            deserialize_with_version_check(&mut de, VersionCheck::Strict);
+        assert!(result.is_err());
+
+        let json = serde_json::to_string(&Versioned::<i32, 2>::new(7)).unwrap();
+        assert!(serde_json::from_str::<Versioned<i32, 1>>(&json).is_err());
+        assert_eq!(
+            serde_json::from_str::<Versioned<i32, 2>>(&json).unwrap(),
+            Versioned::new(7)
+        );
+    }
+
+    // Checks that another thread still decodes strictly while it is being deserialized
+    struct StrictOnOtherThreads;
+
+    impl<'de> serde::Deserialize<'de> for StrictOnOtherThreads {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let bytes = to_bytes(&Versioned::<i32, 2>::new(7));
+            let strict = std::thread::spawn(move || {
+                bin_prot::from_slice_strict::<Versioned<i32, 1>>(&bytes).is_err()
+            });
+            assert!(strict.join().unwrap());
+            <()>::deserialize(deserializer)?;
+            Ok(Self)
+        }
+    }
+
+    #[test]
+    fn test_version_check_per_thread() {
+        let bytes = to_bytes(&((), Versioned::<i32, 2>::new(7)));
+        let mut de = bin_prot::Deserializer::from_slice(&bytes);
+        let result: Result<(StrictOnOtherThreads, Versioned<i32, 1>), _> =
+            deserialize_with_version_check(&mut de, VersionCheck::Warn);
+        assert_eq!(result.unwrap().1, Versioned { version: 2, t: 7 });
+    }
+
+    #[test]
+    fn test_unknown_json_fields_are_ignored() {
+        let json = r#"{"extra":[1,2],"version":1,"t":7,"other":null}"#;
+        assert_eq!(
+            serde_json::from_str::<Versioned<i32, 1>>(json).unwrap(),
+            Versioned::new(7)
+        );
+        assert_eq!(
+            serde_json::from_str::<AnyVersion>(json).unwrap(),
+            AnyVersion::V1(7)
+        );
+        // the version is needed to decode `t` of a multi versioned type
+        let json = r#"{"t":7,"version":1}"#;
+        assert!(serde_json::from_str::<AnyVersion>(json).is_err());
+    }
+
+    crate::versioned_enum! {
+        #[derive(Debug, PartialEq)]
+        enum AnyVersion {
+            V1(i32) = 1,
+            V2((i32, String)) = 2,
+        }
+    }
+
+    #[test]
+    fn test_multi_versioned() {
+        let v1 = to_bytes(&Versioned::<i32, 1>::new(7));
+        let v2 = to_bytes(&Versioned::<_, 2>::new((7, "seven".to_string())));
+        let v3 = to_bytes(&Versioned::<i32, 3>::new(7));
+
+        assert_eq!(
+            bin_prot::from_slice_strict::<AnyVersion>(&v1).unwrap(),
+            AnyVersion::V1(7)
+        );
+        let any_v2 = bin_prot::from_slice_strict::<AnyVersion>(&v2).unwrap();
+        assert_eq!(any_v2, AnyVersion::V2((7, "seven".to_string())));
+        assert_eq!(to_bytes(&any_v2), v2);
+        assert_eq!(
+            bin_prot::from_slice_strict::<AnyVersion>(&v3)
+                .unwrap_err()
+                .root_cause()
+                .to_string(),
+            "Unexpected version of versioned::tests::AnyVersion. Expected: one of [1, 2], found: 3"
+        );
+
+        let json = serde_json::to_string(&any_v2).unwrap();
+        assert_eq!(json, r#"{"version":2,"t":[7,"seven"]}"#);
+        assert_eq!(serde_json::from_str::<AnyVersion>(&json).unwrap(), any_v2);
+    }
 }
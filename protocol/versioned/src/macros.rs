@@ -116,3 +116,96 @@ macro_rules! impl_from_for_generic_with_proxy {
         }
     };
 }
+
+/// Macro that defines an enum with one variant per version of a type. It is encoded the same way as a
+/// [crate::Versioned] wrapper around the inner type of the variant, and decoding reads the version and
+/// then the inner type of that version.
+///
+/// ```
+/// versioned::versioned_enum! {
+///     /// Either version of an amount
+///     #[derive(Debug, PartialEq)]
+///     pub enum AnyAmount {
+///         /// Version 1 was a 32 bit amount
+///         V1(u32) = 1,
+///         /// Version 2 is a 64 bit amount
+///         V2(u64) = 2,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! versioned_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident($inner:ty) = $version:literal
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant($inner),
+            )+
+        }
+
+        impl $crate::MultiVersioned for $name {
+            const VERSIONS: &'static [u16] = &[$($version),+];
+
+            fn version(&self) -> u16 {
+                match self {
+                    $($name::$variant(_) => $version,)+
+                }
+            }
+
+            fn deserialize_version<'de, D>(version: u16, deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: $crate::serde::Deserializer<'de>,
+            {
+                match version {
+                    $(
+                        $version => <$inner as $crate::serde::Deserialize>::deserialize(deserializer)
+                            .map($name::$variant),
+                    )+
+                    _ => Err(<D::Error as $crate::serde::de::Error>::custom(
+                        $crate::VersionMismatch {
+                            type_name: ::core::any::type_name::<Self>(),
+                            expected: <Self as $crate::MultiVersioned>::VERSIONS.to_vec(),
+                            found: version,
+                        },
+                    )),
+                }
+            }
+
+            fn serialize_inner<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::serde::Serializer,
+            {
+                match self {
+                    $($name::$variant(t) => $crate::serde::Serialize::serialize(t, serializer),)+
+                }
+            }
+        }
+
+        impl $crate::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::serde::Serializer,
+            {
+                $crate::serialize_multi_versioned(self, serializer)
+            }
+        }
+
+        impl<'de> $crate::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: $crate::serde::Deserializer<'de>,
+            {
+                $crate::deserialize_multi_versioned(deserializer)
+            }
+        }
+    };
+}